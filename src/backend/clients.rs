@@ -0,0 +1,131 @@
+use tokio::{sync::oneshot, time::Instant};
+
+use super::Backend;
+
+/// unique id of a connection, never reused
+pub type ConnectionId = u64;
+
+/// what CLIENT LIST reports about a connection, kept up to date by its handler
+#[derive(Debug, Clone)]
+pub struct ConnectionInfo {
+    pub id: u64,
+    pub addr: String,
+    pub name: Option<String>,
+    pub db: usize,
+    // lowercase name of the last command run
+    pub cmd: String,
+    pub created_at: Instant,
+    pub last_interaction: Instant,
+}
+
+// a registry entry, with the way to tell its handler to close the connection
+#[derive(Debug)]
+pub(super) struct Client {
+    info: ConnectionInfo,
+    kill: oneshot::Sender<()>,
+}
+
+impl ConnectionInfo {
+    pub fn new(id: u64, addr: impl Into<String>) -> Self {
+        let now = Instant::now();
+        ConnectionInfo {
+            id,
+            addr: addr.into(),
+            name: None,
+            db: 0,
+            cmd: "NULL".to_string(),
+            created_at: now,
+            last_interaction: now,
+        }
+    }
+}
+
+impl Backend {
+    /// list a connection, the receiver completes once it is killed or unregistered
+    pub fn register_client(&self, info: ConnectionInfo) -> oneshot::Receiver<()> {
+        self.stats().connection_received();
+        let (kill, killed) = oneshot::channel();
+        self.clients.write().insert(info.id, Client { info, kill });
+        killed
+    }
+
+    pub fn unregister_client(&self, id: u64) {
+        self.clients.write().remove(&id);
+    }
+
+    /// change the entry of client `id`, if it is still registered
+    pub fn update_client(&self, id: u64, f: impl FnOnce(&mut ConnectionInfo)) {
+        if let Some(client) = self.clients.write().get_mut(&id) {
+            f(&mut client.info);
+        }
+    }
+
+    /// unregister every client matching `filter` and tell their handlers to close the
+    /// connection, returns how many were killed
+    pub fn kill_clients(&self, filter: impl Fn(&ConnectionInfo) -> bool) -> usize {
+        let mut clients = self.clients.write();
+        let ids: Vec<u64> = clients
+            .values()
+            .filter(|client| filter(&client.info))
+            .map(|client| client.info.id)
+            .collect();
+        for id in ids.iter() {
+            if let Some(client) = clients.remove(id) {
+                // the handler may be gone already
+                let _ = client.kill.send(());
+            }
+        }
+        ids.len()
+    }
+
+    pub fn client_count(&self) -> usize {
+        self.clients.read().len()
+    }
+
+    /// a snapshot of every registered client, by id
+    pub fn clients(&self) -> Vec<ConnectionInfo> {
+        let mut clients: Vec<_> = self
+            .clients
+            .read()
+            .values()
+            .map(|client| client.info.clone())
+            .collect();
+        clients.sort_by_key(|info| info.id);
+        clients
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_registry() {
+        let backend = Backend::default();
+        backend.register_client(ConnectionInfo::new(2, "127.0.0.1:2"));
+        backend.register_client(ConnectionInfo::new(1, "127.0.0.1:1"));
+        backend.update_client(2, |info| info.name = Some("worker".to_string()));
+        // unknown ids are ignored
+        backend.update_client(3, |info| info.name = Some("ghost".to_string()));
+
+        let clients = backend.clients();
+        assert_eq!(clients.iter().map(|c| c.id).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(clients[1].name.as_deref(), Some("worker"));
+
+        backend.unregister_client(1);
+        assert_eq!(backend.clients().len(), 1);
+    }
+
+    #[test]
+    fn test_kill_clients() {
+        let backend = Backend::default();
+        let mut killed1 = backend.register_client(ConnectionInfo::new(1, "127.0.0.1:1"));
+        let mut killed2 = backend.register_client(ConnectionInfo::new(2, "127.0.0.1:2"));
+
+        assert_eq!(backend.kill_clients(|info| info.addr == "127.0.0.1:2"), 1);
+        assert_eq!(killed2.try_recv(), Ok(()));
+        assert!(killed1.try_recv().is_err());
+        assert_eq!(backend.clients().len(), 1);
+        assert_eq!(backend.kill_clients(|info| info.id == 2), 0);
+    }
+}
@@ -0,0 +1,31 @@
+use std::time::SystemTime;
+
+use lazy_static::lazy_static;
+use tokio::time::Instant;
+
+lazy_static! {
+    static ref ANCHOR: (SystemTime, Instant) = (SystemTime::now(), Instant::now());
+}
+
+/// wall clock time, advanced by the tokio clock so that expiry follows `tokio::time::pause`
+/// and `advance` in tests
+pub fn now() -> SystemTime {
+    let (system, instant) = *ANCHOR;
+    system + Instant::now().saturating_duration_since(instant)
+}
+
+/// milliseconds on a monotonic clock, also driven by the tokio clock
+pub fn monotonic_ms() -> u64 {
+    let (_, instant) = *ANCHOR;
+    Instant::now()
+        .saturating_duration_since(instant)
+        .as_millis() as u64
+}
+
+/// milliseconds since the unix epoch
+pub fn unix_time_ms(time: SystemTime) -> i64 {
+    match time.duration_since(SystemTime::UNIX_EPOCH) {
+        Ok(d) => d.as_millis() as i64,
+        Err(e) => -(e.duration().as_millis() as i64),
+    }
+}
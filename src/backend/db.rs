@@ -0,0 +1,443 @@
+use std::{
+    collections::HashMap,
+    mem::size_of,
+    sync::atomic::{AtomicU64, AtomicU8, Ordering},
+    time::{Duration, SystemTime},
+};
+
+use rand::Rng;
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{clock, BackendValue, ConnectionId};
+
+// LFU counter of a new key, so that it isn't the first to be evicted, and how slowly the
+// counter grows: it reaches 255 after about a million accesses
+const LFU_INIT_VAL: u8 = 5;
+const LFU_LOG_FACTOR: f64 = 10.0;
+
+// collection elements measured by MEMORY USAGE without SAMPLES, and by INFO
+pub const MEMORY_USAGE_SAMPLES: usize = 5;
+
+/// a keyspace, always accessed through the backend's read or write lock
+///
+/// expired keys are invisible to every accessor, they are physically removed lazily by
+/// write accessors and periodically by the backend's reaper task
+#[derive(Debug, Default)]
+pub struct Db {
+    map: HashMap<String, Entry>,
+    // the keys of `map` with an expiry, for the reaper to sample from
+    expires: Expires,
+    // WATCHing connections by key, told whenever a write accessor touches the key
+    watched: HashMap<String, Vec<(ConnectionId, UnboundedSender<()>)>>,
+    // keys removed because they expired, until the reaper takes them to notify subscribers
+    expired: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct Entry {
+    pub value: BackendValue,
+    pub expires_at: Option<SystemTime>,
+    // last access as `clock::monotonic_ms`, atomic so that readers can update it under the
+    // read lock
+    accessed_at: AtomicU64,
+    // logarithmic access counter for the LFU policies
+    lfu_freq: AtomicU8,
+}
+
+impl Entry {
+    pub fn new(value: BackendValue) -> Self {
+        Self {
+            value,
+            expires_at: None,
+            accessed_at: AtomicU64::new(clock::monotonic_ms()),
+            lfu_freq: AtomicU8::new(LFU_INIT_VAL),
+        }
+    }
+
+    /// the LFU access counter, 0 to 255
+    pub fn frequency(&self) -> u8 {
+        self.lfu_freq.load(Ordering::Relaxed)
+    }
+
+    /// time since the key was last read or written
+    pub fn idle_time(&self) -> Duration {
+        let accessed_at = self.accessed_at.load(Ordering::Relaxed);
+        Duration::from_millis(clock::monotonic_ms().saturating_sub(accessed_at))
+    }
+
+    fn touch(&self) {
+        self.accessed_at
+            .store(clock::monotonic_ms(), Ordering::Relaxed);
+        self.increment_frequency();
+    }
+
+    // the counter grows with probability 1 / ((counter - LFU_INIT_VAL) * LFU_LOG_FACTOR + 1),
+    // so it approximates the logarithm of the number of accesses
+    fn increment_frequency(&self) {
+        let counter = self.frequency();
+        if counter == u8::MAX {
+            return;
+        }
+        let base = counter.saturating_sub(LFU_INIT_VAL) as f64;
+        if base == 0.0 || rand::thread_rng().gen::<f64>() < 1.0 / (base * LFU_LOG_FACTOR + 1.0) {
+            // a concurrent reader may have incremented it already, one increment is enough
+            let _ = self.lfu_freq.compare_exchange(
+                counter,
+                counter + 1,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            );
+        }
+    }
+
+    fn is_expired(&self, now: SystemTime) -> bool {
+        self.expires_at.is_some_and(|at| at <= now)
+    }
+}
+
+// a set of keys that can be sampled without walking all of them, as redis keeps its expires
+// dict next to the main one
+#[derive(Debug, Default)]
+struct Expires {
+    keys: Vec<String>,
+    // index of each key in `keys`
+    positions: HashMap<String, usize>,
+}
+
+impl Expires {
+    fn insert(&mut self, key: &str) {
+        if !self.positions.contains_key(key) {
+            self.positions.insert(key.to_string(), self.keys.len());
+            self.keys.push(key.to_string());
+        }
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(position) = self.positions.remove(key) {
+            self.keys.swap_remove(position);
+            // the last key took its place
+            if let Some(moved) = self.keys.get(position) {
+                self.positions.insert(moved.clone(), position);
+            }
+        }
+    }
+
+    // up to `n` distinct keys picked uniformly
+    fn sample(&self, n: usize) -> impl Iterator<Item = &String> {
+        let n = n.min(self.keys.len());
+        rand::seq::index::sample(&mut rand::thread_rng(), self.keys.len(), n)
+            .into_iter()
+            .map(|index| &self.keys[index])
+    }
+}
+
+impl Clone for Entry {
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            expires_at: self.expires_at,
+            accessed_at: AtomicU64::new(self.accessed_at.load(Ordering::Relaxed)),
+            lfu_freq: AtomicU8::new(self.frequency()),
+        }
+    }
+}
+
+impl Db {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// number of live keys. only keys with an expiry are walked to leave out the expired ones
+    pub fn len(&self) -> usize {
+        self.map.len() - self.expired_len()
+    }
+
+    /// number of live keys with an expiry set
+    pub fn expires_len(&self) -> usize {
+        self.expires.keys.len() - self.expired_len()
+    }
+
+    /// estimated bytes used by the keys and their values, expired ones included until removed
+    pub fn used_memory(&self) -> usize {
+        self.map
+            .iter()
+            .map(|(key, e)| entry_memory_usage(key, e, MEMORY_USAGE_SAMPLES))
+            .sum()
+    }
+
+    /// bytes of the main hash table itself, keys and values excluded
+    pub fn table_overhead(&self) -> usize {
+        self.map.capacity() * (size_of::<(String, Entry)>() + 1)
+    }
+
+    /// estimated bytes used by `key` and its value, without counting as an access
+    pub fn memory_usage(&self, key: &str, samples: usize) -> Option<usize> {
+        let (key, e) = self.map.get_key_value(key)?;
+        (!e.is_expired(clock::now())).then(|| entry_memory_usage(key, e, samples))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.peek(key).is_some()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&BackendValue> {
+        self.entry(key).map(|e| &e.value)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut BackendValue> {
+        self.entry_mut(key).map(|e| &mut e.value)
+    }
+
+    /// mutable access to the value at `key`, inserting `default()` if the key is absent.
+    /// watchers only learn about a change reported with `signal_modified`
+    pub fn get_or_insert_with(
+        &mut self,
+        key: String,
+        default: impl FnOnce() -> BackendValue,
+    ) -> &mut BackendValue {
+        self.remove_if_expired(&key);
+        &mut self
+            .map
+            .entry(key)
+            .and_modify(|e| e.touch())
+            .or_insert_with(|| Entry::new(default()))
+            .value
+    }
+
+    /// insert a value, discarding any expiry of the previous value
+    pub fn insert(&mut self, key: String, value: BackendValue) -> Option<BackendValue> {
+        self.insert_entry(key, Entry::new(value)).map(|e| e.value)
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<BackendValue> {
+        self.remove_entry(key).map(|e| e.value)
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        let now = clock::now();
+        self.map
+            .iter()
+            .filter(move |(_, e)| !e.is_expired(now))
+            .map(|(k, _)| k)
+    }
+
+    /// every live key and entry, without counting as accesses
+    pub fn entries(&self) -> impl Iterator<Item = (&String, &Entry)> {
+        let now = clock::now();
+        self.map.iter().filter(move |(_, e)| !e.is_expired(now))
+    }
+
+    /// a copy of the live keys and entries, without the watchers
+    pub fn snapshot(&self) -> Db {
+        let mut db = Db::default();
+        for (key, e) in self.entries() {
+            db.insert_entry(key.clone(), e.clone());
+        }
+        db
+    }
+
+    /// the entry at `key`, counting as an access
+    pub fn entry(&self, key: &str) -> Option<&Entry> {
+        let entry = self.peek(key)?;
+        entry.touch();
+        Some(entry)
+    }
+
+    /// the entry at `key` without updating its access time
+    pub fn peek(&self, key: &str) -> Option<&Entry> {
+        self.map.get(key).filter(|e| !e.is_expired(clock::now()))
+    }
+
+    /// mutable access to the entry at `key`. watchers only learn about a change reported with
+    /// `signal_modified`
+    pub fn entry_mut(&mut self, key: &str) -> Option<&mut Entry> {
+        self.remove_if_expired(key);
+        let entry = self.map.get_mut(key)?;
+        entry.touch();
+        Some(entry)
+    }
+
+    pub fn insert_entry(&mut self, key: String, entry: Entry) -> Option<Entry> {
+        self.touch_watched(&key);
+        match entry.expires_at {
+            Some(_) => self.expires.insert(&key),
+            None => self.expires.remove(&key),
+        }
+        self.map
+            .insert(key, entry)
+            .filter(|e| !e.is_expired(clock::now()))
+    }
+
+    pub fn remove_entry(&mut self, key: &str) -> Option<Entry> {
+        self.touch_watched(key);
+        self.expires.remove(key);
+        self.map.remove(key).filter(|e| !e.is_expired(clock::now()))
+    }
+
+    /// set the expiry of an existing key, returns false if the key does not exist
+    pub fn expire(&mut self, key: &str, at: SystemTime) -> bool {
+        match self.entry_mut(key) {
+            Some(entry) => {
+                entry.expires_at = Some(at);
+                self.expires.insert(key);
+                self.touch_watched(key);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// drop the expiry of a key, returns false if the key does not exist or has no expiry
+    pub fn persist(&mut self, key: &str) -> bool {
+        let persisted = self
+            .entry_mut(key)
+            .and_then(|entry| entry.expires_at.take())
+            .is_some();
+        if persisted {
+            self.expires.remove(key);
+            self.touch_watched(key);
+        }
+        persisted
+    }
+
+    /// the keys whose expiry is already due among `n` picked uniformly from those with one
+    pub fn sample_expired_keys(&self, n: usize) -> Vec<String> {
+        let now = clock::now();
+        self.expires
+            .sample(n)
+            .filter(|key| self.map.get(*key).is_some_and(|e| e.is_expired(now)))
+            .cloned()
+            .collect()
+    }
+
+    /// physically remove every expired key, returns how many were removed
+    pub fn purge_expired(&mut self) -> usize {
+        let now = clock::now();
+        let before = self.map.len();
+        let (watched, expires, removed) = (&mut self.watched, &mut self.expires, &mut self.expired);
+        self.map.retain(|key, e| {
+            let expired = e.is_expired(now);
+            if expired {
+                touch_watched(watched, key);
+                expires.remove(key);
+                removed.push(key.clone());
+            }
+            !expired
+        });
+        before - self.map.len()
+    }
+
+    /// physically remove `key` if it has expired, returns true if it was removed
+    pub fn remove_if_expired(&mut self, key: &str) -> bool {
+        let expired = self
+            .map
+            .get(key)
+            .is_some_and(|e| e.is_expired(clock::now()));
+        if expired {
+            self.map.remove(key);
+            self.expires.remove(key);
+            self.touch_watched(key);
+            self.expired.push(key.to_string());
+        }
+        expired
+    }
+
+    /// tell whoever WATCHes `key` that it was changed through `get_mut` or `entry_mut`
+    pub fn signal_modified(&mut self, key: &str) {
+        self.touch_watched(key);
+    }
+
+    /// keys removed because they expired since the last call
+    pub fn take_expired(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.expired)
+    }
+
+    pub fn has_expired(&self) -> bool {
+        !self.expired.is_empty()
+    }
+
+    /// tell connection `id` through `tx` once `key` may have changed
+    pub fn watch(&mut self, key: &str, id: ConnectionId, tx: UnboundedSender<()>) {
+        let watchers = self.watched.entry(key.to_string()).or_default();
+        if !watchers.iter().any(|(watcher, _)| *watcher == id) {
+            watchers.push((id, tx));
+        }
+    }
+
+    pub fn unwatch(&mut self, key: &str, id: ConnectionId) {
+        if let Some(watchers) = self.watched.get_mut(key) {
+            watchers.retain(|(watcher, _)| *watcher != id);
+            if watchers.is_empty() {
+                self.watched.remove(key);
+            }
+        }
+    }
+
+    /// move the keys out, as FLUSHDB does, telling whoever watches one of them
+    pub fn take(&mut self) -> Db {
+        let map = std::mem::take(&mut self.map);
+        for key in map.keys() {
+            touch_watched(&mut self.watched, key);
+        }
+        Db {
+            map,
+            expires: std::mem::take(&mut self.expires),
+            ..Db::default()
+        }
+    }
+
+    /// exchange the keys with another keyspace, as SWAPDB does, telling every watcher of
+    /// either
+    pub fn swap(&mut self, other: &mut Db) {
+        std::mem::swap(&mut self.map, &mut other.map);
+        std::mem::swap(&mut self.expires, &mut other.expires);
+        for db in [self, other] {
+            for (_, watchers) in db.watched.drain() {
+                for (_, tx) in watchers {
+                    let _ = tx.send(());
+                }
+            }
+        }
+    }
+
+    fn touch_watched(&mut self, key: &str) {
+        touch_watched(&mut self.watched, key);
+    }
+
+    // keys that expired but are still stored
+    fn expired_len(&self) -> usize {
+        let now = clock::now();
+        self.expires
+            .keys
+            .iter()
+            .filter(|key| self.map.get(*key).is_some_and(|e| e.is_expired(now)))
+            .count()
+    }
+}
+
+// the table slot holding the key and entry, plus what both allocated. the value lives inline
+// in the entry, so its own size is already part of the slot
+fn entry_memory_usage(key: &String, e: &Entry, samples: usize) -> usize {
+    size_of::<(String, Entry)>() + 1 + key.capacity() + e.value.memory_usage(samples)
+        - size_of::<BackendValue>()
+}
+
+// watchers are told once and then forgotten, the connection has to WATCH again anyway. those
+// that hung up are dropped as well
+fn touch_watched(
+    watched: &mut HashMap<String, Vec<(ConnectionId, UnboundedSender<()>)>>,
+    key: &str,
+) {
+    if watched.is_empty() {
+        return;
+    }
+    if let Some(watchers) = watched.remove(key) {
+        for (_, tx) in watchers {
+            let _ = tx.send(());
+        }
+    }
+}
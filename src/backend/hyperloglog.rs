@@ -0,0 +1,279 @@
+// 2^14 registers, for a standard error of 1.04 / sqrt(16384) = 0.81%
+const P: u32 = 14;
+const REGISTERS: usize = 1 << P;
+const REGISTER_BITS: usize = 6;
+const REGISTER_MAX: u16 = (1 << REGISTER_BITS) - 1;
+// bits of the hash left once the register index is taken
+const Q: usize = 64 - P as usize;
+const ALPHA_INF: f64 = 0.721_347_520_444_481_7;
+const HASH_SEED: u64 = 0xadc8_3b19;
+
+// the layout redis uses: "HYLL", the encoding, 3 unused bytes and a cached cardinality
+const MAGIC: &[u8; 4] = b"HYLL";
+const HEADER_SIZE: usize = 16;
+const DENSE: u8 = 0;
+const SPARSE: u8 = 1;
+// set in the last byte of the cached cardinality when it is stale
+const CACHE_STALE: u8 = 0x80;
+
+/// bytes of the string holding a HyperLogLog, the header then 12 KB of packed registers
+pub const HLL_DENSE_SIZE: usize = HEADER_SIZE + (REGISTERS * REGISTER_BITS).div_ceil(8);
+
+/// a HyperLogLog cardinality estimator, one byte per register while in memory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self {
+            registers: vec![0; REGISTERS],
+        }
+    }
+}
+
+impl HyperLogLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// read a string value, None if it doesn't hold a HyperLogLog. the sparse encoding redis
+    /// uses for small counts is read too, but never written
+    pub fn decode(buf: &[u8]) -> Option<Self> {
+        if buf.len() < HEADER_SIZE || &buf[..MAGIC.len()] != MAGIC {
+            return None;
+        }
+        let data = &buf[HEADER_SIZE..];
+        match buf[MAGIC.len()] {
+            DENSE if buf.len() == HLL_DENSE_SIZE => Some(Self {
+                registers: (0..REGISTERS).map(|i| get_register(data, i)).collect(),
+            }),
+            SPARSE => decode_sparse(data),
+            _ => None,
+        }
+    }
+
+    /// the dense string value, with no cached cardinality
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = vec![0; HLL_DENSE_SIZE];
+        buf[..MAGIC.len()].copy_from_slice(MAGIC);
+        buf[MAGIC.len()] = DENSE;
+        buf[HEADER_SIZE - 1] = CACHE_STALE;
+        for (index, &value) in self.registers.iter().enumerate() {
+            set_register(&mut buf[HEADER_SIZE..], index, value);
+        }
+        buf
+    }
+
+    /// count an element, true if a register changed
+    pub fn add(&mut self, element: &[u8]) -> bool {
+        let (index, count) = position(element);
+        if self.registers[index] < count {
+            self.registers[index] = count;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// fold in the elements counted by `other`
+    pub fn merge(&mut self, other: &HyperLogLog) {
+        for (register, &value) in self.registers.iter_mut().zip(&other.registers) {
+            *register = (*register).max(value);
+        }
+    }
+
+    /// the estimated number of distinct elements, with the estimator from Otmar Ertl's "New
+    /// cardinality estimation algorithms for HyperLogLog sketches" like redis
+    pub fn count(&self) -> u64 {
+        let m = REGISTERS as f64;
+        let mut histogram = [0u32; Q + 2];
+        for &value in &self.registers {
+            histogram[value as usize] += 1;
+        }
+        let mut z = m * tau((m - histogram[Q + 1] as f64) / m);
+        for &n in histogram[1..=Q].iter().rev() {
+            z += n as f64;
+            z *= 0.5;
+        }
+        z += m * sigma(histogram[0] as f64 / m);
+        (ALPHA_INF * m * m / z).round() as u64
+    }
+}
+
+// the register an element falls into and the length of the run of zeroes that follows, plus one
+fn position(element: &[u8]) -> (usize, u8) {
+    let hash = murmurhash64a(element, HASH_SEED);
+    let index = (hash & (REGISTERS as u64 - 1)) as usize;
+    // a set bit past the end bounds the run
+    let rest = (hash >> P) | (1 << Q);
+    (index, rest.trailing_zeros() as u8 + 1)
+}
+
+fn sigma(mut x: f64) -> f64 {
+    if x == 1.0 {
+        return f64::INFINITY;
+    }
+    let mut y = 1.0;
+    let mut z = x;
+    loop {
+        x *= x;
+        let previous = z;
+        z += x * y;
+        y += y;
+        if z == previous {
+            return z;
+        }
+    }
+}
+
+fn tau(mut x: f64) -> f64 {
+    if x == 0.0 || x == 1.0 {
+        return 0.0;
+    }
+    let mut y = 1.0;
+    let mut z = 1.0 - x;
+    loop {
+        x = x.sqrt();
+        let previous = z;
+        y *= 0.5;
+        z -= (1.0 - x).powi(2) * y;
+        if z == previous {
+            return z / 3.0;
+        }
+    }
+}
+
+// registers are packed 6 bits each, the lowest bits first
+fn get_register(data: &[u8], index: usize) -> u8 {
+    let bit = index * REGISTER_BITS;
+    let (byte, shift) = (bit / 8, bit % 8);
+    let word = data[byte] as u16 | ((data.get(byte + 1).copied().unwrap_or(0) as u16) << 8);
+    ((word >> shift) & REGISTER_MAX) as u8
+}
+
+fn set_register(data: &mut [u8], index: usize, value: u8) {
+    let bit = index * REGISTER_BITS;
+    let (byte, shift) = (bit / 8, bit % 8);
+    let mask = REGISTER_MAX << shift;
+    let word = (value as u16 & REGISTER_MAX) << shift;
+    data[byte] = (data[byte] & !(mask as u8)) | word as u8;
+    if let Some(next) = data.get_mut(byte + 1) {
+        *next = (*next & !((mask >> 8) as u8)) | (word >> 8) as u8;
+    }
+}
+
+// runs of registers: 00xxxxxx zeroes, 01xxxxxx xxxxxxxx more zeroes, 1vvvvvxx a value
+fn decode_sparse(data: &[u8]) -> Option<HyperLogLog> {
+    let mut hll = HyperLogLog::new();
+    let mut index = 0;
+    let mut bytes = data.iter();
+    while let Some(&op) = bytes.next() {
+        let (run, value) = match op >> 6 {
+            0 => ((op & 0x3f) as usize + 1, 0),
+            1 => (
+                ((((op & 0x3f) as usize) << 8) | *bytes.next()? as usize) + 1,
+                0,
+            ),
+            _ => ((op & 0x03) as usize + 1, ((op >> 2) & 0x1f) + 1),
+        };
+        hll.registers.get_mut(index..index + run)?.fill(value);
+        index += run;
+    }
+    (index == REGISTERS).then_some(hll)
+}
+
+// the 64 bit MurmurHash2 by Austin Appleby, which redis hashes elements with
+fn murmurhash64a(key: &[u8], seed: u64) -> u64 {
+    const M: u64 = 0xc6a4_a793_5bd1_e995;
+    const R: u32 = 47;
+
+    let mut h = seed ^ (key.len() as u64).wrapping_mul(M);
+    let mut chunks = key.chunks_exact(8);
+    for chunk in &mut chunks {
+        let mut k = u64::from_le_bytes(chunk.try_into().expect("8 byte chunk"));
+        k = k.wrapping_mul(M);
+        k ^= k >> R;
+        k = k.wrapping_mul(M);
+        h ^= k;
+        h = h.wrapping_mul(M);
+    }
+    let tail = chunks.remainder();
+    if !tail.is_empty() {
+        for (i, &b) in tail.iter().enumerate() {
+            h ^= (b as u64) << (8 * i);
+        }
+        h = h.wrapping_mul(M);
+    }
+    h ^= h >> R;
+    h = h.wrapping_mul(M);
+    h ^= h >> R;
+    h
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hyperloglog_estimate() {
+        let mut hll = HyperLogLog::new();
+        assert_eq!(hll.count(), 0);
+        assert!(hll.add(b"a"));
+        assert!(!hll.add(b"a"));
+        assert_eq!(hll.count(), 1);
+
+        for n in 0..100_000 {
+            hll.add(format!("element:{}", n).as_bytes());
+        }
+        let count = hll.count() as f64;
+        // the standard error is 0.81%
+        assert!((count - 100_001.0).abs() < 100_001.0 * 0.05, "{}", count);
+    }
+
+    #[test]
+    fn test_hyperloglog_merge() {
+        let (mut a, mut b, mut both) = (HyperLogLog::new(), HyperLogLog::new(), HyperLogLog::new());
+        for n in 0..1000 {
+            let element = n.to_string();
+            if n % 2 == 0 {
+                a.add(element.as_bytes());
+            } else {
+                b.add(element.as_bytes());
+            }
+            both.add(element.as_bytes());
+        }
+        a.merge(&b);
+        assert_eq!(a, both);
+    }
+
+    #[test]
+    fn test_hyperloglog_encoding() {
+        let mut hll = HyperLogLog::new();
+        for n in 0..5000 {
+            hll.add(n.to_string().as_bytes());
+        }
+        let buf = hll.encode();
+        assert_eq!(buf.len(), HLL_DENSE_SIZE);
+        assert_eq!(&buf[..4], b"HYLL");
+        assert_eq!(HyperLogLog::decode(&buf), Some(hll));
+
+        assert_eq!(HyperLogLog::decode(b"HYLL"), None);
+        assert_eq!(HyperLogLog::decode(&buf[..HLL_DENSE_SIZE - 1]), None);
+        assert_eq!(HyperLogLog::decode(&[b'x'; HLL_DENSE_SIZE]), None);
+
+        // an empty sparse HyperLogLog is one run of 16384 zeroes
+        let mut sparse = b"HYLL\x01\0\0\0\0\0\0\0\0\0\0\0".to_vec();
+        sparse.extend_from_slice(&[0x7f, 0xff]);
+        assert_eq!(HyperLogLog::decode(&sparse), Some(HyperLogLog::new()));
+        // a value of 3 for registers 0 and 1, then zeroes
+        let mut sparse = b"HYLL\x01\0\0\0\0\0\0\0\0\0\0\0".to_vec();
+        sparse.extend_from_slice(&[0x89, 0x7f, 0xfd]);
+        let hll = HyperLogLog::decode(&sparse).unwrap();
+        assert_eq!(&hll.registers[..3], &[3, 3, 0]);
+        // runs past the last register are rejected
+        sparse.push(0x00);
+        assert_eq!(HyperLogLog::decode(&sparse), None);
+    }
+}
@@ -1,38 +1,117 @@
-use std::{ops::Deref, sync::Arc};
+mod clients;
+pub mod clock;
+mod db;
+mod hyperloglog;
+mod notify;
+mod stats;
+mod value;
+mod zset;
 
-use dashmap::DashMap;
+use std::{
+    collections::HashMap,
+    ops::Deref,
+    sync::{Arc, Weak},
+    time::Duration,
+};
 
-use crate::RespFrame;
+use parking_lot::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use tokio::{
+    runtime::Handle,
+    sync::{
+        mpsc::{self, UnboundedReceiver, UnboundedSender},
+        Notify,
+    },
+};
 
+use crate::{
+    monitoring::{LatencyMonitor, SlowLog},
+    network::{Monitor, PauseGate, PubSub, ReplicationState, Shutdown},
+    persistence::Persistence,
+    NotificationConfig, ServerConfig,
+};
+
+use clients::Client;
+pub use clients::{ConnectionId, ConnectionInfo};
+pub use db::{Db, Entry, MEMORY_USAGE_SAMPLES};
+pub use hyperloglog::{HyperLogLog, HLL_DENSE_SIZE};
+pub use stats::{BlockedClient, Stats};
+pub use value::BackendValue;
+pub use zset::{LexBound, Score, ScoreBound, ZSet};
+
+// keys with an expiry sampled by each active expire cycle
+const ACTIVE_EXPIRE_SAMPLE_SIZE: usize = 20;
+
+// anything that is expensive to drop: removed values, whole flushed keyspaces
+type Garbage = Box<dyn Send>;
+
+/// number of databases, SELECT takes an index in `0..DB_COUNT`
+pub const DB_COUNT: usize = 16;
+
+/// a handle on the shared keyspaces, bound to one selected database
 #[derive(Debug, Clone)]
-pub struct Backend(Arc<BackInner>);
+pub struct Backend {
+    inner: Arc<BackInner>,
+    db: usize,
+}
 
 #[derive(Debug)]
 pub struct BackInner {
-    pub map: DashMap<String, RespFrame>,
-    pub hmap: DashMap<String, DashMap<String, RespFrame>>,
+    dbs: Vec<RwLock<Db>>,
+    // values handed to the lazy free task, None outside a tokio runtime
+    lazy_free: Option<UnboundedSender<Garbage>>,
+    // clients blocked on a key, by database index and key
+    key_waiters: Mutex<HashMap<(usize, String), Arc<Notify>>>,
+    // connected clients, by connection id
+    clients: RwLock<HashMap<u64, Client>>,
+    // CLIENT PAUSE, checked before every command
+    pause_gate: PauseGate,
+    config: RwLock<ServerConfig>,
+    // held shared by every command and exclusively by EXEC, so that a transaction runs
+    // without other commands in between
+    exec_lock: RwLock<()>,
+    pubsub: PubSub,
+    replication: ReplicationState,
+    monitor: Monitor,
+    persistence: Persistence,
+    shutdown: Shutdown,
+    stats: Stats,
+    slowlog: SlowLog,
+    latency: LatencyMonitor,
 }
 
 impl Deref for Backend {
     type Target = BackInner;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.inner
     }
 }
 
 impl BackInner {
     pub fn new() -> Self {
         Self {
-            map: DashMap::new(),
-            hmap: DashMap::new(),
+            dbs: (0..DB_COUNT).map(|_| RwLock::new(Db::new())).collect(),
+            lazy_free: None,
+            key_waiters: Mutex::new(HashMap::new()),
+            clients: RwLock::new(HashMap::new()),
+            pause_gate: PauseGate::default(),
+            config: RwLock::new(ServerConfig::default()),
+            exec_lock: RwLock::new(()),
+            pubsub: PubSub::default(),
+            replication: ReplicationState::default(),
+            monitor: Monitor::default(),
+            persistence: Persistence::default(),
+            shutdown: Shutdown::default(),
+            stats: Stats::default(),
+            slowlog: SlowLog::default(),
+            latency: LatencyMonitor::default(),
         }
     }
 }
 
 impl Default for Backend {
     fn default() -> Self {
-        Self(Arc::new(BackInner::default()))
+        Self::from_inner(Arc::new(BackInner::default()))
     }
 }
 
@@ -43,30 +122,360 @@ impl Default for BackInner {
 }
 
 impl Backend {
+    /// create a backend, spawning the expiry reaper and the lazy free task if called inside a
+    /// tokio runtime
     pub fn new() -> Self {
-        Self::default()
+        let Ok(handle) = Handle::try_current() else {
+            return Self::default();
+        };
+        let (tx, rx) = mpsc::unbounded_channel();
+        handle.spawn(lazy_free_worker(rx));
+        let backend = Self::from_inner(Arc::new(BackInner {
+            lazy_free: Some(tx),
+            ..BackInner::default()
+        }));
+        handle.spawn(expire_reaper(Arc::downgrade(&backend.inner)));
+        backend
+    }
+
+    fn from_inner(inner: Arc<BackInner>) -> Self {
+        Self { inner, db: 0 }
+    }
+
+    /// a handle on database `index` sharing the same keyspaces, None if out of range
+    pub fn select(&self, index: usize) -> Option<Backend> {
+        (index < DB_COUNT).then(|| Self {
+            inner: self.inner.clone(),
+            db: index,
+        })
+    }
+
+    /// index of the selected database
+    pub fn db_index(&self) -> usize {
+        self.db
+    }
+
+    /// shared access to the selected keyspace, for read-only commands
+    pub fn read(&self) -> RwLockReadGuard<'_, Db> {
+        self.dbs[self.db].read()
+    }
+
+    /// exclusive access to the selected keyspace, multi-key commands hold it for their whole
+    /// execution
+    pub fn write(&self) -> RwLockWriteGuard<'_, Db> {
+        self.dbs[self.db].write()
+    }
+
+    /// run an active expire cycle on every database, returns how many keys were deleted
+    pub fn active_expire_cycle(&self) -> usize {
+        let start = std::time::Instant::now();
+        let removed = (0..DB_COUNT)
+            .filter_map(|index| self.select(index))
+            .map(|backend| backend.active_expire_cycle_db())
+            .sum();
+        self.record_latency("expire-cycle", start.elapsed());
+        removed
+    }
+
+    // sample keys with an expiry and delete those that are due. candidates are collected under
+    // the read lock so the write lock is only held to delete them. keys removed lazily by
+    // commands since the last cycle are notified here as well
+    fn active_expire_cycle_db(&self) -> usize {
+        let candidates = {
+            let db = self.read();
+            let candidates = db.sample_expired_keys(ACTIVE_EXPIRE_SAMPLE_SIZE);
+            if candidates.is_empty() && !db.has_expired() {
+                return 0;
+            }
+            candidates
+        };
+        let (removed, expired) = {
+            let mut db = self.write();
+            // a candidate may have been rewritten or persisted in between
+            let removed = candidates
+                .iter()
+                .filter(|key| db.remove_if_expired(key))
+                .count();
+            (removed, db.take_expired())
+        };
+        for key in expired {
+            self.notify_keyspace_event(NotificationConfig::EXPIRED, "expired", &key);
+        }
+        removed
+    }
+
+    /// drop removed values or keyspaces in the background, or right away without a runtime
+    pub fn lazy_free(&self, garbage: impl Send + 'static) {
+        match &self.lazy_free {
+            Some(tx) => {
+                // the worker only stops once every sender is gone, so this can't fail
+                let _ = tx.send(Box::new(garbage));
+            }
+            None => drop(garbage),
+        }
+    }
+
+    /// atomically exchange the content of two databases, false if an index is out of range.
+    /// both are write locked, always in index order so that concurrent swaps can't deadlock
+    pub fn swap_db(&self, a: usize, b: usize) -> bool {
+        if a >= DB_COUNT || b >= DB_COUNT {
+            return false;
+        }
+        if a != b {
+            let (lo, hi) = (a.min(b), a.max(b));
+            let mut lo = self.dbs[lo].write();
+            let mut hi = self.dbs[hi].write();
+            lo.swap(&mut hi);
+        }
+        true
+    }
+
+    /// read locks on every database, taken in index order. nothing can be written until they
+    /// are dropped
+    pub fn read_all(&self) -> Vec<RwLockReadGuard<'_, Db>> {
+        self.dbs.iter().map(|db| db.read()).collect()
+    }
+
+    /// a consistent copy of every database
+    pub fn snapshot(&self) -> Vec<Db> {
+        self.read_all().iter().map(|db| db.snapshot()).collect()
+    }
+
+    /// replace the content of every database, as loading a dump does. watchers of the old
+    /// keys are told and the old content is freed in the background
+    pub fn restore(&self, dbs: Vec<Db>) {
+        for (slot, mut db) in self.dbs.iter().zip(dbs) {
+            slot.write().swap(&mut db);
+            self.lazy_free(db);
+        }
+    }
+
+    /// empty the selected database, returning its previous content
+    pub fn flush(&self) -> Db {
+        self.write().take()
+    }
+
+    /// a handle notified whenever `key` in the selected database may have become ready for a
+    /// blocked client. hand it back to `release_key_waiter` once done waiting
+    pub fn key_waiter(&self, key: &str) -> Arc<Notify> {
+        self.key_waiters
+            .lock()
+            .entry((self.db, key.to_string()))
+            .or_default()
+            .clone()
+    }
+
+    /// drop a handle from `key_waiter`, forgetting the key once no client is blocked on it
+    pub fn release_key_waiter(&self, key: &str, waiter: Arc<Notify>) {
+        let mut waiters = self.key_waiters.lock();
+        drop(waiter);
+        let id = (self.db, key.to_string());
+        // the registry holds the last reference
+        if waiters.get(&id).is_some_and(|w| Arc::strong_count(w) == 1) {
+            waiters.remove(&id);
+        }
+    }
+
+    /// wake every client blocked on `key` in the selected database
+    pub fn signal_key(&self, key: &str) {
+        let waiters = self.key_waiters.lock();
+        if waiters.is_empty() {
+            return;
+        }
+        if let Some(waiter) = waiters.get(&(self.db, key.to_string())) {
+            waiter.notify_waiters();
+        }
+    }
+
+    pub fn pause_gate(&self) -> &PauseGate {
+        &self.inner.pause_gate
+    }
+
+    /// hold while executing a command outside of a transaction
+    pub fn command_guard(&self) -> RwLockReadGuard<'_, ()> {
+        self.inner.exec_lock.read()
+    }
+
+    /// hold while executing the commands of a transaction
+    pub fn exec_guard(&self) -> RwLockWriteGuard<'_, ()> {
+        self.inner.exec_lock.write()
+    }
+
+    pub fn pubsub(&self) -> &PubSub {
+        &self.inner.pubsub
+    }
+
+    pub fn replication(&self) -> &ReplicationState {
+        &self.inner.replication
+    }
+
+    pub fn monitor(&self) -> &Monitor {
+        &self.inner.monitor
+    }
+
+    pub fn persistence(&self) -> &Persistence {
+        &self.inner.persistence
+    }
+
+    pub fn shutdown(&self) -> &Shutdown {
+        &self.inner.shutdown
+    }
+
+    pub fn stats(&self) -> &Stats {
+        &self.inner.stats
     }
 
-    pub fn get(&self, key: &str) -> Option<RespFrame> {
-        self.map.get(key).map(|r| r.value().clone())
+    pub fn slowlog(&self) -> &SlowLog {
+        &self.inner.slowlog
     }
 
-    pub fn set(&self, key: String, value: RespFrame) {
-        self.map.insert(key, value);
+    pub fn latency(&self) -> &LatencyMonitor {
+        &self.inner.latency
     }
 
-    pub fn hget(&self, key: &str, field: &str) -> Option<RespFrame> {
-        self.hmap
-            .get(key)
-            .and_then(|m| m.get(field).map(|r| r.value().clone()))
+    /// feed the latency monitor, as configured by latency-monitor-threshold
+    pub fn record_latency(&self, event: &str, duration: Duration) {
+        let threshold = self.config().latency_monitor_threshold;
+        self.latency.record(event, duration, threshold);
     }
 
-    pub fn hgetall(&self, key: &str) -> Option<DashMap<String, RespFrame>> {
-        self.hmap.get(key).map(|m| m.clone())
+    /// estimated bytes used by the keys and values of every database
+    pub fn used_memory(&self) -> usize {
+        let used = self.dbs.iter().map(|db| db.read().used_memory()).sum();
+        self.stats.observe_memory(used);
+        used
     }
 
-    pub fn hset(&self, key: String, field: String, value: RespFrame) {
-        let hmap = self.hmap.entry(key).or_default();
-        hmap.insert(field, value);
+    /// (keys, keys with an expiry) of database `index`
+    pub fn keyspace_info(&self, index: usize) -> (usize, usize) {
+        let db = self.dbs[index].read();
+        (db.len(), db.expires_len())
+    }
+
+    /// bytes of the hash table of database `index`, keys and values excluded
+    pub fn table_overhead(&self, index: usize) -> usize {
+        self.dbs[index].read().table_overhead()
+    }
+
+    pub fn config(&self) -> RwLockReadGuard<'_, ServerConfig> {
+        self.inner.config.read()
+    }
+
+    pub fn config_mut(&self) -> RwLockWriteGuard<'_, ServerConfig> {
+        self.inner.config.write()
+    }
+
+    pub fn get(&self, key: &str) -> Option<BackendValue> {
+        self.read().get(key).cloned()
+    }
+
+    pub fn set(&self, key: String, value: impl Into<BackendValue>) {
+        self.write().insert(key, value.into());
+    }
+}
+
+// expired keys are already invisible to readers, the reaper only reclaims their memory. it
+// also samples the command count for INFO. it holds a weak reference so that it stops once the
+// backend is dropped. it runs `hz` times per second, read again after every cycle
+async fn expire_reaper(inner: Weak<BackInner>) {
+    while let Some(inner) = inner.upgrade() {
+        inner.stats.sample_ops();
+        let hz = inner.config.read().hz.max(1);
+        Backend::from_inner(inner).active_expire_cycle();
+        tokio::time::sleep(Duration::from_secs(1) / hz).await;
+    }
+}
+
+// exits once the backend, and with it the sender, is dropped
+async fn lazy_free_worker(mut rx: UnboundedReceiver<Garbage>) {
+    while let Some(garbage) = rx.recv().await {
+        drop(garbage);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::BulkString;
+
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_active_expire_cycle() {
+        let backend = Backend::default();
+        let at = clock::now() + Duration::from_secs(1);
+        for i in 0..10 {
+            let key = format!("volatile{}", i);
+            backend.set(key.clone(), BulkString::new("value"));
+            backend.write().expire(&key, at);
+        }
+        backend.set("persistent".to_string(), BulkString::new("value"));
+        backend.set("later".to_string(), BulkString::new("value"));
+        backend
+            .write()
+            .expire("later", at + Duration::from_secs(10));
+
+        assert_eq!(backend.active_expire_cycle(), 0);
+
+        tokio::time::advance(Duration::from_secs(1)).await;
+        assert_eq!(backend.active_expire_cycle(), 10);
+        assert_eq!(backend.active_expire_cycle(), 0);
+        assert_eq!(backend.write().purge_expired(), 0);
+        assert_eq!(backend.read().len(), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_active_expire_cycle_samples_at_most_20_keys() {
+        let backend = Backend::default();
+        let at = clock::now() + Duration::from_secs(1);
+        for i in 0..50 {
+            let key = format!("volatile{}", i);
+            backend.set(key.clone(), BulkString::new("value"));
+            backend.write().expire(&key, at);
+        }
+
+        tokio::time::advance(Duration::from_secs(1)).await;
+        assert_eq!(backend.active_expire_cycle(), ACTIVE_EXPIRE_SAMPLE_SIZE);
+        assert_eq!(backend.active_expire_cycle(), ACTIVE_EXPIRE_SAMPLE_SIZE);
+        assert_eq!(backend.active_expire_cycle(), 10);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_active_expire_cycle_samples_keys_still_volatile() {
+        let backend = Backend::default();
+        let at = clock::now() + Duration::from_secs(1);
+        for i in 0..30 {
+            let key = format!("volatile{}", i);
+            backend.set(key.clone(), BulkString::new("value"));
+            backend.write().expire(&key, at);
+        }
+        // neither persisted nor overwritten keys are sampled any more
+        for i in 0..10 {
+            backend.write().persist(&format!("volatile{}", i));
+            backend.set(format!("volatile{}", i + 10), BulkString::new("value"));
+        }
+
+        tokio::time::advance(Duration::from_secs(1)).await;
+        assert_eq!(backend.active_expire_cycle(), 10);
+        assert_eq!(backend.read().len(), 20);
+        assert!(backend.read().sample_expired_keys(20).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_key_waiters() {
+        let backend = Backend::default();
+        let waiter = backend.key_waiter("list");
+        let other = backend.key_waiter("list");
+        assert!(Arc::ptr_eq(&waiter, &other));
+
+        let notified = waiter.notified();
+        backend.signal_key("list");
+        notified.await;
+
+        backend.release_key_waiter("list", waiter);
+        assert_eq!(backend.key_waiters.lock().len(), 1);
+        backend.release_key_waiter("list", other);
+        assert!(backend.key_waiters.lock().is_empty());
     }
 }
@@ -0,0 +1,22 @@
+use crate::NotificationConfig;
+
+use super::Backend;
+
+impl Backend {
+    /// publish a keyspace notification for `key` of the selected database, if the configured
+    /// notify-keyspace-events asks for events of `class`
+    pub fn notify_keyspace_event(&self, class: NotificationConfig, event: &str, key: &str) {
+        let config = self.config().notify_keyspace_events;
+        if !config.is_enabled(class) {
+            return;
+        }
+        if config.contains(NotificationConfig::KEYSPACE) {
+            let channel = format!("__keyspace@{}__:{}", self.db, key);
+            self.pubsub().publish(&channel, event.as_bytes());
+        }
+        if config.contains(NotificationConfig::KEYEVENT) {
+            let channel = format!("__keyevent@{}__:{}", self.db, event);
+            self.pubsub().publish(&channel, key.as_bytes());
+        }
+    }
+}
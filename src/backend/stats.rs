@@ -0,0 +1,165 @@
+use std::{
+    collections::VecDeque,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use parking_lot::Mutex;
+use tokio::time::Instant;
+
+// instantaneous_ops_per_sec averages over this many samples, one per reaper cycle
+const OPS_SAMPLES: usize = 16;
+
+/// counters reported by INFO, updated by the connection handlers
+#[derive(Debug)]
+pub struct Stats {
+    started_at: Instant,
+    total_connections_received: AtomicU64,
+    // turned away because maxclients was reached
+    rejected_connections: AtomicU64,
+    total_commands_processed: AtomicU64,
+    blocked_clients: AtomicU64,
+    // highest used memory seen
+    peak_memory: AtomicU64,
+    // (when, total_commands_processed) at the last few reaper cycles
+    ops_samples: Mutex<VecDeque<(Instant, u64)>>,
+}
+
+/// counts a client as blocked until dropped
+#[derive(Debug)]
+pub struct BlockedClient<'a>(&'a Stats);
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self {
+            started_at: Instant::now(),
+            total_connections_received: AtomicU64::new(0),
+            rejected_connections: AtomicU64::new(0),
+            total_commands_processed: AtomicU64::new(0),
+            blocked_clients: AtomicU64::new(0),
+            peak_memory: AtomicU64::new(0),
+            ops_samples: Mutex::new(VecDeque::with_capacity(OPS_SAMPLES)),
+        }
+    }
+}
+
+impl Stats {
+    pub fn uptime(&self) -> Duration {
+        self.started_at.elapsed()
+    }
+
+    pub fn connection_received(&self) {
+        self.total_connections_received
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn connection_rejected(&self) {
+        self.rejected_connections.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn command_processed(&self) {
+        self.total_commands_processed
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn blocked(&self) -> BlockedClient<'_> {
+        self.blocked_clients.fetch_add(1, Ordering::Relaxed);
+        BlockedClient(self)
+    }
+
+    pub fn total_connections_received(&self) -> u64 {
+        self.total_connections_received.load(Ordering::Relaxed)
+    }
+
+    pub fn rejected_connections(&self) -> u64 {
+        self.rejected_connections.load(Ordering::Relaxed)
+    }
+
+    pub fn total_commands_processed(&self) -> u64 {
+        self.total_commands_processed.load(Ordering::Relaxed)
+    }
+
+    pub fn blocked_clients(&self) -> u64 {
+        self.blocked_clients.load(Ordering::Relaxed)
+    }
+
+    /// remember `used` if it is the highest used memory so far, returns the peak
+    pub fn observe_memory(&self, used: usize) -> u64 {
+        let used = used as u64;
+        self.peak_memory
+            .fetch_max(used, Ordering::Relaxed)
+            .max(used)
+    }
+
+    /// zero the counters, as CONFIG RESETSTAT does. blocked clients are a gauge and stay
+    pub fn reset(&self) {
+        self.total_connections_received.store(0, Ordering::Relaxed);
+        self.rejected_connections.store(0, Ordering::Relaxed);
+        self.total_commands_processed.store(0, Ordering::Relaxed);
+        self.ops_samples.lock().clear();
+    }
+
+    /// remember the command count, called periodically
+    pub fn sample_ops(&self) {
+        let mut samples = self.ops_samples.lock();
+        if samples.len() == OPS_SAMPLES {
+            samples.pop_front();
+        }
+        samples.push_back((Instant::now(), self.total_commands_processed()));
+    }
+
+    /// commands per second over the recent samples
+    pub fn instantaneous_ops_per_sec(&self) -> u64 {
+        let samples = self.ops_samples.lock();
+        let (Some((first_at, first)), Some((last_at, last))) = (samples.front(), samples.back())
+        else {
+            return 0;
+        };
+        let elapsed = last_at.duration_since(*first_at).as_secs_f64();
+        if elapsed == 0.0 {
+            return 0;
+        }
+        (last.saturating_sub(*first) as f64 / elapsed).round() as u64
+    }
+}
+
+impl Drop for BlockedClient<'_> {
+    fn drop(&mut self) {
+        self.0.blocked_clients.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_instantaneous_ops_per_sec() {
+        let stats = Stats::default();
+        assert_eq!(stats.instantaneous_ops_per_sec(), 0);
+        for _ in 0..OPS_SAMPLES * 2 {
+            stats.sample_ops();
+            for _ in 0..5 {
+                stats.command_processed();
+            }
+            tokio::time::advance(Duration::from_millis(100)).await;
+        }
+        assert_eq!(stats.instantaneous_ops_per_sec(), 50);
+        assert_eq!(stats.total_commands_processed(), OPS_SAMPLES as u64 * 10);
+
+        stats.reset();
+        assert_eq!(stats.instantaneous_ops_per_sec(), 0);
+        assert_eq!(stats.total_commands_processed(), 0);
+    }
+
+    #[test]
+    fn test_blocked_clients() {
+        let stats = Stats::default();
+        let blocked = stats.blocked();
+        let other = stats.blocked();
+        assert_eq!(stats.blocked_clients(), 2);
+        drop(blocked);
+        drop(other);
+        assert_eq!(stats.blocked_clients(), 0);
+    }
+}
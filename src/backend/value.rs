@@ -0,0 +1,147 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    mem::size_of,
+};
+
+use crate::BulkString;
+
+use super::ZSet;
+
+// strings up to this length are allocated together with their object header
+const EMBSTR_MAX_LEN: usize = 44;
+// collections stay compact while they have at most this many elements of at most this size
+const LISTPACK_MAX_ENTRIES: usize = 128;
+const LISTPACK_MAX_VALUE_LEN: usize = 64;
+const INTSET_MAX_ENTRIES: usize = 512;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BackendValue {
+    String(BulkString),
+    List(VecDeque<Vec<u8>>),
+    Hash(HashMap<Vec<u8>, Vec<u8>>),
+    Set(HashSet<Vec<u8>>),
+    ZSet(ZSet),
+}
+
+impl BackendValue {
+    /// name of the data type as reported by the TYPE command
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            BackendValue::String(_) => "string",
+            BackendValue::List(_) => "list",
+            BackendValue::Hash(_) => "hash",
+            BackendValue::Set(_) => "set",
+            BackendValue::ZSet(_) => "zset",
+        }
+    }
+
+    /// name of the internal representation redis would use, as reported by OBJECT ENCODING
+    pub fn encoding(&self) -> &'static str {
+        match self {
+            BackendValue::String(s) => {
+                if parse_i64(s).is_some() {
+                    "int"
+                } else if s.len() <= EMBSTR_MAX_LEN {
+                    "embstr"
+                } else {
+                    "raw"
+                }
+            }
+            BackendValue::List(list) => {
+                if is_compact(list.len(), list.iter().map(|v| v.len())) {
+                    "listpack"
+                } else {
+                    "quicklist"
+                }
+            }
+            BackendValue::Hash(hash) => {
+                let sizes = hash.iter().flat_map(|(k, v)| [k.len(), v.len()]);
+                if is_compact(hash.len(), sizes) {
+                    "listpack"
+                } else {
+                    "hashtable"
+                }
+            }
+            BackendValue::Set(set) => {
+                if set.len() <= INTSET_MAX_ENTRIES && set.iter().all(|m| parse_i64(m).is_some()) {
+                    "intset"
+                } else if is_compact(set.len(), set.iter().map(|m| m.len())) {
+                    "listpack"
+                } else {
+                    "hashtable"
+                }
+            }
+            BackendValue::ZSet(zset) => {
+                if is_compact(zset.len(), zset.iter().map(|(m, _)| m.len())) {
+                    "listpack"
+                } else {
+                    "skiplist"
+                }
+            }
+        }
+    }
+
+    /// estimated bytes used by the value, its own size plus what it allocated. collections
+    /// measure `samples` of their elements and extrapolate, 0 measures every element
+    pub fn memory_usage(&self, samples: usize) -> usize {
+        let heap = match self {
+            BackendValue::String(s) => s.capacity(),
+            BackendValue::List(list) => {
+                list.capacity() * size_of::<Vec<u8>>()
+                    + sampled(list.len(), list.iter().map(|v| v.capacity()), samples)
+            }
+            BackendValue::Hash(hash) => {
+                hash_table_size(hash.capacity(), size_of::<(Vec<u8>, Vec<u8>)>())
+                    + sampled(
+                        hash.len(),
+                        hash.iter().map(|(k, v)| k.capacity() + v.capacity()),
+                        samples,
+                    )
+            }
+            BackendValue::Set(set) => {
+                hash_table_size(set.capacity(), size_of::<Vec<u8>>())
+                    + sampled(set.len(), set.iter().map(|m| m.capacity()), samples)
+            }
+            BackendValue::ZSet(zset) => zset.memory_usage(samples),
+        };
+        size_of::<BackendValue>() + heap
+    }
+}
+
+/// the heap used by `len` elements, extrapolated from the first `samples` of `sizes`, or from
+/// all of them if `samples` is 0
+pub(super) fn sampled(len: usize, sizes: impl Iterator<Item = usize>, samples: usize) -> usize {
+    let samples = if samples == 0 { len } else { samples.min(len) };
+    if samples == 0 {
+        return 0;
+    }
+    let measured: usize = sizes.take(samples).sum();
+    measured * len / samples
+}
+
+/// a hash table of `capacity` slots of `slot` bytes, each with a control byte
+pub(super) fn hash_table_size(capacity: usize, slot: usize) -> usize {
+    capacity * (slot + 1)
+}
+
+fn is_compact(len: usize, mut sizes: impl Iterator<Item = usize>) -> bool {
+    len <= LISTPACK_MAX_ENTRIES && sizes.all(|size| size <= LISTPACK_MAX_VALUE_LEN)
+}
+
+// only the canonical form counts, "+1" or "01" would not survive a round trip
+fn parse_i64(v: &[u8]) -> Option<i64> {
+    let n: i64 = std::str::from_utf8(v).ok()?.parse().ok()?;
+    (n.to_string().as_bytes() == v).then_some(n)
+}
+
+impl From<BulkString> for BackendValue {
+    fn from(s: BulkString) -> Self {
+        BackendValue::String(s)
+    }
+}
+
+impl From<ZSet> for BackendValue {
+    fn from(zset: ZSet) -> Self {
+        BackendValue::ZSet(zset)
+    }
+}
@@ -0,0 +1,308 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeSet, HashMap},
+    mem::size_of,
+};
+
+use super::value::{hash_table_size, sampled};
+
+// node bookkeeping per element of a B-tree, edges and lengths spread over its entries
+const BTREE_OVERHEAD: usize = 16;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Score(pub f64);
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ZSet {
+    scores: HashMap<Vec<u8>, f64>,
+    ordered: BTreeSet<(Score, Vec<u8>)>,
+}
+
+// score boundary of a range query, `(` prefix makes it exclusive
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScoreBound {
+    Inclusive(f64),
+    Exclusive(f64),
+}
+
+// lexicographic boundary of a range query: `[` inclusive, `(` exclusive, `-`/`+` infinity
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LexBound {
+    NegInf,
+    PosInf,
+    Inclusive(Vec<u8>),
+    Exclusive(Vec<u8>),
+}
+
+impl ZSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    /// estimated heap used, see `BackendValue::memory_usage`
+    pub fn memory_usage(&self, samples: usize) -> usize {
+        // members are held twice, by the score map and by the ordered index
+        let members = sampled(
+            self.len(),
+            self.scores.keys().map(|m| m.capacity()),
+            samples,
+        );
+        hash_table_size(self.scores.capacity(), size_of::<(Vec<u8>, f64)>())
+            + self.ordered.len() * (size_of::<(Score, Vec<u8>)>() + BTREE_OVERHEAD)
+            + 2 * members
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+
+    pub fn score(&self, member: &[u8]) -> Option<f64> {
+        self.scores.get(member).copied()
+    }
+
+    /// insert or update a member, returns true if the member is new
+    pub fn insert(&mut self, member: Vec<u8>, score: f64) -> bool {
+        // normalize -0.0 so that it sorts together with 0.0
+        let score = score + 0.0;
+        match self.scores.insert(member.clone(), score) {
+            Some(old) => {
+                self.ordered.remove(&(Score(old), member.clone()));
+                self.ordered.insert((Score(score), member));
+                false
+            }
+            None => {
+                self.ordered.insert((Score(score), member));
+                true
+            }
+        }
+    }
+
+    /// returns true if the member was present
+    pub fn remove(&mut self, member: &[u8]) -> bool {
+        match self.scores.remove(member) {
+            Some(score) => {
+                self.ordered.remove(&(Score(score), member.to_vec()));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// remove and return the member with the lowest score, ties broken by member
+    pub fn pop_min(&mut self) -> Option<(Vec<u8>, f64)> {
+        let (score, member) = self.ordered.pop_first()?;
+        self.scores.remove(&member);
+        Some((member, score.0))
+    }
+
+    /// remove and return the member with the highest score, ties broken by member
+    pub fn pop_max(&mut self) -> Option<(Vec<u8>, f64)> {
+        let (score, member) = self.ordered.pop_last()?;
+        self.scores.remove(&member);
+        Some((member, score.0))
+    }
+
+    /// 0-based position of the member in ascending score order. the BTreeSet keeps no
+    /// subtree sizes, so this walks every member ranked before it
+    pub fn rank(&self, member: &[u8]) -> Option<usize> {
+        let score = self.score(member)?;
+        Some(
+            self.ordered
+                .range(..(Score(score), member.to_vec()))
+                .count(),
+        )
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&[u8], f64)> {
+        self.ordered.iter().map(|(s, m)| (m.as_slice(), s.0))
+    }
+
+    pub fn range_by_score(
+        &self,
+        min: ScoreBound,
+        max: ScoreBound,
+    ) -> impl Iterator<Item = (&[u8], f64)> {
+        self.ordered
+            .range((Score(min.value()), Vec::new())..)
+            .skip_while(move |(s, _)| !min.allows_above(s.0))
+            .take_while(move |(s, _)| max.allows_below(s.0))
+            .map(|(s, m)| (m.as_slice(), s.0))
+    }
+
+    /// members are expected to share the same score, as in redis
+    pub fn range_by_lex<'a, 'b>(
+        &'a self,
+        min: &'b LexBound,
+        max: &'b LexBound,
+    ) -> impl Iterator<Item = (&'a [u8], f64)> + 'b
+    where
+        'a: 'b,
+    {
+        self.iter()
+            .skip_while(move |(m, _)| !min.allows_above(m))
+            .take_while(move |(m, _)| max.allows_below(m))
+    }
+}
+
+impl ScoreBound {
+    pub fn value(&self) -> f64 {
+        match self {
+            ScoreBound::Inclusive(v) | ScoreBound::Exclusive(v) => *v,
+        }
+    }
+
+    // whether a score satisfies this bound used as the minimum
+    fn allows_above(&self, score: f64) -> bool {
+        match self {
+            ScoreBound::Inclusive(v) => score >= *v,
+            ScoreBound::Exclusive(v) => score > *v,
+        }
+    }
+
+    // whether a score satisfies this bound used as the maximum
+    fn allows_below(&self, score: f64) -> bool {
+        match self {
+            ScoreBound::Inclusive(v) => score <= *v,
+            ScoreBound::Exclusive(v) => score < *v,
+        }
+    }
+}
+
+impl LexBound {
+    fn allows_above(&self, member: &[u8]) -> bool {
+        match self {
+            LexBound::NegInf => true,
+            LexBound::PosInf => false,
+            LexBound::Inclusive(v) => member >= v.as_slice(),
+            LexBound::Exclusive(v) => member > v.as_slice(),
+        }
+    }
+
+    fn allows_below(&self, member: &[u8]) -> bool {
+        match self {
+            LexBound::NegInf => false,
+            LexBound::PosInf => true,
+            LexBound::Inclusive(v) => member <= v.as_slice(),
+            LexBound::Exclusive(v) => member < v.as_slice(),
+        }
+    }
+}
+
+impl PartialEq for Score {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Score {}
+
+impl PartialOrd for Score {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Score {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn members<'a>(iter: impl Iterator<Item = (&'a [u8], f64)>) -> Vec<&'a [u8]> {
+        iter.map(|(m, _)| m).collect()
+    }
+
+    #[test]
+    fn test_zset_insert() {
+        let mut zset = ZSet::new();
+        assert!(zset.insert(b"a".to_vec(), 2.0));
+        assert!(zset.insert(b"b".to_vec(), 1.0));
+        assert!(!zset.insert(b"a".to_vec(), 0.5));
+        assert_eq!(zset.len(), 2);
+        assert_eq!(zset.score(b"a"), Some(0.5));
+        assert_eq!(members(zset.iter()), vec![b"a", b"b"]);
+    }
+
+    #[test]
+    fn test_zset_rank_and_remove() {
+        let mut zset = ZSet::new();
+        zset.insert(b"c".to_vec(), 3.0);
+        zset.insert(b"a".to_vec(), 1.0);
+        zset.insert(b"b".to_vec(), 1.0);
+        assert_eq!(zset.rank(b"a"), Some(0));
+        assert_eq!(zset.rank(b"b"), Some(1));
+        assert_eq!(zset.rank(b"c"), Some(2));
+        assert_eq!(zset.rank(b"z"), None);
+
+        assert!(zset.remove(b"a"));
+        assert!(!zset.remove(b"a"));
+        assert_eq!(zset.len(), 2);
+        assert_eq!(zset.rank(b"c"), Some(1));
+        assert_eq!(members(zset.iter()), vec![b"b", b"c"]);
+    }
+
+    #[test]
+    fn test_zset_pop() {
+        let mut zset = ZSet::new();
+        zset.insert(b"b".to_vec(), 2.0);
+        zset.insert(b"a".to_vec(), 1.0);
+        zset.insert(b"c".to_vec(), 3.0);
+        assert_eq!(zset.pop_min(), Some((b"a".to_vec(), 1.0)));
+        assert_eq!(zset.pop_max(), Some((b"c".to_vec(), 3.0)));
+        assert_eq!(zset.len(), 1);
+        assert_eq!(zset.score(b"c"), None);
+        assert_eq!(zset.pop_max(), Some((b"b".to_vec(), 2.0)));
+        assert_eq!(zset.pop_min(), None);
+    }
+
+    #[test]
+    fn test_zset_range_by_score() {
+        let mut zset = ZSet::new();
+        zset.insert(b"one".to_vec(), 1.0);
+        zset.insert(b"two".to_vec(), 2.0);
+        zset.insert(b"three".to_vec(), 3.0);
+
+        let ret = zset.range_by_score(ScoreBound::Exclusive(1.0), ScoreBound::Inclusive(3.0));
+        assert_eq!(members(ret), vec![&b"two"[..], b"three"]);
+
+        let ret = zset.range_by_score(ScoreBound::Inclusive(1.0), ScoreBound::Exclusive(3.0));
+        assert_eq!(members(ret), vec![&b"one"[..], b"two"]);
+
+        let ret = zset.range_by_score(
+            ScoreBound::Inclusive(f64::NEG_INFINITY),
+            ScoreBound::Inclusive(f64::INFINITY),
+        );
+        assert_eq!(ret.count(), 3);
+    }
+
+    #[test]
+    fn test_zset_range_by_lex() {
+        let mut zset = ZSet::new();
+        for m in ["a", "b", "c", "d", "e"] {
+            zset.insert(m.as_bytes().to_vec(), 0.0);
+        }
+
+        let (min, max) = (
+            LexBound::Inclusive(b"b".to_vec()),
+            LexBound::Exclusive(b"d".to_vec()),
+        );
+        assert_eq!(members(zset.range_by_lex(&min, &max)), vec![b"b", b"c"]);
+
+        let (min, max) = (LexBound::Exclusive(b"c".to_vec()), LexBound::PosInf);
+        assert_eq!(members(zset.range_by_lex(&min, &max)), vec![b"d", b"e"]);
+
+        let (min, max) = (LexBound::NegInf, LexBound::Inclusive(b"a".to_vec()));
+        assert_eq!(members(zset.range_by_lex(&min, &max)), vec![b"a"]);
+
+        let (min, max) = (LexBound::PosInf, LexBound::NegInf);
+        assert_eq!(zset.range_by_lex(&min, &max).count(), 0);
+    }
+}
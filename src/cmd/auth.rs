@@ -0,0 +1,143 @@
+use crate::{network::ConnectionState, Backend, RespArray, RespFrame, SimpleError};
+
+use super::{
+    extract_args, extract_string, validate_command, CommandError, CommandExecutor, RESP_OK,
+};
+
+/// AUTH [username] password, only the `default` user exists
+#[derive(Debug)]
+pub struct Auth {
+    pub username: Option<String>,
+    pub password: String,
+}
+
+impl Auth {
+    fn check(&self, backend: &Backend) -> Result<(), RespFrame> {
+        let config = backend.config();
+        let Some(requirepass) = &config.requirepass else {
+            return Err(SimpleError::new(
+                "ERR AUTH <password> called without any password configured for the default \
+                 user. Are you sure your configuration is correct?",
+            )
+            .into());
+        };
+        let user_ok = self.username.as_deref().is_none_or(|u| u == "default");
+        if !user_ok || !secure_eq(self.password.as_bytes(), requirepass.as_bytes()) {
+            return Err(SimpleError::new(
+                "WRONGPASS invalid username-password pair or user is disabled.",
+            )
+            .into());
+        }
+        Ok(())
+    }
+}
+
+// compare without returning early, so that the time taken doesn't tell how much of the
+// password matched
+fn secure_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+// without a connection there is nobody to authenticate, this only checks the password
+impl CommandExecutor for Auth {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match self.check(backend) {
+            Ok(()) => RESP_OK.clone(),
+            Err(e) => e,
+        }
+    }
+
+    fn execute_on(self, backend: &Backend, conn: &mut ConnectionState) -> RespFrame {
+        match self.check(backend) {
+            Ok(()) => {
+                conn.authenticated = true;
+                RESP_OK.clone()
+            }
+            Err(e) => e,
+        }
+    }
+}
+
+impl TryFrom<RespArray> for Auth {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let with_username = validate_command(&value, &["auth"], 1)
+            .map(|_| false)
+            .or_else(|_| validate_command(&value, &["auth"], 2).map(|_| true))?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let username = if with_username {
+            Some(extract_string(args.next())?)
+        } else {
+            None
+        };
+        Ok(Auth {
+            username,
+            password: extract_string(args.next())?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    fn auth(username: Option<&str>, password: &str) -> Auth {
+        Auth {
+            username: username.map(|u| u.to_string()),
+            password: password.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_auth_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*2\r\n$4\r\nauth\r\n$6\r\nsecret\r\n");
+        let cmd: Auth = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.username, None);
+        assert_eq!(cmd.password, "secret");
+
+        let mut buf = BytesMut::from("*3\r\n$4\r\nauth\r\n$7\r\ndefault\r\n$6\r\nsecret\r\n");
+        let cmd: Auth = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.username.as_deref(), Some("default"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_auth_without_password_configured() {
+        let backend = Backend::new();
+        let mut conn = ConnectionState::new();
+        assert!(matches!(
+            auth(None, "secret").execute_on(&backend, &mut conn),
+            RespFrame::Error(_)
+        ));
+        assert!(!conn.authenticated);
+    }
+
+    #[test]
+    fn test_auth() {
+        let backend = Backend::new();
+        backend.config_mut().requirepass = Some("secret".to_string());
+        let mut conn = ConnectionState::new();
+
+        for cmd in [
+            auth(None, "wrong"),
+            auth(None, "secre"),
+            auth(Some("admin"), "secret"),
+        ] {
+            let ret = cmd.execute_on(&backend, &mut conn);
+            assert!(matches!(ret, RespFrame::Error(e) if e.starts_with("WRONGPASS")));
+            assert!(!conn.authenticated);
+        }
+
+        assert_eq!(
+            auth(Some("default"), "secret").execute_on(&backend, &mut conn),
+            RESP_OK.clone()
+        );
+        assert!(conn.authenticated);
+    }
+}
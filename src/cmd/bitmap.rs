@@ -0,0 +1,614 @@
+use crate::{Backend, BackendValue, BulkString, Db, RespArray, RespFrame};
+
+use super::{
+    extract_args, extract_string, get_string, getrange::byte_range, parse_number,
+    setrange::MAX_STRING_SIZE, validate_command, validate_variadic_command, CommandError,
+    CommandExecutor, RESP_WRONGTYPE,
+};
+
+/// GETBIT key offset, bits are numbered from the most significant bit of the first byte
+#[derive(Debug)]
+pub struct GetBit {
+    pub key: String,
+    pub offset: usize,
+}
+
+/// SETBIT key offset value, replies with the previous bit and grows the string with zeroes
+#[derive(Debug)]
+pub struct SetBit {
+    pub key: String,
+    pub offset: usize,
+    pub value: bool,
+}
+
+/// BITCOUNT key [start end [BYTE|BIT]]
+#[derive(Debug)]
+pub struct BitCount {
+    pub key: String,
+    pub range: Option<(i64, i64, BitUnit)>,
+}
+
+/// BITPOS key bit [start [end [BYTE|BIT]]]
+#[derive(Debug)]
+pub struct BitPos {
+    pub key: String,
+    pub bit: bool,
+    pub start: i64,
+    pub end: Option<i64>,
+    pub unit: BitUnit,
+}
+
+/// BITOP AND|OR|XOR|NOT destkey srckey [srckey ...], replies with the length of the result
+#[derive(Debug)]
+pub struct BitOp {
+    pub op: BitOperation,
+    pub dst: String,
+    pub keys: Vec<String>,
+}
+
+/// what the start and end of BITCOUNT and BITPOS count in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BitUnit {
+    #[default]
+    Byte,
+    Bit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitOperation {
+    And,
+    Or,
+    Xor,
+    Not,
+}
+
+impl CommandExecutor for GetBit {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match get_string(backend, &self.key) {
+            Ok(Some(value)) => RespFrame::Integer(get_bit(&value, self.offset) as i64),
+            Ok(None) => RespFrame::Integer(0),
+            Err(e) => e,
+        }
+    }
+}
+
+impl CommandExecutor for SetBit {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let mut db = backend.write();
+        let s = match db.get_or_insert_with(self.key.clone(), || BulkString::new(vec![]).into()) {
+            BackendValue::String(s) => s,
+            _ => return RESP_WRONGTYPE.clone(),
+        };
+        let byte = self.offset / 8;
+        if s.0.len() <= byte {
+            s.0.resize(byte + 1, 0);
+        }
+        let old = get_bit(&s.0, self.offset);
+        let mask = 0x80 >> (self.offset % 8);
+        if self.value {
+            s.0[byte] |= mask;
+        } else {
+            s.0[byte] &= !mask;
+        }
+        db.signal_modified(&self.key);
+        RespFrame::Integer(old as i64)
+    }
+}
+
+impl CommandExecutor for BitCount {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let value = match get_string(backend, &self.key) {
+            Ok(Some(value)) => value,
+            Ok(None) => return RespFrame::Integer(0),
+            Err(e) => return e,
+        };
+        let (start, end, unit) = self.range.unwrap_or((0, -1, BitUnit::Byte));
+        match bit_range(value.len(), start, end, unit) {
+            Some((start, end)) => RespFrame::Integer(count_bits(&value, start, end) as i64),
+            None => RespFrame::Integer(0),
+        }
+    }
+}
+
+impl CommandExecutor for BitPos {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let value = match get_string(backend, &self.key) {
+            Ok(Some(value)) => value,
+            // a missing key is an empty string, with clear bits all the way
+            Ok(None) => return RespFrame::Integer(if self.bit { -1 } else { 0 }),
+            Err(e) => return e,
+        };
+        let Some((start, end)) =
+            bit_range(value.len(), self.start, self.end.unwrap_or(-1), self.unit)
+        else {
+            return RespFrame::Integer(-1);
+        };
+        match first_bit(&value, self.bit, start, end) {
+            Some(pos) => RespFrame::Integer(pos as i64),
+            // without an end the string counts as padded with clear bits
+            None if !self.bit && self.end.is_none() => RespFrame::Integer(end as i64 + 1),
+            None => RespFrame::Integer(-1),
+        }
+    }
+}
+
+impl CommandExecutor for BitOp {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        // one write lock covers reading the sources and storing the result
+        let mut db = backend.write();
+        let sources = match sources(&db, &self.keys) {
+            Ok(sources) => sources,
+            Err(e) => return e,
+        };
+        // shorter strings are padded with zeroes
+        let len = sources.iter().map(|s| s.len()).max().unwrap_or(0);
+        let byte = |s: &[u8], i: usize| s.get(i).copied().unwrap_or(0);
+        let result: Vec<u8> = (0..len)
+            .map(|i| {
+                let mut bytes = sources.iter().map(|s| byte(s, i));
+                let first = bytes.next().unwrap_or(0);
+                match self.op {
+                    BitOperation::And => bytes.fold(first, |acc, b| acc & b),
+                    BitOperation::Or => bytes.fold(first, |acc, b| acc | b),
+                    BitOperation::Xor => bytes.fold(first, |acc, b| acc ^ b),
+                    BitOperation::Not => !first,
+                }
+            })
+            .collect();
+        // an empty result deletes the destination, like any empty string produced by a command
+        if result.is_empty() {
+            db.remove(&self.dst);
+        } else {
+            db.insert(self.dst, BulkString::new(result).into());
+        }
+        RespFrame::Integer(len as i64)
+    }
+}
+
+// missing keys count as empty strings
+fn sources(db: &Db, keys: &[String]) -> Result<Vec<Vec<u8>>, RespFrame> {
+    keys.iter()
+        .map(|key| match db.get(key) {
+            Some(BackendValue::String(s)) => Ok(s.0.clone()),
+            Some(_) => Err(RESP_WRONGTYPE.clone()),
+            None => Ok(vec![]),
+        })
+        .collect()
+}
+
+fn get_bit(value: &[u8], offset: usize) -> bool {
+    value
+        .get(offset / 8)
+        .is_some_and(|byte| byte & (0x80 >> (offset % 8)) != 0)
+}
+
+// an inclusive range of bits within a string of `len` bytes
+fn bit_range(len: usize, start: i64, end: i64, unit: BitUnit) -> Option<(usize, usize)> {
+    match unit {
+        BitUnit::Byte => byte_range(len, start, end).map(|(start, end)| (start * 8, end * 8 + 7)),
+        BitUnit::Bit => byte_range(len * 8, start, end),
+    }
+}
+
+// set bits between `start` and `end` inclusive, whole bytes at a time
+fn count_bits(value: &[u8], start: usize, end: usize) -> usize {
+    let (first, last) = (start / 8, end / 8);
+    let head = 0xffu8 >> (start % 8);
+    let tail = 0xffu8 << (7 - end % 8);
+    if first == last {
+        return (value[first] & head & tail).count_ones() as usize;
+    }
+    let middle: usize = value[first + 1..last]
+        .iter()
+        .map(|byte| byte.count_ones() as usize)
+        .sum();
+    (value[first] & head).count_ones() as usize
+        + middle
+        + (value[last] & tail).count_ones() as usize
+}
+
+// the first bit equal to `bit` between `start` and `end` inclusive
+fn first_bit(value: &[u8], bit: bool, start: usize, end: usize) -> Option<usize> {
+    // bytes that can't hold the bit are skipped whole
+    let skip = if bit { 0x00 } else { 0xff };
+    let mut pos = start;
+    while pos <= end {
+        if pos.is_multiple_of(8) && pos + 7 <= end && value[pos / 8] == skip {
+            pos += 8;
+            continue;
+        }
+        if get_bit(value, pos) == bit {
+            return Some(pos);
+        }
+        pos += 1;
+    }
+    None
+}
+
+fn parse_offset(frame: Option<RespFrame>) -> Result<usize, CommandError> {
+    parse_number::<usize>(frame)
+        .ok()
+        .filter(|offset| *offset < MAX_STRING_SIZE * 8)
+        .ok_or_else(|| {
+            CommandError::InvalidArgument(
+                "bit offset is not an integer or out of range".to_string(),
+            )
+        })
+}
+
+fn parse_bit(frame: Option<RespFrame>) -> Result<bool, CommandError> {
+    match extract_string(frame)?.as_str() {
+        "0" => Ok(false),
+        "1" => Ok(true),
+        _ => Err(CommandError::InvalidArgument(
+            "The bit argument must be 1 or 0.".to_string(),
+        )),
+    }
+}
+
+fn parse_unit(frame: Option<RespFrame>) -> Result<BitUnit, CommandError> {
+    match extract_string(frame)?.to_ascii_lowercase().as_str() {
+        "byte" => Ok(BitUnit::Byte),
+        "bit" => Ok(BitUnit::Bit),
+        _ => Err(CommandError::InvalidArgument("syntax error".to_string())),
+    }
+}
+
+impl TryFrom<RespArray> for GetBit {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["getbit"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(GetBit {
+            key: extract_string(args.next())?,
+            offset: parse_offset(args.next())?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for SetBit {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["setbit"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = extract_string(args.next())?;
+        let offset = parse_offset(args.next())?;
+        let value = parse_bit(args.next()).map_err(|_| {
+            CommandError::InvalidArgument("bit is not an integer or out of range".to_string())
+        })?;
+        Ok(SetBit { key, offset, value })
+    }
+}
+
+impl TryFrom<RespArray> for BitCount {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["bitcount"], 1)?;
+        // a start needs an end
+        if !matches!(value.len(), 2 | 4 | 5) {
+            return Err(CommandError::InvalidArgument("syntax error".to_string()));
+        }
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = extract_string(args.next())?;
+        let range = match args.next() {
+            Some(start) => {
+                let start = parse_number(Some(start))?;
+                let end = parse_number(args.next())?;
+                let unit = match args.next() {
+                    Some(unit) => parse_unit(Some(unit))?,
+                    None => BitUnit::Byte,
+                };
+                Some((start, end, unit))
+            }
+            None => None,
+        };
+        Ok(BitCount { key, range })
+    }
+}
+
+impl TryFrom<RespArray> for BitPos {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["bitpos"], 2)?;
+        if value.len() > 6 {
+            return Err(CommandError::InvalidArgument("syntax error".to_string()));
+        }
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let mut cmd = BitPos {
+            key: extract_string(args.next())?,
+            bit: parse_bit(args.next())?,
+            start: 0,
+            end: None,
+            unit: BitUnit::Byte,
+        };
+        if let Some(start) = args.next() {
+            cmd.start = parse_number(Some(start))?;
+        }
+        if let Some(end) = args.next() {
+            cmd.end = Some(parse_number(Some(end))?);
+        }
+        if let Some(unit) = args.next() {
+            cmd.unit = parse_unit(Some(unit))?;
+        }
+        Ok(cmd)
+    }
+}
+
+impl TryFrom<RespArray> for BitOp {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["bitop"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let op = match extract_string(args.next())?.to_ascii_lowercase().as_str() {
+            "and" => BitOperation::And,
+            "or" => BitOperation::Or,
+            "xor" => BitOperation::Xor,
+            "not" => BitOperation::Not,
+            _ => return Err(CommandError::InvalidArgument("syntax error".to_string())),
+        };
+        let dst = extract_string(args.next())?;
+        let keys: Vec<String> = args
+            .map(|arg| extract_string(Some(arg)))
+            .collect::<Result<_, _>>()?;
+        if op == BitOperation::Not && keys.len() != 1 {
+            return Err(CommandError::InvalidArgument(
+                "BITOP NOT must be called with a single source key.".to_string(),
+            ));
+        }
+        Ok(BitOp { op, dst, keys })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{clock, RespDecode};
+
+    use super::*;
+
+    fn setbit(backend: &Backend, key: &str, offset: usize, value: bool) -> RespFrame {
+        SetBit {
+            key: key.to_string(),
+            offset,
+            value,
+        }
+        .execute(backend)
+    }
+
+    fn bitcount(backend: &Backend, key: &str, range: Option<(i64, i64, BitUnit)>) -> RespFrame {
+        BitCount {
+            key: key.to_string(),
+            range,
+        }
+        .execute(backend)
+    }
+
+    fn bitpos(
+        backend: &Backend,
+        bit: bool,
+        start: i64,
+        end: Option<i64>,
+        unit: BitUnit,
+    ) -> RespFrame {
+        BitPos {
+            key: "key".to_string(),
+            bit,
+            start,
+            end,
+            unit,
+        }
+        .execute(backend)
+    }
+
+    fn bitop(backend: &Backend, op: BitOperation, dst: &str, keys: &[&str]) -> RespFrame {
+        BitOp {
+            op,
+            dst: dst.to_string(),
+            keys: keys.iter().map(|k| k.to_string()).collect(),
+        }
+        .execute(backend)
+    }
+
+    fn decode<T: TryFrom<RespArray, Error = CommandError>>(args: &[&str]) -> Result<T> {
+        let mut buf = BytesMut::from(format!("*{}\r\n", args.len()).as_str());
+        for arg in args {
+            buf.extend_from_slice(format!("${}\r\n{}\r\n", arg.len(), arg).as_bytes());
+        }
+        Ok(RespArray::decode(&mut buf)?.try_into()?)
+    }
+
+    #[test]
+    fn test_bitmap_try_from_resp_array() -> Result<()> {
+        let cmd: SetBit = decode(&["setbit", "key", "7", "1"])?;
+        assert_eq!((cmd.key.as_str(), cmd.offset, cmd.value), ("key", 7, true));
+        assert!(decode::<SetBit>(&["setbit", "key", "7", "2"]).is_err());
+        assert!(decode::<GetBit>(&["getbit", "key", "-1"]).is_err());
+        assert!(decode::<GetBit>(&["getbit", "key", "4294967296"]).is_err());
+
+        let cmd: BitCount = decode(&["bitcount", "key", "1", "-1", "BIT"])?;
+        assert_eq!(cmd.range, Some((1, -1, BitUnit::Bit)));
+        assert!(decode::<BitCount>(&["bitcount", "key", "1"]).is_err());
+        assert!(decode::<BitCount>(&["bitcount", "key", "1", "2", "word"]).is_err());
+
+        let cmd: BitPos = decode(&["bitpos", "key", "0", "2"])?;
+        assert_eq!((cmd.bit, cmd.start, cmd.end), (false, 2, None));
+        assert!(decode::<BitPos>(&["bitpos", "key", "2"]).is_err());
+
+        let cmd: BitOp = decode(&["bitop", "XOR", "dst", "a", "b"])?;
+        assert_eq!(cmd.op, BitOperation::Xor);
+        assert_eq!(cmd.keys, vec!["a".to_string(), "b".to_string()]);
+        assert!(decode::<BitOp>(&["bitop", "not", "dst", "a", "b"]).is_err());
+        assert!(decode::<BitOp>(&["bitop", "nand", "dst", "a"]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_setbit_and_getbit() {
+        let backend = Backend::new();
+        let getbit = |offset| {
+            GetBit {
+                key: "key".to_string(),
+                offset,
+            }
+            .execute(&backend)
+        };
+        assert_eq!(getbit(0), RespFrame::Integer(0));
+
+        assert_eq!(setbit(&backend, "key", 1, true), RespFrame::Integer(0));
+        assert_eq!(setbit(&backend, "key", 1, true), RespFrame::Integer(1));
+        assert_eq!(setbit(&backend, "key", 18, true), RespFrame::Integer(0));
+        // bits are big-endian within each byte, the string grows with zeroes
+        assert_eq!(
+            backend.get("key"),
+            Some(BulkString::new(vec![0b0100_0000, 0, 0b0010_0000]).into())
+        );
+        assert_eq!(getbit(1), RespFrame::Integer(1));
+        assert_eq!(getbit(2), RespFrame::Integer(0));
+        assert_eq!(getbit(100), RespFrame::Integer(0));
+
+        assert_eq!(setbit(&backend, "key", 1, false), RespFrame::Integer(1));
+        assert_eq!(getbit(1), RespFrame::Integer(0));
+
+        backend
+            .write()
+            .insert("set".to_string(), BackendValue::Set([b"a".to_vec()].into()));
+        assert_eq!(setbit(&backend, "set", 0, true), RESP_WRONGTYPE.clone());
+    }
+
+    #[test]
+    fn test_bitcount_byte_and_bit_ranges() {
+        let backend = Backend::new();
+        backend.set("key".to_string(), BulkString::new("foobar"));
+        assert_eq!(bitcount(&backend, "key", None), RespFrame::Integer(26));
+        assert_eq!(
+            bitcount(&backend, "key", Some((0, 0, BitUnit::Byte))),
+            RespFrame::Integer(4)
+        );
+        assert_eq!(
+            bitcount(&backend, "key", Some((1, 1, BitUnit::Byte))),
+            RespFrame::Integer(6)
+        );
+        assert_eq!(
+            bitcount(&backend, "key", Some((-2, -1, BitUnit::Byte))),
+            RespFrame::Integer(7)
+        );
+        // 'o' is 0b01101111, bits 5 to 30 cross four bytes
+        assert_eq!(
+            bitcount(&backend, "key", Some((5, 30, BitUnit::Bit))),
+            RespFrame::Integer(17)
+        );
+        assert_eq!(
+            bitcount(&backend, "key", Some((9, 11, BitUnit::Bit))),
+            RespFrame::Integer(2)
+        );
+        assert_eq!(
+            bitcount(&backend, "key", Some((5, 2, BitUnit::Bit))),
+            RespFrame::Integer(0)
+        );
+        assert_eq!(bitcount(&backend, "missing", None), RespFrame::Integer(0));
+    }
+
+    #[test]
+    fn test_bitpos_byte_and_bit_ranges() {
+        let backend = Backend::new();
+        assert_eq!(
+            bitpos(&backend, true, 0, None, BitUnit::Byte),
+            RespFrame::Integer(-1)
+        );
+        assert_eq!(
+            bitpos(&backend, false, 0, None, BitUnit::Byte),
+            RespFrame::Integer(0)
+        );
+
+        backend.set("key".to_string(), BulkString::new(vec![0xff, 0xf0, 0x00]));
+        assert_eq!(
+            bitpos(&backend, false, 0, None, BitUnit::Byte),
+            RespFrame::Integer(12)
+        );
+        assert_eq!(
+            bitpos(&backend, true, 1, None, BitUnit::Byte),
+            RespFrame::Integer(8)
+        );
+        assert_eq!(
+            bitpos(&backend, true, 2, Some(-1), BitUnit::Byte),
+            RespFrame::Integer(-1)
+        );
+        assert_eq!(
+            bitpos(&backend, true, 10, Some(15), BitUnit::Bit),
+            RespFrame::Integer(10)
+        );
+        assert_eq!(
+            bitpos(&backend, false, 3, Some(11), BitUnit::Bit),
+            RespFrame::Integer(-1)
+        );
+
+        // the string counts as padded with clear bits only without an end
+        backend.set("key".to_string(), BulkString::new(vec![0xff, 0xff]));
+        assert_eq!(
+            bitpos(&backend, false, 0, None, BitUnit::Byte),
+            RespFrame::Integer(16)
+        );
+        assert_eq!(
+            bitpos(&backend, false, 0, Some(-1), BitUnit::Byte),
+            RespFrame::Integer(-1)
+        );
+        assert_eq!(
+            bitpos(&backend, false, 3, None, BitUnit::Bit),
+            RespFrame::Integer(16)
+        );
+    }
+
+    #[test]
+    fn test_bitop() {
+        let backend = Backend::new();
+        backend.set("a".to_string(), BulkString::new(vec![0b1100, 0xff]));
+        backend.set("b".to_string(), BulkString::new(vec![0b1010]));
+
+        assert_eq!(
+            bitop(&backend, BitOperation::And, "dst", &["a", "b"]),
+            RespFrame::Integer(2)
+        );
+        assert_eq!(
+            backend.get("dst"),
+            Some(BulkString::new(vec![0b1000, 0]).into())
+        );
+        bitop(&backend, BitOperation::Or, "dst", &["a", "b", "missing"]);
+        assert_eq!(
+            backend.get("dst"),
+            Some(BulkString::new(vec![0b1110, 0xff]).into())
+        );
+        bitop(&backend, BitOperation::Xor, "dst", &["a", "b"]);
+        assert_eq!(
+            backend.get("dst"),
+            Some(BulkString::new(vec![0b0110, 0xff]).into())
+        );
+        bitop(&backend, BitOperation::Not, "dst", &["b"]);
+        assert_eq!(
+            backend.get("dst"),
+            Some(BulkString::new(vec![!0b1010]).into())
+        );
+
+        // the result replaces the destination, expiry included
+        backend
+            .write()
+            .expire("dst", clock::now() + Duration::from_secs(100));
+        bitop(&backend, BitOperation::Not, "dst", &["a"]);
+        assert!(backend.read().peek("dst").unwrap().expires_at.is_none());
+
+        // only missing sources delete the destination
+        assert_eq!(
+            bitop(&backend, BitOperation::Or, "dst", &["missing"]),
+            RespFrame::Integer(0)
+        );
+        assert!(!backend.read().contains_key("dst"));
+    }
+}
@@ -0,0 +1,213 @@
+use std::time::Duration;
+
+use crate::{Backend, BulkString, RespArray, RespFrame, RespNullBulkString};
+
+use super::{
+    blpop::{block_on_keys, parse_timeout},
+    extract_args, extract_string,
+    lmove::{move_element, parse_side},
+    validate_command, CommandError, CommandExecutor,
+};
+
+#[derive(Debug)]
+pub struct BLMove {
+    pub src: String,
+    pub dst: String,
+    // LEFT: pop from the head of `src` instead of the tail
+    pub from_left: bool,
+    // LEFT: push to the head of `dst` instead of the tail
+    pub to_left: bool,
+    // None blocks forever
+    pub timeout: Option<Duration>,
+}
+
+impl CommandExecutor for BLMove {
+    // like LMOVE when there is no connection to block
+    fn execute(self, backend: &Backend) -> RespFrame {
+        self.try_move(backend)
+            .unwrap_or_else(|| RespNullBulkString.into())
+    }
+}
+
+impl BLMove {
+    /// move an element once `src` is non-empty, or give up when the timeout expires
+    pub async fn execute_blocking(self, backend: &Backend, raw: Option<RespFrame>) -> RespFrame {
+        let keys = [self.src.clone()];
+        block_on_keys(backend, &keys, self.timeout, raw, || self.try_move(backend))
+            .await
+            .unwrap_or_else(|| RespNullBulkString.into())
+    }
+
+    // the moved element, None if `src` is empty. the lock is only taken for the move itself
+    fn try_move(&self, backend: &Backend) -> Option<RespFrame> {
+        let mut db = backend.write();
+        match move_element(&mut db, &self.src, &self.dst, self.from_left, self.to_left) {
+            Ok(Some(element)) => {
+                backend.signal_key(&self.dst);
+                Some(BulkString::new(element).into())
+            }
+            Ok(None) => None,
+            Err(e) => Some(e),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for BLMove {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["blmove"], 5)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(BLMove {
+            src: extract_string(args.next())?,
+            dst: extract_string(args.next())?,
+            from_left: parse_side(extract_string(args.next())?)?,
+            to_left: parse_side(extract_string(args.next())?)?,
+            timeout: parse_timeout(args.next())?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{
+        cmd::{BPop, LRange, Push},
+        RespDecode,
+    };
+
+    use super::*;
+
+    fn push(backend: &Backend, key: &str, elements: &[&str]) {
+        let cmd = Push {
+            key: key.to_string(),
+            elements: elements.iter().map(|e| e.as_bytes().to_vec()).collect(),
+            left: false,
+        };
+        cmd.execute(backend);
+    }
+
+    fn blmove(src: &str, dst: &str, timeout: Option<Duration>) -> BLMove {
+        BLMove {
+            src: src.to_string(),
+            dst: dst.to_string(),
+            from_left: true,
+            to_left: false,
+            timeout,
+        }
+    }
+
+    fn contents(backend: &Backend, key: &str) -> RespFrame {
+        LRange {
+            key: key.to_string(),
+            start: 0,
+            stop: -1,
+        }
+        .execute(backend)
+    }
+
+    #[test]
+    fn test_blmove_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from(
+            "*6\r\n$6\r\nblmove\r\n$1\r\na\r\n$1\r\nb\r\n$5\r\nRIGHT\r\n$4\r\nLEFT\r\n$3\r\n0.1\r\n",
+        );
+        let cmd: BLMove = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.src, "a");
+        assert_eq!(cmd.dst, "b");
+        assert!(!cmd.from_left);
+        assert!(cmd.to_left);
+        assert_eq!(cmd.timeout, Some(Duration::from_millis(100)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_blmove_ready_source() {
+        let backend = Backend::new();
+        push(&backend, "src", &["a", "b"]);
+
+        let ret = blmove("src", "dst", None)
+            .execute_blocking(&backend, None)
+            .await;
+        assert_eq!(ret, BulkString::new("a").into());
+        assert_eq!(
+            contents(&backend, "dst"),
+            RespArray::new(vec![BulkString::new("a").into()]).into()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_blmove_wakes_on_push() {
+        let backend = Backend::new();
+        let blocked = tokio::spawn({
+            let backend = backend.clone();
+            async move {
+                blmove("src", "dst", None)
+                    .execute_blocking(&backend, None)
+                    .await
+            }
+        });
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        // the backend is not locked while the move waits
+        assert_eq!(backend.get("dst"), None);
+        push(&backend, "src", &["x"]);
+
+        assert_eq!(blocked.await.unwrap(), BulkString::new("x").into());
+        assert_eq!(backend.get("src"), None);
+        assert_eq!(
+            contents(&backend, "dst"),
+            RespArray::new(vec![BulkString::new("x").into()]).into()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_blmove_chain_wakes_next_client() {
+        // a client blocked on the destination is woken by the move
+        let backend = Backend::new();
+        let popper = tokio::spawn({
+            let backend = backend.clone();
+            async move {
+                BPop {
+                    keys: vec!["dst".to_string()],
+                    left: true,
+                    timeout: Some(Duration::from_secs(5)),
+                }
+                .execute_blocking(&backend, None)
+                .await
+            }
+        });
+        let mover = tokio::spawn({
+            let backend = backend.clone();
+            async move {
+                blmove("src", "dst", Some(Duration::from_secs(5)))
+                    .execute_blocking(&backend, None)
+                    .await
+            }
+        });
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        push(&backend, "src", &["x"]);
+
+        assert_eq!(mover.await.unwrap(), BulkString::new("x").into());
+        assert_eq!(
+            popper.await.unwrap(),
+            RespArray::new(vec![
+                BulkString::new("dst").into(),
+                BulkString::new("x").into(),
+            ])
+            .into()
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_blmove_timeout() {
+        let backend = Backend::new();
+        let ret = blmove("src", "dst", Some(Duration::from_secs(1)))
+            .execute_blocking(&backend, None)
+            .await;
+        assert_eq!(ret, RespNullBulkString.into());
+        assert_eq!(backend.get("dst"), None);
+    }
+}
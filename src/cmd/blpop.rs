@@ -0,0 +1,268 @@
+use std::time::Duration;
+
+use futures::future::select_all;
+use tokio::time::Instant;
+
+use crate::{network::propagate, Backend, BulkString, RespArray, RespFrame, RespNullArray};
+
+use super::{
+    extract_args, extract_string, lmpop::pop_first, parse_number, validate_variadic_command,
+    CommandError, CommandExecutor,
+};
+
+#[derive(Debug)]
+pub struct BPop {
+    pub keys: Vec<String>,
+    // BLPOP: pop from the head instead of the tail
+    pub left: bool,
+    // None blocks forever
+    pub timeout: Option<Duration>,
+}
+
+impl CommandExecutor for BPop {
+    // without a connection to block, e.g. inside a transaction, this behaves like a pop that
+    // timed out right away
+    fn execute(self, backend: &Backend) -> RespFrame {
+        self.try_pop(backend)
+            .unwrap_or_else(|| RespNullArray.into())
+    }
+}
+
+impl BPop {
+    /// pop from the first non-empty list, waiting until one of the keys is pushed to or the
+    /// timeout expires
+    pub async fn execute_blocking(self, backend: &Backend, raw: Option<RespFrame>) -> RespFrame {
+        block_on_keys(backend, &self.keys, self.timeout, raw, || {
+            self.try_pop(backend)
+        })
+        .await
+        .unwrap_or_else(|| RespNullArray.into())
+    }
+
+    // [key, element] from the first non-empty list, None if they are all empty
+    fn try_pop(&self, backend: &Backend) -> Option<RespFrame> {
+        let mut db = backend.write();
+        match pop_first(&mut db, &self.keys, self.left, 1) {
+            Ok(Some((key, mut elements))) => Some(
+                RespArray::new(vec![
+                    BulkString::new(key).into(),
+                    BulkString::new(elements.pop()?).into(),
+                ])
+                .into(),
+            ),
+            Ok(None) => None,
+            Err(e) => Some(e),
+        }
+    }
+}
+
+// retry `attempt` each time one of `keys` is signaled, until it returns a reply or the timeout
+// expires. the backend lock is never held while waiting, only by `attempt` itself. `raw`, the
+// command as sent, is logged once it was served
+pub(super) async fn block_on_keys(
+    backend: &Backend,
+    keys: &[String],
+    timeout: Option<Duration>,
+    raw: Option<RespFrame>,
+    mut attempt: impl FnMut() -> Option<RespFrame>,
+) -> Option<RespFrame> {
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    let waiters: Vec<_> = keys.iter().map(|key| backend.key_waiter(key)).collect();
+    let ret = loop {
+        // register interest before looking at the lists, so that a push landing between the
+        // attempt and the wait still wakes us up
+        let mut notified: Vec<_> = waiters.iter().map(|w| Box::pin(w.notified())).collect();
+        for n in notified.iter_mut() {
+            n.as_mut().enable();
+        }
+        let attempted = {
+            let _guard = backend.command_guard();
+            let attempted = attempt();
+            if let (Some(raw), Some(frame)) = (&raw, &attempted) {
+                propagate(backend, raw.clone(), frame);
+            }
+            attempted
+        };
+        if let Some(frame) = attempted {
+            break Some(frame);
+        }
+        let _blocked = backend.stats().blocked();
+        let woken = select_all(notified);
+        match deadline {
+            Some(deadline) => {
+                if tokio::time::timeout_at(deadline, woken).await.is_err() {
+                    break None;
+                }
+            }
+            None => {
+                woken.await;
+            }
+        }
+    };
+    for (key, waiter) in keys.iter().zip(waiters) {
+        backend.release_key_waiter(key, waiter);
+    }
+    ret
+}
+
+// - timeout in seconds, fractions allowed, 0 to block forever
+pub(super) fn parse_timeout(frame: Option<RespFrame>) -> Result<Option<Duration>, CommandError> {
+    let timeout: f64 = parse_number(frame)?;
+    if !timeout.is_finite() || timeout < 0.0 {
+        return Err(CommandError::InvalidArgument(
+            "timeout is negative or not finite".to_string(),
+        ));
+    }
+    Ok((timeout > 0.0).then(|| Duration::from_secs_f64(timeout)))
+}
+
+impl TryFrom<RespArray> for BPop {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let left = validate_variadic_command(&value, &["blpop"], 2)
+            .map(|_| true)
+            .or_else(|_| validate_variadic_command(&value, &["brpop"], 2).map(|_| false))?;
+
+        let mut args = extract_args(value, 1)?;
+        let timeout = parse_timeout(args.pop())?;
+        let keys = args
+            .into_iter()
+            .map(|arg| extract_string(Some(arg)))
+            .collect::<Result<_, _>>()?;
+        Ok(BPop {
+            keys,
+            left,
+            timeout,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{cmd::Push, RespDecode};
+
+    use super::*;
+
+    fn push(backend: &Backend, key: &str, elements: &[&str]) {
+        let cmd = Push {
+            key: key.to_string(),
+            elements: elements.iter().map(|e| e.as_bytes().to_vec()).collect(),
+            left: false,
+        };
+        cmd.execute(backend);
+    }
+
+    fn bpop(keys: &[&str], left: bool, timeout: Option<Duration>) -> BPop {
+        BPop {
+            keys: keys.iter().map(|k| k.to_string()).collect(),
+            left,
+            timeout,
+        }
+    }
+
+    fn reply(key: &str, element: &str) -> RespFrame {
+        RespArray::new(vec![
+            BulkString::new(key).into(),
+            BulkString::new(element).into(),
+        ])
+        .into()
+    }
+
+    #[test]
+    fn test_bpop_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*4\r\n$5\r\nbrpop\r\n$1\r\na\r\n$1\r\nb\r\n$3\r\n1.5\r\n");
+        let cmd: BPop = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.keys, vec!["a", "b"]);
+        assert!(!cmd.left);
+        assert_eq!(cmd.timeout, Some(Duration::from_millis(1500)));
+
+        let mut buf = BytesMut::from("*3\r\n$5\r\nblpop\r\n$1\r\na\r\n$1\r\n0\r\n");
+        let cmd: BPop = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.timeout, None);
+
+        let mut buf = BytesMut::from("*3\r\n$5\r\nblpop\r\n$1\r\na\r\n$2\r\n-1\r\n");
+        let ret: Result<BPop, _> = RespArray::decode(&mut buf)?.try_into();
+        assert!(ret.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_bpop_ready_list() {
+        let backend = Backend::new();
+        push(&backend, "b", &["1", "2"]);
+
+        let ret = bpop(&["a", "b"], false, None)
+            .execute_blocking(&backend, None)
+            .await;
+        assert_eq!(ret, reply("b", "2"));
+        let ret = bpop(&["a", "b"], true, None).execute(&backend);
+        assert_eq!(ret, reply("b", "1"));
+        assert_eq!(
+            bpop(&["a", "b"], true, None).execute(&backend),
+            RespNullArray.into()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bpop_wakes_on_push() {
+        let backend = Backend::new();
+        let blocked = tokio::spawn({
+            let backend = backend.clone();
+            async move {
+                bpop(&["a", "b"], true, None)
+                    .execute_blocking(&backend, None)
+                    .await
+            }
+        });
+
+        // let the pop block before pushing
+        tokio::task::yield_now().await;
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        push(&backend, "b", &["x", "y"]);
+
+        assert_eq!(blocked.await.unwrap(), reply("b", "x"));
+    }
+
+    #[tokio::test]
+    async fn test_bpop_each_element_goes_to_one_client() {
+        let backend = Backend::new();
+        let clients: Vec<_> = (0..3)
+            .map(|_| {
+                let backend = backend.clone();
+                tokio::spawn(async move {
+                    bpop(&["list"], true, Some(Duration::from_secs(5)))
+                        .execute_blocking(&backend, None)
+                        .await
+                })
+            })
+            .collect();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        push(&backend, "list", &["1", "2", "3"]);
+
+        let mut popped = vec![];
+        for client in clients {
+            let RespFrame::Array(ret) = client.await.unwrap() else {
+                panic!("expected an array");
+            };
+            popped.push(ret[1].clone());
+        }
+        popped.sort_by_key(|frame| format!("{:?}", frame));
+        let expected: Vec<RespFrame> = ["1", "2", "3"]
+            .iter()
+            .map(|e| BulkString::new(*e).into())
+            .collect();
+        assert_eq!(popped, expected);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_bpop_timeout() {
+        let backend = Backend::new();
+        let ret = bpop(&["a"], true, Some(Duration::from_secs(1)))
+            .execute_blocking(&backend, None)
+            .await;
+        assert_eq!(ret, RespNullArray.into());
+    }
+}
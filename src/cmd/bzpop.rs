@@ -0,0 +1,172 @@
+use std::time::Duration;
+
+use crate::{Backend, BulkString, RespArray, RespFrame, RespNullArray};
+
+use super::{
+    blpop::{block_on_keys, parse_timeout},
+    extract_args, extract_string, validate_variadic_command,
+    zpop::zpop,
+    zset::format_score,
+    CommandError, CommandExecutor,
+};
+
+#[derive(Debug)]
+pub struct BZPop {
+    pub keys: Vec<String>,
+    // BZPOPMAX: pop the highest score instead of the lowest
+    pub max: bool,
+    // None blocks forever
+    pub timeout: Option<Duration>,
+}
+
+impl CommandExecutor for BZPop {
+    // like ZPOPMIN/ZPOPMAX over the keys when there is no connection to block
+    fn execute(self, backend: &Backend) -> RespFrame {
+        self.try_pop(backend)
+            .unwrap_or_else(|| RespNullArray.into())
+    }
+}
+
+impl BZPop {
+    /// pop from the first non-empty sorted set, waiting until one of the keys is added to or
+    /// the timeout expires
+    pub async fn execute_blocking(self, backend: &Backend, raw: Option<RespFrame>) -> RespFrame {
+        block_on_keys(backend, &self.keys, self.timeout, raw, || {
+            self.try_pop(backend)
+        })
+        .await
+        .unwrap_or_else(|| RespNullArray.into())
+    }
+
+    // [key, member, score] from the first non-empty sorted set, None if they are all empty
+    fn try_pop(&self, backend: &Backend) -> Option<RespFrame> {
+        let mut db = backend.write();
+        for key in self.keys.iter() {
+            match zpop(&mut db, key, 1, self.max) {
+                Ok(popped) => {
+                    if let Some((member, score)) = popped.into_iter().next() {
+                        return Some(
+                            RespArray::new(vec![
+                                BulkString::new(key.as_str()).into(),
+                                BulkString::new(member).into(),
+                                BulkString::new(format_score(score)).into(),
+                            ])
+                            .into(),
+                        );
+                    }
+                }
+                Err(e) => return Some(e),
+            }
+        }
+        None
+    }
+}
+
+impl TryFrom<RespArray> for BZPop {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let max = validate_variadic_command(&value, &["bzpopmin"], 2)
+            .map(|_| false)
+            .or_else(|_| validate_variadic_command(&value, &["bzpopmax"], 2).map(|_| true))?;
+
+        let mut args = extract_args(value, 1)?;
+        let timeout = parse_timeout(args.pop())?;
+        let keys = args
+            .into_iter()
+            .map(|arg| extract_string(Some(arg)))
+            .collect::<Result<_, _>>()?;
+        Ok(BZPop { keys, max, timeout })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{
+        cmd::{ZAdd, ZAddOptions},
+        RespDecode,
+    };
+
+    use super::*;
+
+    fn zadd(backend: &Backend, key: &str, members: &[(f64, &str)]) {
+        let cmd = ZAdd {
+            key: key.to_string(),
+            members: members
+                .iter()
+                .map(|(s, m)| (*s, m.as_bytes().to_vec()))
+                .collect(),
+            options: ZAddOptions::default(),
+        };
+        cmd.execute(backend);
+    }
+
+    fn bzpop(keys: &[&str], max: bool, timeout: Option<Duration>) -> BZPop {
+        BZPop {
+            keys: keys.iter().map(|k| k.to_string()).collect(),
+            max,
+            timeout,
+        }
+    }
+
+    fn reply(items: &[&str]) -> RespFrame {
+        let items: Vec<RespFrame> = items.iter().map(|i| BulkString::new(*i).into()).collect();
+        RespArray::new(items).into()
+    }
+
+    #[test]
+    fn test_bzpop_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*4\r\n$8\r\nbzpopmax\r\n$1\r\na\r\n$1\r\nb\r\n$1\r\n2\r\n");
+        let cmd: BZPop = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.keys, vec!["a", "b"]);
+        assert!(cmd.max);
+        assert_eq!(cmd.timeout, Some(Duration::from_secs(2)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_bzpop_ready() {
+        let backend = Backend::new();
+        zadd(&backend, "b", &[(1.0, "x"), (2.0, "y")]);
+
+        let ret = bzpop(&["a", "b"], true, None)
+            .execute_blocking(&backend, None)
+            .await;
+        assert_eq!(ret, reply(&["b", "y", "2"]));
+        let ret = bzpop(&["a", "b"], false, None).execute(&backend);
+        assert_eq!(ret, reply(&["b", "x", "1"]));
+        assert_eq!(
+            bzpop(&["a", "b"], false, None).execute(&backend),
+            RespNullArray.into()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bzpop_wakes_on_zadd() {
+        let backend = Backend::new();
+        let blocked = tokio::spawn({
+            let backend = backend.clone();
+            async move {
+                bzpop(&["z"], false, None)
+                    .execute_blocking(&backend, None)
+                    .await
+            }
+        });
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        zadd(&backend, "z", &[(3.0, "c"), (1.5, "a")]);
+
+        assert_eq!(blocked.await.unwrap(), reply(&["z", "a", "1.5"]));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_bzpop_timeout() {
+        let backend = Backend::new();
+        let ret = bzpop(&["z"], true, Some(Duration::from_millis(500)))
+            .execute_blocking(&backend, None)
+            .await;
+        assert_eq!(ret, RespNullArray.into());
+    }
+}
@@ -0,0 +1,462 @@
+use std::{fmt::Write, time::Duration};
+
+use tokio::time::Instant;
+
+use crate::{
+    network::{ConnectionState, PauseMode},
+    Backend, BulkString, RespArray, RespFrame, RespNullBulkString, SimpleError,
+};
+
+use super::{
+    extract_args, extract_string, parse_number, validate_command, validate_variadic_command,
+    CommandError, CommandExecutor, RESP_NO_CONNECTION, RESP_OK,
+};
+
+#[derive(Debug)]
+pub struct ClientId;
+
+#[derive(Debug)]
+pub struct ClientSetName {
+    // an empty name clears it
+    pub name: String,
+}
+
+#[derive(Debug)]
+pub struct ClientGetName;
+
+#[derive(Debug)]
+pub struct ClientList;
+
+/// CLIENT KILL ip:port, or CLIENT KILL [ID client-id] [ADDR ip:port] [SKIPME yes|no]
+#[derive(Debug, Default)]
+pub struct ClientKill {
+    pub id: Option<u64>,
+    pub addr: Option<String>,
+    // leave the calling connection alone, only for the filter form
+    pub skip_me: bool,
+    // the old single address form, which replies OK or an error instead of a count
+    pub legacy: bool,
+}
+
+/// CLIENT PAUSE timeout [WRITE | ALL]
+#[derive(Debug)]
+pub struct ClientPause {
+    pub timeout: Duration,
+    pub mode: PauseMode,
+}
+
+#[derive(Debug)]
+pub struct ClientUnpause;
+
+impl CommandExecutor for ClientId {
+    fn execute(self, _backend: &Backend) -> RespFrame {
+        RESP_NO_CONNECTION.clone()
+    }
+
+    fn execute_on(self, _backend: &Backend, conn: &mut ConnectionState) -> RespFrame {
+        RespFrame::Integer(conn.id as i64)
+    }
+}
+
+impl CommandExecutor for ClientSetName {
+    fn execute(self, _backend: &Backend) -> RespFrame {
+        RESP_NO_CONNECTION.clone()
+    }
+
+    fn execute_on(self, _backend: &Backend, conn: &mut ConnectionState) -> RespFrame {
+        let valid = self
+            .name
+            .bytes()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, b'.' | b'-' | b'_'));
+        if !valid {
+            return SimpleError::new(
+                "ERR Client names cannot contain spaces, newlines or special characters.",
+            )
+            .into();
+        }
+        conn.client_name = (!self.name.is_empty()).then_some(self.name);
+        RESP_OK.clone()
+    }
+}
+
+impl CommandExecutor for ClientGetName {
+    fn execute(self, _backend: &Backend) -> RespFrame {
+        RESP_NO_CONNECTION.clone()
+    }
+
+    fn execute_on(self, _backend: &Backend, conn: &mut ConnectionState) -> RespFrame {
+        match &conn.client_name {
+            Some(name) => BulkString::new(name.as_str()).into(),
+            None => RespNullBulkString.into(),
+        }
+    }
+}
+
+impl CommandExecutor for ClientList {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let now = Instant::now();
+        let mut ret = String::new();
+        for info in backend.clients() {
+            // writing to a String can't fail
+            let _ = writeln!(
+                ret,
+                "id={} addr={} name={} age={} idle={} db={} cmd={}",
+                info.id,
+                info.addr,
+                info.name.as_deref().unwrap_or(""),
+                now.saturating_duration_since(info.created_at).as_secs(),
+                now.saturating_duration_since(info.last_interaction)
+                    .as_secs(),
+                info.db,
+                info.cmd,
+            );
+        }
+        BulkString::new(ret).into()
+    }
+}
+
+impl CommandExecutor for ClientKill {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        self.kill(backend, None)
+    }
+
+    fn execute_on(self, backend: &Backend, conn: &mut ConnectionState) -> RespFrame {
+        let me = self.skip_me.then_some(conn.id);
+        self.kill(backend, me)
+    }
+}
+
+impl CommandExecutor for ClientPause {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        backend.pause_gate().pause(self.mode, self.timeout);
+        RESP_OK.clone()
+    }
+}
+
+impl CommandExecutor for ClientUnpause {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        backend.pause_gate().unpause();
+        RESP_OK.clone()
+    }
+}
+
+impl ClientKill {
+    fn kill(self, backend: &Backend, skip: Option<u64>) -> RespFrame {
+        let killed = backend.kill_clients(|info| {
+            Some(info.id) != skip
+                && self.id.is_none_or(|id| id == info.id)
+                && self.addr.as_ref().is_none_or(|addr| *addr == info.addr)
+        });
+        match (self.legacy, killed) {
+            (true, 0) => SimpleError::new("ERR No such client").into(),
+            (true, _) => RESP_OK.clone(),
+            (false, n) => RespFrame::Integer(n as i64),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for ClientId {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["client", "id"], 0)?;
+        Ok(ClientId)
+    }
+}
+
+impl TryFrom<RespArray> for ClientSetName {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["client", "setname"], 1)?;
+
+        let mut args = extract_args(value, 2)?.into_iter();
+        Ok(ClientSetName {
+            name: extract_string(args.next())?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for ClientGetName {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["client", "getname"], 0)?;
+        Ok(ClientGetName)
+    }
+}
+
+impl TryFrom<RespArray> for ClientKill {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        if validate_command(&value, &["client", "kill"], 1).is_ok() {
+            let mut args = extract_args(value, 2)?.into_iter();
+            return Ok(ClientKill {
+                addr: Some(extract_string(args.next())?),
+                legacy: true,
+                ..Default::default()
+            });
+        }
+        validate_variadic_command(&value, &["client", "kill"], 2)?;
+
+        let mut args = extract_args(value, 2)?.into_iter();
+        let mut cmd = ClientKill {
+            skip_me: true,
+            ..Default::default()
+        };
+        while let Some(arg) = args.next() {
+            match extract_string(Some(arg))?.to_ascii_lowercase().as_str() {
+                "id" => cmd.id = Some(parse_number(args.next())?),
+                "addr" => cmd.addr = Some(extract_string(args.next())?),
+                "skipme" => {
+                    cmd.skip_me = match extract_string(args.next())?.to_ascii_lowercase().as_str() {
+                        "yes" => true,
+                        "no" => false,
+                        v => {
+                            return Err(CommandError::InvalidArgument(format!(
+                                "Invalid skipme: {}",
+                                v
+                            )))
+                        }
+                    }
+                }
+                v => {
+                    return Err(CommandError::InvalidArgument(format!(
+                        "Invalid filter: {}",
+                        v
+                    )))
+                }
+            }
+        }
+        Ok(cmd)
+    }
+}
+
+impl TryFrom<RespArray> for ClientPause {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let with_mode = validate_command(&value, &["client", "pause"], 1)
+            .map(|_| false)
+            .or_else(|_| validate_command(&value, &["client", "pause"], 2).map(|_| true))?;
+
+        let mut args = extract_args(value, 2)?.into_iter();
+        // - timeout in milliseconds
+        let timeout: u64 = parse_number(args.next())?;
+        let mode = if with_mode {
+            match extract_string(args.next())?.to_ascii_lowercase().as_str() {
+                "write" => PauseMode::Write,
+                "all" => PauseMode::All,
+                v => {
+                    return Err(CommandError::InvalidArgument(format!(
+                        "Invalid pause mode: {}",
+                        v
+                    )))
+                }
+            }
+        } else {
+            PauseMode::All
+        };
+        Ok(ClientPause {
+            timeout: Duration::from_millis(timeout),
+            mode,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for ClientUnpause {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["client", "unpause"], 0)?;
+        Ok(ClientUnpause)
+    }
+}
+
+impl TryFrom<RespArray> for ClientList {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["client", "list"], 0)?;
+        Ok(ClientList)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{ConnectionInfo, RespDecode};
+
+    use super::*;
+
+    #[test]
+    fn test_client_setname_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*3\r\n$6\r\nclient\r\n$7\r\nsetname\r\n$6\r\nworker\r\n");
+        let cmd: ClientSetName = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.name, "worker");
+
+        let mut buf = BytesMut::from("*3\r\n$6\r\nclient\r\n$2\r\nid\r\n$1\r\nx\r\n");
+        let ret: Result<ClientId, _> = RespArray::decode(&mut buf)?.try_into();
+        assert!(ret.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_client_kill_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*3\r\n$6\r\nclient\r\n$4\r\nkill\r\n$11\r\n127.0.0.1:1\r\n");
+        let cmd: ClientKill = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.addr.as_deref(), Some("127.0.0.1:1"));
+        assert!(cmd.legacy);
+
+        let mut buf = BytesMut::from(
+            "*6\r\n$6\r\nclient\r\n$4\r\nkill\r\n$2\r\nID\r\n$1\r\n7\r\n$6\r\nSKIPME\r\n$2\r\nno\r\n",
+        );
+        let cmd: ClientKill = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.id, Some(7));
+        assert!(!cmd.skip_me);
+        assert!(!cmd.legacy);
+        Ok(())
+    }
+
+    #[test]
+    fn test_client_kill() {
+        let backend = Backend::new();
+        let mut conn = ConnectionState::new();
+        let _me = backend.register_client(ConnectionInfo::new(conn.id, "127.0.0.1:1"));
+        let _other = backend.register_client(ConnectionInfo::new(u64::MAX, "127.0.0.1:2"));
+
+        let kill = |addr: &str| ClientKill {
+            addr: Some(addr.to_string()),
+            legacy: true,
+            ..Default::default()
+        };
+        assert!(matches!(
+            kill("127.0.0.1:3").execute_on(&backend, &mut conn),
+            RespFrame::Error(_)
+        ));
+
+        // the filter form skips the caller unless told otherwise
+        let by_id = |id: u64, skip_me: bool| ClientKill {
+            id: Some(id),
+            skip_me,
+            ..Default::default()
+        };
+        assert_eq!(
+            by_id(conn.id, true).execute_on(&backend, &mut conn),
+            RespFrame::Integer(0)
+        );
+        assert_eq!(
+            by_id(conn.id, false).execute_on(&backend, &mut conn),
+            RespFrame::Integer(1)
+        );
+        assert_eq!(
+            kill("127.0.0.1:2").execute_on(&backend, &mut conn),
+            RESP_OK.clone()
+        );
+        assert!(backend.clients().is_empty());
+    }
+
+    #[test]
+    fn test_client_pause_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*3\r\n$6\r\nclient\r\n$5\r\npause\r\n$3\r\n100\r\n");
+        let cmd: ClientPause = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.timeout, Duration::from_millis(100));
+        assert_eq!(cmd.mode, PauseMode::All);
+
+        let mut buf =
+            BytesMut::from("*4\r\n$6\r\nclient\r\n$5\r\npause\r\n$3\r\n100\r\n$5\r\nWRITE\r\n");
+        let cmd: ClientPause = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.mode, PauseMode::Write);
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_client_pause_unpause() {
+        let backend = Backend::new();
+        let cmd = ClientPause {
+            timeout: Duration::from_secs(10),
+            mode: PauseMode::Write,
+        };
+        assert_eq!(cmd.execute(&backend), RESP_OK.clone());
+
+        let start = Instant::now();
+        backend.pause_gate().wait(false).await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        let write = tokio::spawn({
+            let backend = backend.clone();
+            async move { backend.pause_gate().wait(true).await }
+        });
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert!(!write.is_finished());
+
+        assert_eq!(ClientUnpause.execute(&backend), RESP_OK.clone());
+        write.await.unwrap();
+        assert_eq!(start.elapsed(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_client_id() {
+        let backend = Backend::new();
+        let mut conn = ConnectionState::new();
+        assert_eq!(
+            ClientId.execute_on(&backend, &mut conn),
+            RespFrame::Integer(conn.id as i64)
+        );
+        assert!(matches!(ClientId.execute(&backend), RespFrame::Error(_)));
+    }
+
+    #[test]
+    fn test_client_setname_getname() {
+        let backend = Backend::new();
+        let mut conn = ConnectionState::new();
+        assert_eq!(
+            ClientGetName.execute_on(&backend, &mut conn),
+            RespNullBulkString.into()
+        );
+
+        let setname = |name: &str| ClientSetName {
+            name: name.to_string(),
+        };
+        assert_eq!(
+            setname("worker-1.a_b").execute_on(&backend, &mut conn),
+            RESP_OK.clone()
+        );
+        assert_eq!(
+            ClientGetName.execute_on(&backend, &mut conn),
+            BulkString::new("worker-1.a_b").into()
+        );
+
+        for invalid in ["with space", "new\nline", "star*"] {
+            assert!(matches!(
+                setname(invalid).execute_on(&backend, &mut conn),
+                RespFrame::Error(_)
+            ));
+        }
+        assert_eq!(conn.client_name.as_deref(), Some("worker-1.a_b"));
+
+        // an empty name clears it
+        setname("").execute_on(&backend, &mut conn);
+        assert_eq!(conn.client_name, None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_client_list() {
+        let backend = Backend::new();
+        backend.register_client(ConnectionInfo::new(7, "127.0.0.1:5000"));
+        tokio::time::advance(Duration::from_secs(3)).await;
+        backend.register_client(ConnectionInfo::new(8, "127.0.0.1:5001"));
+        backend.update_client(8, |info| {
+            info.name = Some("worker".to_string());
+            info.db = 2;
+            info.cmd = "get".to_string();
+        });
+        tokio::time::advance(Duration::from_secs(1)).await;
+
+        assert_eq!(
+            ClientList.execute(&backend),
+            BulkString::new(
+                "id=7 addr=127.0.0.1:5000 name= age=4 idle=4 db=0 cmd=NULL\n\
+                 id=8 addr=127.0.0.1:5001 name=worker age=1 idle=1 db=2 cmd=get\n"
+            )
+            .into()
+        );
+    }
+}
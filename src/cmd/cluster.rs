@@ -0,0 +1,253 @@
+use crate::{Backend, BulkString, RespArray, RespFrame};
+
+use super::{
+    extract_args, extract_bytes, parse_number, validate_command, CommandError, CommandExecutor,
+};
+
+const CLUSTER_SLOTS: u16 = 16384;
+
+#[derive(Debug)]
+pub struct ClusterCountKeysInSlot {
+    pub slot: u16,
+}
+
+#[derive(Debug)]
+pub struct ClusterGetKeysInSlot {
+    pub slot: u16,
+    pub count: usize,
+}
+
+#[derive(Debug)]
+pub struct ClusterKeySlot {
+    pub key: Vec<u8>,
+}
+
+impl CommandExecutor for ClusterCountKeysInSlot {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        // standalone mode: every key of the current database is a candidate
+        let count = backend
+            .read()
+            .keys()
+            .filter(|key| key_hash_slot(key.as_bytes()) == self.slot)
+            .count();
+        RespFrame::Integer(count as i64)
+    }
+}
+
+impl CommandExecutor for ClusterGetKeysInSlot {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let keys: Vec<RespFrame> = backend
+            .read()
+            .keys()
+            .filter(|key| key_hash_slot(key.as_bytes()) == self.slot)
+            .take(self.count)
+            .map(|key| BulkString::new(key.as_bytes()).into())
+            .collect();
+        RespArray::new(keys).into()
+    }
+}
+
+impl CommandExecutor for ClusterKeySlot {
+    fn execute(self, _backend: &Backend) -> RespFrame {
+        RespFrame::Integer(key_hash_slot(&self.key) as i64)
+    }
+}
+
+impl TryFrom<RespArray> for ClusterCountKeysInSlot {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["cluster", "countkeysinslot"], 1)?;
+
+        let mut args = extract_args(value, 2)?.into_iter();
+        Ok(ClusterCountKeysInSlot {
+            slot: parse_slot(args.next())?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for ClusterGetKeysInSlot {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["cluster", "getkeysinslot"], 2)?;
+
+        let mut args = extract_args(value, 2)?.into_iter();
+        let slot = parse_slot(args.next())?;
+        let count: i64 = parse_number(args.next())?;
+        if count < 0 {
+            return Err(CommandError::InvalidArgument(
+                "Invalid number of keys".to_string(),
+            ));
+        }
+        Ok(ClusterGetKeysInSlot {
+            slot,
+            count: count as usize,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for ClusterKeySlot {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["cluster", "keyslot"], 1)?;
+
+        let mut args = extract_args(value, 2)?.into_iter();
+        Ok(ClusterKeySlot {
+            key: extract_bytes(args.next())?,
+        })
+    }
+}
+
+fn parse_slot(frame: Option<RespFrame>) -> Result<u16, CommandError> {
+    let slot: i64 = parse_number(frame)?;
+    if !(0..CLUSTER_SLOTS as i64).contains(&slot) {
+        return Err(CommandError::InvalidArgument("Invalid slot".to_string()));
+    }
+    Ok(slot as u16)
+}
+
+/// hash slot of a key, only the `{tag}` part is hashed if the key has a non-empty hash tag
+pub fn key_hash_slot(key: &[u8]) -> u16 {
+    let key = match key.iter().position(|&c| c == b'{') {
+        Some(start) => match key[start + 1..].iter().position(|&c| c == b'}') {
+            Some(len) if len > 0 => &key[start + 1..start + 1 + len],
+            _ => key,
+        },
+        None => key,
+    };
+    crc16(key) % CLUSTER_SLOTS
+}
+
+// CRC16 XMODEM: polynomial 0x1021, initial value 0
+fn crc16(data: &[u8]) -> u16 {
+    data.iter().fold(0u16, |crc, &b| {
+        (0..8).fold(crc ^ ((b as u16) << 8), |crc, _| {
+            if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            }
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{BulkString, RespDecode};
+
+    use super::*;
+
+    #[test]
+    fn test_crc16() {
+        assert_eq!(crc16(b"123456789"), 0x31c3);
+    }
+
+    #[test]
+    fn test_key_hash_slot() {
+        assert_eq!(key_hash_slot(b"foo"), 12182);
+        assert_eq!(key_hash_slot(b"{tag}key1"), key_hash_slot(b"tag"));
+        assert_eq!(key_hash_slot(b"{}key"), crc16(b"{}key") % CLUSTER_SLOTS);
+    }
+
+    #[test]
+    fn test_cluster_countkeysinslot_try_from_resp_array() -> Result<()> {
+        let mut buf =
+            BytesMut::from("*3\r\n$7\r\ncluster\r\n$15\r\ncountkeysinslot\r\n$4\r\n7000\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+        let cmd: ClusterCountKeysInSlot = frame.try_into()?;
+        assert_eq!(cmd.slot, 7000);
+
+        let mut buf =
+            BytesMut::from("*3\r\n$7\r\ncluster\r\n$15\r\ncountkeysinslot\r\n$5\r\n16384\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(ClusterCountKeysInSlot::try_from(frame).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_cluster_countkeysinslot() {
+        let backend = Backend::new();
+        for key in ["foo", "bar", "{tag}key1", "{tag}key2"] {
+            backend.set(key.to_string(), BulkString::new("value"));
+        }
+
+        let cmd = ClusterCountKeysInSlot {
+            slot: key_hash_slot(b"{tag}"),
+        };
+        assert_eq!(cmd.execute(&backend), RespFrame::Integer(2));
+    }
+
+    #[test]
+    fn test_cluster_getkeysinslot_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from(
+            "*4\r\n$7\r\ncluster\r\n$13\r\ngetkeysinslot\r\n$4\r\n7000\r\n$2\r\n10\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+        let cmd: ClusterGetKeysInSlot = frame.try_into()?;
+        assert_eq!(cmd.slot, 7000);
+        assert_eq!(cmd.count, 10);
+
+        let mut buf = BytesMut::from(
+            "*4\r\n$7\r\ncluster\r\n$13\r\ngetkeysinslot\r\n$4\r\n7000\r\n$2\r\n-1\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(ClusterGetKeysInSlot::try_from(frame).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_cluster_getkeysinslot() {
+        let backend = Backend::new();
+        for key in ["foo", "bar", "{tag}key1", "{tag}key2", "{tag}key3"] {
+            backend.set(key.to_string(), BulkString::new("value"));
+        }
+        let slot = key_hash_slot(b"tag");
+
+        let cmd = ClusterGetKeysInSlot { slot, count: 10 };
+        let RespFrame::Array(keys) = cmd.execute(&backend) else {
+            panic!("expected an array");
+        };
+        let mut keys = keys.0;
+        keys.sort_by_key(|key| format!("{:?}", key));
+        assert_eq!(
+            keys,
+            vec![
+                BulkString::new("{tag}key1").into(),
+                BulkString::new("{tag}key2").into(),
+                BulkString::new("{tag}key3").into(),
+            ]
+        );
+
+        let cmd = ClusterGetKeysInSlot { slot, count: 2 };
+        let RespFrame::Array(keys) = cmd.execute(&backend) else {
+            panic!("expected an array");
+        };
+        assert_eq!(keys.len(), 2);
+    }
+
+    #[test]
+    fn test_cluster_keyslot() -> Result<()> {
+        let backend = Backend::new();
+        let keyslot = |key: &str| -> Result<RespFrame> {
+            let mut buf = BytesMut::from(
+                format!(
+                    "*3\r\n$7\r\ncluster\r\n$7\r\nkeyslot\r\n${}\r\n{}\r\n",
+                    key.len(),
+                    key
+                )
+                .as_str(),
+            );
+            let cmd: ClusterKeySlot = RespArray::decode(&mut buf)?.try_into()?;
+            Ok(cmd.execute(&backend))
+        };
+
+        assert_eq!(keyslot("foo")?, RespFrame::Integer(12182));
+        assert_eq!(keyslot("{foo}bar")?, keyslot("{foo}baz")?);
+        assert_eq!(keyslot("{foo}bar")?, keyslot("foo")?);
+        Ok(())
+    }
+}
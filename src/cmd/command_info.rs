@@ -0,0 +1,776 @@
+use crate::{Backend, BulkString, RespArray, RespFrame, RespMap, RespNullArray, SimpleString};
+
+use super::{
+    extract_args, extract_string, glob_match, validate_command, validate_variadic_command,
+    CommandError, CommandExecutor,
+};
+
+/// what COMMAND tells about a command
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandMetadata {
+    pub name: &'static str,
+    // the number of arguments including the name, negative for at least that many
+    pub arity: i64,
+    pub flags: &'static [&'static str],
+    // positions of the first and last key and the step between keys, 0 for no keys and a
+    // negative last key counts from the end
+    pub first_key: i64,
+    pub last_key: i64,
+    pub step: i64,
+    pub group: &'static str,
+    pub summary: &'static str,
+    pub complexity: &'static str,
+    // the arguments after the name, in the notation of the redis documentation
+    pub syntax: &'static str,
+    // for container commands such as CLIENT
+    pub subcommands: &'static [&'static str],
+}
+
+/// COMMAND COUNT
+#[derive(Debug)]
+pub struct CommandCount;
+
+/// COMMAND INFO [name ...], COMMAND alone describes every command
+#[derive(Debug)]
+pub struct CommandInfo {
+    pub names: Vec<String>,
+}
+
+/// COMMAND DOCS [name ...], every command when none is given
+#[derive(Debug)]
+pub struct CommandDocs {
+    pub names: Vec<String>,
+}
+
+/// COMMAND LIST [FILTERBY MODULE name | ACLCAT category | PATTERN pattern]
+#[derive(Debug)]
+pub struct CommandList {
+    pub filter: Option<CommandFilter>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CommandFilter {
+    Module(String),
+    AclCat(String),
+    Pattern(String),
+}
+
+const READ: &[&str] = &["readonly"];
+const READ_FAST: &[&str] = &["readonly", "fast"];
+const WRITE: &[&str] = &["write"];
+const WRITE_FAST: &[&str] = &["write", "fast"];
+const WRITE_GROW: &[&str] = &["write", "denyoom"];
+const WRITE_GROW_FAST: &[&str] = &["write", "denyoom", "fast"];
+const BLOCKING: &[&str] = &["write", "blocking"];
+const ADMIN: &[&str] = &["admin", "noscript", "loading", "stale"];
+const CONNECTION: &[&str] = &["noscript", "loading", "stale"];
+const TRANSACTION: &[&str] = &["noscript", "loading", "stale", "fast", "allow_busy"];
+const PUBSUB: &[&str] = &["pubsub", "noscript", "loading", "stale"];
+const PUBSUB_FAST: &[&str] = &["pubsub", "loading", "stale", "fast"];
+
+#[allow(clippy::too_many_arguments)]
+const fn command(
+    name: &'static str,
+    arity: i64,
+    flags: &'static [&'static str],
+    keys: (i64, i64, i64),
+    group: &'static str,
+    summary: &'static str,
+    complexity: &'static str,
+    syntax: &'static str,
+) -> CommandMetadata {
+    CommandMetadata {
+        name,
+        arity,
+        flags,
+        first_key: keys.0,
+        last_key: keys.1,
+        step: keys.2,
+        group,
+        summary,
+        complexity,
+        syntax,
+        subcommands: &[],
+    }
+}
+
+const fn container(
+    name: &'static str,
+    flags: &'static [&'static str],
+    group: &'static str,
+    summary: &'static str,
+    subcommands: &'static [&'static str],
+) -> CommandMetadata {
+    CommandMetadata {
+        subcommands,
+        ..command(
+            name,
+            -2,
+            flags,
+            (0, 0, 0),
+            group,
+            summary,
+            "Depends on subcommand.",
+            "subcommand [argument ...]",
+        )
+    }
+}
+
+const NO_KEYS: (i64, i64, i64) = (0, 0, 0);
+const ONE_KEY: (i64, i64, i64) = (1, 1, 1);
+const TWO_KEYS: (i64, i64, i64) = (1, 2, 1);
+const ALL_KEYS: (i64, i64, i64) = (1, -1, 1);
+// keys follow a numkeys argument, their position depends on it
+const MOVABLE_KEYS: (i64, i64, i64) = (0, 0, 0);
+
+/// every command the server knows, in alphabetical order
+#[rustfmt::skip]
+pub const COMMANDS: &[CommandMetadata] = &[
+    command("auth", -2, &["noscript", "loading", "stale", "fast", "no_auth"], NO_KEYS, "connection", "Authenticates the connection.", "O(N) where N is the number of passwords defined for the user", "[username] password"),
+    command("bgrewriteaof", 1, &["admin", "noscript", "no_async_loading"], NO_KEYS, "server", "Asynchronously rewrites the append-only file to disk.", "O(1)", ""),
+    command("bgsave", -1, &["admin", "noscript", "no_async_loading"], NO_KEYS, "server", "Asynchronously saves the database(s) to disk.", "O(1)", "[SCHEDULE]"),
+    command("bitcount", -2, READ, ONE_KEY, "bitmap", "Counts the number of set bits (population counting) in a string.", "O(N)", "key [start end [BYTE|BIT]]"),
+    command("bitop", -4, WRITE_GROW, (2, -1, 1), "bitmap", "Performs bitwise operations on multiple strings, and stores the result.", "O(N)", "AND|OR|XOR|NOT destkey key [key ...]"),
+    command("bitpos", -3, READ, ONE_KEY, "bitmap", "Finds the first set (1) or clear (0) bit in a string.", "O(N)", "key bit [start [end [BYTE|BIT]]]"),
+    command("blmove", 6, BLOCKING, TWO_KEYS, "list", "Pops an element from a list, pushes it to another list and returns it. Blocks until an element is available otherwise.", "O(1)", "source destination LEFT|RIGHT LEFT|RIGHT timeout"),
+    command("blpop", -3, BLOCKING, (1, -2, 1), "list", "Removes and returns the first element in a list. Blocks until an element is available otherwise.", "O(N) where N is the number of provided keys.", "key [key ...] timeout"),
+    command("brpop", -3, BLOCKING, (1, -2, 1), "list", "Removes and returns the last element in a list. Blocks until an element is available otherwise.", "O(N) where N is the number of provided keys.", "key [key ...] timeout"),
+    command("bzpopmax", -3, &["write", "blocking", "fast"], (1, -2, 1), "sorted_set", "Removes and returns the member with the highest score from one or more sorted sets. Blocks until a member is available otherwise.", "O(log(N)) with N being the number of elements in the sorted set.", "key [key ...] timeout"),
+    command("bzpopmin", -3, &["write", "blocking", "fast"], (1, -2, 1), "sorted_set", "Removes and returns the member with the lowest score from one or more sorted sets. Blocks until a member is available otherwise.", "O(log(N)) with N being the number of elements in the sorted set.", "key [key ...] timeout"),
+    container("client", CONNECTION, "connection", "A container for client connection commands.", &["getname", "id", "kill", "list", "pause", "setname", "unpause"]),
+    container("cluster", &["stale"], "cluster", "A container for Redis Cluster commands.", &["countkeysinslot", "getkeysinslot", "keyslot"]),
+    container("command", &["loading", "stale"], "server", "Returns detailed information about all commands.", &["count", "docs", "info", "list"]),
+    container("config", ADMIN, "server", "A container for server configuration commands.", &["get", "resetstat", "set"]),
+    command("copy", -3, WRITE_GROW, TWO_KEYS, "generic", "Copies the value of a key to a new key.", "O(N) worst case for collections, where N is the number of nested items. O(1) for string values.", "source destination [DB destination-db] [REPLACE]"),
+    command("dbsize", 1, READ_FAST, NO_KEYS, "server", "Returns the number of keys in the database.", "O(1)", ""),
+    container("debug", ADMIN, "server", "A container for debugging commands.", &["reload", "sleep"]),
+    command("del", -2, WRITE, ALL_KEYS, "generic", "Deletes one or more keys.", "O(N) where N is the number of keys that will be removed.", "key [key ...]"),
+    command("discard", 1, TRANSACTION, NO_KEYS, "transactions", "Discards a transaction.", "O(N), when N is the number of queued commands", ""),
+    command("exec", 1, &["noscript", "loading", "stale", "skip_slowlog"], NO_KEYS, "transactions", "Executes all commands in a transaction.", "Depends on commands in the transaction", ""),
+    command("exists", -2, READ_FAST, ALL_KEYS, "generic", "Determines whether one or more keys exist.", "O(N) where N is the number of keys to check.", "key [key ...]"),
+    command("expire", -3, WRITE_FAST, ONE_KEY, "generic", "Sets the expiration time of a key in seconds.", "O(1)", "key seconds"),
+    command("expireat", -3, WRITE_FAST, ONE_KEY, "generic", "Sets the expiration time of a key to a Unix timestamp.", "O(1)", "key unix-time-seconds"),
+    command("expiretime", 2, READ_FAST, ONE_KEY, "generic", "Returns the expiration time of a key as a Unix timestamp.", "O(1)", "key"),
+    command("flushall", -1, WRITE, NO_KEYS, "server", "Removes all keys from all databases.", "O(N) where N is the total number of keys in all databases", "[ASYNC|SYNC]"),
+    command("flushdb", -1, WRITE, NO_KEYS, "server", "Remove all keys from the current database.", "O(N) where N is the number of keys in the selected database", "[ASYNC|SYNC]"),
+    command("geoadd", -5, WRITE_GROW, ONE_KEY, "geo", "Adds one or more members to a geospatial index. The key is created if it doesn't exist.", "O(log(N)) for each item added, where N is the number of elements in the sorted set.", "key longitude latitude member [longitude latitude member ...]"),
+    command("georadiusbymember", -5, WRITE_GROW, ONE_KEY, "geo", "Queries a geospatial index for members within a distance from a member, optionally stores the result.", "O(N+log(M)) where N is the number of elements inside the bounding box of the circular area delimited by center and radius and M is the number of items inside the index.", "key member radius M|KM|FT|MI [WITHCOORD] [WITHDIST] [WITHHASH] [COUNT count] [ASC|DESC]"),
+    command("geosearch", -7, READ, ONE_KEY, "geo", "Queries a geospatial index for members inside an area of a box or a circle.", "O(N+log(M)) where N is the number of elements in the grid-aligned bounding box area around the shape provided as the filter and M is the number of items inside the shape", "key FROMMEMBER member|FROMLONLAT longitude latitude BYRADIUS radius unit|BYBOX width height unit [ASC|DESC] [COUNT count] [WITHCOORD] [WITHDIST] [WITHHASH]"),
+    command("get", 2, READ_FAST, ONE_KEY, "string", "Returns the string value of a key.", "O(1)", "key"),
+    command("getbit", 3, READ_FAST, ONE_KEY, "bitmap", "Returns a bit value by offset.", "O(1)", "key offset"),
+    command("getrange", 4, READ, ONE_KEY, "string", "Returns a substring of the string stored at a key.", "O(N) where N is the length of the returned string.", "key start end"),
+    command("hdel", -3, WRITE_FAST, ONE_KEY, "hash", "Deletes one or more fields and their values from a hash. Deletes the hash if no fields remain.", "O(N) where N is the number of fields to be removed.", "key field [field ...]"),
+    command("hexists", 3, READ_FAST, ONE_KEY, "hash", "Determines whether a field exists in a hash.", "O(1)", "key field"),
+    command("hget", 3, READ_FAST, ONE_KEY, "hash", "Returns the value of a field in a hash.", "O(1)", "key field"),
+    command("hgetall", 2, READ, ONE_KEY, "hash", "Returns all fields and values in a hash.", "O(N) where N is the size of the hash.", "key"),
+    command("hincrby", 4, WRITE_GROW_FAST, ONE_KEY, "hash", "Increments the integer value of a field in a hash by a number. Uses 0 as initial value if the field doesn't exist.", "O(1)", "key field increment"),
+    command("hincrbyfloat", 4, WRITE_GROW_FAST, ONE_KEY, "hash", "Increments the floating point value of a field by a number. Uses 0 as initial value if the field doesn't exist.", "O(1)", "key field increment"),
+    command("hkeys", 2, READ, ONE_KEY, "hash", "Returns all fields in a hash.", "O(N) where N is the size of the hash.", "key"),
+    command("hlen", 2, READ_FAST, ONE_KEY, "hash", "Returns the number of fields in a hash.", "O(1)", "key"),
+    command("hmget", -3, READ_FAST, ONE_KEY, "hash", "Returns the values of all fields in a hash.", "O(N) where N is the number of fields being requested.", "key field [field ...]"),
+    command("hmset", -4, WRITE_GROW_FAST, ONE_KEY, "hash", "Sets the values of multiple fields.", "O(N) where N is the number of fields being set.", "key field value [field value ...]"),
+    command("hrandfield", -2, READ, ONE_KEY, "hash", "Returns one or more random fields from a hash.", "O(N) where N is the number of fields returned", "key [count [WITHVALUES]]"),
+    command("hscan", -3, READ, ONE_KEY, "hash", "Iterates over fields and values of a hash.", "O(1) for every call. O(N) for a complete iteration, including enough command calls for the cursor to return back to 0. N is the number of elements inside the collection.", "key cursor [MATCH pattern] [COUNT count]"),
+    command("hset", -4, WRITE_GROW_FAST, ONE_KEY, "hash", "Creates or modifies the value of a field in a hash.", "O(1) for each field/value pair added, so O(N) to add N field/value pairs when the command is called with multiple field/value pairs.", "key field value [field value ...]"),
+    command("hsetnx", 4, WRITE_GROW_FAST, ONE_KEY, "hash", "Sets the value of a field in a hash only when the field doesn't exist.", "O(1)", "key field value"),
+    command("hvals", 2, READ, ONE_KEY, "hash", "Returns all values in a hash.", "O(N) where N is the size of the hash.", "key"),
+    command("info", -1, &["loading", "stale"], NO_KEYS, "server", "Returns information and statistics about the server.", "O(1)", "[section [section ...]]"),
+    command("keys", 2, READ, NO_KEYS, "generic", "Returns all key names that match a pattern.", "O(N) with N being the number of keys in the database", "pattern"),
+    command("lastsave", 1, &["fast", "loading", "stale"], NO_KEYS, "server", "Returns the Unix timestamp of the last successful save to disk.", "O(1)", ""),
+    container("latency", ADMIN, "server", "A container for latency diagnostics commands.", &["history", "latest", "reset"]),
+    command("lindex", 3, READ, ONE_KEY, "list", "Returns an element from a list by its index.", "O(N) where N is the number of elements to traverse to get to the element at index.", "key index"),
+    command("linsert", 5, WRITE_GROW, ONE_KEY, "list", "Inserts an element before or after another element in a list.", "O(N) where N is the number of elements to traverse before seeing the value pivot.", "key BEFORE|AFTER pivot element"),
+    command("llen", 2, READ_FAST, ONE_KEY, "list", "Returns the length of a list.", "O(1)", "key"),
+    command("lmove", 5, WRITE_GROW, TWO_KEYS, "list", "Returns an element after popping it from one list and pushing it to another. Deletes the list if the last element was moved.", "O(1)", "source destination LEFT|RIGHT LEFT|RIGHT"),
+    command("lmpop", -4, &["write", "movablekeys"], MOVABLE_KEYS, "list", "Returns multiple elements from a list after removing them. Deletes the list if the last element was popped.", "O(N+M) where N is the number of provided keys and M is the number of elements returned.", "numkeys key [key ...] LEFT|RIGHT [COUNT count]"),
+    command("lpop", -2, WRITE_FAST, ONE_KEY, "list", "Returns the first elements in a list after removing it. Deletes the list if the last element was popped.", "O(N) where N is the number of elements returned", "key [count]"),
+    command("lpos", -3, READ, ONE_KEY, "list", "Returns the index of matching elements in a list.", "O(N) where N is the number of elements in the list, for the average case.", "key element [RANK rank] [COUNT num-matches] [MAXLEN len]"),
+    command("lpush", -3, WRITE_GROW_FAST, ONE_KEY, "list", "Prepends one or more elements to a list. Creates the key if it doesn't exist.", "O(1) for each element added, so O(N) to add N elements when the command is called with multiple arguments.", "key element [element ...]"),
+    command("lrange", 4, READ, ONE_KEY, "list", "Returns a range of elements from a list.", "O(S+N) where S is the distance of start offset from HEAD for small lists, from nearest end (HEAD or TAIL) for large lists; and N is the number of elements in the specified range.", "key start stop"),
+    command("lrem", 4, WRITE, ONE_KEY, "list", "Removes elements from a list. Deletes the list if the last element was removed.", "O(N+M) where N is the length of the list and M is the number of elements removed.", "key count element"),
+    command("lset", 4, WRITE_GROW, ONE_KEY, "list", "Sets the value of an element in a list by its index.", "O(N) where N is the length of the list.", "key index element"),
+    command("ltrim", 4, WRITE, ONE_KEY, "list", "Removes elements from both ends a list. Deletes the list if all elements were trimmed.", "O(N) where N is the number of elements to be removed by the operation.", "key start stop"),
+    container("memory", &[], "server", "A container for memory diagnostics commands.", &["stats", "usage"]),
+    command("monitor", 1, ADMIN, NO_KEYS, "server", "Listens for all requests received by the server in real-time.", "", ""),
+    command("multi", 1, TRANSACTION, NO_KEYS, "transactions", "Starts a transaction.", "O(1)", ""),
+    container("object", &[], "generic", "A container for object introspection commands.", &["encoding", "freq", "help", "idletime", "refcount"]),
+    command("persist", 2, WRITE_FAST, ONE_KEY, "generic", "Removes the expiration time of a key.", "O(1)", "key"),
+    command("pexpire", -3, WRITE_FAST, ONE_KEY, "generic", "Sets the expiration time of a key in milliseconds.", "O(1)", "key milliseconds"),
+    command("pexpireat", -3, WRITE_FAST, ONE_KEY, "generic", "Sets the expiration time of a key to a Unix milliseconds timestamp.", "O(1)", "key unix-time-milliseconds"),
+    command("pexpiretime", 2, READ_FAST, ONE_KEY, "generic", "Returns the expiration time of a key as a Unix milliseconds timestamp.", "O(1)", "key"),
+    command("pfadd", -2, WRITE_GROW_FAST, ONE_KEY, "hyperloglog", "Adds elements to a HyperLogLog key. Creates the key if it doesn't exist.", "O(1) to add every element.", "key [element [element ...]]"),
+    command("pfcount", -2, READ, ALL_KEYS, "hyperloglog", "Returns the approximated cardinality of the set(s) observed by the HyperLogLog key(s).", "O(1) with a very small average constant time when called with a single key. O(N) with N being the number of keys, and much bigger constant times, when called with multiple keys.", "key [key ...]"),
+    command("pfmerge", -2, WRITE_GROW, ALL_KEYS, "hyperloglog", "Merges one or more HyperLogLog values into a single key.", "O(N) to merge N HyperLogLogs, but with high constant times.", "destkey [sourcekey [sourcekey ...]]"),
+    command("psubscribe", -2, PUBSUB, NO_KEYS, "pubsub", "Listens for messages published to channels that match one or more patterns.", "O(N) where N is the number of patterns to subscribe to.", "pattern [pattern ...]"),
+    command("psync", 3, &["admin", "noscript", "no_async_loading", "no_multi"], NO_KEYS, "server", "An internal command used in replication.", "", "replicationid offset"),
+    command("pttl", 2, READ_FAST, ONE_KEY, "generic", "Returns the expiration time in milliseconds of a key.", "O(1)", "key"),
+    command("publish", 3, PUBSUB_FAST, NO_KEYS, "pubsub", "Posts a message to a channel.", "O(N+M) where N is the number of clients subscribed to the receiving channel and M is the total number of subscribed patterns (by any client).", "channel message"),
+    container("pubsub", &[], "pubsub", "A container for Pub/Sub commands.", &["channels", "numpat", "numsub"]),
+    command("punsubscribe", -1, PUBSUB, NO_KEYS, "pubsub", "Stops listening to messages published to channels that match one or more patterns.", "O(N) where N is the number of patterns to unsubscribe.", "[pattern [pattern ...]]"),
+    command("quit", -1, &["noscript", "loading", "stale", "fast", "no_auth", "allow_busy"], NO_KEYS, "connection", "Closes the connection.", "O(1)", ""),
+    command("randomkey", 1, READ, NO_KEYS, "generic", "Returns a random key name from the database.", "O(1)", ""),
+    command("rename", 3, WRITE, TWO_KEYS, "generic", "Renames a key and overwrites the destination.", "O(1)", "key newkey"),
+    command("renamenx", 3, WRITE_FAST, TWO_KEYS, "generic", "Renames a key only when the target key name doesn't exist.", "O(1)", "key newkey"),
+    command("replconf", -1, &["admin", "noscript", "loading", "stale", "allow_busy"], NO_KEYS, "server", "An internal command for configuring the replication stream.", "O(1)", ""),
+    command("replicaof", 3, &["admin", "noscript", "stale", "no_async_loading"], NO_KEYS, "server", "Configures a server as replica of another, or promotes it to a master.", "O(1)", "host port"),
+    command("reset", 1, &["noscript", "loading", "stale", "fast", "no_auth", "allow_busy"], NO_KEYS, "connection", "Resets the connection.", "O(1)", ""),
+    command("rpop", -2, WRITE_FAST, ONE_KEY, "list", "Returns and removes the last elements of a list. Deletes the list if the last element was popped.", "O(N) where N is the number of elements returned", "key [count]"),
+    command("rpush", -3, WRITE_GROW_FAST, ONE_KEY, "list", "Appends one or more elements to a list. Creates the key if it doesn't exist.", "O(1) for each element added, so O(N) to add N elements when the command is called with multiple arguments.", "key element [element ...]"),
+    command("sadd", -3, WRITE_GROW_FAST, ONE_KEY, "set", "Adds one or more members to a set. Creates the key if it doesn't exist.", "O(1) for each element added, so O(N) to add N elements when the command is called with multiple arguments.", "key member [member ...]"),
+    command("save", 1, &["admin", "noscript", "no_async_loading", "no_multi"], NO_KEYS, "server", "Synchronously saves the database(s) to disk.", "O(N) where N is the total number of keys in all databases", ""),
+    command("scan", -2, READ, NO_KEYS, "generic", "Iterates over the key names in the database.", "O(1) for every call. O(N) for a complete iteration, including enough command calls for the cursor to return back to 0. N is the number of elements inside the collection.", "cursor [MATCH pattern] [COUNT count] [TYPE type]"),
+    command("scard", 2, READ_FAST, ONE_KEY, "set", "Returns the number of members in a set.", "O(1)", "key"),
+    command("sdiff", -2, READ, ALL_KEYS, "set", "Returns the difference of multiple sets.", "O(N) where N is the total number of elements in all given sets.", "key [key ...]"),
+    command("sdiffstore", -3, WRITE_GROW, ALL_KEYS, "set", "Stores the difference of multiple sets in a key.", "O(N) where N is the total number of elements in all given sets.", "destination key [key ...]"),
+    command("select", 2, &["loading", "stale", "fast"], NO_KEYS, "connection", "Changes the selected database.", "O(1)", "index"),
+    command("set", 3, WRITE_GROW, ONE_KEY, "string", "Sets the string value of a key, ignoring its type. The key is created if it doesn't exist.", "O(1)", "key value"),
+    command("setbit", 4, WRITE_GROW, ONE_KEY, "bitmap", "Sets or clears the bit at offset of the string value. Creates the key if it doesn't exist.", "O(1)", "key offset value"),
+    command("setrange", 4, WRITE_GROW, ONE_KEY, "string", "Overwrites a part of a string value with another by an offset. Creates the key if it doesn't exist.", "O(1), not counting the time taken to copy the new string in place.", "key offset value"),
+    command("sinter", -2, READ, ALL_KEYS, "set", "Returns the intersect of multiple sets.", "O(N*M) worst case where N is the cardinality of the smallest set and M is the number of sets.", "key [key ...]"),
+    command("sintercard", -3, &["readonly", "movablekeys"], MOVABLE_KEYS, "set", "Returns the number of members of the intersect of multiple sets.", "O(N*M) worst case where N is the cardinality of the smallest set and M is the number of sets.", "numkeys key [key ...] [LIMIT limit]"),
+    command("sinterstore", -3, WRITE_GROW, ALL_KEYS, "set", "Stores the intersect of multiple sets in a key.", "O(N*M) worst case where N is the cardinality of the smallest set and M is the number of sets.", "destination key [key ...]"),
+    command("sismember", 3, READ_FAST, ONE_KEY, "set", "Determines whether a member belongs to a set.", "O(1)", "key member"),
+    command("slaveof", 3, &["admin", "noscript", "stale", "no_async_loading"], NO_KEYS, "server", "Sets a Redis server as a replica of another, or promotes it to being a master.", "O(1)", "host port"),
+    container("slowlog", ADMIN, "server", "A container for slow log commands.", &["get", "len", "reset"]),
+    command("smembers", 2, READ, ONE_KEY, "set", "Returns all members of a set.", "O(N) where N is the set cardinality.", "key"),
+    command("smismember", -3, READ_FAST, ONE_KEY, "set", "Determines whether multiple members belong to a set.", "O(N) where N is the number of elements being checked for membership", "key member [member ...]"),
+    command("smove", 4, WRITE_FAST, TWO_KEYS, "set", "Moves a member from one set to another.", "O(1)", "source destination member"),
+    command("spop", -2, WRITE_FAST, ONE_KEY, "set", "Returns one or more random members from a set after removing them. Deletes the set if the last member was popped.", "Without the count argument O(1), otherwise O(N) where N is the value of the passed count.", "key [count]"),
+    command("srandmember", -2, READ, ONE_KEY, "set", "Get one or multiple random members from a set", "Without the count argument O(1), otherwise O(N) where N is the absolute value of the passed count.", "key [count]"),
+    command("srem", -3, WRITE_FAST, ONE_KEY, "set", "Removes one or more members from a set. Deletes the set if the last member was removed.", "O(N) where N is the number of members to be removed.", "key member [member ...]"),
+    command("strlen", 2, READ_FAST, ONE_KEY, "string", "Returns the length of a string value.", "O(1)", "key"),
+    command("subscribe", -2, PUBSUB, NO_KEYS, "pubsub", "Listens for messages published to channels.", "O(N) where N is the number of channels to subscribe to.", "channel [channel ...]"),
+    command("substr", 4, READ, ONE_KEY, "string", "Returns a substring from a string value.", "O(N) where N is the length of the returned string.", "key start end"),
+    command("sunion", -2, READ, ALL_KEYS, "set", "Returns the union of multiple sets.", "O(N) where N is the total number of elements in all given sets.", "key [key ...]"),
+    command("sunionstore", -3, WRITE_GROW, ALL_KEYS, "set", "Stores the union of multiple sets in a key.", "O(N) where N is the total number of elements in all given sets.", "destination key [key ...]"),
+    command("swapdb", 3, &["write", "fast"], NO_KEYS, "server", "Swaps two Redis databases.", "O(N) where N is the count of clients watching or blocking on keys from both databases.", "index1 index2"),
+    command("ttl", 2, READ_FAST, ONE_KEY, "generic", "Returns the expiration time in seconds of a key.", "O(1)", "key"),
+    command("type", 2, READ_FAST, ONE_KEY, "generic", "Determines the type of value stored at a key.", "O(1)", "key"),
+    command("unlink", -2, WRITE_FAST, ALL_KEYS, "generic", "Asynchronously deletes one or more keys.", "O(1) for each key removed regardless of its size. Then the command does O(N) work in a different thread in order to reclaim memory, where N is the number of allocations the deleted objects where composed of.", "key [key ...]"),
+    command("unsubscribe", -1, PUBSUB, NO_KEYS, "pubsub", "Stops listening to messages posted to channels.", "O(N) where N is the number of channels to unsubscribe.", "[channel [channel ...]]"),
+    command("unwatch", 1, TRANSACTION, NO_KEYS, "transactions", "Forgets about watched keys of a transaction.", "O(1)", ""),
+    command("wait", 3, &["noscript"], NO_KEYS, "generic", "Blocks until the asynchronous replication of all preceding write commands sent by the connection is completed.", "O(1)", "numreplicas timeout"),
+    command("watch", -2, TRANSACTION, ALL_KEYS, "transactions", "Monitors changes to keys to determine the execution of a transaction.", "O(1) for every key.", "key [key ...]"),
+    command("zadd", -4, WRITE_GROW_FAST, ONE_KEY, "sorted_set", "Adds one or more members to a sorted set, or updates their scores. Creates the key if it doesn't exist.", "O(log(N)) for each item added, where N is the number of elements in the sorted set.", "key [NX|XX] [GT|LT] [CH] [INCR] score member [score member ...]"),
+    command("zcard", 2, READ_FAST, ONE_KEY, "sorted_set", "Returns the number of members in a sorted set.", "O(1)", "key"),
+    command("zcount", 4, READ_FAST, ONE_KEY, "sorted_set", "Returns the count of members in a sorted set that have scores within a range.", "O(log(N)) with N being the number of elements in the sorted set.", "key min max"),
+    command("zdiff", -3, &["readonly", "movablekeys"], MOVABLE_KEYS, "sorted_set", "Returns the difference between multiple sorted sets.", "O(L + (N-K)log(N)) worst case where L is the total number of elements in all the sets, N is the size of the first set, and K is the size of the result set.", "numkeys key [key ...] [WITHSCORES]"),
+    command("zdiffstore", -4, &["write", "denyoom", "movablekeys"], ONE_KEY, "sorted_set", "Stores the difference of multiple sorted sets in a key.", "O(L + (N-K)log(N)) worst case where L is the total number of elements in all the sets, N is the size of the first set, and K is the size of the result set.", "destination numkeys key [key ...]"),
+    command("zincrby", 4, WRITE_GROW_FAST, ONE_KEY, "sorted_set", "Increments the score of a member in a sorted set.", "O(log(N)) where N is the number of elements in the sorted set.", "key increment member"),
+    command("zinter", -3, &["readonly", "movablekeys"], MOVABLE_KEYS, "sorted_set", "Returns the intersect of multiple sorted sets.", "O(N*K)+O(M*log(M)) worst case with N being the smallest input sorted set, K being the number of input sorted sets and M being the number of elements in the resulting sorted set.", "numkeys key [key ...] [WEIGHTS weight [weight ...]] [AGGREGATE SUM|MIN|MAX] [WITHSCORES]"),
+    command("zintercard", -3, &["readonly", "movablekeys"], MOVABLE_KEYS, "sorted_set", "Returns the number of members of the intersect of multiple sorted sets.", "O(N*K) worst case with N being the smallest input sorted set, K being the number of input sorted sets.", "numkeys key [key ...] [LIMIT limit]"),
+    command("zinterstore", -4, &["write", "denyoom", "movablekeys"], ONE_KEY, "sorted_set", "Stores the intersect of multiple sorted sets in a key.", "O(N*K)+O(M*log(M)) worst case with N being the smallest input sorted set, K being the number of input sorted sets and M being the number of elements in the resulting sorted set.", "destination numkeys key [key ...] [WEIGHTS weight [weight ...]] [AGGREGATE SUM|MIN|MAX]"),
+    command("zlexcount", 4, READ_FAST, ONE_KEY, "sorted_set", "Returns the number of members in a sorted set within a lexicographical range.", "O(log(N)) with N being the number of elements in the sorted set.", "key min max"),
+    command("zmscore", -3, READ_FAST, ONE_KEY, "sorted_set", "Returns the score of one or more members in a sorted set.", "O(N) where N is the number of members being requested.", "key member [member ...]"),
+    command("zpopmax", -2, WRITE_FAST, ONE_KEY, "sorted_set", "Returns the highest-scoring members from a sorted set after removing them. Deletes the sorted set if the last member was popped.", "O(log(N)*M) with N being the number of elements in the sorted set, and M being the number of elements popped.", "key [count]"),
+    command("zpopmin", -2, WRITE_FAST, ONE_KEY, "sorted_set", "Returns the lowest-scoring members from a sorted set after removing them. Deletes the sorted set if the last member was popped.", "O(log(N)*M) with N being the number of elements in the sorted set, and M being the number of elements popped.", "key [count]"),
+    command("zrandmember", -2, READ, ONE_KEY, "sorted_set", "Returns one or more random members from a sorted set.", "O(N) where N is the number of members returned", "key [count [WITHSCORES]]"),
+    command("zrange", -4, READ, ONE_KEY, "sorted_set", "Returns members in a sorted set within a range of indexes.", "O(log(N)+M) with N being the number of elements in the sorted set and M the number of elements returned.", "key start stop [BYSCORE|BYLEX] [REV] [LIMIT offset count] [WITHSCORES]"),
+    command("zrangebylex", -4, READ, ONE_KEY, "sorted_set", "Returns members in a sorted set within a lexicographical range.", "O(log(N)+M) with N being the number of elements in the sorted set and M the number of elements being returned.", "key min max [LIMIT offset count]"),
+    command("zrangebyscore", -4, READ, ONE_KEY, "sorted_set", "Returns members in a sorted set within a range of scores.", "O(log(N)+M) with N being the number of elements in the sorted set and M the number of elements being returned.", "key min max [WITHSCORES] [LIMIT offset count]"),
+    command("zrangestore", -5, WRITE_GROW, TWO_KEYS, "sorted_set", "Stores a range of members from sorted set in a key.", "O(log(N)+M) with N being the number of elements in the sorted set and M the number of elements stored into the destination key.", "dst src min max [BYSCORE|BYLEX] [REV] [LIMIT offset count]"),
+    command("zrank", -3, READ_FAST, ONE_KEY, "sorted_set", "Returns the index of a member in a sorted set ordered by ascending scores.", "O(log(N))", "key member [WITHSCORE]"),
+    command("zrevrangebylex", -4, READ, ONE_KEY, "sorted_set", "Returns members in a sorted set within a lexicographical range in reverse order.", "O(log(N)+M) with N being the number of elements in the sorted set and M the number of elements being returned.", "key max min [LIMIT offset count]"),
+    command("zrevrangebyscore", -4, READ, ONE_KEY, "sorted_set", "Returns members in a sorted set within a range of scores in reverse order.", "O(log(N)+M) with N being the number of elements in the sorted set and M the number of elements being returned.", "key max min [WITHSCORES] [LIMIT offset count]"),
+    command("zrevrank", -3, READ_FAST, ONE_KEY, "sorted_set", "Returns the index of a member in a sorted set ordered by descending scores.", "O(log(N))", "key member [WITHSCORE]"),
+    command("zscore", 3, READ_FAST, ONE_KEY, "sorted_set", "Returns the score of a member in a sorted set.", "O(1)", "key member"),
+    command("zunion", -3, &["readonly", "movablekeys"], MOVABLE_KEYS, "sorted_set", "Returns the union of multiple sorted sets.", "O(N)+O(M*log(M)) with N being the sum of the sizes of the input sorted sets, and M being the number of elements in the resulting sorted set.", "numkeys key [key ...] [WEIGHTS weight [weight ...]] [AGGREGATE SUM|MIN|MAX] [WITHSCORES]"),
+    command("zunionstore", -4, &["write", "denyoom", "movablekeys"], ONE_KEY, "sorted_set", "Stores the union of multiple sorted sets in a key.", "O(N)+O(M*log(M)) with N being the sum of the sizes of the input sorted sets, and M being the number of elements in the resulting sorted set.", "destination numkeys key [key ...] [WEIGHTS weight [weight ...]] [AGGREGATE SUM|MIN|MAX]"),
+];
+
+// argument names that are key names
+const KEY_ARGUMENTS: [&str; 7] = [
+    "key",
+    "source",
+    "destination",
+    "newkey",
+    "dst",
+    "src",
+    "keys",
+];
+
+/// the metadata of `name`, case insensitive
+pub fn command_metadata(name: &str) -> Option<&'static CommandMetadata> {
+    let name = name.to_ascii_lowercase();
+    COMMANDS.iter().find(|meta| meta.name == name)
+}
+
+impl CommandMetadata {
+    /// the ACL categories a command belongs to, derived from its flags and group
+    pub fn acl_categories(&self) -> Vec<&'static str> {
+        let mut categories = vec![];
+        let flag = |f: &str| self.flags.contains(&f);
+        if flag("write") {
+            categories.push("write");
+        }
+        if flag("readonly") {
+            categories.push("read");
+        }
+        if flag("admin") {
+            categories.extend(["admin", "dangerous"]);
+        }
+        if flag("pubsub") {
+            categories.push("pubsub");
+        }
+        if flag("blocking") {
+            categories.push("blocking");
+        }
+        categories.push(if flag("fast") { "fast" } else { "slow" });
+        match self.group {
+            "generic" => categories.push("keyspace"),
+            "sorted_set" => categories.push("sortedset"),
+            "transactions" => categories.push("transaction"),
+            "string" | "list" | "hash" | "set" | "geo" | "hyperloglog" | "bitmap"
+            | "connection" => categories.push(self.group),
+            _ => {}
+        }
+        categories
+    }
+
+    // [name, arity, [flag ...], first key, last key, step, [@category ...]]
+    fn info_frame(&self) -> RespFrame {
+        let flags = self
+            .flags
+            .iter()
+            .map(|flag| SimpleString::new(*flag).into())
+            .collect::<Vec<RespFrame>>();
+        let categories = self
+            .acl_categories()
+            .into_iter()
+            .map(|category| SimpleString::new(format!("@{}", category)).into())
+            .collect::<Vec<RespFrame>>();
+        RespArray::new(vec![
+            BulkString::new(self.name).into(),
+            RespFrame::Integer(self.arity),
+            RespArray::new(flags).into(),
+            RespFrame::Integer(self.first_key),
+            RespFrame::Integer(self.last_key),
+            RespFrame::Integer(self.step),
+            RespArray::new(categories).into(),
+        ])
+        .into()
+    }
+
+    fn docs_frame(&self) -> RespFrame {
+        let arguments = arguments(self.syntax)
+            .into_iter()
+            .map(|arg| arg.into())
+            .collect::<Vec<RespFrame>>();
+        let mut docs = RespMap::new();
+        docs.insert("summary".to_string(), BulkString::new(self.summary).into());
+        docs.insert("group".to_string(), BulkString::new(self.group).into());
+        docs.insert(
+            "complexity".to_string(),
+            BulkString::new(self.complexity).into(),
+        );
+        docs.insert("arguments".to_string(), RespArray::new(arguments).into());
+        if !self.subcommands.is_empty() {
+            let subcommands = self
+                .subcommands
+                .iter()
+                .map(|sub| BulkString::new(format!("{}|{}", self.name, sub)).into())
+                .collect::<Vec<RespFrame>>();
+            docs.insert(
+                "subcommands".to_string(),
+                RespArray::new(subcommands).into(),
+            );
+        }
+        docs.into()
+    }
+}
+
+struct Argument {
+    name: String,
+    kind: &'static str,
+    display: Option<String>,
+    flags: Vec<&'static str>,
+}
+
+impl From<Argument> for RespFrame {
+    fn from(arg: Argument) -> Self {
+        let mut map = RespMap::new();
+        map.insert("name".to_string(), BulkString::new(arg.name).into());
+        map.insert("type".to_string(), BulkString::new(arg.kind).into());
+        if let Some(display) = arg.display {
+            map.insert("display_text".to_string(), BulkString::new(display).into());
+        }
+        if !arg.flags.is_empty() {
+            let flags = arg
+                .flags
+                .into_iter()
+                .map(|flag| SimpleString::new(flag).into())
+                .collect::<Vec<RespFrame>>();
+            map.insert("flags".to_string(), RespArray::new(flags).into());
+        }
+        map.into()
+    }
+}
+
+// the top level arguments of a syntax line. `[...]` marks an optional argument, `x [x ...]`
+// one that repeats, `|` alternatives and a group of words a block
+fn arguments(syntax: &str) -> Vec<Argument> {
+    let mut groups = vec![];
+    let (mut depth, mut current) = (0, String::new());
+    for c in syntax.chars() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            ' ' if depth == 0 => {
+                groups.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    groups.push(current);
+
+    let mut ret: Vec<Argument> = vec![];
+    for group in groups.into_iter().filter(|g| !g.is_empty()) {
+        let optional = group.starts_with('[') && group.ends_with(']');
+        let inner = if optional {
+            &group[1..group.len() - 1]
+        } else {
+            group.as_str()
+        };
+        // "key [key ...]" is a single key argument that repeats
+        if let Some(name) = inner.strip_suffix(" ...") {
+            if let Some(last) = ret.last_mut().filter(|arg| arg.name == name) {
+                last.flags.push("multiple");
+                continue;
+            }
+        }
+
+        let first = inner
+            .split([' ', '|', '['])
+            .find(|s| !s.is_empty())
+            .unwrap_or(inner);
+        let kind = if inner.contains('|') {
+            "oneof"
+        } else if inner.contains(' ') {
+            "block"
+        } else if first.chars().all(|c| c.is_ascii_uppercase() || c == '-') {
+            "pure-token"
+        } else if KEY_ARGUMENTS.contains(&first) {
+            "key"
+        } else {
+            "string"
+        };
+        let mut arg = Argument {
+            name: first.to_ascii_lowercase(),
+            kind,
+            display: (kind != "key" && kind != "string").then(|| inner.to_string()),
+            flags: vec![],
+        };
+        if optional {
+            arg.flags.push("optional");
+        }
+        if inner.ends_with(" ...]") {
+            arg.flags.push("multiple");
+        }
+        ret.push(arg);
+    }
+    ret
+}
+
+impl CommandExecutor for CommandCount {
+    fn execute(self, _: &Backend) -> RespFrame {
+        RespFrame::Integer(COMMANDS.len() as i64)
+    }
+}
+
+impl CommandExecutor for CommandInfo {
+    fn execute(self, _: &Backend) -> RespFrame {
+        if self.names.is_empty() {
+            return RespArray::new(
+                COMMANDS
+                    .iter()
+                    .map(|meta| meta.info_frame())
+                    .collect::<Vec<_>>(),
+            )
+            .into();
+        }
+        RespArray::new(
+            self.names
+                .iter()
+                .map(|name| match command_metadata(name) {
+                    Some(meta) => meta.info_frame(),
+                    None => RespNullArray.into(),
+                })
+                .collect::<Vec<_>>(),
+        )
+        .into()
+    }
+}
+
+impl CommandExecutor for CommandDocs {
+    fn execute(self, _: &Backend) -> RespFrame {
+        // unknown names are left out
+        let mut ret = RespMap::new();
+        let metas: Vec<&CommandMetadata> = if self.names.is_empty() {
+            COMMANDS.iter().collect()
+        } else {
+            self.names
+                .iter()
+                .filter_map(|name| command_metadata(name))
+                .collect()
+        };
+        for meta in metas {
+            ret.insert(meta.name.to_string(), meta.docs_frame());
+        }
+        ret.into()
+    }
+}
+
+impl CommandExecutor for CommandList {
+    fn execute(self, _: &Backend) -> RespFrame {
+        let names = COMMANDS
+            .iter()
+            .filter(|meta| match &self.filter {
+                None => true,
+                // no modules can be loaded
+                Some(CommandFilter::Module(_)) => false,
+                Some(CommandFilter::AclCat(category)) => meta
+                    .acl_categories()
+                    .iter()
+                    .any(|c| c.eq_ignore_ascii_case(category)),
+                Some(CommandFilter::Pattern(pattern)) => {
+                    glob_match(pattern.as_bytes(), meta.name.as_bytes())
+                }
+            })
+            .map(|meta| BulkString::new(meta.name).into())
+            .collect::<Vec<RespFrame>>();
+        RespArray::new(names).into()
+    }
+}
+
+impl TryFrom<RespArray> for CommandCount {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["command", "count"], 0)?;
+        Ok(CommandCount)
+    }
+}
+
+fn extract_names(value: RespArray) -> Result<Vec<String>, CommandError> {
+    extract_args(value, 2)?
+        .into_iter()
+        .map(|arg| extract_string(Some(arg)))
+        .collect()
+}
+
+impl TryFrom<RespArray> for CommandInfo {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["command", "info"], 0)?;
+        Ok(CommandInfo {
+            names: extract_names(value)?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for CommandDocs {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["command", "docs"], 0)?;
+        Ok(CommandDocs {
+            names: extract_names(value)?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for CommandList {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["command", "list"], 0)?;
+        let mut args = extract_args(value, 2)?.into_iter();
+        let filter = match args.next() {
+            None => None,
+            Some(arg) => {
+                let option = extract_string(Some(arg))?.to_ascii_lowercase();
+                if option != "filterby" {
+                    return Err(CommandError::InvalidArgument(format!(
+                        "Invalid option: {}",
+                        option
+                    )));
+                }
+                let kind = extract_string(args.next())?.to_ascii_lowercase();
+                let arg = extract_string(args.next())?;
+                Some(match kind.as_str() {
+                    "module" => CommandFilter::Module(arg),
+                    "aclcat" => CommandFilter::AclCat(arg),
+                    "pattern" => CommandFilter::Pattern(arg),
+                    v => {
+                        return Err(CommandError::InvalidArgument(format!(
+                            "Invalid filter: {}",
+                            v
+                        )))
+                    }
+                })
+            }
+        };
+        if args.next().is_some() {
+            return Err(CommandError::InvalidArgument(
+                "command list command has too many arguments".to_string(),
+            ));
+        }
+        Ok(CommandList { filter })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{cmd::Command, RespDecode};
+
+    use super::*;
+
+    fn bulk(s: &str) -> RespFrame {
+        BulkString::new(s).into()
+    }
+
+    fn request(args: &[&str]) -> RespArray {
+        RespArray::new(args.iter().map(|arg| bulk(arg)).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_command_try_from_resp_array() -> Result<()> {
+        let mut buf =
+            BytesMut::from("*4\r\n$7\r\ncommand\r\n$4\r\ninfo\r\n$3\r\nget\r\n$3\r\nSET\r\n");
+        let cmd: CommandInfo = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.names, vec!["get", "SET"]);
+
+        let cmd: CommandList =
+            request(&["command", "list", "FILTERBY", "pattern", "z*"]).try_into()?;
+        assert_eq!(cmd.filter, Some(CommandFilter::Pattern("z*".to_string())));
+
+        let ret: Result<CommandList, _> =
+            request(&["command", "list", "FILTERBY", "x", "y"]).try_into();
+        assert!(ret.is_err());
+        let ret: Result<CommandCount, _> = request(&["command", "count", "x"]).try_into();
+        assert!(ret.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_commands_are_registered() -> Result<()> {
+        // the table is sorted and every entry is something the dispatcher knows
+        assert!(COMMANDS.windows(2).all(|w| w[0].name < w[1].name));
+        for meta in COMMANDS {
+            let mut args = vec![meta.name];
+            args.extend(meta.subcommands.first());
+            let cmd = Command::try_from(request(&args));
+            assert!(
+                !matches!(cmd, Ok(Command::Unrecognized(_))),
+                "{} is not dispatched",
+                meta.name
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_command_count_and_info() {
+        let backend = Backend::new();
+        assert_eq!(
+            CommandCount.execute(&backend),
+            RespFrame::Integer(COMMANDS.len() as i64)
+        );
+
+        let info = CommandInfo {
+            names: vec!["GET".to_string(), "nosuch".to_string()],
+        }
+        .execute(&backend);
+        let expected = RespArray::new(vec![
+            RespArray::new(vec![
+                bulk("get"),
+                RespFrame::Integer(2),
+                RespArray::new(vec![
+                    SimpleString::new("readonly").into(),
+                    SimpleString::new("fast").into(),
+                ])
+                .into(),
+                RespFrame::Integer(1),
+                RespFrame::Integer(1),
+                RespFrame::Integer(1),
+                RespArray::new(vec![
+                    SimpleString::new("@read").into(),
+                    SimpleString::new("@fast").into(),
+                    SimpleString::new("@string").into(),
+                ])
+                .into(),
+            ])
+            .into(),
+            RespNullArray.into(),
+        ]);
+        assert_eq!(info, expected.into());
+
+        match (CommandInfo { names: vec![] }).execute(&backend) {
+            RespFrame::Array(all) => assert_eq!(all.len(), COMMANDS.len()),
+            v => panic!("unexpected reply {:?}", v),
+        }
+    }
+
+    #[test]
+    fn test_command_docs() {
+        let backend = Backend::new();
+        let docs = CommandDocs {
+            names: vec!["lpush".to_string(), "nosuch".to_string()],
+        }
+        .execute(&backend);
+        let RespFrame::Map(docs) = docs else {
+            panic!("unexpected reply {:?}", docs);
+        };
+        assert_eq!(docs.len(), 1);
+        let Some(RespFrame::Map(lpush)) = docs.get("lpush") else {
+            panic!("lpush is not documented");
+        };
+        assert_eq!(lpush.get("group"), Some(&bulk("list")));
+        let Some(RespFrame::Array(arguments)) = lpush.get("arguments") else {
+            panic!("lpush has no arguments");
+        };
+        let mut key = RespMap::new();
+        key.insert("name".to_string(), bulk("key"));
+        key.insert("type".to_string(), bulk("key"));
+        let mut element = RespMap::new();
+        element.insert("name".to_string(), bulk("element"));
+        element.insert("type".to_string(), bulk("string"));
+        element.insert(
+            "flags".to_string(),
+            RespArray::new(vec![SimpleString::new("multiple").into()]).into(),
+        );
+        assert_eq!(arguments, &RespArray::new(vec![key.into(), element.into()]));
+    }
+
+    #[test]
+    fn test_command_list() {
+        let backend = Backend::new();
+        let list = |filter| CommandList { filter }.execute(&backend);
+
+        match list(None) {
+            RespFrame::Array(all) => assert_eq!(all.len(), COMMANDS.len()),
+            v => panic!("unexpected reply {:?}", v),
+        }
+        assert_eq!(
+            list(Some(CommandFilter::Pattern("hk*".to_string()))),
+            RespArray::new(vec![bulk("hkeys")]).into()
+        );
+        assert_eq!(
+            list(Some(CommandFilter::AclCat("Transaction".to_string()))),
+            RespArray::new(vec![
+                bulk("discard"),
+                bulk("exec"),
+                bulk("multi"),
+                bulk("unwatch"),
+                bulk("watch")
+            ])
+            .into()
+        );
+        assert_eq!(
+            list(Some(CommandFilter::Module("json".to_string()))),
+            RespArray::new(vec![]).into()
+        );
+    }
+}
@@ -0,0 +1,256 @@
+use crate::{
+    config::PARAMETERS, persistence::AppendFsync, Backend, BulkString, RespArray, RespFrame,
+    ServerConfig, SimpleError,
+};
+
+use super::{
+    extract_args, extract_string, glob_match, validate_command, validate_variadic_command,
+    CommandError, CommandExecutor, RESP_OK,
+};
+
+/// CONFIG GET pattern [pattern ...]
+#[derive(Debug)]
+pub struct ConfigGet {
+    pub patterns: Vec<String>,
+}
+
+/// CONFIG SET parameter value [parameter value ...]
+#[derive(Debug)]
+pub struct ConfigSet {
+    pub params: Vec<(String, String)>,
+}
+
+#[derive(Debug)]
+pub struct ConfigResetStat;
+
+impl CommandExecutor for ConfigGet {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let config = backend.config();
+        // a flat list of name, value pairs, each parameter once even if several patterns match
+        let mut ret = Vec::new();
+        for name in PARAMETERS {
+            let matched = self
+                .patterns
+                .iter()
+                .any(|p| glob_match(p.to_ascii_lowercase().as_bytes(), name.as_bytes()));
+            if let (true, Some(value)) = (matched, config.get(name)) {
+                ret.push(BulkString::new(name).into());
+                ret.push(BulkString::new(value).into());
+            }
+        }
+        RespArray::new(ret).into()
+    }
+}
+
+impl CommandExecutor for ConfigSet {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        // all or nothing: apply to a copy and only keep it if every parameter was accepted
+        let mut config = backend.config_mut();
+        let mut updated = config.clone();
+        for (name, value) in self.params.iter() {
+            if let Err(e) = updated.set_live(name, value) {
+                return SimpleError::new(format!("ERR CONFIG SET failed: {}", e)).into();
+            }
+        }
+        let aof_changed =
+            (config.appendonly, &config.appendfsync) != (updated.appendonly, &updated.appendfsync);
+        let backlog_changed = config.repl_backlog_size != updated.repl_backlog_size;
+        *config = updated;
+        if aof_changed {
+            apply_aof_config(backend, &config);
+        }
+        if backlog_changed {
+            backend
+                .replication()
+                .resize_backlog(config.repl_backlog_size as usize);
+        }
+        RESP_OK.clone()
+    }
+}
+
+// turning appendonly on starts the log with a rewrite of the current dataset
+fn apply_aof_config(backend: &Backend, config: &ServerConfig) {
+    let aof = backend.persistence().aof();
+    let fsync = AppendFsync::from_config(&config.appendfsync);
+    match (config.appendonly, aof.is_enabled()) {
+        (true, false) => aof.start(config.aof_path(), fsync, Some(backend.snapshot())),
+        (true, true) => aof.set_fsync(fsync),
+        (false, true) => aof.stop(),
+        (false, false) => {}
+    }
+}
+
+impl CommandExecutor for ConfigResetStat {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        backend.stats().reset();
+        RESP_OK.clone()
+    }
+}
+
+impl TryFrom<RespArray> for ConfigGet {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["config", "get"], 1)?;
+
+        let patterns = extract_args(value, 2)?
+            .into_iter()
+            .map(|arg| extract_string(Some(arg)))
+            .collect::<Result<_, _>>()?;
+        Ok(ConfigGet { patterns })
+    }
+}
+
+impl TryFrom<RespArray> for ConfigResetStat {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["config", "resetstat"], 0)?;
+        Ok(ConfigResetStat)
+    }
+}
+
+impl TryFrom<RespArray> for ConfigSet {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["config", "set"], 2)?;
+        if !value.len().is_multiple_of(2) {
+            return Err(CommandError::InvalidArgument(
+                "config set takes parameter value pairs".to_string(),
+            ));
+        }
+
+        let mut args = extract_args(value, 2)?.into_iter();
+        let mut params = Vec::new();
+        while let Some(name) = args.next() {
+            params.push((extract_string(Some(name))?, extract_string(args.next())?));
+        }
+        Ok(ConfigSet { params })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{cmd::Info, ConnectionInfo, RespDecode};
+
+    use super::*;
+
+    #[test]
+    fn test_config_set_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from(
+            "*4\r\n$6\r\nconfig\r\n$3\r\nset\r\n$11\r\nrequirepass\r\n$6\r\nsecret\r\n",
+        );
+        let cmd: ConfigSet = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(
+            cmd.params,
+            vec![("requirepass".to_string(), "secret".to_string())]
+        );
+
+        let mut buf = BytesMut::from("*3\r\n$6\r\nconfig\r\n$3\r\nset\r\n$11\r\nrequirepass\r\n");
+        let ret: Result<ConfigSet, _> = RespArray::decode(&mut buf)?.try_into();
+        assert!(ret.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_set_requirepass() {
+        let backend = Backend::new();
+        let set = |params: &[(&str, &str)]| ConfigSet {
+            params: params
+                .iter()
+                .map(|(n, v)| (n.to_string(), v.to_string()))
+                .collect(),
+        };
+
+        assert_eq!(
+            set(&[("requirepass", "secret")]).execute(&backend),
+            RESP_OK.clone()
+        );
+        assert_eq!(backend.config().requirepass.as_deref(), Some("secret"));
+
+        // an unknown parameter rejects the whole command
+        let ret = set(&[("requirepass", "other"), ("unknown", "1")]).execute(&backend);
+        assert!(matches!(ret, RespFrame::Error(_)));
+        assert_eq!(backend.config().requirepass.as_deref(), Some("secret"));
+
+        set(&[("requirepass", "")]).execute(&backend);
+        assert_eq!(backend.config().requirepass, None);
+    }
+
+    fn get(backend: &Backend, patterns: &[&str]) -> RespFrame {
+        ConfigGet {
+            patterns: patterns.iter().map(|p| p.to_string()).collect(),
+        }
+        .execute(backend)
+    }
+
+    fn pairs(pairs: &[(&str, &str)]) -> RespFrame {
+        RespArray::new(
+            pairs
+                .iter()
+                .flat_map(|(n, v)| [BulkString::new(*n).into(), BulkString::new(*v).into()])
+                .collect::<Vec<RespFrame>>(),
+        )
+        .into()
+    }
+
+    #[test]
+    fn test_config_get_try_from_resp_array() -> Result<()> {
+        let mut buf =
+            BytesMut::from("*4\r\n$6\r\nconfig\r\n$3\r\nget\r\n$2\r\nhz\r\n$4\r\nport\r\n");
+        let cmd: ConfigGet = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.patterns, vec!["hz".to_string(), "port".to_string()]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_get_and_set() {
+        let backend = Backend::new();
+        let cmd = ConfigSet {
+            params: vec![("hz".to_string(), "20".to_string())],
+        };
+        assert_eq!(cmd.execute(&backend), RESP_OK.clone());
+        assert_eq!(get(&backend, &["hz"]), pairs(&[("hz", "20")]));
+
+        assert_eq!(
+            get(&backend, &["maxmemory*", "MAXMEMORY"]),
+            pairs(&[("maxmemory", "0"), ("maxmemory-policy", "noeviction")])
+        );
+        assert_eq!(get(&backend, &["unknown"]), pairs(&[]));
+
+        // invalid values and parameters fixed at startup are rejected
+        for (name, value) in [("hz", "fast"), ("port", "7000")] {
+            let cmd = ConfigSet {
+                params: vec![(name.to_string(), value.to_string())],
+            };
+            assert!(matches!(cmd.execute(&backend), RespFrame::Error(_)));
+        }
+        assert_eq!(get(&backend, &["port"]), pairs(&[("port", "6379")]));
+    }
+
+    #[test]
+    fn test_config_resetstat() {
+        let backend = Backend::new();
+        backend.register_client(ConnectionInfo::new(1, "127.0.0.1:1"));
+        for _ in 0..3 {
+            backend.stats().command_processed();
+        }
+        backend.stats().sample_ops();
+        backend.stats().connection_rejected();
+
+        assert_eq!(ConfigResetStat.execute(&backend), RESP_OK.clone());
+        let stats = Info {
+            sections: vec!["stats".to_string()],
+        }
+        .execute(&backend);
+        assert_eq!(
+            stats,
+            BulkString::new(
+                "# Stats\r\ntotal_connections_received:0\r\ntotal_commands_processed:0\r\n\
+                 instantaneous_ops_per_sec:0\r\nrejected_connections:0\r\n"
+            )
+            .into()
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use crate::{Backend, Db, Entry, RespArray, RespFrame, SimpleError};
+
+use super::{
+    extract_args, extract_string, parse_number, validate_variadic_command, CommandError,
+    CommandExecutor,
+};
+
+#[derive(Debug)]
+pub struct Copy {
+    pub src: String,
+    pub dst: String,
+    pub db: Option<i64>,
+    pub replace: bool,
+}
+
+impl CommandExecutor for Copy {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let dst_backend = match self.db {
+            None => Some(backend.clone()),
+            Some(index) => usize::try_from(index)
+                .ok()
+                .and_then(|index| backend.select(index)),
+        };
+        let Some(dst_backend) = dst_backend else {
+            return SimpleError::new("ERR DB index is out of range").into();
+        };
+
+        if dst_backend.db_index() == backend.db_index() {
+            if self.src == self.dst {
+                return SimpleError::new("ERR source and destination objects are the same").into();
+            }
+            let mut db = backend.write();
+            let Some(entry) = db.entry(&self.src).cloned() else {
+                return RespFrame::Integer(0);
+            };
+            return copy_entry(&mut db, self.dst, entry, self.replace);
+        }
+
+        // across databases the source is released before locking the destination, so that
+        // two crossing copies can't deadlock
+        let Some(entry) = backend.read().entry(&self.src).cloned() else {
+            return RespFrame::Integer(0);
+        };
+        let mut db = dst_backend.write();
+        copy_entry(&mut db, self.dst, entry, self.replace)
+    }
+}
+
+// the clone carries the expiry along with the value
+fn copy_entry(db: &mut Db, dst: String, entry: Entry, replace: bool) -> RespFrame {
+    if !replace && db.contains_key(&dst) {
+        return RespFrame::Integer(0);
+    }
+    db.insert_entry(dst, entry);
+    RespFrame::Integer(1)
+}
+
+impl TryFrom<RespArray> for Copy {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["copy"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let mut cmd = Copy {
+            src: extract_string(args.next())?,
+            dst: extract_string(args.next())?,
+            db: None,
+            replace: false,
+        };
+        while let Some(arg) = args.next() {
+            match extract_string(Some(arg))?.to_ascii_lowercase().as_str() {
+                "db" => cmd.db = Some(parse_number(args.next())?),
+                "replace" => cmd.replace = true,
+                v => {
+                    return Err(CommandError::InvalidArgument(format!(
+                        "Invalid option: {}",
+                        v
+                    )))
+                }
+            }
+        }
+        Ok(cmd)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{clock, BulkString, RespDecode};
+
+    use super::*;
+
+    fn copy(backend: &Backend, src: &str, dst: &str, replace: bool) -> RespFrame {
+        Copy {
+            src: src.to_string(),
+            dst: dst.to_string(),
+            db: None,
+            replace,
+        }
+        .execute(backend)
+    }
+
+    #[test]
+    fn test_copy_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from(
+            "*6\r\n$4\r\ncopy\r\n$1\r\na\r\n$1\r\nb\r\n$2\r\nDB\r\n$1\r\n0\r\n$7\r\nREPLACE\r\n",
+        );
+        let cmd: Copy = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!((cmd.src.as_str(), cmd.dst.as_str()), ("a", "b"));
+        assert_eq!(cmd.db, Some(0));
+        assert!(cmd.replace);
+        Ok(())
+    }
+
+    #[test]
+    fn test_copy_same_db() {
+        let backend = Backend::new();
+        assert_eq!(copy(&backend, "src", "dst", false), RespFrame::Integer(0));
+
+        backend.set("src".to_string(), BulkString::new("1"));
+        let at = clock::now() + Duration::from_secs(100);
+        backend.write().expire("src", at);
+        assert_eq!(copy(&backend, "src", "dst", false), RespFrame::Integer(1));
+        let entry = backend.read().entry("dst").cloned().unwrap();
+        assert_eq!(entry.value, BulkString::new("1").into());
+        assert_eq!(entry.expires_at, Some(at));
+
+        backend.set("src".to_string(), BulkString::new("2"));
+        assert_eq!(copy(&backend, "src", "dst", false), RespFrame::Integer(0));
+        assert_eq!(backend.get("dst"), Some(BulkString::new("1").into()));
+
+        assert_eq!(copy(&backend, "src", "dst", true), RespFrame::Integer(1));
+        assert_eq!(backend.get("dst"), Some(BulkString::new("2").into()));
+        assert_eq!(backend.read().entry("dst").unwrap().expires_at, None);
+    }
+
+    #[test]
+    fn test_copy_invalid_db() {
+        let backend = Backend::new();
+        backend.set("src".to_string(), BulkString::new("1"));
+        for db in [16, -1] {
+            let cmd = Copy {
+                src: "src".to_string(),
+                dst: "dst".to_string(),
+                db: Some(db),
+                replace: false,
+            };
+            assert_eq!(
+                cmd.execute(&backend),
+                SimpleError::new("ERR DB index is out of range").into()
+            );
+        }
+    }
+
+    #[test]
+    fn test_copy_across_databases() {
+        let backend = Backend::new();
+        let db1 = backend.select(1).unwrap();
+        backend.set("key".to_string(), BulkString::new("1"));
+        db1.set("key".to_string(), BulkString::new("2"));
+
+        let copy_to_db1 = |replace| Copy {
+            src: "key".to_string(),
+            dst: "key".to_string(),
+            db: Some(1),
+            replace,
+        };
+        assert_eq!(copy_to_db1(false).execute(&backend), RespFrame::Integer(0));
+        assert_eq!(db1.get("key"), Some(BulkString::new("2").into()));
+
+        assert_eq!(copy_to_db1(true).execute(&backend), RespFrame::Integer(1));
+        assert_eq!(db1.get("key"), Some(BulkString::new("1").into()));
+        assert_eq!(backend.get("key"), Some(BulkString::new("1").into()));
+
+        let cmd = Copy {
+            src: "key".to_string(),
+            dst: "key".to_string(),
+            db: Some(0),
+            replace: true,
+        };
+        assert_eq!(
+            cmd.execute(&backend),
+            SimpleError::new("ERR source and destination objects are the same").into()
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{Backend, RespArray, RespFrame};
+
+use super::{validate_command, CommandError, CommandExecutor};
+
+#[derive(Debug)]
+pub struct DbSize;
+
+impl CommandExecutor for DbSize {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        // expired keys the reaper hasn't reclaimed yet are skipped by Db::len
+        RespFrame::Integer(backend.read().len() as i64)
+    }
+}
+
+impl TryFrom<RespArray> for DbSize {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["dbsize"], 0)?;
+        Ok(DbSize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{
+        clock,
+        cmd::{Del, Set},
+        BulkString, RespDecode,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_dbsize_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*1\r\n$6\r\nDBSIZE\r\n");
+        let _: DbSize = RespArray::decode(&mut buf)?.try_into()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_dbsize_command() {
+        let backend = Backend::new();
+        assert_eq!(DbSize.execute(&backend), RespFrame::Integer(0));
+
+        for key in ["a", "b", "c"] {
+            let cmd = Set {
+                key: key.to_string(),
+                value: BulkString::new("value"),
+            };
+            cmd.execute(&backend);
+        }
+        assert_eq!(DbSize.execute(&backend), RespFrame::Integer(3));
+
+        let cmd = Del {
+            keys: vec!["a".to_string()],
+        };
+        cmd.execute(&backend);
+        assert_eq!(DbSize.execute(&backend), RespFrame::Integer(2));
+    }
+
+    #[test]
+    fn test_dbsize_skips_expired_keys() {
+        // no runtime, so there is no reaper to remove the key
+        let backend = Backend::new();
+        backend.set("a".to_string(), BulkString::new("value"));
+        backend.set("b".to_string(), BulkString::new("value"));
+        backend
+            .write()
+            .expire("a", clock::now() - Duration::from_secs(1));
+        assert_eq!(DbSize.execute(&backend), RespFrame::Integer(1));
+    }
+}
@@ -0,0 +1,172 @@
+use std::time::Duration;
+
+use crate::{persistence, Backend, RespArray, RespFrame, SimpleError};
+
+use super::{
+    parse_number, save::RESP_SAVE_IN_PROGRESS, validate_command, CommandError, CommandExecutor,
+    RESP_OK,
+};
+
+/// DEBUG SLEEP seconds, fractions of a second are allowed
+#[derive(Debug)]
+pub struct DebugSleep {
+    pub duration: Duration,
+}
+
+/// DEBUG RELOAD, save the dataset and load it back in place of the one in memory
+#[derive(Debug)]
+pub struct DebugReload;
+
+impl CommandExecutor for DebugSleep {
+    // inside a transaction EXEC holds the exec guard, so the sleep holds up every client, as
+    // it does in redis
+    fn execute(self, _: &Backend) -> RespFrame {
+        std::thread::sleep(self.duration);
+        RESP_OK.clone()
+    }
+}
+
+impl DebugSleep {
+    /// reply once the duration has passed, only the calling connection waits
+    pub async fn execute_sleeping(self) -> RespFrame {
+        tokio::time::sleep(self.duration).await;
+        RESP_OK.clone()
+    }
+}
+
+impl CommandExecutor for DebugReload {
+    // goes through the RDB file, so that a dataset that doesn't survive a restart shows
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let Some(_guard) = backend.begin_save() else {
+            return RESP_SAVE_IN_PROGRESS.clone();
+        };
+        let path = backend.config().rdb_path();
+        let reloaded =
+            persistence::save(&path, backend).and_then(|_| persistence::load_into(&path, backend));
+        match reloaded {
+            Ok(()) => RESP_OK.clone(),
+            Err(e) => SimpleError::new(format!("ERR Error trying to reload: {}", e)).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for DebugSleep {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["debug", "sleep"], 1)?;
+
+        let seconds: f64 = parse_number(value.0.into_iter().nth(2))?;
+        let duration = Duration::try_from_secs_f64(seconds).map_err(|_| {
+            CommandError::InvalidArgument(format!("Invalid sleep duration: {}", seconds))
+        })?;
+        Ok(DebugSleep { duration })
+    }
+}
+
+impl TryFrom<RespArray> for DebugReload {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["debug", "reload"], 0)?;
+        Ok(DebugReload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{
+        clock,
+        cmd::{Exec, Multi},
+        network::ConnectionState,
+        BackendValue, BulkString, RespDecode, ZSet,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_debug_sleep_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*3\r\n$5\r\ndebug\r\n$5\r\nSLEEP\r\n$3\r\n0.5\r\n");
+        let cmd: DebugSleep = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.duration, Duration::from_millis(500));
+
+        let mut buf = BytesMut::from("*3\r\n$5\r\ndebug\r\n$5\r\nsleep\r\n$2\r\n-1\r\n");
+        let ret: Result<DebugSleep, _> = RespArray::decode(&mut buf)?.try_into();
+        assert!(ret.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_debug_reload() -> Result<()> {
+        let mut buf = BytesMut::from("*2\r\n$5\r\ndebug\r\n$6\r\nreload\r\n");
+        let cmd: DebugReload = RespArray::decode(&mut buf)?.try_into()?;
+
+        let dir = std::env::temp_dir().join(format!("simple-redis-reload-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let backend = Backend::new();
+        backend.config_mut().dir = dir.to_string_lossy().into_owned();
+        backend.set("key".to_string(), BulkString::new("value"));
+        let list = BackendValue::List([b"a".to_vec(), b"b".to_vec()].into());
+        backend.set("list".to_string(), list.clone());
+        let hash = BackendValue::Hash([(b"f".to_vec(), b"v".to_vec())].into());
+        backend.set("hash".to_string(), hash.clone());
+        let set = BackendValue::Set([b"a".to_vec(), b"b".to_vec()].into());
+        backend.set("set".to_string(), set.clone());
+        let mut zset = ZSet::new();
+        zset.insert(b"a".to_vec(), 0.1);
+        zset.insert(b"b".to_vec(), -2.5e-7);
+        let zset = BackendValue::ZSet(zset);
+        backend.set("zset".to_string(), zset.clone());
+        backend.set("volatile".to_string(), BulkString::new("value"));
+        let at = clock::now() + Duration::from_secs(100);
+        backend.write().expire("volatile", at);
+
+        assert_eq!(cmd.execute(&backend), RESP_OK.clone());
+        assert_eq!(backend.get("key"), Some(BulkString::new("value").into()));
+        assert_eq!(backend.get("list"), Some(list));
+        assert_eq!(backend.get("hash"), Some(hash));
+        assert_eq!(backend.get("set"), Some(set));
+        assert_eq!(backend.get("zset"), Some(zset));
+        // the expiry is kept to the millisecond
+        let expires_at = backend.read().peek("volatile").unwrap().expires_at.unwrap();
+        assert_eq!(clock::unix_time_ms(expires_at), clock::unix_time_ms(at));
+
+        // a directory that doesn't exist can't be saved to
+        backend.config_mut().dir = dir.join("missing").to_string_lossy().into_owned();
+        let RespFrame::Error(e) = DebugReload.execute(&backend) else {
+            panic!("reloaded without a file");
+        };
+        assert!(e.starts_with("ERR Error trying to reload"), "{:?}", e);
+
+        std::fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_debug_sleep_in_transaction_waits() {
+        let backend = Backend::new();
+        let mut conn = ConnectionState::new();
+        Multi.execute_on(&backend, &mut conn);
+        conn.queued.push(
+            DebugSleep {
+                duration: Duration::from_millis(50),
+            }
+            .into(),
+        );
+        let start = std::time::Instant::now();
+        let ret = Exec.execute_on(&backend, &mut conn);
+        assert_eq!(ret, RespArray::new(vec![RESP_OK.clone()]).into());
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_debug_sleep_waits() {
+        let start = tokio::time::Instant::now();
+        let cmd = DebugSleep {
+            duration: Duration::from_secs(1),
+        };
+        assert_eq!(cmd.execute_sleeping().await, RESP_OK.clone());
+        assert_eq!(start.elapsed(), Duration::from_secs(1));
+    }
+}
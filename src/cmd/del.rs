@@ -0,0 +1,84 @@
+use crate::{Backend, RespArray, RespFrame};
+
+use super::{
+    extract_args, extract_string, validate_variadic_command, CommandError, CommandExecutor,
+};
+
+#[derive(Debug)]
+pub struct Del {
+    pub keys: Vec<String>,
+}
+
+impl CommandExecutor for Del {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        // one write lock for all keys, so the deletion is atomic
+        let mut db = backend.write();
+        let deleted = self
+            .keys
+            .iter()
+            .filter(|key| db.remove(key).is_some())
+            .count();
+        RespFrame::Integer(deleted as i64)
+    }
+}
+
+impl TryFrom<RespArray> for Del {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["del"], 1)?;
+
+        let keys = extract_args(value, 1)?
+            .into_iter()
+            .map(|arg| extract_string(Some(arg)))
+            .collect::<Result<_, _>>()?;
+        Ok(Del { keys })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{BulkString, RespDecode, ZSet};
+
+    use super::*;
+
+    #[test]
+    fn test_del_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*3\r\n$3\r\ndel\r\n$1\r\na\r\n$1\r\nb\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+        let cmd: Del = frame.try_into()?;
+        assert_eq!(cmd.keys, vec!["a", "b"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_del_without_keys() -> Result<()> {
+        let mut buf = BytesMut::from("*1\r\n$3\r\ndel\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(Del::try_from(frame).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_del_command() {
+        let backend = Backend::new();
+        backend.set("a".to_string(), BulkString::new("1"));
+        backend.set("b".to_string(), ZSet::new());
+        backend.set("c".to_string(), BulkString::new("3"));
+
+        let cmd = Del {
+            keys: ["a", "missing", "b", "a"]
+                .iter()
+                .map(|k| k.to_string())
+                .collect(),
+        };
+        assert_eq!(cmd.execute(&backend), RespFrame::Integer(2));
+        assert_eq!(backend.get("a"), None);
+        assert_eq!(backend.get("b"), None);
+        assert!(backend.get("c").is_some());
+    }
+}
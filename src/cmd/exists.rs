@@ -0,0 +1,73 @@
+use crate::{Backend, RespArray, RespFrame};
+
+use super::{
+    extract_args, extract_string, validate_variadic_command, CommandError, CommandExecutor,
+};
+
+#[derive(Debug)]
+pub struct Exists {
+    pub keys: Vec<String>,
+}
+
+impl CommandExecutor for Exists {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        // repeated keys are counted every time they appear
+        let db = backend.read();
+        let found = self.keys.iter().filter(|key| db.contains_key(key)).count();
+        RespFrame::Integer(found as i64)
+    }
+}
+
+impl TryFrom<RespArray> for Exists {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["exists"], 1)?;
+
+        let keys = extract_args(value, 1)?
+            .into_iter()
+            .map(|arg| extract_string(Some(arg)))
+            .collect::<Result<_, _>>()?;
+        Ok(Exists { keys })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{BulkString, RespDecode};
+
+    use super::*;
+
+    fn exists(backend: &Backend, keys: &[&str]) -> RespFrame {
+        Exists {
+            keys: keys.iter().map(|k| k.to_string()).collect(),
+        }
+        .execute(backend)
+    }
+
+    #[test]
+    fn test_exists_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*3\r\n$6\r\nexists\r\n$1\r\na\r\n$1\r\nb\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+        let cmd: Exists = frame.try_into()?;
+        assert_eq!(cmd.keys, vec!["a", "b"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_exists_command() {
+        let backend = Backend::new();
+        backend.set("k".to_string(), BulkString::new("v"));
+        backend.set("other".to_string(), BulkString::new("v"));
+
+        assert_eq!(exists(&backend, &["k", "k", "k"]), RespFrame::Integer(3));
+        assert_eq!(
+            exists(&backend, &["k", "missing", "other", "nope"]),
+            RespFrame::Integer(2)
+        );
+        assert_eq!(exists(&backend, &["missing"]), RespFrame::Integer(0));
+    }
+}
@@ -0,0 +1,185 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::{clock, Backend, RespArray, RespFrame};
+
+use super::{
+    extract_args, extract_string, parse_number, validate_command, CommandError, CommandExecutor,
+};
+
+/// EXPIRE, PEXPIRE, EXPIREAT and PEXPIREAT, all normalized to milliseconds
+#[derive(Debug)]
+pub struct Expire {
+    pub key: String,
+    pub time: Expiry,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expiry {
+    // milliseconds from now
+    In(i64),
+    // unix timestamp in milliseconds
+    At(i64),
+}
+
+impl CommandExecutor for Expire {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let mut db = backend.write();
+        if !db.contains_key(&self.key) {
+            return RespFrame::Integer(0);
+        }
+        match self.time.deadline() {
+            Some(at) => db.expire(&self.key, at),
+            // a deadline in the past deletes the key right away
+            None => db.remove(&self.key).is_some(),
+        };
+        RespFrame::Integer(1)
+    }
+}
+
+impl Expiry {
+    /// the time the key expires at, None if it is already due
+    pub fn deadline(self) -> Option<SystemTime> {
+        let now = clock::now();
+        let at = match self {
+            Expiry::In(ms) if ms <= 0 => return None,
+            Expiry::In(ms) => now.checked_add(Duration::from_millis(ms as u64))?,
+            Expiry::At(ms) if ms <= 0 => return None,
+            Expiry::At(ms) => UNIX_EPOCH.checked_add(Duration::from_millis(ms as u64))?,
+        };
+        (at > now).then_some(at)
+    }
+}
+
+impl TryFrom<RespArray> for Expire {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let name = match value.first() {
+            Some(RespFrame::BulkString(name)) => name.to_ascii_lowercase(),
+            _ => vec![],
+        };
+        // (command, milliseconds per unit, absolute)
+        let (name, unit, absolute) = match name.as_slice() {
+            b"expire" => ("expire", 1000, false),
+            b"pexpire" => ("pexpire", 1, false),
+            b"expireat" => ("expireat", 1000, true),
+            b"pexpireat" => ("pexpireat", 1, true),
+            _ => {
+                return Err(CommandError::InvalidCommand(
+                    "Invalid command: expected an EXPIRE family command".to_string(),
+                ))
+            }
+        };
+        validate_command(&value, &[name], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = extract_string(args.next())?;
+        let time: i64 = parse_number(args.next())?;
+        let ms = time.checked_mul(unit).ok_or_else(|| {
+            CommandError::InvalidArgument(format!("invalid expire time in '{}' command", name))
+        })?;
+        let time = if absolute {
+            Expiry::At(ms)
+        } else {
+            Expiry::In(ms)
+        };
+        Ok(Expire { key, time })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{BulkString, RespDecode};
+
+    use super::*;
+
+    fn unix_time_ms() -> i64 {
+        clock::unix_time_ms(clock::now())
+    }
+
+    fn expire(backend: &Backend, key: &str, time: Expiry) -> RespFrame {
+        Expire {
+            key: key.to_string(),
+            time,
+        }
+        .execute(backend)
+    }
+
+    #[test]
+    fn test_expire_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*3\r\n$6\r\nexpire\r\n$3\r\nkey\r\n$2\r\n10\r\n");
+        let cmd: Expire = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.key, "key");
+        assert_eq!(cmd.time, Expiry::In(10_000));
+
+        let mut buf = BytesMut::from("*3\r\n$7\r\nPEXPIRE\r\n$3\r\nkey\r\n$2\r\n10\r\n");
+        let cmd: Expire = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.time, Expiry::In(10));
+
+        let mut buf = BytesMut::from("*3\r\n$8\r\nexpireat\r\n$3\r\nkey\r\n$2\r\n10\r\n");
+        let cmd: Expire = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.time, Expiry::At(10_000));
+
+        let mut buf = BytesMut::from("*3\r\n$9\r\npexpireat\r\n$3\r\nkey\r\n$2\r\n10\r\n");
+        let cmd: Expire = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.time, Expiry::At(10));
+
+        let mut buf =
+            BytesMut::from("*3\r\n$6\r\nexpire\r\n$3\r\nkey\r\n$19\r\n9223372036854775807\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(Expire::try_from(frame).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_expire_missing_key() {
+        let backend = Backend::new();
+        assert_eq!(
+            expire(&backend, "key", Expiry::In(10_000)),
+            RespFrame::Integer(0)
+        );
+        assert_eq!(
+            expire(&backend, "key", Expiry::At(unix_time_ms() + 10_000)),
+            RespFrame::Integer(0)
+        );
+    }
+
+    #[test]
+    fn test_expire_in_the_past_deletes_key() {
+        let backend = Backend::new();
+        backend.set("key".to_string(), BulkString::new("value"));
+        assert_eq!(
+            expire(&backend, "key", Expiry::At(unix_time_ms() - 1000)),
+            RespFrame::Integer(1)
+        );
+        assert_eq!(backend.get("key"), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_expire_key_expires() {
+        let backend = Backend::new();
+        backend.set("key".to_string(), BulkString::new("value"));
+        assert_eq!(
+            expire(&backend, "key", Expiry::In(100)),
+            RespFrame::Integer(1)
+        );
+
+        tokio::time::advance(Duration::from_millis(99)).await;
+        assert!(backend.get("key").is_some());
+
+        tokio::time::advance(Duration::from_millis(1)).await;
+        assert_eq!(backend.get("key"), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_expire_reaper_purges_keys() {
+        let backend = Backend::new();
+        backend.set("key".to_string(), BulkString::new("value"));
+        expire(&backend, "key", Expiry::In(100));
+
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert_eq!(backend.write().purge_expired(), 0);
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{clock, Backend, RespArray, RespFrame};
+
+use super::{extract_args, extract_string, validate_command, CommandError, CommandExecutor};
+
+/// EXPIRETIME and PEXPIRETIME
+#[derive(Debug)]
+pub struct ExpireTime {
+    pub key: String,
+    pub millis: bool,
+}
+
+impl CommandExecutor for ExpireTime {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let ret = match backend.read().entry(&self.key) {
+            None => -2,
+            Some(entry) => match entry.expires_at {
+                None => -1,
+                Some(at) => {
+                    let ms = clock::unix_time_ms(at);
+                    if self.millis {
+                        ms
+                    } else {
+                        ms / 1000
+                    }
+                }
+            },
+        };
+        RespFrame::Integer(ret)
+    }
+}
+
+impl TryFrom<RespArray> for ExpireTime {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let millis = validate_command(&value, &["expiretime"], 1)
+            .map(|_| false)
+            .or_else(|_| validate_command(&value, &["pexpiretime"], 1).map(|_| true))?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(ExpireTime {
+            key: extract_string(args.next())?,
+            millis,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{
+        cmd::{Expire, Expiry},
+        BulkString, RespDecode,
+    };
+
+    use super::*;
+
+    fn expiretime(backend: &Backend, key: &str, millis: bool) -> RespFrame {
+        ExpireTime {
+            key: key.to_string(),
+            millis,
+        }
+        .execute(backend)
+    }
+
+    #[test]
+    fn test_expiretime_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*2\r\n$10\r\nexpiretime\r\n$3\r\nkey\r\n");
+        let cmd: ExpireTime = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.key, "key");
+        assert!(!cmd.millis);
+
+        let mut buf = BytesMut::from("*2\r\n$11\r\nPEXPIRETIME\r\n$3\r\nkey\r\n");
+        let cmd: ExpireTime = RespArray::decode(&mut buf)?.try_into()?;
+        assert!(cmd.millis);
+        Ok(())
+    }
+
+    #[test]
+    fn test_expiretime_command() {
+        let backend = Backend::new();
+        assert_eq!(expiretime(&backend, "key", false), RespFrame::Integer(-2));
+        assert_eq!(expiretime(&backend, "key", true), RespFrame::Integer(-2));
+
+        backend.set("key".to_string(), BulkString::new("value"));
+        assert_eq!(expiretime(&backend, "key", false), RespFrame::Integer(-1));
+        assert_eq!(expiretime(&backend, "key", true), RespFrame::Integer(-1));
+
+        // far in the future, 2100-01-01T00:00:00.123Z
+        let at = 4_102_444_800_123;
+        let cmd = Expire {
+            key: "key".to_string(),
+            time: Expiry::At(at),
+        };
+        assert_eq!(cmd.execute(&backend), RespFrame::Integer(1));
+        assert_eq!(expiretime(&backend, "key", true), RespFrame::Integer(at));
+        assert_eq!(
+            expiretime(&backend, "key", false),
+            RespFrame::Integer(at / 1000)
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{Backend, RespArray, RespFrame, DB_COUNT};
+
+use super::{
+    extract_args, extract_string, validate_variadic_command, CommandError, CommandExecutor, RESP_OK,
+};
+
+/// FLUSHDB and FLUSHALL
+#[derive(Debug)]
+pub struct Flush {
+    pub all: bool,
+    // ASYNC: the old keyspaces are dropped by the lazy free task
+    pub lazy: bool,
+}
+
+impl CommandExecutor for Flush {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let backends = if self.all {
+            (0..DB_COUNT).filter_map(|i| backend.select(i)).collect()
+        } else {
+            vec![backend.clone()]
+        };
+        for backend in backends {
+            let db = backend.flush();
+            if self.lazy {
+                backend.lazy_free(db);
+            }
+        }
+        RESP_OK.clone()
+    }
+}
+
+impl TryFrom<RespArray> for Flush {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let all = validate_variadic_command(&value, &["flushall"], 0)
+            .map(|_| true)
+            .or_else(|_| validate_variadic_command(&value, &["flushdb"], 0).map(|_| false))?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let lazy = match args.next() {
+            None => false,
+            Some(arg) => match extract_string(Some(arg))?.to_ascii_lowercase().as_str() {
+                "async" => true,
+                "sync" => false,
+                v => {
+                    return Err(CommandError::InvalidArgument(format!(
+                        "Invalid option: {}",
+                        v
+                    )))
+                }
+            },
+        };
+        if args.next().is_some() {
+            return Err(CommandError::InvalidArgument(
+                "Too many arguments".to_string(),
+            ));
+        }
+        Ok(Flush { all, lazy })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{BulkString, RespDecode};
+
+    use super::*;
+
+    fn populate(backend: &Backend) -> Backend {
+        let db1 = backend.select(1).unwrap();
+        for key in ["a", "b"] {
+            backend.set(key.to_string(), BulkString::new("value"));
+            db1.set(key.to_string(), BulkString::new("value"));
+        }
+        db1
+    }
+
+    #[test]
+    fn test_flush_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*1\r\n$7\r\nflushdb\r\n");
+        let cmd: Flush = RespArray::decode(&mut buf)?.try_into()?;
+        assert!(!cmd.all && !cmd.lazy);
+
+        let mut buf = BytesMut::from("*2\r\n$8\r\nFLUSHALL\r\n$5\r\nASYNC\r\n");
+        let cmd: Flush = RespArray::decode(&mut buf)?.try_into()?;
+        assert!(cmd.all && cmd.lazy);
+
+        let mut buf = BytesMut::from("*2\r\n$7\r\nflushdb\r\n$4\r\nsoon\r\n");
+        assert!(Flush::try_from(RespArray::decode(&mut buf)?).is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_flushdb_keeps_other_databases() {
+        for lazy in [false, true] {
+            let backend = Backend::new();
+            let db1 = populate(&backend);
+
+            let cmd = Flush { all: false, lazy };
+            assert_eq!(cmd.execute(&backend), RESP_OK.clone());
+            assert!(backend.read().is_empty());
+            assert_eq!(db1.read().len(), 2);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_flushall() {
+        for lazy in [false, true] {
+            let backend = Backend::new();
+            let db1 = populate(&backend);
+
+            let cmd = Flush { all: true, lazy };
+            assert_eq!(cmd.execute(&db1), RESP_OK.clone());
+            assert!(backend.read().is_empty());
+            assert!(db1.read().is_empty());
+        }
+    }
+}
@@ -0,0 +1,578 @@
+use crate::{Backend, BackendValue, BulkString, RespArray, RespFrame, SimpleError};
+
+use super::{
+    extract_args, extract_bytes, extract_string, parse_number, validate_variadic_command,
+    zset::zadd, CommandError, CommandExecutor, ZAddOptions, RESP_WRONGTYPE,
+};
+
+const GEO_STEP: u32 = 26;
+const GEO_LAT_MIN: f64 = -85.05112878;
+const GEO_LAT_MAX: f64 = 85.05112878;
+const GEO_LONG_MIN: f64 = -180.0;
+const GEO_LONG_MAX: f64 = 180.0;
+const EARTH_RADIUS_IN_METERS: f64 = 6372797.560856;
+
+#[derive(Debug)]
+pub struct GeoAdd {
+    pub key: String,
+    pub members: Vec<(f64, f64, Vec<u8>)>,
+}
+
+#[derive(Debug)]
+pub struct GeoSearch {
+    pub key: String,
+    pub origin: GeoOrigin,
+    pub radius: f64,
+    pub unit: GeoUnit,
+    pub desc: bool,
+    pub count: Option<usize>,
+    pub with_coord: bool,
+    pub with_dist: bool,
+}
+
+// GEORADIUSBYMEMBER is a GEOSEARCH FROMMEMBER BYRADIUS in disguise
+#[derive(Debug)]
+pub struct GeoRadiusByMember {
+    pub search: GeoSearch,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GeoOrigin {
+    Member(Vec<u8>),
+    LonLat(f64, f64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeoUnit {
+    M,
+    Km,
+    Mi,
+    Ft,
+}
+
+impl CommandExecutor for GeoAdd {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let members = self
+            .members
+            .into_iter()
+            .map(|(lon, lat, member)| (geohash_encode(lon, lat) as f64, member))
+            .collect();
+        zadd(backend, self.key, members, ZAddOptions::default())
+    }
+}
+
+impl CommandExecutor for GeoSearch {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let db = backend.read();
+        let zset = match db.get(&self.key) {
+            Some(BackendValue::ZSet(zset)) => zset,
+            Some(_) => return RESP_WRONGTYPE.clone(),
+            None => return RespArray::new([]).into(),
+        };
+        let (lon, lat) = match &self.origin {
+            GeoOrigin::LonLat(lon, lat) => (*lon, *lat),
+            GeoOrigin::Member(member) => match zset.score(member) {
+                Some(score) => geohash_decode(score as u64),
+                None => {
+                    return SimpleError::new(
+                        "ERR could not perform this operation on a key that doesn't exist",
+                    )
+                    .into()
+                }
+            },
+        };
+
+        let radius = self.radius * self.unit.to_meters();
+        let mut found: Vec<_> = zset
+            .iter()
+            .filter_map(|(member, score)| {
+                let (m_lon, m_lat) = geohash_decode(score as u64);
+                let dist = geo_distance(lon, lat, m_lon, m_lat);
+                (dist <= radius).then_some((member.to_vec(), dist, m_lon, m_lat))
+            })
+            .collect();
+        found.sort_by(|a, b| a.1.total_cmp(&b.1));
+        if self.desc {
+            found.reverse();
+        }
+        if let Some(count) = self.count {
+            found.truncate(count);
+        }
+
+        let ret = found
+            .into_iter()
+            .map(|(member, dist, m_lon, m_lat)| {
+                if !self.with_coord && !self.with_dist {
+                    return BulkString::new(member).into();
+                }
+                let mut item = vec![BulkString::new(member).into()];
+                if self.with_dist {
+                    let dist = dist / self.unit.to_meters();
+                    item.push(BulkString::new(format!("{:.4}", dist)).into());
+                }
+                if self.with_coord {
+                    item.push(
+                        RespArray::new(vec![
+                            BulkString::new(m_lon.to_string()).into(),
+                            BulkString::new(m_lat.to_string()).into(),
+                        ])
+                        .into(),
+                    );
+                }
+                RespArray::new(item).into()
+            })
+            .collect::<Vec<RespFrame>>();
+        RespArray::new(ret).into()
+    }
+}
+
+impl CommandExecutor for GeoRadiusByMember {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        self.search.execute(backend)
+    }
+}
+
+impl TryFrom<RespArray> for GeoAdd {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["geoadd"], 4)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = extract_string(args.next())?;
+        if args.len() % 3 != 0 {
+            return Err(CommandError::InvalidArgument(
+                "Expected longitude, latitude and member triples".to_string(),
+            ));
+        }
+        let mut members = Vec::with_capacity(args.len() / 3);
+        while args.len() > 0 {
+            let (lon, lat) = parse_lon_lat(args.next(), args.next())?;
+            members.push((lon, lat, extract_bytes(args.next())?));
+        }
+        Ok(GeoAdd { key, members })
+    }
+}
+
+// GEOSEARCH key <FROMMEMBER member | FROMLONLAT lon lat> BYRADIUS radius <M | KM | FT | MI>
+//   [ASC | DESC] [COUNT count] [WITHCOORD] [WITHDIST]
+impl TryFrom<RespArray> for GeoSearch {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["geosearch"], 5)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = extract_string(args.next())?;
+        let exactly_one = || {
+            CommandError::InvalidArgument(
+                "Exactly one of FROMMEMBER or FROMLONLAT and BYRADIUS is required".to_string(),
+            )
+        };
+        let mut origin = None;
+        let mut radius = None;
+        let mut cmd = GeoSearch {
+            key,
+            origin: GeoOrigin::LonLat(0.0, 0.0),
+            radius: 0.0,
+            unit: GeoUnit::M,
+            desc: false,
+            count: None,
+            with_coord: false,
+            with_dist: false,
+        };
+        while let Some(arg) = args.next() {
+            match extract_string(Some(arg))?.to_ascii_lowercase().as_str() {
+                "frommember" | "fromlonlat" if origin.is_some() => return Err(exactly_one()),
+                "frommember" => origin = Some(GeoOrigin::Member(extract_bytes(args.next())?)),
+                "fromlonlat" => {
+                    let (lon, lat) = parse_lon_lat(args.next(), args.next())?;
+                    origin = Some(GeoOrigin::LonLat(lon, lat));
+                }
+                "byradius" => {
+                    radius = Some(parse_number(args.next())?);
+                    cmd.unit = parse_unit(args.next())?;
+                }
+                v => parse_search_option(&mut cmd, v, &mut args)?,
+            }
+        }
+        match (origin, radius) {
+            (Some(origin), Some(radius)) if radius >= 0.0 => {
+                cmd.origin = origin;
+                cmd.radius = radius;
+                Ok(cmd)
+            }
+            _ => Err(exactly_one()),
+        }
+    }
+}
+
+// GEORADIUSBYMEMBER key member radius <M | KM | FT | MI> [WITHCOORD] [WITHDIST]
+//   [COUNT count] [ASC | DESC]
+impl TryFrom<RespArray> for GeoRadiusByMember {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["georadiusbymember"], 4)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let mut search = GeoSearch {
+            key: extract_string(args.next())?,
+            origin: GeoOrigin::Member(extract_bytes(args.next())?),
+            radius: parse_number(args.next())?,
+            unit: parse_unit(args.next())?,
+            desc: false,
+            count: None,
+            with_coord: false,
+            with_dist: false,
+        };
+        if search.radius < 0.0 {
+            return Err(CommandError::InvalidArgument(
+                "Radius cannot be negative".to_string(),
+            ));
+        }
+        while let Some(arg) = args.next() {
+            let opt = extract_string(Some(arg))?.to_ascii_lowercase();
+            parse_search_option(&mut search, &opt, &mut args)?;
+        }
+        Ok(GeoRadiusByMember { search })
+    }
+}
+
+// options shared by GEOSEARCH and GEORADIUSBYMEMBER
+fn parse_search_option(
+    cmd: &mut GeoSearch,
+    opt: &str,
+    args: &mut impl Iterator<Item = RespFrame>,
+) -> Result<(), CommandError> {
+    match opt {
+        "asc" => cmd.desc = false,
+        "desc" => cmd.desc = true,
+        "count" => cmd.count = Some(parse_number(args.next())?),
+        "withcoord" => cmd.with_coord = true,
+        "withdist" => cmd.with_dist = true,
+        v => {
+            return Err(CommandError::InvalidArgument(format!(
+                "Invalid option: {}",
+                v
+            )))
+        }
+    }
+    Ok(())
+}
+
+impl GeoUnit {
+    pub fn to_meters(self) -> f64 {
+        match self {
+            GeoUnit::M => 1.0,
+            GeoUnit::Km => 1000.0,
+            GeoUnit::Mi => 1609.34,
+            GeoUnit::Ft => 0.3048,
+        }
+    }
+}
+
+fn parse_unit(frame: Option<RespFrame>) -> Result<GeoUnit, CommandError> {
+    match extract_string(frame)?.to_ascii_lowercase().as_str() {
+        "m" => Ok(GeoUnit::M),
+        "km" => Ok(GeoUnit::Km),
+        "mi" => Ok(GeoUnit::Mi),
+        "ft" => Ok(GeoUnit::Ft),
+        v => Err(CommandError::InvalidArgument(format!(
+            "Unsupported unit provided: {}, please use M, KM, FT, MI",
+            v
+        ))),
+    }
+}
+
+fn parse_lon_lat(
+    lon: Option<RespFrame>,
+    lat: Option<RespFrame>,
+) -> Result<(f64, f64), CommandError> {
+    let lon: f64 = parse_number(lon)?;
+    let lat: f64 = parse_number(lat)?;
+    if !(GEO_LONG_MIN..=GEO_LONG_MAX).contains(&lon) || !(GEO_LAT_MIN..=GEO_LAT_MAX).contains(&lat)
+    {
+        return Err(CommandError::InvalidArgument(format!(
+            "Invalid longitude,latitude pair {},{}",
+            lon, lat
+        )));
+    }
+    Ok((lon, lat))
+}
+
+// 52 bit geohash: latitude bits on even positions, longitude bits on odd positions
+fn geohash_encode(lon: f64, lat: f64) -> u64 {
+    let scale = (1u64 << GEO_STEP) as f64;
+    let lat_offset = (lat - GEO_LAT_MIN) / (GEO_LAT_MAX - GEO_LAT_MIN) * scale;
+    let lon_offset = (lon - GEO_LONG_MIN) / (GEO_LONG_MAX - GEO_LONG_MIN) * scale;
+    let max = (1u64 << GEO_STEP) - 1;
+    interleave((lat_offset as u64).min(max), (lon_offset as u64).min(max))
+}
+
+// decode to the center of the geohash cell
+fn geohash_decode(hash: u64) -> (f64, f64) {
+    let (ilat, ilon) = deinterleave(hash);
+    let scale = (1u64 << GEO_STEP) as f64;
+    let lat_step = (GEO_LAT_MAX - GEO_LAT_MIN) / scale;
+    let lon_step = (GEO_LONG_MAX - GEO_LONG_MIN) / scale;
+    let lat = GEO_LAT_MIN + (ilat as f64 + 0.5) * lat_step;
+    let lon = GEO_LONG_MIN + (ilon as f64 + 0.5) * lon_step;
+    (
+        lon.clamp(GEO_LONG_MIN, GEO_LONG_MAX),
+        lat.clamp(GEO_LAT_MIN, GEO_LAT_MAX),
+    )
+}
+
+fn interleave(x: u64, y: u64) -> u64 {
+    (0..GEO_STEP).fold(0, |acc, i| {
+        acc | ((x >> i) & 1) << (2 * i) | ((y >> i) & 1) << (2 * i + 1)
+    })
+}
+
+fn deinterleave(hash: u64) -> (u64, u64) {
+    (0..GEO_STEP).fold((0, 0), |(x, y), i| {
+        (
+            x | ((hash >> (2 * i)) & 1) << i,
+            y | ((hash >> (2 * i + 1)) & 1) << i,
+        )
+    })
+}
+
+// haversine distance in meters
+fn geo_distance(lon1: f64, lat1: f64, lon2: f64, lat2: f64) -> f64 {
+    let (lat1r, lat2r) = (lat1.to_radians(), lat2.to_radians());
+    let u = ((lat2r - lat1r) / 2.0).sin();
+    let v = ((lon2.to_radians() - lon1.to_radians()) / 2.0).sin();
+    2.0 * EARTH_RADIUS_IN_METERS * (u * u + lat1r.cos() * lat2r.cos() * v * v).sqrt().asin()
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    fn add_cities(backend: &Backend) {
+        let cmd = GeoAdd {
+            key: "cities".to_string(),
+            members: vec![
+                (2.1301, 48.8049, b"versailles".to_vec()),
+                (2.3574, 48.9362, b"saint-denis".to_vec()),
+                (4.8357, 45.7640, b"lyon".to_vec()),
+                (-0.1276, 51.5072, b"london".to_vec()),
+            ],
+        };
+        assert_eq!(cmd.execute(backend), RespFrame::Integer(4));
+    }
+
+    #[test]
+    fn test_geohash_round_trip() {
+        let (lon, lat) = geohash_decode(geohash_encode(13.361389, 38.115556));
+        assert!((lon - 13.361389).abs() < 1e-5);
+        assert!((lat - 38.115556).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_geo_distance() {
+        // Palermo to Catania, as in the redis documentation
+        let dist = geo_distance(13.361389, 38.115556, 15.087269, 37.502669);
+        assert!((dist - 166274.1516).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_geosearch_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from(
+            "*8\r\n$9\r\ngeosearch\r\n$6\r\ncities\r\n$10\r\nFROMLONLAT\r\n$6\r\n2.3522\r\n$7\r\n48.8566\r\n$8\r\nBYRADIUS\r\n$3\r\n100\r\n$2\r\nkm\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+        let cmd: GeoSearch = frame.try_into()?;
+        assert_eq!(cmd.key, "cities");
+        assert_eq!(cmd.origin, GeoOrigin::LonLat(2.3522, 48.8566));
+        assert_eq!(cmd.radius, 100.0);
+        assert_eq!(cmd.unit, GeoUnit::Km);
+
+        // both origins at once
+        let mut buf = BytesMut::from(
+            "*10\r\n$9\r\ngeosearch\r\n$6\r\ncities\r\n$10\r\nFROMMEMBER\r\n$5\r\nparis\r\n$10\r\nFROMLONLAT\r\n$6\r\n2.3522\r\n$7\r\n48.8566\r\n$8\r\nBYRADIUS\r\n$3\r\n100\r\n$2\r\nkm\r\n",
+        );
+        let ret: Result<GeoSearch, _> = RespArray::decode(&mut buf)?.try_into();
+        assert!(ret.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_geosearch_from_lon_lat() {
+        let backend = Backend::new();
+        add_cities(&backend);
+
+        let cmd = GeoSearch {
+            key: "cities".to_string(),
+            origin: GeoOrigin::LonLat(2.3522, 48.8566),
+            radius: 100.0,
+            unit: GeoUnit::Km,
+            desc: false,
+            count: None,
+            with_coord: false,
+            with_dist: false,
+        };
+        assert_eq!(
+            cmd.execute(&backend),
+            RespArray::new(vec![
+                BulkString::new("saint-denis").into(),
+                BulkString::new("versailles").into(),
+            ])
+            .into()
+        );
+    }
+
+    #[test]
+    fn test_georadiusbymember_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from(
+            "*6\r\n$17\r\ngeoradiusbymember\r\n$6\r\ncities\r\n$10\r\nversailles\r\n$2\r\n50\r\n$2\r\nkm\r\n$8\r\nWITHDIST\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+        let cmd: GeoRadiusByMember = frame.try_into()?;
+        assert_eq!(cmd.search.key, "cities");
+        assert_eq!(cmd.search.origin, GeoOrigin::Member(b"versailles".to_vec()));
+        assert_eq!(cmd.search.radius, 50.0);
+        assert_eq!(cmd.search.unit, GeoUnit::Km);
+        assert!(cmd.search.with_dist);
+        Ok(())
+    }
+
+    #[test]
+    fn test_georadiusbymember_missing_member() {
+        let backend = Backend::new();
+        add_cities(&backend);
+
+        let cmd = GeoRadiusByMember {
+            search: GeoSearch {
+                key: "cities".to_string(),
+                origin: GeoOrigin::Member(b"paris".to_vec()),
+                radius: 100.0,
+                unit: GeoUnit::Km,
+                desc: false,
+                count: None,
+                with_coord: false,
+                with_dist: false,
+            },
+        };
+        assert_eq!(
+            cmd.execute(&backend),
+            SimpleError::new("ERR could not perform this operation on a key that doesn't exist")
+                .into()
+        );
+    }
+
+    #[test]
+    fn test_geo_units_are_consistent() {
+        let backend = Backend::new();
+        // "north" sits 5 km due north of "center"
+        let north_lat = (5000.0 / EARTH_RADIUS_IN_METERS).to_degrees();
+        let cmd = GeoAdd {
+            key: "points".to_string(),
+            members: vec![
+                (0.0, 0.0, b"center".to_vec()),
+                (0.0, north_lat, b"north".to_vec()),
+            ],
+        };
+        assert_eq!(cmd.execute(&backend), RespFrame::Integer(2));
+
+        let search = |origin: GeoOrigin, radius: f64, unit: GeoUnit| GeoSearch {
+            key: "points".to_string(),
+            origin,
+            radius,
+            unit,
+            desc: false,
+            count: None,
+            with_coord: false,
+            with_dist: false,
+        };
+        let both = RespArray::new(vec![
+            BulkString::new("center").into(),
+            BulkString::new("north").into(),
+        ])
+        .into();
+        let center_only = RespArray::new(vec![BulkString::new("center").into()]).into();
+
+        for (radius, unit) in [
+            (5.1, GeoUnit::Km),
+            (5100.0, GeoUnit::M),
+            (3.17, GeoUnit::Mi),
+            (16733.0, GeoUnit::Ft),
+        ] {
+            let cmd = search(GeoOrigin::LonLat(0.0, 0.0), radius, unit);
+            assert_eq!(cmd.execute(&backend), both, "{} {:?}", radius, unit);
+
+            let cmd = GeoRadiusByMember {
+                search: search(GeoOrigin::Member(b"center".to_vec()), radius, unit),
+            };
+            assert_eq!(cmd.execute(&backend), both, "{} {:?}", radius, unit);
+        }
+
+        for (radius, unit) in [
+            (4.9, GeoUnit::Km),
+            (4900.0, GeoUnit::M),
+            (3.04, GeoUnit::Mi),
+            (16076.0, GeoUnit::Ft),
+        ] {
+            let cmd = search(GeoOrigin::LonLat(0.0, 0.0), radius, unit);
+            assert_eq!(cmd.execute(&backend), center_only, "{} {:?}", radius, unit);
+        }
+    }
+
+    #[test]
+    fn test_georadiusbymember_withdist_unit() {
+        let backend = Backend::new();
+        let north_lat = (5000.0 / EARTH_RADIUS_IN_METERS).to_degrees();
+        let cmd = GeoAdd {
+            key: "points".to_string(),
+            members: vec![
+                (0.0, 0.0, b"center".to_vec()),
+                (0.0, north_lat, b"north".to_vec()),
+            ],
+        };
+        cmd.execute(&backend);
+
+        for (radius, unit, expected) in [
+            (10.0, GeoUnit::Km, 5.0),
+            (10000.0, GeoUnit::M, 5000.0),
+            (10.0, GeoUnit::Mi, 5000.0 / 1609.34),
+            (30000.0, GeoUnit::Ft, 5000.0 / 0.3048),
+        ] {
+            let cmd = GeoRadiusByMember {
+                search: GeoSearch {
+                    key: "points".to_string(),
+                    origin: GeoOrigin::Member(b"center".to_vec()),
+                    radius,
+                    unit,
+                    desc: true,
+                    count: Some(1),
+                    with_coord: false,
+                    with_dist: true,
+                },
+            };
+            let RespFrame::Array(ret) = cmd.execute(&backend) else {
+                panic!("expected an array");
+            };
+            let RespFrame::Array(item) = &ret[0] else {
+                panic!("expected a [member, distance] pair");
+            };
+            assert_eq!(item[0], BulkString::new("north").into());
+            let RespFrame::BulkString(dist) = &item[1] else {
+                panic!("expected a distance");
+            };
+            let dist: f64 = String::from_utf8_lossy(dist).parse().unwrap();
+            // geohash quantization is well below a meter
+            assert!(
+                (dist - expected).abs() < expected * 1e-3,
+                "{:?}: {}",
+                unit,
+                dist
+            );
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{Backend, BulkString, RespArray, RespFrame};
+
+use super::{
+    extract_args, extract_string, get_string, parse_number, validate_command, CommandError,
+    CommandExecutor,
+};
+
+#[derive(Debug)]
+pub struct GetRange {
+    pub key: String,
+    pub start: i64,
+    pub end: i64,
+}
+
+impl CommandExecutor for GetRange {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match get_string(backend, &self.key) {
+            Ok(Some(value)) => match byte_range(value.len(), self.start, self.end) {
+                Some((start, end)) => BulkString::new(&value[start..=end]).into(),
+                None => BulkString::new(vec![]).into(),
+            },
+            Ok(None) => BulkString::new(vec![]).into(),
+            Err(e) => e,
+        }
+    }
+}
+
+impl TryFrom<RespArray> for GetRange {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["getrange"], 3)
+            .or_else(|_| validate_command(&value, &["substr"], 3))?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(GetRange {
+            key: extract_string(args.next())?,
+            start: parse_number(args.next())?,
+            end: parse_number(args.next())?,
+        })
+    }
+}
+
+// resolve possibly negative offsets into an inclusive range within `len`
+pub(super) fn byte_range(len: usize, start: i64, end: i64) -> Option<(usize, usize)> {
+    let len = len as i64;
+    let start = if start < 0 {
+        (len + start).max(0)
+    } else {
+        start
+    };
+    let end = if end < 0 {
+        (len + end).max(0)
+    } else {
+        end.min(len - 1)
+    };
+    if len == 0 || start > end {
+        return None;
+    }
+    Some((start as usize, end as usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    fn getrange(backend: &Backend, key: &str, start: i64, end: i64) -> RespFrame {
+        GetRange {
+            key: key.to_string(),
+            start,
+            end,
+        }
+        .execute(backend)
+    }
+
+    #[test]
+    fn test_getrange_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*4\r\n$6\r\nsubstr\r\n$3\r\nkey\r\n$1\r\n0\r\n$2\r\n-1\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+        let cmd: GetRange = frame.try_into()?;
+        assert_eq!(cmd.key, "key");
+        assert_eq!(cmd.start, 0);
+        assert_eq!(cmd.end, -1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_getrange_command() {
+        let backend = Backend::new();
+        backend.set("key".to_string(), BulkString::new("Hello, World"));
+
+        let hello: RespFrame = BulkString::new("Hello").into();
+        let world: RespFrame = BulkString::new("World").into();
+        // positive start and end
+        assert_eq!(getrange(&backend, "key", 0, 4), hello);
+        assert_eq!(getrange(&backend, "key", 7, 100), world);
+        // negative start and end
+        assert_eq!(getrange(&backend, "key", -5, -1), world);
+        assert_eq!(getrange(&backend, "key", -100, -8), hello);
+        // positive start, negative end
+        assert_eq!(getrange(&backend, "key", 7, -1), world);
+        // negative start, positive end
+        assert_eq!(getrange(&backend, "key", -12, 4), hello);
+
+        let empty: RespFrame = BulkString::new("").into();
+        assert_eq!(getrange(&backend, "key", 5, 2), empty);
+        assert_eq!(getrange(&backend, "key", 20, 30), empty);
+        assert_eq!(getrange(&backend, "missing", 0, -1), empty);
+    }
+
+    #[test]
+    fn test_getrange_empty_string() {
+        let backend = Backend::new();
+        backend.set("key".to_string(), BulkString::new(""));
+
+        let empty: RespFrame = BulkString::new("").into();
+        for (start, end) in [(0, 0), (0, -1), (-1, -1), (-5, 5), (3, 1)] {
+            assert_eq!(getrange(&backend, "key", start, end), empty);
+        }
+    }
+}
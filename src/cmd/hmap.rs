@@ -1,40 +1,319 @@
-use crate::{BulkString, RespArray, RespFrame};
+use std::collections::HashMap;
+
+use crate::{Backend, BackendValue, BulkString, RespArray, RespFrame, RespNull, SimpleError};
 
 use super::{
-    extract_args, validate_command, CommandError, CommandExecutor, HGet, HGetAll, HSet, RESP_OK,
+    extract_args, extract_bytes, extract_string, parse_number, validate_command,
+    validate_variadic_command, CommandError, CommandExecutor, HGet, HGetAll, HSet, RESP_OK,
+    RESP_WRONGTYPE,
 };
 
+#[derive(Debug)]
+pub struct HDel {
+    pub key: String,
+    pub fields: Vec<Vec<u8>>,
+}
+
+#[derive(Debug)]
+pub struct HExists {
+    pub key: String,
+    pub field: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub struct HKeys {
+    pub key: String,
+}
+
+#[derive(Debug)]
+pub struct HVals {
+    pub key: String,
+}
+
+#[derive(Debug)]
+pub struct HLen {
+    pub key: String,
+}
+
+// deprecated alias of a multi field HSET, replies OK instead of the number of new fields
+#[derive(Debug)]
+pub struct HMSet {
+    pub key: String,
+    pub fields: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+#[derive(Debug)]
+pub struct HMGet {
+    pub key: String,
+    pub fields: Vec<Vec<u8>>,
+}
+
+#[derive(Debug)]
+pub struct HIncrBy {
+    pub key: String,
+    pub field: Vec<u8>,
+    pub increment: i64,
+}
+
+#[derive(Debug)]
+pub struct HIncrByFloat {
+    pub key: String,
+    pub field: Vec<u8>,
+    pub increment: f64,
+}
+
+#[derive(Debug)]
+pub struct HSetNx {
+    pub key: String,
+    pub field: Vec<u8>,
+    pub value: Vec<u8>,
+}
+
 impl CommandExecutor for HGet {
-    fn execute(self, backend: &crate::Backend) -> RespFrame {
-        match backend.hget(&self.key, &self.field) {
-            Some(value) => value,
-            None => RespFrame::Null(crate::RespNull),
-        }
+    fn execute(self, backend: &Backend) -> RespFrame {
+        read_hash(
+            backend,
+            &self.key,
+            RespFrame::Null(RespNull),
+            |hmap| match hmap.get(&self.field) {
+                Some(value) => BulkString::new(value.as_slice()).into(),
+                None => RespFrame::Null(RespNull),
+            },
+        )
     }
 }
 
 impl CommandExecutor for HGetAll {
-    fn execute(self, backend: &crate::Backend) -> RespFrame {
-        let hmap = backend.hmap.get(&self.key);
-        match hmap {
-            Some(hmap) => {
-                let mut ret = Vec::with_capacity(hmap.len() * 2);
-                for v in hmap.iter() {
-                    let key = v.key().to_owned();
-                    ret.push(BulkString::new(key).into());
-                    ret.push(v.value().clone());
+    fn execute(self, backend: &Backend) -> RespFrame {
+        read_hash(backend, &self.key, RespArray::new([]).into(), |hmap| {
+            let mut ret = Vec::with_capacity(hmap.len() * 2);
+            for (field, value) in hmap.iter() {
+                ret.push(BulkString::new(field.as_slice()).into());
+                ret.push(BulkString::new(value.as_slice()).into());
+            }
+            RespArray::new(ret).into()
+        })
+    }
+}
+
+impl CommandExecutor for HSet {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        write_hash(backend, self.key, |hmap| {
+            let mut added = 0;
+            for (field, value) in self.fields {
+                if hmap.insert(field, value).is_none() {
+                    added += 1;
                 }
-                RespArray::new(ret).into()
             }
-            None => RespArray::new([]).into(),
+            (RespFrame::Integer(added), true)
+        })
+    }
+}
+
+impl CommandExecutor for HMSet {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        write_hash(backend, self.key, |hmap| {
+            hmap.extend(self.fields);
+            (RESP_OK.clone(), true)
+        })
+    }
+}
+
+impl CommandExecutor for HMGet {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let nulls = || -> RespFrame {
+            RespArray::new(vec![RespFrame::Null(RespNull); self.fields.len()]).into()
+        };
+        read_hash(backend, &self.key, nulls(), |hmap| {
+            let ret: Vec<RespFrame> = self
+                .fields
+                .iter()
+                .map(|field| match hmap.get(field) {
+                    Some(value) => BulkString::new(value.as_slice()).into(),
+                    None => RespFrame::Null(RespNull),
+                })
+                .collect();
+            RespArray::new(ret).into()
+        })
+    }
+}
+
+impl CommandExecutor for HIncrBy {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        write_hash(backend, self.key, |hmap| {
+            let current = match hmap.get(&self.field) {
+                Some(value) => match std::str::from_utf8(value).ok().and_then(|v| v.parse().ok()) {
+                    Some(v) => v,
+                    None => {
+                        return (
+                            SimpleError::new("ERR hash value is not an integer").into(),
+                            false,
+                        )
+                    }
+                },
+                None => 0i64,
+            };
+            match current.checked_add(self.increment) {
+                Some(v) => {
+                    hmap.insert(self.field, v.to_string().into_bytes());
+                    (RespFrame::Integer(v), true)
+                }
+                None => (
+                    SimpleError::new("ERR increment or decrement would overflow").into(),
+                    false,
+                ),
+            }
+        })
+    }
+}
+
+impl CommandExecutor for HIncrByFloat {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        write_hash(backend, self.key, |hmap| {
+            let current = match hmap.get(&self.field) {
+                Some(value) => match std::str::from_utf8(value)
+                    .ok()
+                    .and_then(|v| v.parse::<f64>().ok())
+                    .filter(|v| v.is_finite())
+                {
+                    Some(v) => v,
+                    None => {
+                        return (
+                            SimpleError::new("ERR hash value is not a float").into(),
+                            false,
+                        )
+                    }
+                },
+                None => 0.0,
+            };
+            let v = current + self.increment;
+            if !v.is_finite() {
+                let e = SimpleError::new("ERR increment would produce NaN or Infinity");
+                return (e.into(), false);
+            }
+            // shortest representation that round trips, without exponent: 10.5, 3, 5000
+            let v = v.to_string();
+            hmap.insert(self.field, v.clone().into_bytes());
+            (BulkString::new(v).into(), true)
+        })
+    }
+}
+
+impl CommandExecutor for HSetNx {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        write_hash(backend, self.key, |hmap| {
+            if hmap.contains_key(&self.field) {
+                return (RespFrame::Integer(0), false);
+            }
+            hmap.insert(self.field, self.value);
+            (RespFrame::Integer(1), true)
+        })
+    }
+}
+
+impl CommandExecutor for HDel {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let mut db = backend.write();
+        let (removed, now_empty) = match db.get_mut(&self.key) {
+            Some(BackendValue::Hash(hmap)) => {
+                let removed = self
+                    .fields
+                    .iter()
+                    .filter(|field| hmap.remove(*field).is_some())
+                    .count();
+                (removed, hmap.is_empty())
+            }
+            Some(_) => return RESP_WRONGTYPE.clone(),
+            None => return RespFrame::Integer(0),
+        };
+        if removed > 0 {
+            db.signal_modified(&self.key);
+        }
+        // an empty hash doesn't exist
+        if now_empty {
+            db.remove(&self.key);
         }
+        RespFrame::Integer(removed as i64)
     }
 }
 
-impl CommandExecutor for HSet {
-    fn execute(self, backend: &crate::Backend) -> RespFrame {
-        backend.hset(self.key, self.field, self.value);
-        RESP_OK.clone()
+impl CommandExecutor for HExists {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        read_hash(backend, &self.key, RespFrame::Integer(0), |hmap| {
+            RespFrame::Integer(hmap.contains_key(&self.field) as i64)
+        })
+    }
+}
+
+impl CommandExecutor for HKeys {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        read_hash(backend, &self.key, RespArray::new([]).into(), |hmap| {
+            let ret: Vec<RespFrame> = hmap
+                .keys()
+                .map(|field| BulkString::new(field.as_slice()).into())
+                .collect();
+            RespArray::new(ret).into()
+        })
+    }
+}
+
+impl CommandExecutor for HVals {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        read_hash(backend, &self.key, RespArray::new([]).into(), |hmap| {
+            let ret: Vec<RespFrame> = hmap
+                .values()
+                .map(|value| BulkString::new(value.as_slice()).into())
+                .collect();
+            RespArray::new(ret).into()
+        })
+    }
+}
+
+impl CommandExecutor for HLen {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        read_hash(backend, &self.key, RespFrame::Integer(0), |hmap| {
+            RespFrame::Integer(hmap.len() as i64)
+        })
+    }
+}
+
+// run `f` on the hash at `key` under the write lock, it replies and tells whether it changed
+// the hash. a missing key is only created once `f` sets a field. every hash write goes through
+// the database write lock, so they never interleave
+fn write_hash(
+    backend: &Backend,
+    key: String,
+    f: impl FnOnce(&mut HashMap<Vec<u8>, Vec<u8>>) -> (RespFrame, bool),
+) -> RespFrame {
+    let mut db = backend.write();
+    let mut created = None;
+    let hmap = match db.get_mut(&key) {
+        Some(BackendValue::Hash(hmap)) => hmap,
+        Some(_) => return RESP_WRONGTYPE.clone(),
+        None => created.insert(HashMap::new()),
+    };
+    let (ret, modified) = f(hmap);
+    match created {
+        Some(hmap) if !hmap.is_empty() => {
+            db.insert(key, BackendValue::Hash(hmap));
+        }
+        _ if modified => db.signal_modified(&key),
+        _ => {}
+    }
+    ret
+}
+
+// run `f` on the hash at `key` under the read lock, `missing` if there is no such key
+pub(super) fn read_hash(
+    backend: &Backend,
+    key: &str,
+    missing: RespFrame,
+    f: impl FnOnce(&HashMap<Vec<u8>, Vec<u8>>) -> RespFrame,
+) -> RespFrame {
+    match backend.read().get(key) {
+        Some(BackendValue::Hash(hmap)) => f(hmap),
+        Some(_) => RESP_WRONGTYPE.clone(),
+        None => missing,
     }
 }
 
@@ -45,15 +324,10 @@ impl TryFrom<RespArray> for HGet {
         validate_command(&value, &["hget"], 2)?;
 
         let mut args = extract_args(value, 1)?.into_iter();
-        match (args.next(), args.next()) {
-            (Some(RespFrame::BulkString(key)), Some(RespFrame::BulkString(field))) => Ok(HGet {
-                key: String::from_utf8(key.0)?,
-                field: String::from_utf8(field.0)?,
-            }),
-            _ => Err(CommandError::InvalidArgument(
-                "Expected key and field arguments".to_string(),
-            )),
-        }
+        Ok(HGet {
+            key: extract_string(args.next())?,
+            field: extract_bytes(args.next())?,
+        })
     }
 }
 
@@ -63,35 +337,177 @@ impl TryFrom<RespArray> for HGetAll {
         validate_command(&value, &["hgetall"], 1)?;
 
         let mut args = extract_args(value, 1)?.into_iter();
-        match args.next() {
-            Some(RespFrame::BulkString(key)) => Ok(HGetAll {
-                key: String::from_utf8(key.0)?,
-            }),
-            _ => Err(CommandError::InvalidArgument(
-                "Expected key argument".to_string(),
-            )),
-        }
+        Ok(HGetAll {
+            key: extract_string(args.next())?,
+        })
     }
 }
 
 impl TryFrom<RespArray> for HSet {
     type Error = CommandError;
     fn try_from(value: RespArray) -> Result<Self, Self::Error> {
-        validate_command(&value, &["hset"], 3)?;
+        let (key, fields) = parse_field_values(value, "hset")?;
+        Ok(HSet { key, fields })
+    }
+}
+
+impl TryFrom<RespArray> for HMSet {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let (key, fields) = parse_field_values(value, "hmset")?;
+        Ok(HMSet { key, fields })
+    }
+}
+
+impl TryFrom<RespArray> for HMGet {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["hmget"], 2)?;
 
         let mut args = extract_args(value, 1)?.into_iter();
-        match (args.next(), args.next(), args.next()) {
-            (Some(RespFrame::BulkString(key)), Some(RespFrame::BulkString(field)), Some(value)) => {
-                Ok(HSet {
-                    key: String::from_utf8(key.0)?,
-                    field: String::from_utf8(field.0)?,
-                    value,
-                })
-            }
-            _ => Err(CommandError::InvalidArgument(
-                "Expected key, field and value arguments".to_string(),
-            )),
+        let key = extract_string(args.next())?;
+        let fields = args
+            .map(|arg| extract_bytes(Some(arg)))
+            .collect::<Result<_, _>>()?;
+        Ok(HMGet { key, fields })
+    }
+}
+
+impl TryFrom<RespArray> for HIncrBy {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["hincrby"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(HIncrBy {
+            key: extract_string(args.next())?,
+            field: extract_bytes(args.next())?,
+            increment: parse_number(args.next())?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for HIncrByFloat {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["hincrbyfloat"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = extract_string(args.next())?;
+        let field = extract_bytes(args.next())?;
+        let increment: f64 = parse_number(args.next())?;
+        if !increment.is_finite() {
+            return Err(CommandError::InvalidArgument(
+                "value is not a valid float".to_string(),
+            ));
         }
+        Ok(HIncrByFloat {
+            key,
+            field,
+            increment,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for HSetNx {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["hsetnx"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(HSetNx {
+            key: extract_string(args.next())?,
+            field: extract_bytes(args.next())?,
+            value: extract_bytes(args.next())?,
+        })
+    }
+}
+
+type FieldValues = Vec<(Vec<u8>, Vec<u8>)>;
+
+// - "<cmd> key field value [field value ...]"
+fn parse_field_values(
+    value: RespArray,
+    name: &'static str,
+) -> Result<(String, FieldValues), CommandError> {
+    validate_variadic_command(&value, &[name], 3)?;
+
+    let mut args = extract_args(value, 1)?.into_iter();
+    let key = extract_string(args.next())?;
+    if args.len() % 2 != 0 {
+        return Err(CommandError::InvalidArgument(
+            "Expected field and value pairs".to_string(),
+        ));
+    }
+    let mut fields = Vec::with_capacity(args.len() / 2);
+    while args.len() > 0 {
+        let field = extract_bytes(args.next())?;
+        let value = extract_bytes(args.next())?;
+        fields.push((field, value));
+    }
+    Ok((key, fields))
+}
+
+impl TryFrom<RespArray> for HDel {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["hdel"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = extract_string(args.next())?;
+        let fields = args
+            .map(|arg| extract_bytes(Some(arg)))
+            .collect::<Result<_, _>>()?;
+        Ok(HDel { key, fields })
+    }
+}
+
+impl TryFrom<RespArray> for HExists {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["hexists"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(HExists {
+            key: extract_string(args.next())?,
+            field: extract_bytes(args.next())?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for HKeys {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["hkeys"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(HKeys {
+            key: extract_string(args.next())?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for HVals {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["hvals"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(HVals {
+            key: extract_string(args.next())?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for HLen {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["hlen"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(HLen {
+            key: extract_string(args.next())?,
+        })
     }
 }
 
@@ -103,6 +519,26 @@ mod tests {
 
     use super::*;
 
+    fn hset(backend: &Backend, key: &str, fields: &[(&str, &str)]) -> RespFrame {
+        HSet {
+            key: key.to_string(),
+            fields: fields
+                .iter()
+                .map(|(f, v)| (f.as_bytes().to_vec(), v.as_bytes().to_vec()))
+                .collect(),
+        }
+        .execute(backend)
+    }
+
+    fn sorted(frame: RespFrame) -> Vec<RespFrame> {
+        let RespFrame::Array(array) = frame else {
+            panic!("expected an array");
+        };
+        let mut items = array.0;
+        items.sort_by_key(|item| format!("{:?}", item));
+        items
+    }
+
     #[test]
     fn test_hget() -> anyhow::Result<()> {
         let mut buf = BytesMut::from("*3\r\n$4\r\nhget\r\n$3\r\nkey\r\n$5\r\nfield\r\n");
@@ -110,7 +546,7 @@ mod tests {
 
         let hget: HGet = frame.try_into()?;
         assert_eq!(hget.key, "key");
-        assert_eq!(hget.field, "field");
+        assert_eq!(hget.field, b"field");
         Ok(())
     }
 
@@ -132,8 +568,268 @@ mod tests {
 
         let hset: HSet = frame.try_into()?;
         assert_eq!(hset.key, "key");
-        assert_eq!(hset.field, "field");
-        assert_eq!(hset.value, RespFrame::BulkString(b"value".into()));
+        assert_eq!(hset.fields, vec![(b"field".to_vec(), b"value".to_vec())]);
+
+        let mut buf =
+            BytesMut::from("*5\r\n$4\r\nhset\r\n$3\r\nkey\r\n$1\r\na\r\n$1\r\n1\r\n$1\r\nb\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(HSet::try_from(frame).is_err());
         Ok(())
     }
+
+    #[test]
+    fn test_hdel() -> anyhow::Result<()> {
+        let mut buf = BytesMut::from("*4\r\n$4\r\nhdel\r\n$3\r\nkey\r\n$1\r\na\r\n$1\r\nb\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+
+        let hdel: HDel = frame.try_into()?;
+        assert_eq!(hdel.key, "key");
+        assert_eq!(hdel.fields, vec![b"a".to_vec(), b"b".to_vec()]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_hash_commands() {
+        let backend = Backend::new();
+        let key = || "key".to_string();
+        assert_eq!(
+            hset(&backend, "key", &[("a", "1"), ("b", "2")]),
+            RespFrame::Integer(2)
+        );
+        assert_eq!(
+            hset(&backend, "key", &[("b", "3"), ("c", "4")]),
+            RespFrame::Integer(1)
+        );
+
+        let cmd = HGet {
+            key: key(),
+            field: b"b".to_vec(),
+        };
+        assert_eq!(cmd.execute(&backend), BulkString::new("3").into());
+        let cmd = HGet {
+            key: key(),
+            field: b"z".to_vec(),
+        };
+        assert_eq!(cmd.execute(&backend), RespFrame::Null(RespNull));
+
+        assert_eq!(HLen { key: key() }.execute(&backend), RespFrame::Integer(3));
+        let cmd = HExists {
+            key: key(),
+            field: b"a".to_vec(),
+        };
+        assert_eq!(cmd.execute(&backend), RespFrame::Integer(1));
+        let cmd = HExists {
+            key: key(),
+            field: b"z".to_vec(),
+        };
+        assert_eq!(cmd.execute(&backend), RespFrame::Integer(0));
+
+        assert_eq!(
+            sorted(HKeys { key: key() }.execute(&backend)),
+            vec![
+                BulkString::new("a").into(),
+                BulkString::new("b").into(),
+                BulkString::new("c").into()
+            ]
+        );
+        assert_eq!(
+            sorted(HVals { key: key() }.execute(&backend)),
+            vec![
+                BulkString::new("1").into(),
+                BulkString::new("3").into(),
+                BulkString::new("4").into()
+            ]
+        );
+        assert_eq!(
+            sorted(HGetAll { key: key() }.execute(&backend)).len(),
+            6,
+            "field/value pairs"
+        );
+
+        let cmd = HDel {
+            key: key(),
+            fields: vec![b"a".to_vec(), b"z".to_vec(), b"a".to_vec()],
+        };
+        assert_eq!(cmd.execute(&backend), RespFrame::Integer(1));
+        let cmd = HDel {
+            key: key(),
+            fields: vec![b"b".to_vec(), b"c".to_vec()],
+        };
+        assert_eq!(cmd.execute(&backend), RespFrame::Integer(2));
+        assert_eq!(backend.get("key"), None);
+    }
+
+    #[test]
+    fn test_hash_commands_on_missing_key() {
+        let backend = Backend::new();
+        let key = || "missing".to_string();
+        assert_eq!(HLen { key: key() }.execute(&backend), RespFrame::Integer(0));
+        assert_eq!(
+            HKeys { key: key() }.execute(&backend),
+            RespArray::new([]).into()
+        );
+        let cmd = HDel {
+            key: key(),
+            fields: vec![b"a".to_vec()],
+        };
+        assert_eq!(cmd.execute(&backend), RespFrame::Integer(0));
+    }
+
+    #[test]
+    fn test_hash_commands_wrong_type() {
+        let backend = Backend::new();
+        backend.set("key".to_string(), BulkString::new("value"));
+        let key = || "key".to_string();
+        let field = || b"a".to_vec();
+
+        assert_eq!(hset(&backend, "key", &[("a", "1")]), RESP_WRONGTYPE.clone());
+        let frames = [
+            HGet {
+                key: key(),
+                field: field(),
+            }
+            .execute(&backend),
+            HGetAll { key: key() }.execute(&backend),
+            HDel {
+                key: key(),
+                fields: vec![field()],
+            }
+            .execute(&backend),
+            HExists {
+                key: key(),
+                field: field(),
+            }
+            .execute(&backend),
+            HKeys { key: key() }.execute(&backend),
+            HVals { key: key() }.execute(&backend),
+            HLen { key: key() }.execute(&backend),
+        ];
+        for frame in frames {
+            assert_eq!(frame, RESP_WRONGTYPE.clone());
+        }
+    }
+
+    #[test]
+    fn test_hmset_hmget() -> anyhow::Result<()> {
+        let mut buf = BytesMut::from(
+            "*6\r\n$5\r\nhmset\r\n$3\r\nkey\r\n$1\r\na\r\n$1\r\n1\r\n$1\r\nb\r\n$1\r\n2\r\n",
+        );
+        let cmd: HMSet = RespArray::decode(&mut buf)?.try_into()?;
+        let backend = Backend::new();
+        assert_eq!(cmd.execute(&backend), RESP_OK.clone());
+
+        let mut buf =
+            BytesMut::from("*5\r\n$5\r\nhmget\r\n$3\r\nkey\r\n$1\r\na\r\n$1\r\nz\r\n$1\r\nb\r\n");
+        let cmd: HMGet = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(
+            cmd.execute(&backend),
+            RespArray::new(vec![
+                BulkString::new("1").into(),
+                RespFrame::Null(RespNull),
+                BulkString::new("2").into(),
+            ])
+            .into()
+        );
+
+        let cmd = HMGet {
+            key: "missing".to_string(),
+            fields: vec![b"a".to_vec(), b"b".to_vec()],
+        };
+        assert_eq!(
+            cmd.execute(&backend),
+            RespArray::new(vec![RespFrame::Null(RespNull), RespFrame::Null(RespNull)]).into()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_hincrby() {
+        let backend = Backend::new();
+        let incr = |field: &str, increment| {
+            HIncrBy {
+                key: "key".to_string(),
+                field: field.as_bytes().to_vec(),
+                increment,
+            }
+            .execute(&backend)
+        };
+        assert_eq!(incr("n", 5), RespFrame::Integer(5));
+        assert_eq!(incr("n", -7), RespFrame::Integer(-2));
+
+        hset(
+            &backend,
+            "key",
+            &[("s", "abc"), ("max", "9223372036854775807")],
+        );
+        assert_eq!(
+            incr("s", 1),
+            SimpleError::new("ERR hash value is not an integer").into()
+        );
+        assert_eq!(
+            incr("max", 1),
+            SimpleError::new("ERR increment or decrement would overflow").into()
+        );
+
+        backend.set("str".to_string(), BulkString::new("value"));
+        let cmd = HIncrBy {
+            key: "str".to_string(),
+            field: b"n".to_vec(),
+            increment: 1,
+        };
+        assert_eq!(cmd.execute(&backend), RESP_WRONGTYPE.clone());
+    }
+
+    #[test]
+    fn test_hincrbyfloat() {
+        let backend = Backend::new();
+        let incr = |field: &str, increment| {
+            HIncrByFloat {
+                key: "key".to_string(),
+                field: field.as_bytes().to_vec(),
+                increment,
+            }
+            .execute(&backend)
+        };
+        hset(
+            &backend,
+            "key",
+            &[
+                ("f", "10.50"),
+                ("e", "5.0e3"),
+                ("s", "abc"),
+                ("big", "1.7e308"),
+            ],
+        );
+        assert_eq!(incr("f", 0.1), BulkString::new("10.6").into());
+        assert_eq!(incr("e", 200.0), BulkString::new("5200").into());
+        assert_eq!(incr("new", -2.5), BulkString::new("-2.5").into());
+        assert_eq!(
+            incr("s", 1.0),
+            SimpleError::new("ERR hash value is not a float").into()
+        );
+        assert_eq!(
+            incr("big", f64::MAX),
+            SimpleError::new("ERR increment would produce NaN or Infinity").into()
+        );
+    }
+
+    #[test]
+    fn test_hsetnx() {
+        let backend = Backend::new();
+        let setnx = |value: &str| {
+            HSetNx {
+                key: "key".to_string(),
+                field: b"f".to_vec(),
+                value: value.as_bytes().to_vec(),
+            }
+            .execute(&backend)
+        };
+        assert_eq!(setnx("1"), RespFrame::Integer(1));
+        assert_eq!(setnx("2"), RespFrame::Integer(0));
+        let cmd = HGet {
+            key: "key".to_string(),
+            field: b"f".to_vec(),
+        };
+        assert_eq!(cmd.execute(&backend), BulkString::new("1").into());
+    }
 }
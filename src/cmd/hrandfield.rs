@@ -0,0 +1,212 @@
+use rand::{seq::IteratorRandom, Rng};
+
+use crate::{Backend, BulkString, RespArray, RespFrame, RespNullBulkString};
+
+use super::{
+    extract_args, extract_string, hmap::read_hash, parse_number, validate_variadic_command,
+    CommandError, CommandExecutor,
+};
+
+#[derive(Debug)]
+pub struct HRandField {
+    pub key: String,
+    // None replies with a single field, Some with an array: a positive count picks distinct
+    // fields, a negative one allows the same field to be picked again
+    pub count: Option<i64>,
+    pub with_values: bool,
+}
+
+impl CommandExecutor for HRandField {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let missing = match self.count {
+            Some(_) => RespArray::new(vec![]).into(),
+            None => RespNullBulkString.into(),
+        };
+        read_hash(backend, &self.key, missing, |hmap| {
+            let mut rng = rand::thread_rng();
+            let Some(count) = self.count else {
+                return match hmap.keys().choose(&mut rng) {
+                    Some(field) => BulkString::new(field.as_slice()).into(),
+                    None => RespNullBulkString.into(),
+                };
+            };
+
+            let picked: Vec<_> = if count >= 0 {
+                hmap.iter().choose_multiple(&mut rng, count as usize)
+            } else if hmap.is_empty() {
+                vec![]
+            } else {
+                let entries: Vec<_> = hmap.iter().collect();
+                (0..count.unsigned_abs())
+                    .map(|_| entries[rng.gen_range(0..entries.len())])
+                    .collect()
+            };
+
+            let mut ret = Vec::with_capacity(picked.len() * if self.with_values { 2 } else { 1 });
+            for (field, value) in picked {
+                ret.push(BulkString::new(field.as_slice()).into());
+                if self.with_values {
+                    ret.push(BulkString::new(value.as_slice()).into());
+                }
+            }
+            RespArray::new(ret).into()
+        })
+    }
+}
+
+impl TryFrom<RespArray> for HRandField {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["hrandfield"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = extract_string(args.next())?;
+        let count = match args.next() {
+            Some(arg) => Some(parse_number(Some(arg))?),
+            None => None,
+        };
+        let with_values = match args.next() {
+            Some(arg) if extract_string(Some(arg.clone()))?.eq_ignore_ascii_case("withvalues") => {
+                true
+            }
+            Some(arg) => {
+                return Err(CommandError::InvalidArgument(format!(
+                    "Invalid option: {}",
+                    extract_string(Some(arg))?
+                )))
+            }
+            None => false,
+        };
+        if args.next().is_some() {
+            return Err(CommandError::InvalidArgument(
+                "Too many arguments".to_string(),
+            ));
+        }
+        Ok(HRandField {
+            key,
+            count,
+            with_values,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{cmd::HSet, RespDecode};
+
+    use super::*;
+
+    fn fill(backend: &Backend, n: usize) {
+        let cmd = HSet {
+            key: "hash".to_string(),
+            fields: (0..n)
+                .map(|i| {
+                    (
+                        format!("f{}", i).into_bytes(),
+                        format!("v{}", i).into_bytes(),
+                    )
+                })
+                .collect(),
+        };
+        cmd.execute(backend);
+    }
+
+    fn hrandfield(backend: &Backend, count: i64, with_values: bool) -> Vec<String> {
+        let cmd = HRandField {
+            key: "hash".to_string(),
+            count: Some(count),
+            with_values,
+        };
+        let RespFrame::Array(ret) = cmd.execute(backend) else {
+            panic!("expected an array");
+        };
+        ret.iter()
+            .map(|frame| {
+                let RespFrame::BulkString(s) = frame else {
+                    panic!("expected a bulk string");
+                };
+                String::from_utf8_lossy(s).to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_hrandfield_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from(
+            "*4\r\n$10\r\nhrandfield\r\n$4\r\nhash\r\n$2\r\n-5\r\n$10\r\nWITHVALUES\r\n",
+        );
+        let cmd: HRandField = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.key, "hash");
+        assert_eq!(cmd.count, Some(-5));
+        assert!(cmd.with_values);
+
+        let mut buf = BytesMut::from("*2\r\n$10\r\nhrandfield\r\n$4\r\nhash\r\n");
+        let cmd: HRandField = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.count, None);
+        assert!(!cmd.with_values);
+        Ok(())
+    }
+
+    #[test]
+    fn test_hrandfield_positive_count_is_distinct() {
+        let backend = Backend::new();
+        fill(&backend, 5);
+
+        let fields = hrandfield(&backend, 3, false);
+        assert_eq!(fields.len(), 3);
+        assert_eq!(fields.iter().collect::<HashSet<_>>().len(), 3);
+
+        // asking for more than the hash holds returns every field once
+        let fields = hrandfield(&backend, 10, false);
+        assert_eq!(fields.len(), 5);
+        assert_eq!(fields.iter().collect::<HashSet<_>>().len(), 5);
+    }
+
+    #[test]
+    fn test_hrandfield_negative_count_repeats() {
+        let backend = Backend::new();
+        fill(&backend, 2);
+
+        let fields = hrandfield(&backend, -20, false);
+        assert_eq!(fields.len(), 20);
+        assert!(fields.iter().all(|f| f == "f0" || f == "f1"));
+        assert!(fields.iter().collect::<HashSet<_>>().len() <= 2);
+    }
+
+    #[test]
+    fn test_hrandfield_with_values() {
+        let backend = Backend::new();
+        fill(&backend, 3);
+
+        let ret = hrandfield(&backend, -6, true);
+        assert_eq!(ret.len(), 12);
+        for pair in ret.chunks(2) {
+            assert_eq!(pair[0][1..], pair[1][1..]);
+        }
+    }
+
+    #[test]
+    fn test_hrandfield_missing_key() {
+        let backend = Backend::new();
+        let cmd = HRandField {
+            key: "hash".to_string(),
+            count: None,
+            with_values: false,
+        };
+        assert_eq!(cmd.execute(&backend), RespNullBulkString.into());
+        assert!(hrandfield(&backend, 3, false).is_empty());
+
+        fill(&backend, 1);
+        let cmd = HRandField {
+            key: "hash".to_string(),
+            count: None,
+            with_values: false,
+        };
+        assert_eq!(cmd.execute(&backend), BulkString::new("f0").into());
+    }
+}
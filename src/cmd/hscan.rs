@@ -0,0 +1,196 @@
+use crate::{Backend, BulkString, RespArray, RespFrame};
+
+use super::{
+    extract_args, extract_bytes, extract_string, hmap::read_hash, keys::glob_match, parse_number,
+    validate_variadic_command, CommandError, CommandExecutor,
+};
+
+const DEFAULT_SCAN_COUNT: usize = 10;
+
+#[derive(Debug)]
+pub struct HScan {
+    pub key: String,
+    pub cursor: u64,
+    pub pattern: Option<Vec<u8>>,
+    pub count: usize,
+}
+
+impl CommandExecutor for HScan {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let done = RespArray::new(vec![
+            BulkString::new("0").into(),
+            RespArray::new(vec![]).into(),
+        ])
+        .into();
+        read_hash(backend, &self.key, done, |hmap| {
+            // same scheme as SCAN: the cursor indexes the sorted field set
+            let mut fields: Vec<_> = hmap.iter().collect();
+            fields.sort_unstable();
+
+            let start = (self.cursor as usize).min(fields.len());
+            let end = start.saturating_add(self.count).min(fields.len());
+            let next = if end == fields.len() { 0 } else { end as u64 };
+
+            let found: Vec<RespFrame> = fields[start..end]
+                .iter()
+                .filter(|(field, _)| match &self.pattern {
+                    Some(pattern) => glob_match(pattern, field),
+                    None => true,
+                })
+                .flat_map(|(field, value)| {
+                    [
+                        BulkString::new(field.as_slice()).into(),
+                        BulkString::new(value.as_slice()).into(),
+                    ]
+                })
+                .collect();
+            RespArray::new(vec![
+                BulkString::new(next.to_string()).into(),
+                RespArray::new(found).into(),
+            ])
+            .into()
+        })
+    }
+}
+
+impl TryFrom<RespArray> for HScan {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["hscan"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let mut cmd = HScan {
+            key: extract_string(args.next())?,
+            cursor: parse_number(args.next())?,
+            pattern: None,
+            count: DEFAULT_SCAN_COUNT,
+        };
+        while let Some(arg) = args.next() {
+            match extract_string(Some(arg))?.to_ascii_lowercase().as_str() {
+                "match" => cmd.pattern = Some(extract_bytes(args.next())?),
+                "count" => {
+                    cmd.count = parse_number(args.next())?;
+                    if cmd.count == 0 {
+                        return Err(CommandError::InvalidArgument(
+                            "COUNT must be positive".to_string(),
+                        ));
+                    }
+                }
+                v => {
+                    return Err(CommandError::InvalidArgument(format!(
+                        "Invalid option: {}",
+                        v
+                    )))
+                }
+            }
+        }
+        Ok(cmd)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{cmd::HSet, RespDecode};
+
+    use super::*;
+
+    // run HSCAN until the cursor comes back to 0, returning every field/value pair seen
+    fn hscan_all(backend: &Backend, pattern: Option<&str>) -> Vec<(String, String)> {
+        let mut cursor = 0;
+        let mut seen = vec![];
+        loop {
+            let cmd = HScan {
+                key: "hash".to_string(),
+                cursor,
+                pattern: pattern.map(|p| p.as_bytes().to_vec()),
+                count: 7,
+            };
+            let RespFrame::Array(ret) = cmd.execute(backend) else {
+                panic!("expected an array");
+            };
+            let (RespFrame::BulkString(next), RespFrame::Array(items)) = (&ret[0], &ret[1]) else {
+                panic!("expected [cursor, items]");
+            };
+            for pair in items.chunks(2) {
+                let [RespFrame::BulkString(field), RespFrame::BulkString(value)] = pair else {
+                    panic!("expected field and value bulk strings");
+                };
+                seen.push((
+                    String::from_utf8_lossy(field).to_string(),
+                    String::from_utf8_lossy(value).to_string(),
+                ));
+            }
+            cursor = String::from_utf8_lossy(next).parse().unwrap();
+            if cursor == 0 {
+                return seen;
+            }
+        }
+    }
+
+    fn fill(backend: &Backend, n: usize) {
+        let cmd = HSet {
+            key: "hash".to_string(),
+            fields: (0..n)
+                .map(|i| {
+                    (
+                        format!("field:{}", i).into_bytes(),
+                        format!("value:{}", i).into_bytes(),
+                    )
+                })
+                .collect(),
+        };
+        cmd.execute(backend);
+    }
+
+    #[test]
+    fn test_hscan_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from(
+            "*7\r\n$5\r\nhscan\r\n$4\r\nhash\r\n$1\r\n5\r\n$5\r\nMATCH\r\n$3\r\nf:*\r\n$5\r\nCOUNT\r\n$2\r\n20\r\n",
+        );
+        let cmd: HScan = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.key, "hash");
+        assert_eq!(cmd.cursor, 5);
+        assert_eq!(cmd.pattern, Some(b"f:*".to_vec()));
+        assert_eq!(cmd.count, 20);
+        Ok(())
+    }
+
+    #[test]
+    fn test_hscan_visits_every_field_once() {
+        let backend = Backend::new();
+        fill(&backend, 1000);
+
+        let seen = hscan_all(&backend, None);
+        assert_eq!(seen.len(), 1000);
+        let unique: HashMap<_, _> = seen.into_iter().collect();
+        assert_eq!(unique.len(), 1000);
+        for i in 0..1000 {
+            assert_eq!(unique[&format!("field:{}", i)], format!("value:{}", i));
+        }
+    }
+
+    #[test]
+    fn test_hscan_match() {
+        let backend = Backend::new();
+        fill(&backend, 1000);
+
+        let mut seen: Vec<String> = hscan_all(&backend, Some("field:1?"))
+            .into_iter()
+            .map(|(field, _)| field)
+            .collect();
+        seen.sort();
+        let expected: Vec<String> = (10..20).map(|i| format!("field:{}", i)).collect();
+        assert_eq!(seen, expected);
+    }
+
+    #[test]
+    fn test_hscan_missing_key() {
+        let backend = Backend::new();
+        assert!(hscan_all(&backend, None).is_empty());
+    }
+}
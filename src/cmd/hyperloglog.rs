@@ -0,0 +1,252 @@
+use lazy_static::lazy_static;
+
+use crate::{
+    Backend, BackendValue, BulkString, Db, HyperLogLog, RespArray, RespFrame, SimpleError,
+};
+
+use super::{
+    extract_args, extract_bytes, extract_string, validate_variadic_command, CommandError,
+    CommandExecutor, RESP_OK, RESP_WRONGTYPE,
+};
+
+lazy_static! {
+    static ref RESP_INVALID_HLL: RespFrame =
+        SimpleError::new("WRONGTYPE Key is not a valid HyperLogLog string value.").into();
+}
+
+/// PFADD key [element ...], replies 1 if the estimate may have changed, creating the key counts
+#[derive(Debug)]
+pub struct PfAdd {
+    pub key: String,
+    pub elements: Vec<Vec<u8>>,
+}
+
+/// PFCOUNT key [key ...], the estimated size of the union of the keys
+#[derive(Debug)]
+pub struct PfCount {
+    pub keys: Vec<String>,
+}
+
+/// PFMERGE destkey [sourcekey ...], destkey counts as a source if it exists
+#[derive(Debug)]
+pub struct PfMerge {
+    pub dst: String,
+    pub keys: Vec<String>,
+}
+
+impl CommandExecutor for PfAdd {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let mut db = backend.write();
+        let (mut hll, mut changed) = match load(&db, &self.key) {
+            Ok(Some(hll)) => (hll, false),
+            Ok(None) => (HyperLogLog::new(), true),
+            Err(e) => return e,
+        };
+        for element in &self.elements {
+            changed |= hll.add(element);
+        }
+        if changed {
+            store(&mut db, self.key, &hll);
+        }
+        RespFrame::Integer(changed as i64)
+    }
+}
+
+impl CommandExecutor for PfCount {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let db = backend.read();
+        let mut union = HyperLogLog::new();
+        for key in &self.keys {
+            match load(&db, key) {
+                Ok(Some(hll)) => union.merge(&hll),
+                Ok(None) => {}
+                Err(e) => return e,
+            }
+        }
+        RespFrame::Integer(union.count() as i64)
+    }
+}
+
+impl CommandExecutor for PfMerge {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let mut db = backend.write();
+        let mut union = HyperLogLog::new();
+        for key in std::iter::once(&self.dst).chain(&self.keys) {
+            match load(&db, key) {
+                Ok(Some(hll)) => union.merge(&hll),
+                Ok(None) => {}
+                Err(e) => return e,
+            }
+        }
+        store(&mut db, self.dst, &union);
+        RESP_OK.clone()
+    }
+}
+
+// a missing key is None, anything but a HyperLogLog string is an error
+fn load(db: &Db, key: &str) -> Result<Option<HyperLogLog>, RespFrame> {
+    match db.get(key) {
+        Some(BackendValue::String(s)) => HyperLogLog::decode(s)
+            .map(Some)
+            .ok_or_else(|| RESP_INVALID_HLL.clone()),
+        Some(_) => Err(RESP_WRONGTYPE.clone()),
+        None => Ok(None),
+    }
+}
+
+// overwrite in place, so that an existing key keeps its expiry
+fn store(db: &mut Db, key: String, hll: &HyperLogLog) {
+    let value = db.get_or_insert_with(key.clone(), || BulkString::new(vec![]).into());
+    *value = BulkString::new(hll.encode()).into();
+    db.signal_modified(&key);
+}
+
+impl TryFrom<RespArray> for PfAdd {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["pfadd"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(PfAdd {
+            key: extract_string(args.next())?,
+            elements: args
+                .map(|arg| extract_bytes(Some(arg)))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for PfCount {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["pfcount"], 1)?;
+
+        let keys = extract_args(value, 1)?
+            .into_iter()
+            .map(|arg| extract_string(Some(arg)))
+            .collect::<Result<_, _>>()?;
+        Ok(PfCount { keys })
+    }
+}
+
+impl TryFrom<RespArray> for PfMerge {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["pfmerge"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(PfMerge {
+            dst: extract_string(args.next())?,
+            keys: args
+                .map(|arg| extract_string(Some(arg)))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{clock, RespDecode, HLL_DENSE_SIZE};
+
+    use super::*;
+
+    fn pfadd(backend: &Backend, key: &str, elements: &[&str]) -> RespFrame {
+        PfAdd {
+            key: key.to_string(),
+            elements: elements.iter().map(|e| e.as_bytes().to_vec()).collect(),
+        }
+        .execute(backend)
+    }
+
+    fn pfcount(backend: &Backend, keys: &[&str]) -> RespFrame {
+        PfCount {
+            keys: keys.iter().map(|k| k.to_string()).collect(),
+        }
+        .execute(backend)
+    }
+
+    #[test]
+    fn test_pfadd_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*3\r\n$5\r\npfadd\r\n$3\r\nhll\r\n$1\r\na\r\n");
+        let cmd: PfAdd = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.key, "hll");
+        assert_eq!(cmd.elements, vec![b"a".to_vec()]);
+
+        let mut buf = BytesMut::from("*1\r\n$5\r\npfadd\r\n");
+        let ret: Result<PfAdd, _> = RespArray::decode(&mut buf)?.try_into();
+        assert!(ret.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_pfadd_and_pfcount() {
+        let backend = Backend::new();
+        assert_eq!(pfcount(&backend, &["hll"]), RespFrame::Integer(0));
+
+        // creating the key is a change even without elements
+        assert_eq!(pfadd(&backend, "hll", &[]), RespFrame::Integer(1));
+        assert_eq!(pfadd(&backend, "hll", &[]), RespFrame::Integer(0));
+        assert_eq!(
+            pfadd(&backend, "hll", &["a", "b", "c"]),
+            RespFrame::Integer(1)
+        );
+        assert_eq!(pfadd(&backend, "hll", &["a", "b"]), RespFrame::Integer(0));
+        assert_eq!(pfcount(&backend, &["hll"]), RespFrame::Integer(3));
+
+        let Some(BackendValue::String(s)) = backend.get("hll") else {
+            panic!("expected a string");
+        };
+        assert_eq!(s.len(), HLL_DENSE_SIZE);
+        assert!(s.starts_with(b"HYLL"));
+
+        pfadd(&backend, "other", &["c", "d"]);
+        assert_eq!(
+            pfcount(&backend, &["hll", "other", "missing"]),
+            RespFrame::Integer(4)
+        );
+
+        backend.set("string".to_string(), BulkString::new("value"));
+        assert_eq!(pfadd(&backend, "string", &["a"]), RESP_INVALID_HLL.clone());
+        assert_eq!(
+            pfcount(&backend, &["hll", "string"]),
+            RESP_INVALID_HLL.clone()
+        );
+        backend
+            .write()
+            .insert("set".to_string(), BackendValue::Set([b"a".to_vec()].into()));
+        assert_eq!(pfcount(&backend, &["set"]), RESP_WRONGTYPE.clone());
+    }
+
+    #[test]
+    fn test_pfmerge() {
+        let backend = Backend::new();
+        pfadd(&backend, "a", &["1", "2", "3"]);
+        pfadd(&backend, "b", &["3", "4"]);
+        pfadd(&backend, "dst", &["5"]);
+        backend
+            .write()
+            .expire("dst", clock::now() + Duration::from_secs(100));
+
+        let merge = PfMerge {
+            dst: "dst".to_string(),
+            keys: vec!["a".to_string(), "b".to_string(), "missing".to_string()],
+        };
+        assert_eq!(merge.execute(&backend), RESP_OK.clone());
+        assert_eq!(pfcount(&backend, &["dst"]), RespFrame::Integer(5));
+        assert!(backend.read().peek("dst").unwrap().expires_at.is_some());
+
+        // merging nothing still creates the destination
+        let merge = PfMerge {
+            dst: "empty".to_string(),
+            keys: vec![],
+        };
+        assert_eq!(merge.execute(&backend), RESP_OK.clone());
+        assert_eq!(pfcount(&backend, &["empty"]), RespFrame::Integer(0));
+        assert!(backend.read().contains_key("empty"));
+    }
+}
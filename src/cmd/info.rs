@@ -0,0 +1,319 @@
+use std::fmt::Write;
+
+use crate::{Backend, BulkString, RespArray, RespFrame, DB_COUNT};
+
+use super::{
+    extract_args, extract_string, validate_variadic_command, CommandError, CommandExecutor,
+};
+
+// in the order INFO prints them
+const SECTIONS: [&str; 6] = [
+    "server",
+    "clients",
+    "memory",
+    "stats",
+    "replication",
+    "keyspace",
+];
+
+/// INFO [section ...], every section when none is given
+#[derive(Debug)]
+pub struct Info {
+    pub sections: Vec<String>,
+}
+
+impl CommandExecutor for Info {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let all = self.sections.is_empty()
+            || self
+                .sections
+                .iter()
+                .any(|s| matches!(s.as_str(), "all" | "everything" | "default"));
+        let sections = SECTIONS
+            .iter()
+            .filter(|name| all || self.sections.iter().any(|s| s == *name));
+
+        let mut ret = String::new();
+        for name in sections {
+            if !ret.is_empty() {
+                ret.push_str("\r\n");
+            }
+            write_section(&mut ret, name, backend);
+        }
+        BulkString::new(ret).into()
+    }
+}
+
+// a `# Title` line followed by `field:value` lines
+fn write_section(out: &mut String, name: &str, backend: &Backend) {
+    let mut title = name.to_string();
+    title[..1].make_ascii_uppercase();
+    let _ = write!(out, "# {}\r\n", title);
+    let fields: Vec<(String, String)> = match name {
+        "server" => vec![
+            field("redis_version", env!("CARGO_PKG_VERSION")),
+            field("redis_git_sha1", "00000000"),
+            field(
+                "os",
+                format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+            ),
+            field("process_id", std::process::id()),
+            field("tcp_port", backend.config().port),
+            field("uptime_in_seconds", backend.stats().uptime().as_secs()),
+            field(
+                "uptime_in_days",
+                backend.stats().uptime().as_secs() / (24 * 3600),
+            ),
+        ],
+        "clients" => vec![
+            field("connected_clients", backend.client_count()),
+            field("maxclients", backend.config().maxclients),
+            field("blocked_clients", backend.stats().blocked_clients()),
+        ],
+        "memory" => {
+            let used = backend.used_memory();
+            vec![
+                field("used_memory", used),
+                field("used_memory_human", bytes_to_human(used)),
+            ]
+        }
+        "stats" => {
+            let stats = backend.stats();
+            vec![
+                field(
+                    "total_connections_received",
+                    stats.total_connections_received(),
+                ),
+                field("total_commands_processed", stats.total_commands_processed()),
+                field(
+                    "instantaneous_ops_per_sec",
+                    stats.instantaneous_ops_per_sec(),
+                ),
+                field("rejected_connections", stats.rejected_connections()),
+            ]
+        }
+        "replication" => {
+            let replication = backend.replication();
+            let mut fields = match replication.master() {
+                Some(master) => vec![
+                    field("role", "slave"),
+                    field("master_host", master.host),
+                    field("master_port", master.port),
+                    field(
+                        "master_link_status",
+                        if master.link_up { "up" } else { "down" },
+                    ),
+                ],
+                None => vec![field("role", "master")],
+            };
+            let replicas = replication.replicas();
+            fields.push(field("connected_slaves", replicas.len()));
+            for (index, replica) in replicas.into_iter().enumerate() {
+                fields.push(field(
+                    format!("slave{}", index),
+                    format!(
+                        "ip={},port={},state=online,offset={},lag={}",
+                        replica.ip,
+                        replica.port,
+                        replica.offset,
+                        replica.lag.as_secs()
+                    ),
+                ));
+            }
+            fields.push(field("master_replid", replication.replid()));
+            fields.push(field("master_repl_offset", replication.offset()));
+            fields
+        }
+        // only databases holding keys are listed
+        "keyspace" => (0..DB_COUNT)
+            .filter_map(|index| {
+                let (keys, expires) = backend.keyspace_info(index);
+                (keys > 0).then(|| {
+                    field(
+                        format!("db{}", index),
+                        format!("keys={},expires={},avg_ttl=0", keys, expires),
+                    )
+                })
+            })
+            .collect(),
+        _ => vec![],
+    };
+    for (name, value) in fields {
+        let _ = write!(out, "{}:{}\r\n", name, value);
+    }
+}
+
+fn field(name: impl Into<String>, value: impl ToString) -> (String, String) {
+    (name.into(), value.to_string())
+}
+
+// the way redis abbreviates memory sizes, 1.50K, 2.00M...
+fn bytes_to_human(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["K", "M", "G", "T"];
+    if bytes < 1024 {
+        return format!("{}B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.2}{}", size, UNITS[unit])
+}
+
+impl TryFrom<RespArray> for Info {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["info"], 0)?;
+
+        let sections = extract_args(value, 1)?
+            .into_iter()
+            .map(|arg| extract_string(Some(arg)).map(|s| s.to_ascii_lowercase()))
+            .collect::<Result<_, _>>()?;
+        Ok(Info { sections })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{clock, network::ReplicaOptions, ConnectionInfo, RespDecode};
+
+    use super::*;
+
+    fn info(backend: &Backend, sections: &[&str]) -> String {
+        let cmd = Info {
+            sections: sections.iter().map(|s| s.to_string()).collect(),
+        };
+        match cmd.execute(backend) {
+            RespFrame::BulkString(s) => String::from_utf8(s.to_vec()).unwrap(),
+            frame => panic!("expected a bulk string, got {:?}", frame),
+        }
+    }
+
+    #[test]
+    fn test_info_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*2\r\n$4\r\ninfo\r\n$6\r\nMEMORY\r\n");
+        let cmd: Info = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.sections, vec!["memory".to_string()]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_info_sections() {
+        let backend = Backend::new();
+        backend.register_client(ConnectionInfo::new(1, "127.0.0.1:1"));
+        backend.stats().command_processed();
+
+        assert_eq!(
+            info(&backend, &["clients"]),
+            "# Clients\r\nconnected_clients:1\r\nmaxclients:10000\r\nblocked_clients:0\r\n"
+        );
+        assert_eq!(
+            info(&backend, &["stats"]),
+            "# Stats\r\ntotal_connections_received:1\r\ntotal_commands_processed:1\r\n\
+             instantaneous_ops_per_sec:0\r\nrejected_connections:0\r\n"
+        );
+        // an empty keyspace lists no database
+        assert_eq!(info(&backend, &["keyspace"]), "# Keyspace\r\n");
+        assert_eq!(info(&backend, &["unknown"]), "");
+
+        let server = info(&backend, &["server"]);
+        assert!(server.starts_with("# Server\r\nredis_version:"));
+        assert!(server.contains("\r\ntcp_port:6379\r\n"));
+    }
+
+    #[tokio::test]
+    async fn test_info_replication() {
+        let backend = Backend::new();
+        let replication = backend.replication();
+        let replid = replication.replid();
+        assert_eq!(
+            info(&backend, &["replication"]),
+            format!(
+                "# Replication\r\nrole:master\r\nconnected_slaves:0\r\n\
+                 master_replid:{}\r\nmaster_repl_offset:0\r\n",
+                replid
+            )
+        );
+
+        let options = ReplicaOptions {
+            listening_port: Some(6380),
+            ip_address: Some("10.0.0.2".to_string()),
+            capa: vec![],
+        };
+        let _stream = replication.add_replica(7, options, vec![], 1024);
+        replication.feed(
+            0,
+            RespArray::new(vec![BulkString::new("set").into()]).into(),
+        );
+        let offset = replication.offset();
+        replication.ack(7, offset);
+        assert_eq!(
+            info(&backend, &["replication"]),
+            format!(
+                "# Replication\r\nrole:master\r\nconnected_slaves:1\r\n\
+                 slave0:ip=10.0.0.2,port=6380,state=online,offset={},lag=0\r\n\
+                 master_replid:{}\r\nmaster_repl_offset:{}\r\n",
+                offset, replid, offset
+            )
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_info_keyspace_and_memory() {
+        let backend = Backend::default();
+        backend.set("a".to_string(), BulkString::new("value"));
+        backend.set("b".to_string(), BulkString::new("value"));
+        backend
+            .write()
+            .expire("b", clock::now() + Duration::from_secs(10));
+        backend
+            .select(3)
+            .unwrap()
+            .set("c".to_string(), BulkString::new("value"));
+
+        assert_eq!(
+            info(&backend, &["keyspace"]),
+            "# Keyspace\r\ndb0:keys=2,expires=1,avg_ttl=0\r\ndb3:keys=1,expires=0,avg_ttl=0\r\n"
+        );
+        let memory = info(&backend, &["memory"]);
+        let used: usize = memory
+            .lines()
+            .find_map(|line| line.strip_prefix("used_memory:"))
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(used, backend.used_memory());
+        assert!(used > 0);
+
+        // every section, separated by a blank line
+        let all = info(&backend, &["all"]);
+        let titles: Vec<_> = all.lines().filter(|line| line.starts_with('#')).collect();
+        assert_eq!(
+            titles,
+            vec![
+                "# Server",
+                "# Clients",
+                "# Memory",
+                "# Stats",
+                "# Replication",
+                "# Keyspace"
+            ]
+        );
+        assert_eq!(all, info(&backend, &[]));
+        assert!(all.contains("\r\n\r\n# Clients\r\n"));
+    }
+
+    #[test]
+    fn test_bytes_to_human() {
+        assert_eq!(bytes_to_human(100), "100B");
+        assert_eq!(bytes_to_human(1536), "1.50K");
+        assert_eq!(bytes_to_human(3 * 1024 * 1024), "3.00M");
+    }
+}
@@ -0,0 +1,161 @@
+use crate::{Backend, BulkString, RespArray, RespFrame};
+
+use super::{extract_args, extract_bytes, validate_command, CommandError, CommandExecutor};
+
+#[derive(Debug)]
+pub struct Keys {
+    pub pattern: Vec<u8>,
+}
+
+impl CommandExecutor for Keys {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let keys: Vec<RespFrame> = backend
+            .read()
+            .keys()
+            .filter(|key| glob_match(&self.pattern, key.as_bytes()))
+            .map(|key| BulkString::new(key.as_bytes()).into())
+            .collect();
+        RespArray::new(keys).into()
+    }
+}
+
+impl TryFrom<RespArray> for Keys {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["keys"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(Keys {
+            pattern: extract_bytes(args.next())?,
+        })
+    }
+}
+
+/// redis style glob: `*`, `?`, `[abc]`, `[^abc]`, `[a-z]` and `\` to escape
+pub(crate) fn glob_match(pattern: &[u8], s: &[u8]) -> bool {
+    match pattern.first() {
+        None => s.is_empty(),
+        Some(b'*') => {
+            // try every split point of the remaining input
+            let rest = &pattern[1..];
+            if rest.is_empty() {
+                return true;
+            }
+            (0..=s.len()).any(|i| glob_match(rest, &s[i..]))
+        }
+        Some(b'?') => !s.is_empty() && glob_match(&pattern[1..], &s[1..]),
+        Some(b'[') => match (s.first(), match_class(&pattern[1..], s.first().copied())) {
+            (Some(_), Some((true, rest))) => glob_match(rest, &s[1..]),
+            _ => false,
+        },
+        Some(b'\\') if pattern.len() > 1 => {
+            s.first() == Some(&pattern[1]) && glob_match(&pattern[2..], &s[1..])
+        }
+        Some(&c) => s.first() == Some(&c) && glob_match(&pattern[1..], &s[1..]),
+    }
+}
+
+// match one character against the class starting right after `[`,
+// returns whether it matched and the pattern after the closing `]`
+fn match_class(pattern: &[u8], c: Option<u8>) -> Option<(bool, &[u8])> {
+    let c = c?;
+    let (negate, mut p) = match pattern.first() {
+        Some(b'^') => (true, &pattern[1..]),
+        _ => (false, pattern),
+    };
+    let mut matched = false;
+    loop {
+        match p {
+            // an unterminated class never matches
+            [] => return None,
+            [b']', rest @ ..] => return Some((matched != negate, rest)),
+            [b'\\', x, rest @ ..] => {
+                matched |= *x == c;
+                p = rest;
+            }
+            [lo, b'-', hi, rest @ ..] if *hi != b']' => {
+                let (lo, hi) = if lo <= hi { (*lo, *hi) } else { (*hi, *lo) };
+                matched |= (lo..=hi).contains(&c);
+                p = rest;
+            }
+            [x, rest @ ..] => {
+                matched |= *x == c;
+                p = rest;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    fn keys(backend: &Backend, pattern: &str) -> Vec<String> {
+        let cmd = Keys {
+            pattern: pattern.as_bytes().to_vec(),
+        };
+        let RespFrame::Array(keys) = cmd.execute(backend) else {
+            panic!("expected an array");
+        };
+        let mut keys: Vec<String> = keys
+            .iter()
+            .map(|key| match key {
+                RespFrame::BulkString(key) => String::from_utf8_lossy(key).to_string(),
+                _ => panic!("expected a bulk string"),
+            })
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match(b"*", b""));
+        assert!(glob_match(b"h*o", b"hello"));
+        assert!(glob_match(b"h**o", b"ho"));
+        assert!(!glob_match(b"h*o", b"hell"));
+        assert!(glob_match(b"h?llo", b"hallo"));
+        assert!(!glob_match(b"h?llo", b"hllo"));
+        assert!(glob_match(b"h[ae]llo", b"hello"));
+        assert!(!glob_match(b"h[ae]llo", b"hillo"));
+        assert!(glob_match(b"h[^e]llo", b"hallo"));
+        assert!(!glob_match(b"h[^e]llo", b"hello"));
+        assert!(glob_match(b"h[a-c]llo", b"hbllo"));
+        assert!(!glob_match(b"h[a-c]llo", b"hdllo"));
+        assert!(glob_match(b"h\\*llo", b"h*llo"));
+        assert!(!glob_match(b"h\\*llo", b"hello"));
+        assert!(!glob_match(b"h[ae", b"ha"));
+    }
+
+    #[test]
+    fn test_keys_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*2\r\n$4\r\nkeys\r\n$1\r\n*\r\n");
+        let cmd: Keys = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.pattern, b"*");
+        Ok(())
+    }
+
+    #[test]
+    fn test_keys_command() {
+        let backend = Backend::new();
+        for key in ["hello", "hallo", "hillo", "hxllo", "world"] {
+            backend.set(key.to_string(), BulkString::new("value"));
+        }
+
+        assert_eq!(
+            keys(&backend, "*"),
+            vec!["hallo", "hello", "hillo", "hxllo", "world"]
+        );
+        assert_eq!(
+            keys(&backend, "h?llo"),
+            vec!["hallo", "hello", "hillo", "hxllo"]
+        );
+        assert_eq!(keys(&backend, "h[ae]llo"), vec!["hallo", "hello"]);
+        assert!(keys(&backend, "nothing*").is_empty());
+    }
+}
@@ -0,0 +1,66 @@
+use crate::{Backend, RespArray, RespFrame};
+
+use super::{validate_command, CommandError, CommandExecutor};
+
+/// LASTSAVE, the unix time of the last successful save, or of the start if there was none
+#[derive(Debug)]
+pub struct LastSave;
+
+impl CommandExecutor for LastSave {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        RespFrame::Integer(backend.persistence().last_save())
+    }
+}
+
+impl TryFrom<RespArray> for LastSave {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["lastsave"], 0)?;
+        Ok(LastSave)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{cmd::Save, RespDecode};
+
+    use super::*;
+
+    #[test]
+    fn test_lastsave_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*1\r\n$8\r\nlastsave\r\n");
+        let _: LastSave = RespArray::decode(&mut buf)?.try_into()?;
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_lastsave_follows_saves() -> Result<()> {
+        let dir =
+            std::env::temp_dir().join(format!("simple-redis-lastsave-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let backend = Backend::new();
+        let RespFrame::Integer(started) = LastSave.execute(&backend) else {
+            panic!("not an integer");
+        };
+
+        tokio::time::advance(Duration::from_secs(10)).await;
+        assert_eq!(LastSave.execute(&backend), RespFrame::Integer(started));
+
+        // a failed save doesn't count
+        backend.config_mut().dir = dir.join("missing").to_string_lossy().into_owned();
+        Save.execute(&backend);
+        assert_eq!(LastSave.execute(&backend), RespFrame::Integer(started));
+
+        backend.config_mut().dir = dir.to_string_lossy().into_owned();
+        Save.execute(&backend);
+        assert_eq!(LastSave.execute(&backend), RespFrame::Integer(started + 10));
+
+        std::fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,161 @@
+use crate::{Backend, BulkString, RespArray, RespFrame};
+
+use super::{
+    extract_args, extract_string, validate_command, validate_variadic_command, CommandError,
+    CommandExecutor,
+};
+
+/// LATENCY HISTORY event
+#[derive(Debug)]
+pub struct LatencyHistory {
+    pub event: String,
+}
+
+#[derive(Debug)]
+pub struct LatencyLatest;
+
+/// LATENCY RESET [event ...], every event when none is given
+#[derive(Debug)]
+pub struct LatencyReset {
+    pub events: Vec<String>,
+}
+
+impl CommandExecutor for LatencyHistory {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        // [[timestamp, latency in ms], ...], oldest first
+        let samples = backend
+            .latency()
+            .history(&self.event)
+            .into_iter()
+            .map(|(at, ms)| {
+                RespArray::new(vec![RespFrame::Integer(at), RespFrame::Integer(ms as i64)]).into()
+            })
+            .collect::<Vec<RespFrame>>();
+        RespArray::new(samples).into()
+    }
+}
+
+impl CommandExecutor for LatencyLatest {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        // [[event, timestamp, latest latency, max latency], ...]
+        let events = backend
+            .latency()
+            .latest()
+            .into_iter()
+            .filter_map(|(name, event)| {
+                let (at, ms) = *event.samples.back()?;
+                Some(
+                    RespArray::new(vec![
+                        BulkString::new(name).into(),
+                        RespFrame::Integer(at),
+                        RespFrame::Integer(ms as i64),
+                        RespFrame::Integer(event.max as i64),
+                    ])
+                    .into(),
+                )
+            })
+            .collect::<Vec<RespFrame>>();
+        RespArray::new(events).into()
+    }
+}
+
+impl CommandExecutor for LatencyReset {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        RespFrame::Integer(backend.latency().reset(&self.events) as i64)
+    }
+}
+
+impl TryFrom<RespArray> for LatencyHistory {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["latency", "history"], 1)?;
+
+        let mut args = extract_args(value, 2)?.into_iter();
+        Ok(LatencyHistory {
+            event: extract_string(args.next())?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for LatencyLatest {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["latency", "latest"], 0)?;
+        Ok(LatencyLatest)
+    }
+}
+
+impl TryFrom<RespArray> for LatencyReset {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["latency", "reset"], 0)?;
+
+        let events = extract_args(value, 2)?
+            .into_iter()
+            .map(|arg| extract_string(Some(arg)))
+            .collect::<Result<_, _>>()?;
+        Ok(LatencyReset { events })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_latency_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*3\r\n$7\r\nlatency\r\n$7\r\nhistory\r\n$7\r\ncommand\r\n");
+        let cmd: LatencyHistory = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.event, "command");
+
+        let mut buf = BytesMut::from("*2\r\n$7\r\nlatency\r\n$5\r\nreset\r\n");
+        let cmd: LatencyReset = RespArray::decode(&mut buf)?.try_into()?;
+        assert!(cmd.events.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_latency_commands() {
+        let backend = Backend::new();
+        backend.config_mut().latency_monitor_threshold = 10;
+        backend.record_latency("command", Duration::from_millis(40));
+        tokio::time::advance(Duration::from_secs(1)).await;
+        backend.record_latency("command", Duration::from_millis(25));
+
+        let RespFrame::Array(history) = (LatencyHistory {
+            event: "command".to_string(),
+        })
+        .execute(&backend) else {
+            panic!("expected an array");
+        };
+        assert_eq!(history.len(), 2);
+        let RespFrame::Array(ref first) = history[0] else {
+            panic!("expected an array");
+        };
+        assert_eq!(first[1], RespFrame::Integer(40));
+
+        let RespFrame::Array(latest) = LatencyLatest.execute(&backend) else {
+            panic!("expected an array");
+        };
+        let RespFrame::Array(ref command) = latest[0] else {
+            panic!("expected an array");
+        };
+        assert_eq!(command[0], BulkString::new("command").into());
+        assert_eq!(command[2], RespFrame::Integer(25));
+        assert_eq!(command[3], RespFrame::Integer(40));
+
+        let reset = LatencyReset { events: vec![] };
+        assert_eq!(reset.execute(&backend), RespFrame::Integer(1));
+        assert_eq!(
+            LatencyLatest.execute(&backend),
+            RespArray::new(vec![]).into()
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{Backend, RespArray, RespFrame};
+
+use super::{
+    extract_args, extract_bytes, extract_string, list::write_list, validate_command, CommandError,
+    CommandExecutor,
+};
+
+#[derive(Debug)]
+pub struct LInsert {
+    pub key: String,
+    // BEFORE: insert in front of the pivot instead of after it
+    pub before: bool,
+    pub pivot: Vec<u8>,
+    pub element: Vec<u8>,
+}
+
+impl CommandExecutor for LInsert {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        write_list(backend, &self.key, RespFrame::Integer(0), |list| match list
+            .iter()
+            .position(|e| *e == self.pivot)
+        {
+            Some(pos) => {
+                let index = if self.before { pos } else { pos + 1 };
+                list.insert(index, self.element);
+                (RespFrame::Integer(list.len() as i64), true)
+            }
+            None => (RespFrame::Integer(-1), false),
+        })
+    }
+}
+
+impl TryFrom<RespArray> for LInsert {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["linsert"], 4)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = extract_string(args.next())?;
+        let before = match extract_string(args.next())?.to_ascii_lowercase().as_str() {
+            "before" => true,
+            "after" => false,
+            v => {
+                return Err(CommandError::InvalidArgument(format!(
+                    "Invalid option: {}",
+                    v
+                )))
+            }
+        };
+        Ok(LInsert {
+            key,
+            before,
+            pivot: extract_bytes(args.next())?,
+            element: extract_bytes(args.next())?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{
+        cmd::{LRange, Push},
+        BulkString, RespDecode,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_linsert_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from(
+            "*5\r\n$7\r\nlinsert\r\n$4\r\nlist\r\n$6\r\nBEFORE\r\n$1\r\nb\r\n$1\r\nx\r\n",
+        );
+        let cmd: LInsert = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.key, "list");
+        assert!(cmd.before);
+        assert_eq!(cmd.pivot, b"b");
+        assert_eq!(cmd.element, b"x");
+        Ok(())
+    }
+
+    #[test]
+    fn test_linsert() {
+        let backend = Backend::new();
+        let linsert = |before, pivot: &str, element: &str| {
+            LInsert {
+                key: "list".to_string(),
+                before,
+                pivot: pivot.as_bytes().to_vec(),
+                element: element.as_bytes().to_vec(),
+            }
+            .execute(&backend)
+        };
+        assert_eq!(linsert(true, "a", "x"), RespFrame::Integer(0));
+
+        let cmd = Push {
+            key: "list".to_string(),
+            elements: vec![b"a".to_vec(), b"b".to_vec(), b"b".to_vec()],
+            left: false,
+        };
+        cmd.execute(&backend);
+        assert_eq!(linsert(true, "b", "x"), RespFrame::Integer(4));
+        assert_eq!(linsert(false, "b", "y"), RespFrame::Integer(5));
+        assert_eq!(linsert(false, "z", "y"), RespFrame::Integer(-1));
+
+        let cmd = LRange {
+            key: "list".to_string(),
+            start: 0,
+            stop: -1,
+        };
+        let expected: Vec<RespFrame> = ["a", "x", "b", "y", "b"]
+            .iter()
+            .map(|e| BulkString::new(*e).into())
+            .collect();
+        assert_eq!(cmd.execute(&backend), RespArray::new(expected).into());
+    }
+}
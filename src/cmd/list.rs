@@ -0,0 +1,483 @@
+use std::collections::VecDeque;
+
+use crate::{
+    Backend, BackendValue, BulkString, RespArray, RespFrame, RespNullArray, RespNullBulkString,
+    SimpleError,
+};
+
+use super::{
+    extract_args, extract_bytes, extract_string, parse_number, validate_command,
+    validate_variadic_command, CommandError, CommandExecutor, RESP_OK, RESP_WRONGTYPE,
+};
+
+#[derive(Debug)]
+pub struct Push {
+    pub key: String,
+    pub elements: Vec<Vec<u8>>,
+    // LPUSH: push to the head instead of the tail
+    pub left: bool,
+}
+
+#[derive(Debug)]
+pub struct Pop {
+    pub key: String,
+    // None replies with a single element, Some with an array of up to `count` elements
+    pub count: Option<usize>,
+    // LPOP: pop from the head instead of the tail
+    pub left: bool,
+}
+
+#[derive(Debug)]
+pub struct LLen {
+    pub key: String,
+}
+
+#[derive(Debug)]
+pub struct LRange {
+    pub key: String,
+    pub start: i64,
+    pub stop: i64,
+}
+
+#[derive(Debug)]
+pub struct LIndex {
+    pub key: String,
+    pub index: i64,
+}
+
+#[derive(Debug)]
+pub struct LSet {
+    pub key: String,
+    pub index: i64,
+    pub element: Vec<u8>,
+}
+
+impl CommandExecutor for Push {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let mut db = backend.write();
+        match db.get_or_insert_with(self.key.clone(), || BackendValue::List(VecDeque::new())) {
+            BackendValue::List(list) => {
+                for element in self.elements {
+                    if self.left {
+                        list.push_front(element);
+                    } else {
+                        list.push_back(element);
+                    }
+                }
+                let len = list.len();
+                db.signal_modified(&self.key);
+                // woken clients only get the lock once this push is done
+                backend.signal_key(&self.key);
+                RespFrame::Integer(len as i64)
+            }
+            _ => RESP_WRONGTYPE.clone(),
+        }
+    }
+}
+
+impl CommandExecutor for Pop {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let missing = match self.count {
+            Some(_) => RespNullArray.into(),
+            None => RespNullBulkString.into(),
+        };
+        write_list(backend, &self.key, missing, |list| {
+            let len = list.len();
+            let mut pop = || {
+                if self.left {
+                    list.pop_front()
+                } else {
+                    list.pop_back()
+                }
+            };
+            let ret = match self.count {
+                Some(count) => {
+                    let popped: Vec<RespFrame> = std::iter::from_fn(pop)
+                        .take(count)
+                        .map(|element| BulkString::new(element).into())
+                        .collect();
+                    RespArray::new(popped).into()
+                }
+                None => match pop() {
+                    Some(element) => BulkString::new(element).into(),
+                    None => RespNullBulkString.into(),
+                },
+            };
+            (ret, list.len() != len)
+        })
+    }
+}
+
+impl CommandExecutor for LLen {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        read_list(backend, &self.key, RespFrame::Integer(0), |list| {
+            RespFrame::Integer(list.len() as i64)
+        })
+    }
+}
+
+impl CommandExecutor for LRange {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        read_list(backend, &self.key, RespArray::new(vec![]).into(), |list| {
+            let ret = match list_range(list.len(), self.start, self.stop) {
+                Some((start, stop)) => list
+                    .range(start..=stop)
+                    .map(|element| BulkString::new(element.as_slice()).into())
+                    .collect(),
+                None => vec![],
+            };
+            RespArray::new(ret).into()
+        })
+    }
+}
+
+impl CommandExecutor for LIndex {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        read_list(
+            backend,
+            &self.key,
+            RespNullBulkString.into(),
+            |list| match list_index(list.len(), self.index).and_then(|i| list.get(i)) {
+                Some(element) => BulkString::new(element.as_slice()).into(),
+                None => RespNullBulkString.into(),
+            },
+        )
+    }
+}
+
+impl CommandExecutor for LSet {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let missing = SimpleError::new("ERR no such key").into();
+        write_list(backend, &self.key, missing, |list| {
+            match list_index(list.len(), self.index).and_then(|i| list.get_mut(i)) {
+                Some(element) => {
+                    *element = self.element;
+                    (RESP_OK.clone(), true)
+                }
+                None => (SimpleError::new("ERR index out of range").into(), false),
+            }
+        })
+    }
+}
+
+// run `f` on the list at `key` under the read lock
+pub(super) fn read_list(
+    backend: &Backend,
+    key: &str,
+    missing: RespFrame,
+    f: impl FnOnce(&VecDeque<Vec<u8>>) -> RespFrame,
+) -> RespFrame {
+    match backend.read().get(key) {
+        Some(BackendValue::List(list)) => f(list),
+        Some(_) => RESP_WRONGTYPE.clone(),
+        None => missing,
+    }
+}
+
+// run `f` on the list at `key` under the write lock, it replies and tells whether it changed
+// the list. an empty list is never stored, so the key goes away once `f` removes the last
+// element
+pub(super) fn write_list(
+    backend: &Backend,
+    key: &str,
+    missing: RespFrame,
+    f: impl FnOnce(&mut VecDeque<Vec<u8>>) -> (RespFrame, bool),
+) -> RespFrame {
+    let mut db = backend.write();
+    let (ret, modified) = match db.get_mut(key) {
+        Some(BackendValue::List(list)) => f(list),
+        Some(_) => return RESP_WRONGTYPE.clone(),
+        None => return missing,
+    };
+    if modified {
+        db.signal_modified(key);
+    }
+    if matches!(db.get(key), Some(BackendValue::List(list)) if list.is_empty()) {
+        db.remove(key);
+    }
+    ret
+}
+
+// resolve a possibly negative index into a position within `len`
+pub(super) fn list_index(len: usize, index: i64) -> Option<usize> {
+    let index = if index < 0 { len as i64 + index } else { index };
+    (0..len as i64).contains(&index).then_some(index as usize)
+}
+
+// resolve possibly negative start/stop offsets into an inclusive range within `len`
+pub(super) fn list_range(len: usize, start: i64, stop: i64) -> Option<(usize, usize)> {
+    let len = len as i64;
+    let start = if start < 0 {
+        (len + start).max(0)
+    } else {
+        start
+    };
+    let stop = if stop < 0 {
+        len + stop
+    } else {
+        stop.min(len - 1)
+    };
+    if start > stop || start >= len {
+        return None;
+    }
+    Some((start as usize, stop as usize))
+}
+
+impl TryFrom<RespArray> for Push {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let left = validate_variadic_command(&value, &["lpush"], 2)
+            .map(|_| true)
+            .or_else(|_| validate_variadic_command(&value, &["rpush"], 2).map(|_| false))?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = extract_string(args.next())?;
+        let elements = args
+            .map(|arg| extract_bytes(Some(arg)))
+            .collect::<Result<_, _>>()?;
+        Ok(Push {
+            key,
+            elements,
+            left,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for Pop {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let left = validate_variadic_command(&value, &["lpop"], 1)
+            .map(|_| true)
+            .or_else(|_| validate_variadic_command(&value, &["rpop"], 1).map(|_| false))?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = extract_string(args.next())?;
+        let count = match args.next() {
+            Some(arg) => Some(parse_number(Some(arg))?),
+            None => None,
+        };
+        if args.next().is_some() {
+            return Err(CommandError::InvalidArgument(
+                "Too many arguments".to_string(),
+            ));
+        }
+        Ok(Pop { key, count, left })
+    }
+}
+
+impl TryFrom<RespArray> for LLen {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["llen"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(LLen {
+            key: extract_string(args.next())?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for LRange {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["lrange"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(LRange {
+            key: extract_string(args.next())?,
+            start: parse_number(args.next())?,
+            stop: parse_number(args.next())?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for LIndex {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["lindex"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(LIndex {
+            key: extract_string(args.next())?,
+            index: parse_number(args.next())?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for LSet {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["lset"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(LSet {
+            key: extract_string(args.next())?,
+            index: parse_number(args.next())?,
+            element: extract_bytes(args.next())?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    fn push(backend: &Backend, left: bool, elements: &[&str]) -> RespFrame {
+        Push {
+            key: "list".to_string(),
+            elements: elements.iter().map(|e| e.as_bytes().to_vec()).collect(),
+            left,
+        }
+        .execute(backend)
+    }
+
+    fn lrange(backend: &Backend, start: i64, stop: i64) -> RespFrame {
+        LRange {
+            key: "list".to_string(),
+            start,
+            stop,
+        }
+        .execute(backend)
+    }
+
+    fn array(elements: &[&str]) -> RespFrame {
+        RespArray::new(
+            elements
+                .iter()
+                .map(|e| BulkString::new(*e).into())
+                .collect::<Vec<_>>(),
+        )
+        .into()
+    }
+
+    #[test]
+    fn test_push_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*4\r\n$5\r\nlpush\r\n$4\r\nlist\r\n$1\r\na\r\n$1\r\nb\r\n");
+        let cmd: Push = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.key, "list");
+        assert_eq!(cmd.elements, vec![b"a".to_vec(), b"b".to_vec()]);
+        assert!(cmd.left);
+
+        let mut buf = BytesMut::from("*3\r\n$4\r\nrpop\r\n$4\r\nlist\r\n$1\r\n2\r\n");
+        let cmd: Pop = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.count, Some(2));
+        assert!(!cmd.left);
+        Ok(())
+    }
+
+    #[test]
+    fn test_push_and_range() {
+        let backend = Backend::new();
+        assert_eq!(push(&backend, false, &["b", "c"]), RespFrame::Integer(2));
+        // LPUSH a1 a2 leaves a2 at the head
+        assert_eq!(push(&backend, true, &["a1", "a2"]), RespFrame::Integer(4));
+
+        assert_eq!(lrange(&backend, 0, -1), array(&["a2", "a1", "b", "c"]));
+        assert_eq!(lrange(&backend, 1, 2), array(&["a1", "b"]));
+        assert_eq!(lrange(&backend, -2, 100), array(&["b", "c"]));
+        assert_eq!(lrange(&backend, -100, -3), array(&["a2", "a1"]));
+        assert_eq!(lrange(&backend, 3, 1), array(&[]));
+        assert_eq!(lrange(&backend, 10, 20), array(&[]));
+        assert_eq!(lrange(&backend, 0, -10), array(&[]));
+
+        let cmd = LLen {
+            key: "list".to_string(),
+        };
+        assert_eq!(cmd.execute(&backend), RespFrame::Integer(4));
+    }
+
+    #[test]
+    fn test_pop() {
+        let backend = Backend::new();
+        push(&backend, false, &["a", "b", "c", "d"]);
+
+        let pop = |count, left| {
+            Pop {
+                key: "list".to_string(),
+                count,
+                left,
+            }
+            .execute(&backend)
+        };
+        assert_eq!(pop(None, true), BulkString::new("a").into());
+        assert_eq!(pop(None, false), BulkString::new("d").into());
+        assert_eq!(pop(Some(5), false), array(&["c", "b"]));
+
+        // popping the last element removes the key
+        assert_eq!(backend.get("list"), None);
+        assert_eq!(pop(None, true), RespNullBulkString.into());
+        assert_eq!(pop(Some(1), true), RespNullArray.into());
+    }
+
+    #[test]
+    fn test_lindex_and_lset() {
+        let backend = Backend::new();
+        push(&backend, false, &["a", "b", "c"]);
+
+        let lindex = |index| {
+            LIndex {
+                key: "list".to_string(),
+                index,
+            }
+            .execute(&backend)
+        };
+        assert_eq!(lindex(0), BulkString::new("a").into());
+        assert_eq!(lindex(-1), BulkString::new("c").into());
+        assert_eq!(lindex(3), RespNullBulkString.into());
+        assert_eq!(lindex(-4), RespNullBulkString.into());
+
+        let lset = |key: &str, index| {
+            LSet {
+                key: key.to_string(),
+                index,
+                element: b"x".to_vec(),
+            }
+            .execute(&backend)
+        };
+        assert_eq!(lset("list", -2), RESP_OK.clone());
+        assert_eq!(lrange(&backend, 0, -1), array(&["a", "x", "c"]));
+        assert_eq!(
+            lset("list", 3),
+            SimpleError::new("ERR index out of range").into()
+        );
+        assert_eq!(
+            lset("missing", 0),
+            SimpleError::new("ERR no such key").into()
+        );
+    }
+
+    #[test]
+    fn test_list_wrongtype() {
+        let backend = Backend::new();
+        backend.set("list".to_string(), BulkString::new("value"));
+
+        assert_eq!(push(&backend, true, &["a"]), RESP_WRONGTYPE.clone());
+        assert_eq!(lrange(&backend, 0, -1), RESP_WRONGTYPE.clone());
+        let cmd = Pop {
+            key: "list".to_string(),
+            count: None,
+            left: true,
+        };
+        assert_eq!(cmd.execute(&backend), RESP_WRONGTYPE.clone());
+        let cmd = LLen {
+            key: "list".to_string(),
+        };
+        assert_eq!(cmd.execute(&backend), RESP_WRONGTYPE.clone());
+        let cmd = LIndex {
+            key: "list".to_string(),
+            index: 0,
+        };
+        assert_eq!(cmd.execute(&backend), RESP_WRONGTYPE.clone());
+        let cmd = LSet {
+            key: "list".to_string(),
+            index: 0,
+            element: b"x".to_vec(),
+        };
+        assert_eq!(cmd.execute(&backend), RESP_WRONGTYPE.clone());
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::VecDeque;
+
+use crate::{Backend, BackendValue, BulkString, Db, RespArray, RespFrame, RespNullBulkString};
+
+use super::{
+    extract_args, extract_string, validate_command, CommandError, CommandExecutor, RESP_WRONGTYPE,
+};
+
+#[derive(Debug)]
+pub struct LMove {
+    pub src: String,
+    pub dst: String,
+    // LEFT: pop from the head of `src` instead of the tail
+    pub from_left: bool,
+    // LEFT: push to the head of `dst` instead of the tail
+    pub to_left: bool,
+}
+
+impl CommandExecutor for LMove {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        // both lists live behind the same write lock, so no one sees the element in flight
+        let mut db = backend.write();
+        match move_element(&mut db, &self.src, &self.dst, self.from_left, self.to_left) {
+            Ok(Some(element)) => {
+                backend.signal_key(&self.dst);
+                BulkString::new(element).into()
+            }
+            Ok(None) => RespNullBulkString.into(),
+            Err(e) => e,
+        }
+    }
+}
+
+// pop from one end of `src` and push onto one end of `dst`, None if `src` is missing. nothing
+// moves unless both keys hold lists (or `dst` is missing)
+pub(super) fn move_element(
+    db: &mut Db,
+    src: &str,
+    dst: &str,
+    from_left: bool,
+    to_left: bool,
+) -> Result<Option<Vec<u8>>, RespFrame> {
+    match db.get(src) {
+        Some(BackendValue::List(_)) => {}
+        Some(_) => return Err(RESP_WRONGTYPE.clone()),
+        None => return Ok(None),
+    }
+    if db
+        .get(dst)
+        .is_some_and(|v| !matches!(v, BackendValue::List(_)))
+    {
+        return Err(RESP_WRONGTYPE.clone());
+    }
+
+    let Some(BackendValue::List(list)) = db.get_mut(src) else {
+        return Ok(None);
+    };
+    let element = if from_left {
+        list.pop_front()
+    } else {
+        list.pop_back()
+    };
+    let Some(element) = element else {
+        return Ok(None);
+    };
+    let now_empty = list.is_empty();
+    db.signal_modified(src);
+    if now_empty {
+        db.remove(src);
+    }
+
+    if let BackendValue::List(list) =
+        db.get_or_insert_with(dst.to_string(), || BackendValue::List(VecDeque::new()))
+    {
+        if to_left {
+            list.push_front(element.clone());
+        } else {
+            list.push_back(element.clone());
+        }
+    }
+    db.signal_modified(dst);
+    Ok(Some(element))
+}
+
+// - LEFT | RIGHT, true for LEFT
+pub(super) fn parse_side(side: String) -> Result<bool, CommandError> {
+    match side.to_ascii_lowercase().as_str() {
+        "left" => Ok(true),
+        "right" => Ok(false),
+        v => Err(CommandError::InvalidArgument(format!(
+            "Invalid option: {}",
+            v
+        ))),
+    }
+}
+
+impl TryFrom<RespArray> for LMove {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["lmove"], 4)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(LMove {
+            src: extract_string(args.next())?,
+            dst: extract_string(args.next())?,
+            from_left: parse_side(extract_string(args.next())?)?,
+            to_left: parse_side(extract_string(args.next())?)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{
+        cmd::{LLen, LRange, Push},
+        RespDecode,
+    };
+
+    use super::*;
+
+    fn push(backend: &Backend, key: &str, elements: &[&str]) {
+        let cmd = Push {
+            key: key.to_string(),
+            elements: elements.iter().map(|e| e.as_bytes().to_vec()).collect(),
+            left: false,
+        };
+        cmd.execute(backend);
+    }
+
+    fn lmove(backend: &Backend, src: &str, dst: &str, from_left: bool, to_left: bool) -> RespFrame {
+        LMove {
+            src: src.to_string(),
+            dst: dst.to_string(),
+            from_left,
+            to_left,
+        }
+        .execute(backend)
+    }
+
+    fn contents(backend: &Backend, key: &str) -> RespFrame {
+        LRange {
+            key: key.to_string(),
+            start: 0,
+            stop: -1,
+        }
+        .execute(backend)
+    }
+
+    fn array(elements: &[&str]) -> RespFrame {
+        let elements: Vec<RespFrame> = elements
+            .iter()
+            .map(|e| BulkString::new(*e).into())
+            .collect();
+        RespArray::new(elements).into()
+    }
+
+    #[test]
+    fn test_lmove_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from(
+            "*5\r\n$5\r\nlmove\r\n$1\r\na\r\n$1\r\nb\r\n$4\r\nLEFT\r\n$5\r\nRIGHT\r\n",
+        );
+        let cmd: LMove = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.src, "a");
+        assert_eq!(cmd.dst, "b");
+        assert!(cmd.from_left);
+        assert!(!cmd.to_left);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lmove() {
+        let backend = Backend::new();
+        push(&backend, "src", &["a", "b", "c"]);
+
+        assert_eq!(
+            lmove(&backend, "src", "dst", true, false),
+            BulkString::new("a").into()
+        );
+        assert_eq!(
+            lmove(&backend, "src", "dst", false, true),
+            BulkString::new("c").into()
+        );
+        assert_eq!(contents(&backend, "src"), array(&["b"]));
+        assert_eq!(contents(&backend, "dst"), array(&["c", "a"]));
+
+        // moving within the same list rotates it
+        assert_eq!(
+            lmove(&backend, "dst", "dst", true, false),
+            BulkString::new("c").into()
+        );
+        assert_eq!(contents(&backend, "dst"), array(&["a", "c"]));
+
+        // the emptied source goes away
+        lmove(&backend, "src", "dst", true, true);
+        assert_eq!(backend.get("src"), None);
+        assert_eq!(
+            lmove(&backend, "src", "dst", true, true),
+            RespNullBulkString.into()
+        );
+    }
+
+    #[test]
+    fn test_lmove_wrongtype_moves_nothing() {
+        let backend = Backend::new();
+        push(&backend, "src", &["a"]);
+        backend.set("str".to_string(), BulkString::new("value"));
+
+        assert_eq!(
+            lmove(&backend, "src", "str", true, true),
+            RESP_WRONGTYPE.clone()
+        );
+        assert_eq!(contents(&backend, "src"), array(&["a"]));
+        assert_eq!(
+            lmove(&backend, "str", "src", true, true),
+            RESP_WRONGTYPE.clone()
+        );
+    }
+
+    #[test]
+    fn test_lmove_concurrent_producers_and_consumers() {
+        let backend = Backend::new();
+        std::thread::scope(|s| {
+            for producer in 0..4 {
+                let backend = &backend;
+                s.spawn(move || {
+                    for i in 0..250 {
+                        push(backend, "queue", &[&format!("{}:{}", producer, i)]);
+                    }
+                });
+            }
+            for _ in 0..4 {
+                let backend = &backend;
+                s.spawn(move || {
+                    let mut moved = 0;
+                    while moved < 250 {
+                        if lmove(backend, "queue", "done", true, false) != RespNullBulkString.into()
+                        {
+                            moved += 1;
+                        } else {
+                            std::thread::yield_now();
+                        }
+                    }
+                });
+            }
+        });
+
+        // every element was moved exactly once
+        assert_eq!(backend.get("queue"), None);
+        let cmd = LLen {
+            key: "done".to_string(),
+        };
+        assert_eq!(cmd.execute(&backend), RespFrame::Integer(1000));
+        let RespFrame::Array(done) = contents(&backend, "done") else {
+            panic!("expected an array");
+        };
+        let unique: std::collections::HashSet<_> =
+            done.iter().map(|e| format!("{:?}", e)).collect();
+        assert_eq!(unique.len(), 1000);
+    }
+}
@@ -0,0 +1,209 @@
+use crate::{Backend, BackendValue, BulkString, Db, RespArray, RespFrame, RespNullArray};
+
+use super::{
+    extract_args, extract_string, lmove::parse_side, parse_number, validate_variadic_command,
+    CommandError, CommandExecutor, RESP_WRONGTYPE,
+};
+
+#[derive(Debug)]
+pub struct LMPop {
+    pub keys: Vec<String>,
+    // LEFT: pop from the head instead of the tail
+    pub left: bool,
+    pub count: usize,
+}
+
+impl CommandExecutor for LMPop {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let mut db = backend.write();
+        match pop_first(&mut db, &self.keys, self.left, self.count) {
+            Ok(Some((key, elements))) => {
+                let elements: Vec<RespFrame> = elements
+                    .into_iter()
+                    .map(|e| BulkString::new(e).into())
+                    .collect();
+                RespArray::new(vec![
+                    BulkString::new(key).into(),
+                    RespArray::new(elements).into(),
+                ])
+                .into()
+            }
+            Ok(None) => RespNullArray.into(),
+            Err(e) => e,
+        }
+    }
+}
+
+// the key popped from and the elements, in pop order
+pub(super) type Popped = (String, Vec<Vec<u8>>);
+
+// pop up to `count` elements from the first key holding a non-empty list
+pub(super) fn pop_first(
+    db: &mut Db,
+    keys: &[String],
+    left: bool,
+    count: usize,
+) -> Result<Option<Popped>, RespFrame> {
+    for key in keys {
+        let list = match db.get_mut(key) {
+            Some(BackendValue::List(list)) if !list.is_empty() => list,
+            Some(BackendValue::List(_)) | None => continue,
+            Some(_) => return Err(RESP_WRONGTYPE.clone()),
+        };
+        let n = count.min(list.len());
+        let elements: Vec<Vec<u8>> = if left {
+            list.drain(..n).collect()
+        } else {
+            list.drain(list.len() - n..).rev().collect()
+        };
+        let now_empty = list.is_empty();
+        db.signal_modified(key);
+        if now_empty {
+            db.remove(key);
+        }
+        return Ok(Some((key.clone(), elements)));
+    }
+    Ok(None)
+}
+
+impl TryFrom<RespArray> for LMPop {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["lmpop"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let numkeys: usize = parse_number(args.next())?;
+        if numkeys == 0 || numkeys > args.len() {
+            return Err(CommandError::InvalidArgument(
+                "numkeys must be positive and at most the number of keys given".to_string(),
+            ));
+        }
+        let keys = args
+            .by_ref()
+            .take(numkeys)
+            .map(|arg| extract_string(Some(arg)))
+            .collect::<Result<_, _>>()?;
+        let mut cmd = LMPop {
+            keys,
+            left: parse_side(extract_string(args.next())?)?,
+            count: 1,
+        };
+        while let Some(arg) = args.next() {
+            match extract_string(Some(arg))?.to_ascii_lowercase().as_str() {
+                "count" => {
+                    cmd.count = parse_number(args.next())?;
+                    if cmd.count == 0 {
+                        return Err(CommandError::InvalidArgument(
+                            "COUNT must be positive".to_string(),
+                        ));
+                    }
+                }
+                v => {
+                    return Err(CommandError::InvalidArgument(format!(
+                        "Invalid option: {}",
+                        v
+                    )))
+                }
+            }
+        }
+        Ok(cmd)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{cmd::Push, RespDecode};
+
+    use super::*;
+
+    fn push(backend: &Backend, key: &str, elements: &[&str]) {
+        let cmd = Push {
+            key: key.to_string(),
+            elements: elements.iter().map(|e| e.as_bytes().to_vec()).collect(),
+            left: false,
+        };
+        cmd.execute(backend);
+    }
+
+    fn lmpop(backend: &Backend, keys: &[&str], left: bool, count: usize) -> RespFrame {
+        LMPop {
+            keys: keys.iter().map(|k| k.to_string()).collect(),
+            left,
+            count,
+        }
+        .execute(backend)
+    }
+
+    fn reply(key: &str, elements: &[&str]) -> RespFrame {
+        let elements: Vec<RespFrame> = elements
+            .iter()
+            .map(|e| BulkString::new(*e).into())
+            .collect();
+        RespArray::new(vec![
+            BulkString::new(key).into(),
+            RespArray::new(elements).into(),
+        ])
+        .into()
+    }
+
+    #[test]
+    fn test_lmpop_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from(
+            "*7\r\n$5\r\nlmpop\r\n$1\r\n2\r\n$1\r\na\r\n$1\r\nb\r\n$5\r\nRIGHT\r\n$5\r\nCOUNT\r\n$1\r\n3\r\n",
+        );
+        let cmd: LMPop = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.keys, vec!["a", "b"]);
+        assert!(!cmd.left);
+        assert_eq!(cmd.count, 3);
+
+        let mut buf = BytesMut::from("*4\r\n$5\r\nlmpop\r\n$1\r\n3\r\n$1\r\na\r\n$4\r\nLEFT\r\n");
+        let ret: Result<LMPop, _> = RespArray::decode(&mut buf)?.try_into();
+        assert!(ret.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_lmpop() {
+        let backend = Backend::new();
+        push(&backend, "b", &["1", "2", "3"]);
+        push(&backend, "c", &["x"]);
+
+        assert_eq!(
+            lmpop(&backend, &["a", "b", "c"], true, 2),
+            reply("b", &["1", "2"])
+        );
+        assert_eq!(
+            lmpop(&backend, &["a", "b", "c"], false, 5),
+            reply("b", &["3"])
+        );
+        assert_eq!(backend.get("b"), None);
+        assert_eq!(
+            lmpop(&backend, &["a", "b", "c"], false, 1),
+            reply("c", &["x"])
+        );
+        assert_eq!(
+            lmpop(&backend, &["a", "b", "c"], true, 1),
+            RespNullArray.into()
+        );
+    }
+
+    #[test]
+    fn test_lmpop_right_pops_tail_first() {
+        let backend = Backend::new();
+        push(&backend, "list", &["1", "2", "3"]);
+        assert_eq!(
+            lmpop(&backend, &["list"], false, 2),
+            reply("list", &["3", "2"])
+        );
+    }
+
+    #[test]
+    fn test_lmpop_wrongtype() {
+        let backend = Backend::new();
+        backend.set("str".to_string(), BulkString::new("value"));
+        assert_eq!(lmpop(&backend, &["str"], true, 1), RESP_WRONGTYPE.clone());
+    }
+}
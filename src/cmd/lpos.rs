@@ -0,0 +1,221 @@
+use crate::{Backend, RespArray, RespFrame, RespNull};
+
+use super::{
+    extract_args, extract_bytes, extract_string, list::read_list, parse_number,
+    validate_variadic_command, CommandError, CommandExecutor,
+};
+
+#[derive(Debug)]
+pub struct LPos {
+    pub key: String,
+    pub element: Vec<u8>,
+    // skip the first |rank| - 1 matches; negative ranks scan from the tail
+    pub rank: i64,
+    // None replies with a single index, Some(0) with every match
+    pub count: Option<usize>,
+    // compare at most this many elements, 0 for no limit
+    pub maxlen: usize,
+}
+
+impl CommandExecutor for LPos {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let missing = match self.count {
+            Some(_) => RespArray::new(vec![]).into(),
+            None => RespFrame::Null(RespNull),
+        };
+        read_list(backend, &self.key, missing, |list| {
+            let maxlen = match self.maxlen {
+                0 => list.len(),
+                n => n,
+            };
+            let wanted = match self.count {
+                Some(0) => usize::MAX,
+                Some(n) => n,
+                None => 1,
+            };
+            // indices are always counted from the head, whichever end the scan starts at
+            let scanned: Box<dyn Iterator<Item = (usize, &Vec<u8>)>> = if self.rank > 0 {
+                Box::new(list.iter().enumerate().take(maxlen))
+            } else {
+                Box::new(list.iter().enumerate().rev().take(maxlen))
+            };
+            let found: Vec<usize> = scanned
+                .filter(|(_, e)| **e == self.element)
+                .map(|(i, _)| i)
+                .skip(self.rank.unsigned_abs() as usize - 1)
+                .take(wanted)
+                .collect();
+
+            match self.count {
+                Some(_) => {
+                    let found: Vec<RespFrame> = found
+                        .into_iter()
+                        .map(|i| RespFrame::Integer(i as i64))
+                        .collect();
+                    RespArray::new(found).into()
+                }
+                None => match found.first() {
+                    Some(i) => RespFrame::Integer(*i as i64),
+                    None => RespFrame::Null(RespNull),
+                },
+            }
+        })
+    }
+}
+
+impl TryFrom<RespArray> for LPos {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["lpos"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let mut cmd = LPos {
+            key: extract_string(args.next())?,
+            element: extract_bytes(args.next())?,
+            rank: 1,
+            count: None,
+            maxlen: 0,
+        };
+        while let Some(arg) = args.next() {
+            match extract_string(Some(arg))?.to_ascii_lowercase().as_str() {
+                "rank" => {
+                    cmd.rank = parse_number(args.next())?;
+                    // i64::MIN has no positive counterpart
+                    if cmd.rank == 0 || cmd.rank == i64::MIN {
+                        return Err(CommandError::InvalidArgument(
+                            "RANK can't be zero: use 1 to start from the first match, 2 from the second ... or use negative to start from the end of the list".to_string(),
+                        ));
+                    }
+                }
+                "count" => cmd.count = Some(parse_number(args.next())?),
+                "maxlen" => cmd.maxlen = parse_number(args.next())?,
+                v => {
+                    return Err(CommandError::InvalidArgument(format!(
+                        "Invalid option: {}",
+                        v
+                    )))
+                }
+            }
+        }
+        Ok(cmd)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{cmd::Push, RespDecode};
+
+    use super::*;
+
+    // a b c a b c a b c
+    fn setup() -> Backend {
+        let backend = Backend::new();
+        let cmd = Push {
+            key: "list".to_string(),
+            elements: "abcabcabc".bytes().map(|b| vec![b]).collect(),
+            left: false,
+        };
+        cmd.execute(&backend);
+        backend
+    }
+
+    fn lpos(
+        backend: &Backend,
+        element: &str,
+        rank: i64,
+        count: Option<usize>,
+        maxlen: usize,
+    ) -> RespFrame {
+        LPos {
+            key: "list".to_string(),
+            element: element.as_bytes().to_vec(),
+            rank,
+            count,
+            maxlen,
+        }
+        .execute(backend)
+    }
+
+    fn indices(indices: &[i64]) -> RespFrame {
+        let indices: Vec<RespFrame> = indices.iter().map(|i| RespFrame::Integer(*i)).collect();
+        RespArray::new(indices).into()
+    }
+
+    #[test]
+    fn test_lpos_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from(
+            "*9\r\n$4\r\nlpos\r\n$4\r\nlist\r\n$1\r\nb\r\n$4\r\nRANK\r\n$2\r\n-2\r\n$5\r\nCOUNT\r\n$1\r\n0\r\n$6\r\nMAXLEN\r\n$1\r\n5\r\n",
+        );
+        let cmd: LPos = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.key, "list");
+        assert_eq!(cmd.element, b"b");
+        assert_eq!(cmd.rank, -2);
+        assert_eq!(cmd.count, Some(0));
+        assert_eq!(cmd.maxlen, 5);
+
+        let mut buf = BytesMut::from(
+            "*5\r\n$4\r\nlpos\r\n$4\r\nlist\r\n$1\r\nb\r\n$4\r\nRANK\r\n$1\r\n0\r\n",
+        );
+        let ret: Result<LPos, _> = RespArray::decode(&mut buf)?.try_into();
+        assert!(ret.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_lpos_first_match() {
+        let backend = setup();
+        assert_eq!(lpos(&backend, "b", 1, None, 0), RespFrame::Integer(1));
+        assert_eq!(lpos(&backend, "z", 1, None, 0), RespFrame::Null(RespNull));
+        assert_eq!(
+            LPos {
+                key: "missing".to_string(),
+                element: b"a".to_vec(),
+                rank: 1,
+                count: None,
+                maxlen: 0,
+            }
+            .execute(&backend),
+            RespFrame::Null(RespNull)
+        );
+    }
+
+    #[test]
+    fn test_lpos_rank() {
+        let backend = setup();
+        assert_eq!(lpos(&backend, "b", 2, None, 0), RespFrame::Integer(4));
+        assert_eq!(lpos(&backend, "b", 4, None, 0), RespFrame::Null(RespNull));
+        // negative ranks scan from the tail, indices still count from the head
+        assert_eq!(lpos(&backend, "b", -1, None, 0), RespFrame::Integer(7));
+        assert_eq!(lpos(&backend, "b", -3, None, 0), RespFrame::Integer(1));
+    }
+
+    #[test]
+    fn test_lpos_count() {
+        let backend = setup();
+        assert_eq!(lpos(&backend, "a", 1, Some(2), 0), indices(&[0, 3]));
+        assert_eq!(lpos(&backend, "a", 1, Some(0), 0), indices(&[0, 3, 6]));
+        assert_eq!(lpos(&backend, "z", 1, Some(0), 0), indices(&[]));
+    }
+
+    #[test]
+    fn test_lpos_rank_and_count() {
+        let backend = setup();
+        assert_eq!(lpos(&backend, "c", 2, Some(0), 0), indices(&[5, 8]));
+        assert_eq!(lpos(&backend, "c", -1, Some(2), 0), indices(&[8, 5]));
+        assert_eq!(lpos(&backend, "c", -2, Some(0), 0), indices(&[5, 2]));
+    }
+
+    #[test]
+    fn test_lpos_maxlen() {
+        let backend = setup();
+        assert_eq!(lpos(&backend, "c", 1, None, 2), RespFrame::Null(RespNull));
+        assert_eq!(lpos(&backend, "c", 1, None, 3), RespFrame::Integer(2));
+        assert_eq!(lpos(&backend, "a", 1, Some(0), 4), indices(&[0, 3]));
+        // MAXLEN counts from whichever end the scan starts at
+        assert_eq!(lpos(&backend, "a", -1, Some(0), 4), indices(&[6]));
+        assert_eq!(lpos(&backend, "b", 2, Some(0), 6), indices(&[4]));
+    }
+}
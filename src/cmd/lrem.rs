@@ -0,0 +1,157 @@
+use crate::{Backend, RespArray, RespFrame};
+
+use super::{
+    extract_args, extract_bytes, extract_string, list::write_list, parse_number, validate_command,
+    CommandError, CommandExecutor,
+};
+
+#[derive(Debug)]
+pub struct LRem {
+    pub key: String,
+    // > 0 removes from the head, < 0 from the tail, 0 removes every occurrence
+    pub count: i64,
+    pub element: Vec<u8>,
+}
+
+impl CommandExecutor for LRem {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        write_list(backend, &self.key, RespFrame::Integer(0), |list| {
+            let limit = match self.count {
+                0 => usize::MAX,
+                n => n.unsigned_abs() as usize,
+            };
+            let len = list.len();
+            let mut removed = 0;
+            if self.count >= 0 {
+                list.retain(|e| {
+                    if removed < limit && *e == self.element {
+                        removed += 1;
+                        return false;
+                    }
+                    true
+                });
+            } else {
+                // walk from the tail, remembering which positions to drop
+                let mut keep = vec![true; len];
+                for (i, e) in list.iter().enumerate().rev() {
+                    if removed == limit {
+                        break;
+                    }
+                    if *e == self.element {
+                        keep[i] = false;
+                        removed += 1;
+                    }
+                }
+                let mut keep = keep.into_iter();
+                list.retain(|_| keep.next().unwrap_or(true));
+            }
+            (RespFrame::Integer(removed as i64), removed > 0)
+        })
+    }
+}
+
+impl TryFrom<RespArray> for LRem {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["lrem"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(LRem {
+            key: extract_string(args.next())?,
+            count: parse_number(args.next())?,
+            element: extract_bytes(args.next())?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{
+        cmd::{LRange, Push},
+        BulkString, RespDecode,
+    };
+
+    use super::*;
+
+    fn setup() -> Backend {
+        let backend = Backend::new();
+        let cmd = Push {
+            key: "list".to_string(),
+            elements: ["a", "x", "b", "x", "c", "x"]
+                .iter()
+                .map(|e| e.as_bytes().to_vec())
+                .collect(),
+            left: false,
+        };
+        cmd.execute(&backend);
+        backend
+    }
+
+    fn lrem(backend: &Backend, count: i64, element: &str) -> RespFrame {
+        LRem {
+            key: "list".to_string(),
+            count,
+            element: element.as_bytes().to_vec(),
+        }
+        .execute(backend)
+    }
+
+    fn contents(backend: &Backend) -> RespFrame {
+        LRange {
+            key: "list".to_string(),
+            start: 0,
+            stop: -1,
+        }
+        .execute(backend)
+    }
+
+    fn array(elements: &[&str]) -> RespFrame {
+        let elements: Vec<RespFrame> = elements
+            .iter()
+            .map(|e| BulkString::new(*e).into())
+            .collect();
+        RespArray::new(elements).into()
+    }
+
+    #[test]
+    fn test_lrem_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*4\r\n$4\r\nlrem\r\n$4\r\nlist\r\n$2\r\n-2\r\n$1\r\nx\r\n");
+        let cmd: LRem = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.key, "list");
+        assert_eq!(cmd.count, -2);
+        assert_eq!(cmd.element, b"x");
+        Ok(())
+    }
+
+    #[test]
+    fn test_lrem_from_head() {
+        let backend = setup();
+        assert_eq!(lrem(&backend, 2, "x"), RespFrame::Integer(2));
+        assert_eq!(contents(&backend), array(&["a", "b", "c", "x"]));
+    }
+
+    #[test]
+    fn test_lrem_from_tail() {
+        let backend = setup();
+        assert_eq!(lrem(&backend, -2, "x"), RespFrame::Integer(2));
+        assert_eq!(contents(&backend), array(&["a", "x", "b", "c"]));
+    }
+
+    #[test]
+    fn test_lrem_all() {
+        let backend = setup();
+        assert_eq!(lrem(&backend, 0, "x"), RespFrame::Integer(3));
+        assert_eq!(contents(&backend), array(&["a", "b", "c"]));
+        assert_eq!(lrem(&backend, 0, "z"), RespFrame::Integer(0));
+
+        // removing every element removes the key
+        for e in ["a", "b", "c"] {
+            lrem(&backend, 0, e);
+        }
+        assert_eq!(backend.get("list"), None);
+        assert_eq!(lrem(&backend, 0, "a"), RespFrame::Integer(0));
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{Backend, RespArray, RespFrame};
+
+use super::{
+    extract_args, extract_string, list::list_range, list::write_list, parse_number,
+    validate_command, CommandError, CommandExecutor, RESP_OK,
+};
+
+#[derive(Debug)]
+pub struct LTrim {
+    pub key: String,
+    pub start: i64,
+    pub stop: i64,
+}
+
+impl CommandExecutor for LTrim {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        write_list(backend, &self.key, RESP_OK.clone(), |list| {
+            let len = list.len();
+            match list_range(list.len(), self.start, self.stop) {
+                Some((start, stop)) => {
+                    list.truncate(stop + 1);
+                    list.drain(..start);
+                }
+                // an empty range empties the list, which removes the key
+                None => list.clear(),
+            }
+            (RESP_OK.clone(), list.len() != len)
+        })
+    }
+}
+
+impl TryFrom<RespArray> for LTrim {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["ltrim"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(LTrim {
+            key: extract_string(args.next())?,
+            start: parse_number(args.next())?,
+            stop: parse_number(args.next())?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{
+        cmd::{LRange, Push},
+        BulkString, RespDecode,
+    };
+
+    use super::*;
+
+    fn ltrim(start: i64, stop: i64) -> Backend {
+        let backend = Backend::new();
+        let cmd = Push {
+            key: "list".to_string(),
+            elements: ["a", "b", "c", "d", "e"]
+                .iter()
+                .map(|e| e.as_bytes().to_vec())
+                .collect(),
+            left: false,
+        };
+        cmd.execute(&backend);
+        let cmd = LTrim {
+            key: "list".to_string(),
+            start,
+            stop,
+        };
+        assert_eq!(cmd.execute(&backend), RESP_OK.clone());
+        backend
+    }
+
+    fn contents(backend: &Backend) -> RespFrame {
+        LRange {
+            key: "list".to_string(),
+            start: 0,
+            stop: -1,
+        }
+        .execute(backend)
+    }
+
+    fn array(elements: &[&str]) -> RespFrame {
+        let elements: Vec<RespFrame> = elements
+            .iter()
+            .map(|e| BulkString::new(*e).into())
+            .collect();
+        RespArray::new(elements).into()
+    }
+
+    #[test]
+    fn test_ltrim_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*4\r\n$5\r\nltrim\r\n$4\r\nlist\r\n$1\r\n1\r\n$2\r\n-1\r\n");
+        let cmd: LTrim = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.key, "list");
+        assert_eq!(cmd.start, 1);
+        assert_eq!(cmd.stop, -1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_ltrim() {
+        assert_eq!(contents(&ltrim(1, 3)), array(&["b", "c", "d"]));
+        assert_eq!(contents(&ltrim(-2, -1)), array(&["d", "e"]));
+        assert_eq!(contents(&ltrim(0, 100)), array(&["a", "b", "c", "d", "e"]));
+
+        let backend = ltrim(3, 1);
+        assert_eq!(backend.get("list"), None);
+    }
+}
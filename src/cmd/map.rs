@@ -1,11 +1,15 @@
-use crate::{RespArray, RespFrame, RespNull};
+use crate::{BackendValue, RespArray, RespFrame, RespNull};
 
-use super::{extract_args, validate_command, CommandError, CommandExecutor, Get, Set, RESP_OK};
+use super::{
+    extract_args, validate_command, CommandError, CommandExecutor, Get, Set, RESP_OK,
+    RESP_WRONGTYPE,
+};
 
 impl CommandExecutor for Get {
     fn execute(self, backend: &crate::Backend) -> RespFrame {
-        match backend.get(&self.key) {
-            Some(value) => value,
+        match backend.read().get(&self.key) {
+            Some(BackendValue::String(value)) => value.clone().into(),
+            Some(_) => RESP_WRONGTYPE.clone(),
             None => RespFrame::Null(RespNull),
         }
     }
@@ -40,7 +44,7 @@ impl TryFrom<RespArray> for Set {
 
         let mut args = extract_args(value, 1)?.into_iter();
         match (args.next(), args.next()) {
-            (Some(RespFrame::BulkString(key)), Some(value)) => Ok(Set {
+            (Some(RespFrame::BulkString(key)), Some(RespFrame::BulkString(value))) => Ok(Set {
                 key: String::from_utf8(key.0)?,
                 value,
             }),
@@ -56,7 +60,7 @@ mod tests {
     use anyhow::Result;
     use bytes::BytesMut;
 
-    use crate::{Backend, RespDecode};
+    use crate::{Backend, BulkString, RespDecode};
 
     use super::*;
 
@@ -77,7 +81,7 @@ mod tests {
         let frame = RespArray::decode(&mut buf)?;
         let set: Set = frame.try_into()?;
         assert_eq!(set.key, "key");
-        assert_eq!(set.value, RespFrame::BulkString(b"value".into()));
+        assert_eq!(set.value, BulkString::new("value"));
         Ok(())
     }
 
@@ -86,7 +90,7 @@ mod tests {
         let backend = Backend::new();
         let cmd = Set {
             key: "key".to_string(),
-            value: RespFrame::BulkString(b"value".into()),
+            value: BulkString::new("value"),
         };
         let result = cmd.execute(&backend);
         assert_eq!(result, RESP_OK.clone());
@@ -0,0 +1,217 @@
+use std::mem::size_of;
+
+use parking_lot::RwLock;
+
+use crate::{
+    network::ConnectionState, BackInner, Backend, Db, RespArray, RespFrame, RespMap,
+    RespNullBulkString, DB_COUNT, MEMORY_USAGE_SAMPLES,
+};
+
+use super::{
+    extract_args, extract_string, parse_number, validate_command, validate_variadic_command,
+    CommandError, CommandExecutor,
+};
+
+/// MEMORY USAGE key [SAMPLES count], SAMPLES 0 measures every element
+#[derive(Debug)]
+pub struct MemoryUsage {
+    pub key: String,
+    pub samples: usize,
+}
+
+#[derive(Debug)]
+pub struct MemoryStats;
+
+// read and write buffers of a connection's codec
+const CLIENT_BUFFERS: usize = 2 * 8 * 1024;
+
+impl CommandExecutor for MemoryUsage {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match backend.read().memory_usage(&self.key, self.samples) {
+            Some(bytes) => RespFrame::Integer(bytes as i64),
+            None => RespNullBulkString.into(),
+        }
+    }
+}
+
+// there is no allocator hook, every figure is estimated from the server's own structures.
+// cluster links and the AOF buffer don't exist here and are always 0
+impl CommandExecutor for MemoryStats {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let startup = size_of::<BackInner>() + DB_COUNT * size_of::<RwLock<Db>>();
+        let clients = backend.client_count() * (size_of::<ConnectionState>() + CLIENT_BUFFERS);
+        let used = backend.used_memory();
+        let peak = backend.stats().observe_memory(used);
+
+        let mut ret = RespMap::new();
+        let mut overhead = startup + clients;
+        let mut keys = 0;
+        for index in 0..DB_COUNT {
+            let (count, _) = backend.keyspace_info(index);
+            let table = backend.table_overhead(index);
+            overhead += table;
+            keys += count;
+            // database 0 is always listed, the others only when they hold keys
+            if index > 0 && count == 0 {
+                continue;
+            }
+            let mut db = RespMap::new();
+            db.insert("overhead.hashtable.main".to_string(), integer(table));
+            // expiries live in the entries, there is no separate table
+            db.insert("overhead.hashtable.expires".to_string(), integer(0));
+            db.insert("keys.count".to_string(), integer(count));
+            ret.insert(format!("db.{}", index), db.into());
+        }
+
+        let total = startup + clients + used;
+        for (name, value) in [
+            ("peak.allocated", peak as usize + startup + clients),
+            ("total.allocated", total),
+            ("startup.allocated", startup),
+            ("replication.backlog", backend.replication().backlog_len()),
+            ("clients.slaves", 0),
+            ("clients.normal", clients),
+            ("cluster.links", 0),
+            ("aof.buffer", 0),
+            ("overhead.total", overhead),
+            ("keys.count", keys),
+            ("dataset.bytes", total.saturating_sub(overhead)),
+        ] {
+            ret.insert(name.to_string(), integer(value));
+        }
+        ret.into()
+    }
+}
+
+fn integer(n: usize) -> RespFrame {
+    RespFrame::Integer(n as i64)
+}
+
+impl TryFrom<RespArray> for MemoryUsage {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["memory", "usage"], 1)?;
+
+        let mut args = extract_args(value, 2)?.into_iter();
+        let key = extract_string(args.next())?;
+        let mut samples = MEMORY_USAGE_SAMPLES;
+        while let Some(arg) = args.next() {
+            match extract_string(Some(arg))?.to_ascii_lowercase().as_str() {
+                "samples" => samples = parse_number(args.next())?,
+                v => {
+                    return Err(CommandError::InvalidArgument(format!(
+                        "Invalid option: {}",
+                        v
+                    )))
+                }
+            }
+        }
+        Ok(MemoryUsage { key, samples })
+    }
+}
+
+impl TryFrom<RespArray> for MemoryStats {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["memory", "stats"], 0)?;
+        Ok(MemoryStats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{BackendValue, BulkString, RespDecode};
+
+    use super::*;
+
+    fn usage(backend: &Backend, key: &str, samples: usize) -> i64 {
+        let cmd = MemoryUsage {
+            key: key.to_string(),
+            samples,
+        };
+        match cmd.execute(backend) {
+            RespFrame::Integer(n) => n,
+            frame => panic!("expected an integer, got {:?}", frame),
+        }
+    }
+
+    #[test]
+    fn test_memory_usage_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from(
+            "*5\r\n$6\r\nmemory\r\n$5\r\nusage\r\n$3\r\nkey\r\n$7\r\nSAMPLES\r\n$1\r\n0\r\n",
+        );
+        let cmd: MemoryUsage = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.key, "key");
+        assert_eq!(cmd.samples, 0);
+
+        let mut buf = BytesMut::from("*3\r\n$6\r\nmemory\r\n$5\r\nusage\r\n$3\r\nkey\r\n");
+        let cmd: MemoryUsage = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.samples, MEMORY_USAGE_SAMPLES);
+        Ok(())
+    }
+
+    #[test]
+    fn test_memory_usage() {
+        let backend = Backend::new();
+        let missing = MemoryUsage {
+            key: "missing".to_string(),
+            samples: 5,
+        };
+        assert_eq!(missing.execute(&backend), RespNullBulkString.into());
+
+        // a string is its bytes plus a fixed overhead
+        backend.set("small".to_string(), BulkString::new("x"));
+        backend.set("big".to_string(), BulkString::new(vec![b'x'; 10_000]));
+        let (small, big) = (usage(&backend, "small", 5), usage(&backend, "big", 5));
+        assert!(small > 0);
+        assert!(big - small >= 9_990);
+        assert!(big < 10_000 + 200);
+
+        // 1000 elements of 100 bytes hold about 100KB
+        let list: VecDeque<Vec<u8>> = (0..1000).map(|_| vec![b'x'; 100]).collect();
+        backend
+            .write()
+            .insert("list".to_string(), BackendValue::List(list));
+        let all = usage(&backend, "list", 0);
+        assert!((100_000..200_000).contains(&all), "{}", all);
+        // same sized elements extrapolate exactly
+        assert_eq!(usage(&backend, "list", 5), all);
+    }
+
+    #[test]
+    fn test_memory_stats() {
+        let backend = Backend::new();
+        for i in 0..100 {
+            backend.set(format!("key{}", i), BulkString::new("value"));
+        }
+        backend
+            .select(2)
+            .unwrap()
+            .set("other".to_string(), BulkString::new("value"));
+
+        let RespFrame::Map(stats) = MemoryStats.execute(&backend) else {
+            panic!("expected a map");
+        };
+        let get = |map: &RespMap, name: &str| match map.get(name) {
+            Some(RespFrame::Integer(n)) => *n,
+            frame => panic!("{}: expected an integer, got {:?}", name, frame),
+        };
+        assert_eq!(get(&stats, "keys.count"), 101);
+        assert!(get(&stats, "total.allocated") >= get(&stats, "dataset.bytes"));
+        assert!(get(&stats, "peak.allocated") >= get(&stats, "total.allocated"));
+        assert_eq!(get(&stats, "replication.backlog"), 0);
+
+        let Some(RespFrame::Map(db0)) = stats.get("db.0") else {
+            panic!("expected db.0");
+        };
+        assert_eq!(get(db0, "keys.count"), 100);
+        assert!(get(db0, "overhead.hashtable.main") > 0);
+        assert!(stats.contains_key("db.2"));
+        assert!(!stats.contains_key("db.1"));
+    }
+}
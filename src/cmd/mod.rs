@@ -1,13 +1,177 @@
+mod auth;
+mod bitmap;
+mod blmove;
+mod blpop;
+mod bzpop;
+mod client;
+mod cluster;
+mod command_info;
+mod config;
+mod copy;
+mod dbsize;
+mod debug;
+mod del;
+mod exists;
+mod expire;
+mod expiretime;
+mod flush;
+mod geo;
+mod getrange;
 mod hmap;
+mod hrandfield;
+mod hscan;
+mod hyperloglog;
+mod info;
+mod keys;
+mod lastsave;
+mod latency;
+mod linsert;
+mod list;
+mod lmove;
+mod lmpop;
+mod lpos;
+mod lrem;
+mod ltrim;
 mod map;
+mod memory;
+mod monitor;
+mod multi;
+mod object;
+mod persist;
+mod psync;
+mod pubsub;
+mod quit;
+mod randomkey;
+mod rename;
+mod replconf;
+mod replicaof;
+mod reset;
+mod save;
+mod scan;
+mod select;
+mod setrange;
+mod sets;
+mod sintercard;
+mod slowlog;
+mod sops;
+mod strlen;
+mod subscribe;
+mod swapdb;
+mod ttl;
+mod type_cmd;
+mod unlink;
+mod wait;
+mod watch;
+mod zcount;
+mod zintercard;
+mod zmscore;
+mod zpop;
+mod zrandmember;
+mod zrange;
+mod zrange_compat;
+mod zrangestore;
+mod zset;
+mod zsetops;
 
-use crate::{Backend, RespArray, RespError, RespFrame, SimpleString};
+use crate::{
+    network::ConnectionState, Backend, BackendValue, BulkString, RespArray, RespError, RespFrame,
+    SimpleError, SimpleString,
+};
 use enum_dispatch::enum_dispatch;
 use lazy_static::lazy_static;
 use thiserror::Error;
 
+pub use self::{
+    auth::Auth,
+    bitmap::{BitCount, BitOp, BitOperation, BitPos, BitUnit, GetBit, SetBit},
+    blmove::BLMove,
+    blpop::BPop,
+    bzpop::BZPop,
+    client::{
+        ClientGetName, ClientId, ClientKill, ClientList, ClientPause, ClientSetName, ClientUnpause,
+    },
+    cluster::{ClusterCountKeysInSlot, ClusterGetKeysInSlot, ClusterKeySlot},
+    command_info::{
+        command_metadata, CommandCount, CommandDocs, CommandFilter, CommandInfo, CommandList,
+        CommandMetadata, COMMANDS,
+    },
+    config::{ConfigGet, ConfigResetStat, ConfigSet},
+    copy::Copy,
+    dbsize::DbSize,
+    debug::{DebugReload, DebugSleep},
+    del::Del,
+    exists::Exists,
+    expire::{Expire, Expiry},
+    expiretime::ExpireTime,
+    flush::Flush,
+    geo::{GeoAdd, GeoOrigin, GeoRadiusByMember, GeoSearch, GeoUnit},
+    getrange::GetRange,
+    hmap::{HDel, HExists, HIncrBy, HIncrByFloat, HKeys, HLen, HMGet, HMSet, HSetNx, HVals},
+    hrandfield::HRandField,
+    hscan::HScan,
+    hyperloglog::{PfAdd, PfCount, PfMerge},
+    info::Info,
+    keys::Keys,
+    lastsave::LastSave,
+    latency::{LatencyHistory, LatencyLatest, LatencyReset},
+    linsert::LInsert,
+    list::{LIndex, LLen, LRange, LSet, Pop, Push},
+    lmove::LMove,
+    lmpop::LMPop,
+    lpos::LPos,
+    lrem::LRem,
+    ltrim::LTrim,
+    memory::{MemoryStats, MemoryUsage},
+    monitor::MonitorCmd,
+    multi::{Discard, Exec, Multi},
+    object::{ObjectEncoding, ObjectFreq, ObjectHelp, ObjectIdleTime, ObjectRefCount},
+    persist::Persist,
+    psync::Psync,
+    pubsub::{PubSubChannels, PubSubNumPat, PubSubNumSub},
+    quit::Quit,
+    randomkey::RandomKey,
+    rename::Rename,
+    replconf::ReplConf,
+    replicaof::ReplicaOf,
+    reset::Reset,
+    save::{BgRewriteAof, BgSave, Save},
+    scan::Scan,
+    select::Select,
+    setrange::SetRange,
+    sets::{SAdd, SCard, SIsMember, SMIsMember, SMembers, SPop, SRandMember, SRem},
+    sintercard::SInterCard,
+    slowlog::{SlowLogGet, SlowLogLen, SlowLogReset},
+    sops::{SCombine, SMove, SetOp},
+    strlen::StrLen,
+    subscribe::{Publish, Subscribe, Unsubscribe},
+    swapdb::SwapDb,
+    ttl::Ttl,
+    type_cmd::Type,
+    unlink::Unlink,
+    wait::Wait,
+    watch::{Unwatch, Watch},
+    zcount::{ZCount, ZLexCount},
+    zintercard::ZInterCard,
+    zmscore::ZMScore,
+    zpop::ZPop,
+    zrandmember::ZRandMember,
+    zrange::{ZRange, ZRangeBy},
+    zrange_compat::{ZRangeByLex, ZRangeByScore},
+    zrangestore::ZRangeStore,
+    zset::{ZAdd, ZAddOptions, ZCard, ZIncrBy, ZRank, ZScore},
+    zsetops::{Aggregate, ZCombine},
+};
+
+// channel patterns of PSUBSCRIBE follow the same rules as KEYS
+pub(crate) use self::keys::glob_match;
+
 lazy_static! {
     static ref RESP_OK: RespFrame = SimpleString::new("OK").into();
+    static ref RESP_WRONGTYPE: RespFrame =
+        SimpleError::new("WRONGTYPE Operation against a key holding the wrong kind of value")
+            .into();
+    // for commands about the calling connection, executed without one
+    static ref RESP_NO_CONNECTION: RespFrame = SimpleError::new("ERR no client connection").into();
 }
 
 #[derive(Error, Debug)]
@@ -25,6 +189,15 @@ pub enum CommandError {
 #[enum_dispatch]
 pub trait CommandExecutor {
     fn execute(self, backend: &Backend) -> RespFrame;
+
+    /// execute on behalf of a client connection, commands that read or change its state
+    /// override this
+    fn execute_on(self, backend: &Backend, _conn: &mut ConnectionState) -> RespFrame
+    where
+        Self: Sized,
+    {
+        self.execute(backend)
+    }
 }
 
 #[enum_dispatch(CommandExecutor)]
@@ -32,9 +205,150 @@ pub trait CommandExecutor {
 pub enum Command {
     Get(Get),
     Set(Set),
+    Type(Type),
+    Del(Del),
+    Unlink(Unlink),
+    Exists(Exists),
+    Rename(Rename),
+    Expire(Expire),
+    Ttl(Ttl),
+    ExpireTime(ExpireTime),
+    Persist(Persist),
+    Keys(Keys),
+    Scan(Scan),
+    RandomKey(RandomKey),
+    Copy(Copy),
+    ObjectEncoding(ObjectEncoding),
+    ObjectIdleTime(ObjectIdleTime),
+    ObjectFreq(ObjectFreq),
+    ObjectRefCount(ObjectRefCount),
+    ObjectHelp(ObjectHelp),
+    DbSize(DbSize),
+    Select(Select),
+    Flush(Flush),
+    SwapDb(SwapDb),
+    StrLen(StrLen),
+    GetRange(GetRange),
+    SetRange(SetRange),
     HGet(HGet),
     HSet(HSet),
     HGetAll(HGetAll),
+    HDel(HDel),
+    HExists(HExists),
+    HKeys(HKeys),
+    HVals(HVals),
+    HLen(HLen),
+    HMSet(HMSet),
+    HMGet(HMGet),
+    HIncrBy(HIncrBy),
+    HIncrByFloat(HIncrByFloat),
+    HSetNx(HSetNx),
+    HScan(HScan),
+    HRandField(HRandField),
+    Push(Push),
+    Pop(Pop),
+    LLen(LLen),
+    LRange(LRange),
+    LIndex(LIndex),
+    LSet(LSet),
+    LInsert(LInsert),
+    LRem(LRem),
+    LTrim(LTrim),
+    LMove(LMove),
+    LMPop(LMPop),
+    LPos(LPos),
+    BPop(BPop),
+    BLMove(BLMove),
+    SAdd(SAdd),
+    SRem(SRem),
+    SMembers(SMembers),
+    SIsMember(SIsMember),
+    SMIsMember(SMIsMember),
+    SCard(SCard),
+    SPop(SPop),
+    SRandMember(SRandMember),
+    SMove(SMove),
+    SCombine(SCombine),
+    SInterCard(SInterCard),
+    ZAdd(ZAdd),
+    ZScore(ZScore),
+    ZCard(ZCard),
+    ZRank(ZRank),
+    ZIncrBy(ZIncrBy),
+    ZRange(ZRange),
+    ZPop(ZPop),
+    BZPop(BZPop),
+    ZRandMember(ZRandMember),
+    ZCombine(ZCombine),
+    ZInterCard(ZInterCard),
+    ZRangeStore(ZRangeStore),
+    ZCount(ZCount),
+    ZLexCount(ZLexCount),
+    ZMScore(ZMScore),
+    ZRangeByScore(ZRangeByScore),
+    ZRangeByLex(ZRangeByLex),
+    ClientId(ClientId),
+    ClientSetName(ClientSetName),
+    ClientGetName(ClientGetName),
+    ClientList(ClientList),
+    ClientKill(ClientKill),
+    ClientPause(ClientPause),
+    ClientUnpause(ClientUnpause),
+    Auth(Auth),
+    ConfigGet(ConfigGet),
+    ConfigSet(ConfigSet),
+    ConfigResetStat(ConfigResetStat),
+    Multi(Multi),
+    Exec(Exec),
+    Discard(Discard),
+    Watch(Watch),
+    Unwatch(Unwatch),
+    Reset(Reset),
+    Quit(Quit),
+    Monitor(MonitorCmd),
+    Save(Save),
+    BgSave(BgSave),
+    BgRewriteAof(BgRewriteAof),
+    LastSave(LastSave),
+    ReplicaOf(ReplicaOf),
+    ReplConf(ReplConf),
+    Psync(Psync),
+    Wait(Wait),
+    Subscribe(Subscribe),
+    Unsubscribe(Unsubscribe),
+    Publish(Publish),
+    Info(Info),
+    SlowLogGet(SlowLogGet),
+    SlowLogLen(SlowLogLen),
+    SlowLogReset(SlowLogReset),
+    LatencyHistory(LatencyHistory),
+    LatencyLatest(LatencyLatest),
+    LatencyReset(LatencyReset),
+    MemoryUsage(MemoryUsage),
+    MemoryStats(MemoryStats),
+    PubSubChannels(PubSubChannels),
+    PubSubNumSub(PubSubNumSub),
+    PubSubNumPat(PubSubNumPat),
+    GeoAdd(GeoAdd),
+    GeoSearch(GeoSearch),
+    GeoRadiusByMember(GeoRadiusByMember),
+    PfAdd(PfAdd),
+    PfCount(PfCount),
+    PfMerge(PfMerge),
+    GetBit(GetBit),
+    SetBit(SetBit),
+    BitCount(BitCount),
+    BitPos(BitPos),
+    BitOp(BitOp),
+    ClusterCountKeysInSlot(ClusterCountKeysInSlot),
+    ClusterGetKeysInSlot(ClusterGetKeysInSlot),
+    ClusterKeySlot(ClusterKeySlot),
+    CommandCount(CommandCount),
+    CommandInfo(CommandInfo),
+    CommandDocs(CommandDocs),
+    CommandList(CommandList),
+    DebugSleep(DebugSleep),
+    DebugReload(DebugReload),
 
     Unrecognized(Unrecognized),
 }
@@ -50,20 +364,19 @@ pub struct Get {
 #[derive(Debug)]
 pub struct Set {
     pub key: String,
-    pub value: RespFrame,
+    pub value: BulkString,
 }
 
 #[derive(Debug)]
 pub struct HGet {
     pub key: String,
-    pub field: String,
+    pub field: Vec<u8>,
 }
 
 #[derive(Debug)]
 pub struct HSet {
     pub key: String,
-    pub field: String,
-    pub value: RespFrame,
+    pub fields: Vec<(Vec<u8>, Vec<u8>)>,
 }
 
 #[derive(Debug)]
@@ -90,9 +403,216 @@ impl TryFrom<RespArray> for Command {
             Some(RespFrame::BulkString(ref cmd)) => match cmd.as_ref() {
                 b"get" => Ok(Command::Get(Get::try_from(value)?)),
                 b"set" => Ok(Command::Set(Set::try_from(value)?)),
+                b"type" => Ok(Command::Type(Type::try_from(value)?)),
+                b"del" => Ok(Command::Del(Del::try_from(value)?)),
+                b"unlink" => Ok(Command::Unlink(Unlink::try_from(value)?)),
+                b"exists" => Ok(Command::Exists(Exists::try_from(value)?)),
+                b"rename" | b"renamenx" => Ok(Command::Rename(Rename::try_from(value)?)),
+                b"expire" | b"pexpire" | b"expireat" | b"pexpireat" => {
+                    Ok(Command::Expire(Expire::try_from(value)?))
+                }
+                b"ttl" | b"pttl" => Ok(Command::Ttl(Ttl::try_from(value)?)),
+                b"expiretime" | b"pexpiretime" => {
+                    Ok(Command::ExpireTime(ExpireTime::try_from(value)?))
+                }
+                b"persist" => Ok(Command::Persist(Persist::try_from(value)?)),
+                b"keys" => Ok(Command::Keys(Keys::try_from(value)?)),
+                b"scan" => Ok(Command::Scan(Scan::try_from(value)?)),
+                b"randomkey" => Ok(Command::RandomKey(RandomKey::try_from(value)?)),
+                b"copy" => Ok(Command::Copy(Copy::try_from(value)?)),
+                b"dbsize" => Ok(Command::DbSize(DbSize::try_from(value)?)),
+                b"select" => Ok(Command::Select(Select::try_from(value)?)),
+                b"flushdb" | b"flushall" => Ok(Command::Flush(Flush::try_from(value)?)),
+                b"swapdb" => Ok(Command::SwapDb(SwapDb::try_from(value)?)),
+                b"strlen" => Ok(Command::StrLen(StrLen::try_from(value)?)),
+                b"getrange" | b"substr" => Ok(Command::GetRange(GetRange::try_from(value)?)),
+                b"setrange" => Ok(Command::SetRange(SetRange::try_from(value)?)),
                 b"hget" => Ok(Command::HGet(HGet::try_from(value)?)),
                 b"hset" => Ok(Command::HSet(HSet::try_from(value)?)),
                 b"hgetall" => Ok(Command::HGetAll(HGetAll::try_from(value)?)),
+                b"hdel" => Ok(Command::HDel(HDel::try_from(value)?)),
+                b"hexists" => Ok(Command::HExists(HExists::try_from(value)?)),
+                b"hkeys" => Ok(Command::HKeys(HKeys::try_from(value)?)),
+                b"hvals" => Ok(Command::HVals(HVals::try_from(value)?)),
+                b"hlen" => Ok(Command::HLen(HLen::try_from(value)?)),
+                b"hmset" => Ok(Command::HMSet(HMSet::try_from(value)?)),
+                b"hmget" => Ok(Command::HMGet(HMGet::try_from(value)?)),
+                b"hincrby" => Ok(Command::HIncrBy(HIncrBy::try_from(value)?)),
+                b"hincrbyfloat" => Ok(Command::HIncrByFloat(HIncrByFloat::try_from(value)?)),
+                b"hsetnx" => Ok(Command::HSetNx(HSetNx::try_from(value)?)),
+                b"hscan" => Ok(Command::HScan(HScan::try_from(value)?)),
+                b"hrandfield" => Ok(Command::HRandField(HRandField::try_from(value)?)),
+                b"lpush" | b"rpush" => Ok(Command::Push(Push::try_from(value)?)),
+                b"lpop" | b"rpop" => Ok(Command::Pop(Pop::try_from(value)?)),
+                b"llen" => Ok(Command::LLen(LLen::try_from(value)?)),
+                b"lrange" => Ok(Command::LRange(LRange::try_from(value)?)),
+                b"lindex" => Ok(Command::LIndex(LIndex::try_from(value)?)),
+                b"lset" => Ok(Command::LSet(LSet::try_from(value)?)),
+                b"linsert" => Ok(Command::LInsert(LInsert::try_from(value)?)),
+                b"lrem" => Ok(Command::LRem(LRem::try_from(value)?)),
+                b"ltrim" => Ok(Command::LTrim(LTrim::try_from(value)?)),
+                b"lmove" => Ok(Command::LMove(LMove::try_from(value)?)),
+                b"lmpop" => Ok(Command::LMPop(LMPop::try_from(value)?)),
+                b"lpos" => Ok(Command::LPos(LPos::try_from(value)?)),
+                b"blpop" | b"brpop" => Ok(Command::BPop(BPop::try_from(value)?)),
+                b"blmove" => Ok(Command::BLMove(BLMove::try_from(value)?)),
+                b"sadd" => Ok(Command::SAdd(SAdd::try_from(value)?)),
+                b"srem" => Ok(Command::SRem(SRem::try_from(value)?)),
+                b"smembers" => Ok(Command::SMembers(SMembers::try_from(value)?)),
+                b"sismember" => Ok(Command::SIsMember(SIsMember::try_from(value)?)),
+                b"smismember" => Ok(Command::SMIsMember(SMIsMember::try_from(value)?)),
+                b"scard" => Ok(Command::SCard(SCard::try_from(value)?)),
+                b"spop" => Ok(Command::SPop(SPop::try_from(value)?)),
+                b"srandmember" => Ok(Command::SRandMember(SRandMember::try_from(value)?)),
+                b"smove" => Ok(Command::SMove(SMove::try_from(value)?)),
+                b"sdiff" | b"sunion" | b"sinter" | b"sdiffstore" | b"sunionstore"
+                | b"sinterstore" => Ok(Command::SCombine(SCombine::try_from(value)?)),
+                b"sintercard" => Ok(Command::SInterCard(SInterCard::try_from(value)?)),
+                b"zadd" => Ok(Command::ZAdd(ZAdd::try_from(value)?)),
+                b"zscore" => Ok(Command::ZScore(ZScore::try_from(value)?)),
+                b"zcard" => Ok(Command::ZCard(ZCard::try_from(value)?)),
+                b"zrank" | b"zrevrank" => Ok(Command::ZRank(ZRank::try_from(value)?)),
+                b"zincrby" => Ok(Command::ZIncrBy(ZIncrBy::try_from(value)?)),
+                b"zrange" => Ok(Command::ZRange(ZRange::try_from(value)?)),
+                b"zpopmin" | b"zpopmax" => Ok(Command::ZPop(ZPop::try_from(value)?)),
+                b"bzpopmin" | b"bzpopmax" => Ok(Command::BZPop(BZPop::try_from(value)?)),
+                b"zrandmember" => Ok(Command::ZRandMember(ZRandMember::try_from(value)?)),
+                b"zdiff" | b"zunion" | b"zinter" | b"zdiffstore" | b"zunionstore"
+                | b"zinterstore" => Ok(Command::ZCombine(ZCombine::try_from(value)?)),
+                b"zintercard" => Ok(Command::ZInterCard(ZInterCard::try_from(value)?)),
+                b"zrangestore" => Ok(Command::ZRangeStore(ZRangeStore::try_from(value)?)),
+                b"zcount" => Ok(Command::ZCount(ZCount::try_from(value)?)),
+                b"zlexcount" => Ok(Command::ZLexCount(ZLexCount::try_from(value)?)),
+                b"zmscore" => Ok(Command::ZMScore(ZMScore::try_from(value)?)),
+                b"zrangebyscore" | b"zrevrangebyscore" => {
+                    Ok(Command::ZRangeByScore(ZRangeByScore::try_from(value)?))
+                }
+                b"zrangebylex" | b"zrevrangebylex" => {
+                    Ok(Command::ZRangeByLex(ZRangeByLex::try_from(value)?))
+                }
+                b"geoadd" => Ok(Command::GeoAdd(GeoAdd::try_from(value)?)),
+                b"geosearch" => Ok(Command::GeoSearch(GeoSearch::try_from(value)?)),
+                b"georadiusbymember" => Ok(Command::GeoRadiusByMember(
+                    GeoRadiusByMember::try_from(value)?,
+                )),
+                b"pfadd" => Ok(Command::PfAdd(PfAdd::try_from(value)?)),
+                b"pfcount" => Ok(Command::PfCount(PfCount::try_from(value)?)),
+                b"pfmerge" => Ok(Command::PfMerge(PfMerge::try_from(value)?)),
+                b"getbit" => Ok(Command::GetBit(GetBit::try_from(value)?)),
+                b"setbit" => Ok(Command::SetBit(SetBit::try_from(value)?)),
+                b"bitcount" => Ok(Command::BitCount(BitCount::try_from(value)?)),
+                b"bitpos" => Ok(Command::BitPos(BitPos::try_from(value)?)),
+                b"bitop" => Ok(Command::BitOp(BitOp::try_from(value)?)),
+                b"object" => match subcommand(&value).as_deref() {
+                    Some(b"encoding") => {
+                        Ok(Command::ObjectEncoding(ObjectEncoding::try_from(value)?))
+                    }
+                    Some(b"idletime") => {
+                        Ok(Command::ObjectIdleTime(ObjectIdleTime::try_from(value)?))
+                    }
+                    Some(b"freq") => Ok(Command::ObjectFreq(ObjectFreq::try_from(value)?)),
+                    Some(b"refcount") => {
+                        Ok(Command::ObjectRefCount(ObjectRefCount::try_from(value)?))
+                    }
+                    Some(b"help") => Ok(Command::ObjectHelp(ObjectHelp::try_from(value)?)),
+                    _ => Ok(Unrecognized.into()),
+                },
+                b"auth" => Ok(Command::Auth(Auth::try_from(value)?)),
+                b"multi" => Ok(Command::Multi(Multi::try_from(value)?)),
+                b"exec" => Ok(Command::Exec(Exec::try_from(value)?)),
+                b"discard" => Ok(Command::Discard(Discard::try_from(value)?)),
+                b"watch" => Ok(Command::Watch(Watch::try_from(value)?)),
+                b"unwatch" => Ok(Command::Unwatch(Unwatch::try_from(value)?)),
+                b"wait" => Ok(Command::Wait(Wait::try_from(value)?)),
+                b"reset" => Ok(Command::Reset(Reset::try_from(value)?)),
+                b"quit" => Ok(Command::Quit(Quit::try_from(value)?)),
+                b"monitor" => Ok(Command::Monitor(MonitorCmd::try_from(value)?)),
+                b"save" => Ok(Command::Save(Save::try_from(value)?)),
+                b"bgsave" => Ok(Command::BgSave(BgSave::try_from(value)?)),
+                b"bgrewriteaof" => Ok(Command::BgRewriteAof(BgRewriteAof::try_from(value)?)),
+                b"lastsave" => Ok(Command::LastSave(LastSave::try_from(value)?)),
+                b"replicaof" | b"slaveof" => Ok(Command::ReplicaOf(ReplicaOf::try_from(value)?)),
+                b"psync" => Ok(Command::Psync(Psync::try_from(value)?)),
+                b"replconf" => Ok(Command::ReplConf(ReplConf::try_from(value)?)),
+                b"subscribe" => Ok(Command::Subscribe(Subscribe::try_from(value)?)),
+                b"unsubscribe" => Ok(Command::Unsubscribe(Unsubscribe::try_from(value)?)),
+                b"psubscribe" => Ok(Command::Subscribe(Subscribe::try_from(value)?)),
+                b"punsubscribe" => Ok(Command::Unsubscribe(Unsubscribe::try_from(value)?)),
+                b"publish" => Ok(Command::Publish(Publish::try_from(value)?)),
+                b"info" => Ok(Command::Info(Info::try_from(value)?)),
+                b"latency" => match subcommand(&value).as_deref() {
+                    Some(b"history") => {
+                        Ok(Command::LatencyHistory(LatencyHistory::try_from(value)?))
+                    }
+                    Some(b"latest") => Ok(Command::LatencyLatest(LatencyLatest::try_from(value)?)),
+                    Some(b"reset") => Ok(Command::LatencyReset(LatencyReset::try_from(value)?)),
+                    _ => Ok(Unrecognized.into()),
+                },
+                b"memory" => match subcommand(&value).as_deref() {
+                    Some(b"usage") => Ok(Command::MemoryUsage(MemoryUsage::try_from(value)?)),
+                    Some(b"stats") => Ok(Command::MemoryStats(MemoryStats::try_from(value)?)),
+                    _ => Ok(Unrecognized.into()),
+                },
+                b"slowlog" => match subcommand(&value).as_deref() {
+                    Some(b"get") => Ok(Command::SlowLogGet(SlowLogGet::try_from(value)?)),
+                    Some(b"len") => Ok(Command::SlowLogLen(SlowLogLen::try_from(value)?)),
+                    Some(b"reset") => Ok(Command::SlowLogReset(SlowLogReset::try_from(value)?)),
+                    _ => Ok(Unrecognized.into()),
+                },
+                b"pubsub" => match subcommand(&value).as_deref() {
+                    Some(b"channels") => {
+                        Ok(Command::PubSubChannels(PubSubChannels::try_from(value)?))
+                    }
+                    Some(b"numsub") => Ok(Command::PubSubNumSub(PubSubNumSub::try_from(value)?)),
+                    Some(b"numpat") => Ok(Command::PubSubNumPat(PubSubNumPat::try_from(value)?)),
+                    _ => Ok(Unrecognized.into()),
+                },
+                b"config" => match subcommand(&value).as_deref() {
+                    Some(b"get") => Ok(Command::ConfigGet(ConfigGet::try_from(value)?)),
+                    Some(b"set") => Ok(Command::ConfigSet(ConfigSet::try_from(value)?)),
+                    Some(b"resetstat") => {
+                        Ok(Command::ConfigResetStat(ConfigResetStat::try_from(value)?))
+                    }
+                    _ => Ok(Unrecognized.into()),
+                },
+                b"client" => match subcommand(&value).as_deref() {
+                    Some(b"id") => Ok(Command::ClientId(ClientId::try_from(value)?)),
+                    Some(b"setname") => Ok(Command::ClientSetName(ClientSetName::try_from(value)?)),
+                    Some(b"getname") => Ok(Command::ClientGetName(ClientGetName::try_from(value)?)),
+                    Some(b"list") => Ok(Command::ClientList(ClientList::try_from(value)?)),
+                    Some(b"kill") => Ok(Command::ClientKill(ClientKill::try_from(value)?)),
+                    Some(b"pause") => Ok(Command::ClientPause(ClientPause::try_from(value)?)),
+                    Some(b"unpause") => Ok(Command::ClientUnpause(ClientUnpause::try_from(value)?)),
+                    _ => Ok(Unrecognized.into()),
+                },
+                b"cluster" => match subcommand(&value).as_deref() {
+                    Some(b"countkeysinslot") => Ok(Command::ClusterCountKeysInSlot(
+                        ClusterCountKeysInSlot::try_from(value)?,
+                    )),
+                    Some(b"getkeysinslot") => Ok(Command::ClusterGetKeysInSlot(
+                        ClusterGetKeysInSlot::try_from(value)?,
+                    )),
+                    Some(b"keyslot") => {
+                        Ok(Command::ClusterKeySlot(ClusterKeySlot::try_from(value)?))
+                    }
+                    _ => Ok(Unrecognized.into()),
+                },
+                b"debug" => match subcommand(&value).as_deref() {
+                    Some(b"sleep") => Ok(Command::DebugSleep(DebugSleep::try_from(value)?)),
+                    Some(b"reload") => Ok(Command::DebugReload(DebugReload::try_from(value)?)),
+                    _ => Ok(Unrecognized.into()),
+                },
+                b"command" => match subcommand(&value).as_deref() {
+                    // COMMAND alone describes every command
+                    None if value.len() == 1 => {
+                        Ok(Command::CommandInfo(CommandInfo { names: vec![] }))
+                    }
+                    Some(b"count") => Ok(Command::CommandCount(CommandCount::try_from(value)?)),
+                    Some(b"info") => Ok(Command::CommandInfo(CommandInfo::try_from(value)?)),
+                    Some(b"docs") => Ok(Command::CommandDocs(CommandDocs::try_from(value)?)),
+                    Some(b"list") => Ok(Command::CommandList(CommandList::try_from(value)?)),
+                    _ => Ok(Unrecognized.into()),
+                },
                 _ => Ok(Unrecognized.into()),
             },
             _ => Err(CommandError::InvalidCommand(
@@ -108,6 +628,14 @@ impl CommandExecutor for Unrecognized {
     }
 }
 
+// lowercase name of the subcommand, e.g. `keyslot` in `CLUSTER KEYSLOT key`
+fn subcommand(value: &RespArray) -> Option<Vec<u8>> {
+    match value.get(1) {
+        Some(RespFrame::BulkString(sub)) => Some(sub.to_ascii_lowercase()),
+        _ => None,
+    }
+}
+
 fn validate_command(
     value: &RespArray,
     names: &[&'static str],
@@ -120,6 +648,25 @@ fn validate_command(
             n_args
         )));
     }
+    validate_command_names(value, names)
+}
+
+fn validate_variadic_command(
+    value: &RespArray,
+    names: &[&'static str],
+    min_args: usize,
+) -> Result<(), CommandError> {
+    if value.len() < min_args + names.len() {
+        return Err(CommandError::InvalidArgument(format!(
+            "{} command must have at least {} argument",
+            names.join(" "),
+            min_args
+        )));
+    }
+    validate_command_names(value, names)
+}
+
+fn validate_command_names(value: &RespArray, names: &[&'static str]) -> Result<(), CommandError> {
     for (i, name) in names.iter().enumerate() {
         match value[i] {
             RespFrame::BulkString(ref cmd) => {
@@ -146,6 +693,34 @@ fn extract_args(value: RespArray, start: usize) -> Result<Vec<RespFrame>, Comman
     Ok(value.0.into_iter().skip(start).collect())
 }
 
+// look up a string value, the error carries the WRONGTYPE reply for other kinds of value
+fn get_string(backend: &Backend, key: &str) -> Result<Option<BulkString>, RespFrame> {
+    match backend.read().get(key) {
+        Some(BackendValue::String(s)) => Ok(Some(s.clone())),
+        Some(_) => Err(RESP_WRONGTYPE.clone()),
+        None => Ok(None),
+    }
+}
+
+fn extract_bytes(frame: Option<RespFrame>) -> Result<Vec<u8>, CommandError> {
+    match frame {
+        Some(RespFrame::BulkString(s)) => Ok(s.0),
+        _ => Err(CommandError::InvalidArgument(
+            "Expected bulk string argument".to_string(),
+        )),
+    }
+}
+
+fn extract_string(frame: Option<RespFrame>) -> Result<String, CommandError> {
+    Ok(String::from_utf8(extract_bytes(frame)?)?)
+}
+
+fn parse_number<T: std::str::FromStr>(frame: Option<RespFrame>) -> Result<T, CommandError> {
+    let s = extract_string(frame)?;
+    s.parse()
+        .map_err(|_| CommandError::InvalidArgument(format!("Invalid number: {}", s)))
+}
+
 #[cfg(test)]
 mod tests {
     use bytes::BytesMut;
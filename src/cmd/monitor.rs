@@ -0,0 +1,58 @@
+use tokio_stream::wrappers::BroadcastStream;
+
+use crate::{network::ConnectionState, Backend, RespArray, RespFrame};
+
+use super::{validate_command, CommandError, CommandExecutor, RESP_NO_CONNECTION, RESP_OK};
+
+/// MONITOR, streams every command the server processes to this connection until it is
+/// closed or sends RESET. every other client pays for formatting the lines meanwhile
+#[derive(Debug)]
+pub struct MonitorCmd;
+
+impl CommandExecutor for MonitorCmd {
+    fn execute(self, _backend: &Backend) -> RespFrame {
+        RESP_NO_CONNECTION.clone()
+    }
+
+    fn execute_on(self, backend: &Backend, conn: &mut ConnectionState) -> RespFrame {
+        if conn.monitor.is_none() {
+            conn.monitor = Some(BroadcastStream::new(backend.monitor().subscribe()));
+        }
+        RESP_OK.clone()
+    }
+}
+
+impl TryFrom<RespArray> for MonitorCmd {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["monitor"], 0)?;
+        Ok(MonitorCmd)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_monitor_subscribes_once() -> Result<()> {
+        let mut buf = BytesMut::from("*1\r\n$7\r\nMONITOR\r\n");
+        let cmd: MonitorCmd = RespArray::decode(&mut buf)?.try_into()?;
+
+        let backend = Backend::new();
+        let mut conn = ConnectionState::new();
+        assert!(!backend.monitor().is_active());
+        assert_eq!(cmd.execute_on(&backend, &mut conn), RESP_OK.clone());
+        MonitorCmd.execute_on(&backend, &mut conn);
+        assert!(backend.monitor().is_active());
+
+        drop(conn);
+        assert!(!backend.monitor().is_active());
+        Ok(())
+    }
+}
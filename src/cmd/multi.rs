@@ -0,0 +1,227 @@
+use crate::{
+    network::{execute_command, ConnectionFlags, ConnectionState},
+    persistence::aof,
+    Backend, RespArray, RespFrame, RespNullArray, SimpleError,
+};
+
+use super::{
+    validate_command,
+    watch::{unwatch_all, watched_keys_modified},
+    Command, CommandError, CommandExecutor, RESP_NO_CONNECTION, RESP_OK,
+};
+
+#[derive(Debug)]
+pub struct Multi;
+
+#[derive(Debug)]
+pub struct Exec;
+
+#[derive(Debug)]
+pub struct Discard;
+
+// the network layer queues commands while MULTI is set, these only manage the transaction
+impl CommandExecutor for Multi {
+    fn execute(self, _backend: &Backend) -> RespFrame {
+        RESP_NO_CONNECTION.clone()
+    }
+
+    fn execute_on(self, _backend: &Backend, conn: &mut ConnectionState) -> RespFrame {
+        if conn.flags.contains(ConnectionFlags::MULTI) {
+            return SimpleError::new("ERR MULTI calls can not be nested").into();
+        }
+        conn.flags.insert(ConnectionFlags::MULTI);
+        RESP_OK.clone()
+    }
+}
+
+impl CommandExecutor for Exec {
+    fn execute(self, _backend: &Backend) -> RespFrame {
+        RESP_NO_CONNECTION.clone()
+    }
+
+    fn execute_on(self, backend: &Backend, conn: &mut ConnectionState) -> RespFrame {
+        if !conn.flags.contains(ConnectionFlags::MULTI) {
+            return SimpleError::new("ERR EXEC without MULTI").into();
+        }
+        let dirty = conn.flags.contains(ConnectionFlags::DIRTY_EXEC);
+        let frames = std::mem::take(&mut conn.queued_frames);
+        let queued = end_transaction(conn);
+        if dirty {
+            unwatch_all(backend, conn);
+            return SimpleError::new("EXECABORT Transaction discarded because of previous errors.")
+                .into();
+        }
+
+        // no other command runs from here on, so watched keys can't change after the check
+        let _guard = backend.exec_guard();
+        let aborted = watched_keys_modified(conn);
+        unwatch_all(backend, conn);
+        if aborted {
+            return RespNullArray.into();
+        }
+        let db = conn.selected_db;
+        let mut ret = Vec::with_capacity(queued.len());
+        let mut writes = Vec::new();
+        let frames = frames.into_iter().chain(std::iter::repeat_with(|| None));
+        for (cmd, frame) in queued.into_iter().zip(frames) {
+            // a queued SELECT applies to the commands after it
+            let backend = backend
+                .select(conn.selected_db)
+                .expect("validated by SELECT");
+            // runtime errors are replies like any other, the rest of the transaction still runs
+            let reply = execute_command(cmd, &backend, conn);
+            if let Some(frame) = frame.filter(|_| !matches!(reply, RespFrame::Error(_))) {
+                writes.extend(aof::propagated(frame, &reply, &backend));
+            }
+            ret.push(reply);
+        }
+        if !writes.is_empty() {
+            backend.replication().feed_transaction(db, writes.clone());
+            backend.persistence().aof().feed_transaction(db, writes);
+        }
+        RespArray::new(ret).into()
+    }
+}
+
+impl CommandExecutor for Discard {
+    fn execute(self, _backend: &Backend) -> RespFrame {
+        RESP_NO_CONNECTION.clone()
+    }
+
+    fn execute_on(self, backend: &Backend, conn: &mut ConnectionState) -> RespFrame {
+        if !conn.flags.contains(ConnectionFlags::MULTI) {
+            return SimpleError::new("ERR DISCARD without MULTI").into();
+        }
+        end_transaction(conn);
+        unwatch_all(backend, conn);
+        RESP_OK.clone()
+    }
+}
+
+// leave MULTI, handing back the queued commands
+pub(super) fn end_transaction(conn: &mut ConnectionState) -> Vec<Command> {
+    conn.flags.remove(ConnectionFlags::MULTI);
+    conn.flags.remove(ConnectionFlags::DIRTY_EXEC);
+    conn.queued_frames.clear();
+    std::mem::take(&mut conn.queued)
+}
+
+impl TryFrom<RespArray> for Multi {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["multi"], 0)?;
+        Ok(Multi)
+    }
+}
+
+impl TryFrom<RespArray> for Exec {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["exec"], 0)?;
+        Ok(Exec)
+    }
+}
+
+impl TryFrom<RespArray> for Discard {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["discard"], 0)?;
+        Ok(Discard)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        cmd::{Get, Push, Set},
+        BulkString, RespNull,
+    };
+
+    use super::*;
+
+    fn set(key: &str, value: &str) -> Command {
+        Set {
+            key: key.to_string(),
+            value: BulkString::new(value),
+        }
+        .into()
+    }
+
+    #[test]
+    fn test_multi_exec() {
+        let backend = Backend::new();
+        let mut conn = ConnectionState::new();
+        assert_eq!(Multi.execute_on(&backend, &mut conn), RESP_OK.clone());
+        assert!(matches!(
+            Multi.execute_on(&backend, &mut conn),
+            RespFrame::Error(_)
+        ));
+
+        conn.queued.push(set("key", "value"));
+        // a runtime error doesn't stop the transaction
+        conn.queued.push(
+            Push {
+                key: "key".to_string(),
+                elements: vec![b"a".to_vec()],
+                left: true,
+            }
+            .into(),
+        );
+        conn.queued.push(
+            Get {
+                key: "key".to_string(),
+            }
+            .into(),
+        );
+        let ret = Exec.execute_on(&backend, &mut conn);
+        let RespFrame::Array(ret) = ret else {
+            panic!("expected an array, got {:?}", ret);
+        };
+        assert_eq!(ret.len(), 3);
+        assert_eq!(ret[0], RESP_OK.clone());
+        assert!(matches!(ret[1], RespFrame::Error(_)));
+        assert_eq!(ret[2], BulkString::new("value").into());
+
+        assert!(!conn.flags.contains(ConnectionFlags::MULTI));
+        assert!(conn.queued.is_empty());
+        assert!(matches!(
+            Exec.execute_on(&backend, &mut conn),
+            RespFrame::Error(_)
+        ));
+    }
+
+    #[test]
+    fn test_exec_after_queuing_error() {
+        let backend = Backend::new();
+        let mut conn = ConnectionState::new();
+        Multi.execute_on(&backend, &mut conn);
+        conn.queued.push(set("key", "value"));
+        conn.flags.insert(ConnectionFlags::DIRTY_EXEC);
+
+        let ret = Exec.execute_on(&backend, &mut conn);
+        assert!(matches!(ret, RespFrame::Error(e) if e.starts_with("EXECABORT")));
+        assert_eq!(backend.get("key"), None);
+        assert!(!conn.flags.contains(ConnectionFlags::DIRTY_EXEC));
+    }
+
+    #[test]
+    fn test_discard() {
+        let backend = Backend::new();
+        let mut conn = ConnectionState::new();
+        assert!(matches!(
+            Discard.execute_on(&backend, &mut conn),
+            RespFrame::Error(_)
+        ));
+
+        Multi.execute_on(&backend, &mut conn);
+        conn.queued.push(set("key", "value"));
+        assert_eq!(Discard.execute_on(&backend, &mut conn), RESP_OK.clone());
+        assert!(conn.queued.is_empty());
+        assert!(!conn.flags.contains(ConnectionFlags::MULTI));
+
+        let get = Get {
+            key: "key".to_string(),
+        };
+        assert_eq!(get.execute(&backend), RespFrame::Null(RespNull));
+    }
+}
@@ -0,0 +1,353 @@
+use crate::{Backend, BulkString, RespArray, RespFrame, RespNullBulkString, SimpleError};
+
+use super::{extract_args, extract_string, validate_command, CommandError, CommandExecutor};
+
+#[derive(Debug)]
+pub struct ObjectEncoding {
+    pub key: String,
+}
+
+#[derive(Debug)]
+pub struct ObjectIdleTime {
+    pub key: String,
+}
+
+#[derive(Debug)]
+pub struct ObjectFreq {
+    pub key: String,
+}
+
+#[derive(Debug)]
+pub struct ObjectRefCount {
+    pub key: String,
+}
+
+#[derive(Debug)]
+pub struct ObjectHelp;
+
+const HELP: &[&str] = &[
+    "OBJECT <subcommand> [<arg> [value] [opt] ...]. Subcommands are:",
+    "ENCODING <key>",
+    "    Return the kind of internal representation used in order to store the value",
+    "    associated with a <key>.",
+    "FREQ <key>",
+    "    Return the access frequency index of the <key>. The returned integer is",
+    "    proportional to the logarithm of the recent access frequency of the key.",
+    "IDLETIME <key>",
+    "    Return the idle time of the <key>, that is the approximated number of",
+    "    seconds elapsed since the last access to the key.",
+    "REFCOUNT <key>",
+    "    Return the number of references of the value associated with the specified",
+    "    <key>.",
+    "HELP",
+    "    Print this help.",
+];
+
+impl CommandExecutor for ObjectEncoding {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match backend.read().peek(&self.key) {
+            Some(entry) => BulkString::new(entry.value.encoding()).into(),
+            None => RespNullBulkString.into(),
+        }
+    }
+}
+
+impl CommandExecutor for ObjectIdleTime {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        // peek: asking for the idle time must not reset it
+        match backend.read().peek(&self.key) {
+            Some(entry) => RespFrame::Integer(entry.idle_time().as_secs() as i64),
+            None => RespNullBulkString.into(),
+        }
+    }
+}
+
+impl CommandExecutor for ObjectFreq {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        // the counter is always maintained, but only meaningful to the LFU policies
+        if !backend.config().maxmemory_policy.ends_with("-lfu") {
+            return SimpleError::new(
+                "ERR An LFU maxmemory policy is not selected, access frequency not tracked.",
+            )
+            .into();
+        }
+        match backend.read().peek(&self.key) {
+            Some(entry) => RespFrame::Integer(entry.frequency() as i64),
+            None => RespNullBulkString.into(),
+        }
+    }
+}
+
+impl CommandExecutor for ObjectRefCount {
+    // values are never shared between keys
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match backend.read().peek(&self.key) {
+            Some(_) => RespFrame::Integer(1),
+            None => RespNullBulkString.into(),
+        }
+    }
+}
+
+impl CommandExecutor for ObjectHelp {
+    fn execute(self, _: &Backend) -> RespFrame {
+        RespArray::new(
+            HELP.iter()
+                .map(|line| BulkString::new(*line).into())
+                .collect::<Vec<RespFrame>>(),
+        )
+        .into()
+    }
+}
+
+impl TryFrom<RespArray> for ObjectEncoding {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["object", "encoding"], 1)?;
+
+        let mut args = extract_args(value, 2)?.into_iter();
+        Ok(ObjectEncoding {
+            key: extract_string(args.next())?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for ObjectIdleTime {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["object", "idletime"], 1)?;
+
+        let mut args = extract_args(value, 2)?.into_iter();
+        Ok(ObjectIdleTime {
+            key: extract_string(args.next())?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for ObjectFreq {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["object", "freq"], 1)?;
+
+        let mut args = extract_args(value, 2)?.into_iter();
+        Ok(ObjectFreq {
+            key: extract_string(args.next())?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for ObjectRefCount {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["object", "refcount"], 1)?;
+
+        let mut args = extract_args(value, 2)?.into_iter();
+        Ok(ObjectRefCount {
+            key: extract_string(args.next())?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for ObjectHelp {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["object", "help"], 0)?;
+        Ok(ObjectHelp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{HashMap, HashSet, VecDeque},
+        time::Duration,
+    };
+
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{cmd::Get, BackendValue, RespDecode, ZSet};
+
+    use super::*;
+
+    fn encoding(backend: &Backend, key: &str) -> RespFrame {
+        ObjectEncoding {
+            key: key.to_string(),
+        }
+        .execute(backend)
+    }
+
+    fn set_and_check(value: impl Into<BackendValue>, expected: &str) {
+        let backend = Backend::new();
+        backend.set("key".to_string(), value);
+        assert_eq!(
+            encoding(&backend, "key"),
+            BulkString::new(expected).into(),
+            "{}",
+            expected
+        );
+    }
+
+    #[test]
+    fn test_object_encoding_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*3\r\n$6\r\nobject\r\n$8\r\nENCODING\r\n$3\r\nkey\r\n");
+        let cmd: ObjectEncoding = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.key, "key");
+        Ok(())
+    }
+
+    #[test]
+    fn test_object_encoding_missing_key() {
+        let backend = Backend::new();
+        assert_eq!(encoding(&backend, "key"), RespNullBulkString.into());
+    }
+
+    #[test]
+    fn test_object_encoding_strings() {
+        set_and_check(BulkString::new("12345"), "int");
+        set_and_check(BulkString::new("-9223372036854775808"), "int");
+        set_and_check(BulkString::new("+1"), "embstr");
+        set_and_check(BulkString::new("01"), "embstr");
+        set_and_check(BulkString::new("a".repeat(44)), "embstr");
+        set_and_check(BulkString::new("a".repeat(45)), "raw");
+    }
+
+    #[test]
+    fn test_object_encoding_collections() {
+        let small: VecDeque<Vec<u8>> = (0..128).map(|i| i.to_string().into_bytes()).collect();
+        set_and_check(BackendValue::List(small.clone()), "listpack");
+        let mut large = small.clone();
+        large.push_back(b"one more".to_vec());
+        set_and_check(BackendValue::List(large), "quicklist");
+        set_and_check(
+            BackendValue::List(VecDeque::from([vec![b'a'; 65]])),
+            "quicklist",
+        );
+
+        let hash: HashMap<Vec<u8>, Vec<u8>> = (0..128)
+            .map(|i| (i.to_string().into_bytes(), b"v".to_vec()))
+            .collect();
+        set_and_check(BackendValue::Hash(hash.clone()), "listpack");
+        let mut large = hash;
+        large.insert(b"big".to_vec(), vec![b'v'; 65]);
+        set_and_check(BackendValue::Hash(large), "hashtable");
+
+        let ints: HashSet<Vec<u8>> = (0..512).map(|i| i.to_string().into_bytes()).collect();
+        set_and_check(BackendValue::Set(ints.clone()), "intset");
+        let mut too_many = ints;
+        too_many.insert(b"512".to_vec());
+        set_and_check(BackendValue::Set(too_many), "hashtable");
+        set_and_check(
+            BackendValue::Set(HashSet::from([b"a".to_vec()])),
+            "listpack",
+        );
+
+        let mut zset = ZSet::new();
+        for i in 0..128 {
+            zset.insert(i.to_string().into_bytes(), i as f64);
+        }
+        set_and_check(zset.clone(), "listpack");
+        zset.insert(b"one more".to_vec(), 0.0);
+        set_and_check(zset, "skiplist");
+    }
+
+    #[test]
+    fn test_object_idletime_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*3\r\n$6\r\nOBJECT\r\n$8\r\nidletime\r\n$3\r\nkey\r\n");
+        let cmd: ObjectIdleTime = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.key, "key");
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_object_idletime() {
+        let backend = Backend::new();
+        let idletime = || {
+            ObjectIdleTime {
+                key: "key".to_string(),
+            }
+            .execute(&backend)
+        };
+        assert_eq!(idletime(), RespNullBulkString.into());
+
+        backend.set("key".to_string(), BulkString::new("value"));
+        assert_eq!(idletime(), RespFrame::Integer(0));
+
+        tokio::time::advance(Duration::from_secs(10)).await;
+        assert_eq!(idletime(), RespFrame::Integer(10));
+        // OBJECT IDLETIME itself is not an access
+        assert_eq!(idletime(), RespFrame::Integer(10));
+
+        let cmd = Get {
+            key: "key".to_string(),
+        };
+        cmd.execute(&backend);
+        assert_eq!(idletime(), RespFrame::Integer(0));
+
+        tokio::time::advance(Duration::from_millis(2500)).await;
+        assert_eq!(idletime(), RespFrame::Integer(2));
+    }
+
+    #[test]
+    fn test_object_help() -> Result<()> {
+        let mut buf = BytesMut::from("*2\r\n$6\r\nobject\r\n$4\r\nHELP\r\n");
+        let cmd: ObjectHelp = RespArray::decode(&mut buf)?.try_into()?;
+
+        let backend = Backend::new();
+        match cmd.execute(&backend) {
+            RespFrame::Array(lines) => {
+                assert!(!lines.is_empty());
+                assert!(lines.contains(&BulkString::new("HELP").into()));
+            }
+            v => panic!("unexpected reply {:?}", v),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_object_refcount() {
+        let backend = Backend::new();
+        let refcount = || {
+            ObjectRefCount {
+                key: "key".to_string(),
+            }
+            .execute(&backend)
+        };
+        assert_eq!(refcount(), RespNullBulkString.into());
+        backend.set("key".to_string(), BulkString::new("value"));
+        assert_eq!(refcount(), RespFrame::Integer(1));
+    }
+
+    #[test]
+    fn test_object_freq() {
+        let backend = Backend::new();
+        let freq = || {
+            ObjectFreq {
+                key: "key".to_string(),
+            }
+            .execute(&backend)
+        };
+        assert!(matches!(freq(), RespFrame::Error(_)));
+
+        backend.config_mut().maxmemory_policy = "allkeys-lfu".to_string();
+        assert_eq!(freq(), RespNullBulkString.into());
+        backend.set("key".to_string(), BulkString::new("value"));
+        assert_eq!(freq(), RespFrame::Integer(5));
+
+        let get = || {
+            Get {
+                key: "key".to_string(),
+            }
+            .execute(&backend)
+        };
+        // the first access always counts, later ones ever less likely
+        get();
+        assert_eq!(freq(), RespFrame::Integer(6));
+        for _ in 0..1000 {
+            get();
+        }
+        let RespFrame::Integer(counter) = freq() else {
+            panic!("unexpected reply {:?}", freq());
+        };
+        assert!((7..100).contains(&counter), "{}", counter);
+    }
+}
@@ -0,0 +1,87 @@
+use crate::{Backend, RespArray, RespFrame};
+
+use super::{extract_args, extract_string, validate_command, CommandError, CommandExecutor};
+
+#[derive(Debug)]
+pub struct Persist {
+    pub key: String,
+}
+
+impl CommandExecutor for Persist {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let removed = backend.write().persist(&self.key);
+        RespFrame::Integer(removed as i64)
+    }
+}
+
+impl TryFrom<RespArray> for Persist {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["persist"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(Persist {
+            key: extract_string(args.next())?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{
+        cmd::{Expire, Expiry, Set, Ttl},
+        BulkString, RespDecode,
+    };
+
+    use super::*;
+
+    fn persist(backend: &Backend, key: &str) -> RespFrame {
+        Persist {
+            key: key.to_string(),
+        }
+        .execute(backend)
+    }
+
+    fn ttl(backend: &Backend, key: &str) -> RespFrame {
+        Ttl {
+            key: key.to_string(),
+            millis: false,
+        }
+        .execute(backend)
+    }
+
+    #[test]
+    fn test_persist_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*2\r\n$7\r\npersist\r\n$3\r\nkey\r\n");
+        let cmd: Persist = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.key, "key");
+        Ok(())
+    }
+
+    #[test]
+    fn test_persist_lifecycle() {
+        let backend = Backend::new();
+        assert_eq!(persist(&backend, "key"), RespFrame::Integer(0));
+
+        let cmd = Set {
+            key: "key".to_string(),
+            value: BulkString::new("value"),
+        };
+        cmd.execute(&backend);
+        assert_eq!(persist(&backend, "key"), RespFrame::Integer(0));
+
+        let cmd = Expire {
+            key: "key".to_string(),
+            time: Expiry::In(100_000),
+        };
+        assert_eq!(cmd.execute(&backend), RespFrame::Integer(1));
+        assert_eq!(ttl(&backend, "key"), RespFrame::Integer(100));
+
+        assert_eq!(persist(&backend, "key"), RespFrame::Integer(1));
+        assert_eq!(ttl(&backend, "key"), RespFrame::Integer(-1));
+        assert_eq!(persist(&backend, "key"), RespFrame::Integer(0));
+    }
+}
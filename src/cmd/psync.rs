@@ -0,0 +1,167 @@
+use crate::{
+    network::ConnectionState, persistence, Backend, RespArray, RespFrame, SimpleError, SimpleString,
+};
+
+use super::{
+    extract_args, extract_string, parse_number, validate_command, CommandError, CommandExecutor,
+    RESP_NO_CONNECTION,
+};
+
+/// PSYNC replicationid offset, turns the connection into a replica. it is sent what it missed
+/// from the backlog if it follows this server's stream and the offset, the first byte missing
+/// counting from 1, is still there, the whole dataset otherwise. the caller holds the exec
+/// guard, so that no write happens between the copy and the start of the stream
+#[derive(Debug)]
+pub struct Psync {
+    pub replid: String,
+    pub offset: i64,
+}
+
+impl CommandExecutor for Psync {
+    fn execute(self, _backend: &Backend) -> RespFrame {
+        RESP_NO_CONNECTION.clone()
+    }
+
+    fn execute_on(self, backend: &Backend, conn: &mut ConnectionState) -> RespFrame {
+        let replication = backend.replication();
+        let mut options = conn.replica_options.clone();
+        // where the replica connected from, unless it said otherwise
+        if options.ip_address.is_none() {
+            let ip = conn.addr.rsplit_once(':').map_or("", |(ip, _)| ip);
+            options.ip_address = Some(ip.trim_matches(['[', ']']).to_string());
+        }
+        if self.replid == replication.replid() && self.offset > 0 {
+            // replicas that don't know psync2 expect the same replication id to go on
+            let reply = if conn
+                .replica_options
+                .capa
+                .iter()
+                .any(|capa| capa == "psync2")
+            {
+                format!("CONTINUE {}", replication.replid())
+            } else {
+                "CONTINUE".to_string()
+            };
+            let offset = self.offset as u64 - 1;
+            if let Some(stream) = replication.continue_replica(conn.id, options.clone(), offset) {
+                conn.replica_stream = Some(stream);
+                return SimpleString::new(reply).into();
+            }
+        }
+
+        let dbs = backend.read_all();
+        let rdb = match persistence::dump(&dbs.iter().map(|db| &**db).collect::<Vec<_>>()) {
+            Ok(rdb) => rdb,
+            Err(e) => return SimpleError::new(format!("ERR {}", e)).into(),
+        };
+        drop(dbs);
+        let backlog_size = backend.config().repl_backlog_size as usize;
+        let (offset, stream) = replication.add_replica(conn.id, options, rdb, backlog_size);
+        conn.replica_stream = Some(stream);
+        SimpleString::new(format!("FULLRESYNC {} {}", replication.replid(), offset)).into()
+    }
+}
+
+impl TryFrom<RespArray> for Psync {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["psync"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(Psync {
+            replid: extract_string(args.next())?,
+            offset: parse_number(args.next())?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::{Bytes, BytesMut};
+
+    use crate::{BulkString, RespDecode};
+
+    use super::*;
+
+    #[test]
+    fn test_psync_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*3\r\n$5\r\npsync\r\n$1\r\n?\r\n$2\r\n-1\r\n");
+        let cmd: Psync = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.replid, "?");
+        assert_eq!(cmd.offset, -1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_psync_sends_the_dataset_then_the_stream() -> Result<()> {
+        let backend = Backend::new();
+        backend.set("key".to_string(), BulkString::new("value"));
+        let mut conn = ConnectionState::new();
+        let cmd = Psync {
+            replid: "?".to_string(),
+            offset: -1,
+        };
+        let expected = format!("FULLRESYNC {} 0", backend.replication().replid());
+        assert_eq!(
+            cmd.execute_on(&backend, &mut conn),
+            SimpleString::new(expected).into()
+        );
+        assert_eq!(backend.replication().replica_count(), 1);
+
+        let stream = conn.replica_stream.as_mut().unwrap();
+        let payload = stream.try_recv()?;
+        let (header, rdb) = payload.split_at(payload.iter().position(|&b| b == b'\n').unwrap() + 1);
+        assert_eq!(header, format!("${}\r\n", rdb.len()).as_bytes());
+        let dbs = persistence::parse(rdb)?;
+        assert_eq!(dbs[0].len(), 1);
+
+        backend.replication().feed(0, RespArray::new(vec![]).into());
+        assert_eq!(
+            stream.try_recv()?,
+            Bytes::from("*2\r\n$6\r\nselect\r\n$1\r\n0\r\n*0\r\n")
+        );
+        assert_eq!(backend.replication().offset(), 27);
+        Ok(())
+    }
+
+    #[test]
+    fn test_psync_continues_from_the_backlog() -> Result<()> {
+        let backend = Backend::new();
+        let psync = |replid: &str, offset: i64, conn: &mut ConnectionState| {
+            Psync {
+                replid: replid.to_string(),
+                offset,
+            }
+            .execute_on(&backend, conn)
+        };
+        let replid = backend.replication().replid().to_string();
+        psync("?", -1, &mut ConnectionState::new());
+        backend.replication().feed(0, RespArray::new(vec![]).into());
+
+        // the replica had nothing of the stream yet
+        let mut conn = ConnectionState::new();
+        conn.replica_options.capa = vec!["eof".to_string(), "psync2".to_string()];
+        assert_eq!(
+            psync(&replid, 1, &mut conn),
+            SimpleString::new(format!("CONTINUE {}", replid)).into()
+        );
+        let stream = conn.replica_stream.as_mut().unwrap();
+        assert_eq!(
+            stream.try_recv()?,
+            Bytes::from("*2\r\n$6\r\nselect\r\n$1\r\n0\r\n*0\r\n")
+        );
+        // without psync2 the replication id isn't repeated
+        assert_eq!(
+            psync(&replid, 28, &mut ConnectionState::new()),
+            SimpleString::new("CONTINUE").into()
+        );
+
+        // beyond the stream or of another history, the whole dataset is sent
+        for (replid, offset) in [(replid.as_str(), 100), ("0123", 1)] {
+            let reply = psync(replid, offset, &mut ConnectionState::new());
+            assert!(matches!(reply, RespFrame::SimpleString(s) if s.starts_with("FULLRESYNC")));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,188 @@
+use crate::{Backend, BulkString, RespArray, RespFrame};
+
+use super::{
+    extract_args, extract_string, validate_command, validate_variadic_command, CommandError,
+    CommandExecutor,
+};
+
+/// PUBSUB CHANNELS [pattern]
+#[derive(Debug)]
+pub struct PubSubChannels {
+    pub pattern: Option<String>,
+}
+
+/// PUBSUB NUMSUB [channel ...]
+#[derive(Debug)]
+pub struct PubSubNumSub {
+    pub channels: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct PubSubNumPat;
+
+impl CommandExecutor for PubSubChannels {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let mut channels = backend.pubsub().channels(self.pattern.as_deref());
+        channels.sort();
+        RespArray::new(
+            channels
+                .into_iter()
+                .map(|channel| BulkString::new(channel).into())
+                .collect::<Vec<_>>(),
+        )
+        .into()
+    }
+}
+
+impl CommandExecutor for PubSubNumSub {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        // a flat list of channel, count pairs
+        let mut ret = Vec::with_capacity(self.channels.len() * 2);
+        for channel in self.channels {
+            let count = backend.pubsub().numsub(&channel);
+            ret.push(BulkString::new(channel).into());
+            ret.push(RespFrame::Integer(count as i64));
+        }
+        RespArray::new(ret).into()
+    }
+}
+
+impl CommandExecutor for PubSubNumPat {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        RespFrame::Integer(backend.pubsub().numpat() as i64)
+    }
+}
+
+impl TryFrom<RespArray> for PubSubChannels {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["pubsub", "channels"], 0)?;
+        if value.len() > 3 {
+            return Err(CommandError::InvalidArgument(
+                "pubsub channels command must have at most 1 argument".to_string(),
+            ));
+        }
+
+        let pattern = extract_args(value, 2)?
+            .into_iter()
+            .next()
+            .map(|arg| extract_string(Some(arg)))
+            .transpose()?;
+        Ok(PubSubChannels { pattern })
+    }
+}
+
+impl TryFrom<RespArray> for PubSubNumSub {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["pubsub", "numsub"], 0)?;
+
+        let channels = extract_args(value, 2)?
+            .into_iter()
+            .map(|arg| extract_string(Some(arg)))
+            .collect::<Result<_, _>>()?;
+        Ok(PubSubNumSub { channels })
+    }
+}
+
+impl TryFrom<RespArray> for PubSubNumPat {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["pubsub", "numpat"], 0)?;
+        Ok(PubSubNumPat)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{
+        network::{ConnectionState, Subscription},
+        RespDecode,
+    };
+
+    use super::*;
+
+    fn channels(backend: &Backend, pattern: Option<&str>) -> RespFrame {
+        PubSubChannels {
+            pattern: pattern.map(|p| p.to_string()),
+        }
+        .execute(backend)
+    }
+
+    fn numsub(backend: &Backend, channels: &[&str]) -> RespFrame {
+        PubSubNumSub {
+            channels: channels.iter().map(|c| c.to_string()).collect(),
+        }
+        .execute(backend)
+    }
+
+    fn array(frames: Vec<RespFrame>) -> RespFrame {
+        RespArray::new(frames).into()
+    }
+
+    #[test]
+    fn test_pubsub_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*3\r\n$6\r\npubsub\r\n$8\r\nchannels\r\n$2\r\nn*\r\n");
+        let cmd: PubSubChannels = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.pattern.as_deref(), Some("n*"));
+
+        let mut buf = BytesMut::from("*2\r\n$6\r\npubsub\r\n$6\r\nnumsub\r\n");
+        let cmd: PubSubNumSub = RespArray::decode(&mut buf)?.try_into()?;
+        assert!(cmd.channels.is_empty());
+
+        let mut buf = BytesMut::from("*3\r\n$6\r\npubsub\r\n$6\r\nnumpat\r\n$1\r\nx\r\n");
+        let ret: Result<PubSubNumPat, _> = RespArray::decode(&mut buf)?.try_into();
+        assert!(ret.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_pubsub_counts() {
+        let backend = Backend::new();
+        let pubsub = backend.pubsub();
+        let (mut a, mut b) = (ConnectionState::new(), ConnectionState::new());
+        let channel = |name: &str| Subscription::Channel(name.to_string());
+
+        a.subscribe(pubsub, channel("news"));
+        a.subscribe(pubsub, channel("sports"));
+        b.subscribe(pubsub, channel("news"));
+        b.subscribe(pubsub, Subscription::Pattern("n*".to_string()));
+
+        assert_eq!(
+            channels(&backend, None),
+            array(vec![
+                BulkString::new("news").into(),
+                BulkString::new("sports").into()
+            ])
+        );
+        assert_eq!(
+            channels(&backend, Some("s*")),
+            array(vec![BulkString::new("sports").into()])
+        );
+        assert_eq!(
+            numsub(&backend, &["news", "sports", "weather"]),
+            array(vec![
+                BulkString::new("news").into(),
+                RespFrame::Integer(2),
+                BulkString::new("sports").into(),
+                RespFrame::Integer(1),
+                BulkString::new("weather").into(),
+                RespFrame::Integer(0),
+            ])
+        );
+        assert_eq!(PubSubNumPat.execute(&backend), RespFrame::Integer(1));
+
+        a.unsubscribe(pubsub, &channel("news"));
+        a.unsubscribe(pubsub, &channel("sports"));
+        b.unsubscribe_all(pubsub);
+        assert_eq!(channels(&backend, None), array(vec![]));
+        assert_eq!(
+            numsub(&backend, &["news"]),
+            array(vec![BulkString::new("news").into(), RespFrame::Integer(0)])
+        );
+        assert_eq!(PubSubNumPat.execute(&backend), RespFrame::Integer(0));
+    }
+}
@@ -0,0 +1,51 @@
+use crate::{
+    network::{ConnectionFlags, ConnectionState},
+    Backend, RespArray, RespFrame,
+};
+
+use super::{validate_command, CommandError, CommandExecutor, RESP_NO_CONNECTION, RESP_OK};
+
+/// QUIT, the connection is closed once the reply is sent
+#[derive(Debug)]
+pub struct Quit;
+
+impl CommandExecutor for Quit {
+    fn execute(self, _backend: &Backend) -> RespFrame {
+        RESP_NO_CONNECTION.clone()
+    }
+
+    fn execute_on(self, _backend: &Backend, conn: &mut ConnectionState) -> RespFrame {
+        conn.flags.insert(ConnectionFlags::CLOSE_AFTER_REPLY);
+        RESP_OK.clone()
+    }
+}
+
+impl TryFrom<RespArray> for Quit {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["quit"], 0)?;
+        Ok(Quit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_quit_closes_after_reply() -> Result<()> {
+        let mut buf = BytesMut::from("*1\r\n$4\r\nQUIT\r\n");
+        let cmd: Quit = RespArray::decode(&mut buf)?.try_into()?;
+
+        let backend = Backend::new();
+        let mut conn = ConnectionState::new();
+        assert_eq!(cmd.execute_on(&backend, &mut conn), RESP_OK.clone());
+        assert!(conn.flags.contains(ConnectionFlags::CLOSE_AFTER_REPLY));
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+use rand::Rng;
+
+use crate::{Backend, BulkString, RespArray, RespFrame, RespNullBulkString};
+
+use super::{validate_command, CommandError, CommandExecutor};
+
+#[derive(Debug)]
+pub struct RandomKey;
+
+impl CommandExecutor for RandomKey {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        // the hash map has no random access, so picking a key means walking it up to a random
+        // index. that is O(n) under the read lock: writers wait, but other readers don't, and
+        // only the chosen key is cloned, never the whole key set
+        let db = backend.read();
+        let len = db.len();
+        if len == 0 {
+            return RespNullBulkString.into();
+        }
+        let index = rand::thread_rng().gen_range(0..len);
+        let key = db.keys().nth(index);
+        match key {
+            Some(key) => BulkString::new(key.as_bytes()).into(),
+            None => RespNullBulkString.into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for RandomKey {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["randomkey"], 0)?;
+        Ok(RandomKey)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, time::Duration};
+
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{clock, RespDecode};
+
+    use super::*;
+
+    #[test]
+    fn test_randomkey_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*1\r\n$9\r\nrandomkey\r\n");
+        let _: RandomKey = RespArray::decode(&mut buf)?.try_into()?;
+
+        let mut buf = BytesMut::from("*2\r\n$9\r\nrandomkey\r\n$3\r\nkey\r\n");
+        assert!(RandomKey::try_from(RespArray::decode(&mut buf)?).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_randomkey_command() {
+        let backend = Backend::new();
+        assert_eq!(RandomKey.execute(&backend), RespNullBulkString.into());
+
+        let keys = ["a", "b", "c"];
+        for key in keys {
+            backend.set(key.to_string(), BulkString::new("value"));
+        }
+        let seen: HashSet<Vec<u8>> = (0..200)
+            .map(|_| match RandomKey.execute(&backend) {
+                RespFrame::BulkString(key) => key.to_vec(),
+                v => panic!("expected a bulk string, got {:?}", v),
+            })
+            .collect();
+        let expected: HashSet<Vec<u8>> = keys.iter().map(|key| key.as_bytes().to_vec()).collect();
+        // 200 draws miss one of 3 keys with a probability of about 1e-35
+        assert_eq!(seen, expected);
+    }
+
+    #[test]
+    fn test_randomkey_skips_expired_keys() {
+        // no runtime, so there is no reaper to remove the key
+        let backend = Backend::new();
+        backend.set("expired".to_string(), BulkString::new("value"));
+        backend.set("live".to_string(), BulkString::new("value"));
+        backend
+            .write()
+            .expire("expired", clock::now() - Duration::from_secs(1));
+        for _ in 0..50 {
+            assert_eq!(RandomKey.execute(&backend), BulkString::new("live").into());
+        }
+    }
+}
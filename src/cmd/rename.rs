@@ -0,0 +1,120 @@
+use crate::{Backend, RespArray, RespFrame, SimpleError};
+
+use super::{
+    extract_args, extract_string, validate_command, CommandError, CommandExecutor, RESP_OK,
+};
+
+#[derive(Debug)]
+pub struct Rename {
+    pub src: String,
+    pub dst: String,
+    // RENAMENX: only rename if `dst` does not exist
+    pub nx: bool,
+}
+
+impl CommandExecutor for Rename {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        // a single write lock covers both keys, so the move is atomic
+        let mut db = backend.write();
+        if !db.contains_key(&self.src) {
+            return SimpleError::new("ERR no such key").into();
+        }
+        if self.nx && db.contains_key(&self.dst) {
+            return RespFrame::Integer(0);
+        }
+        // the expiry moves along with the value
+        if let Some(entry) = db.remove_entry(&self.src) {
+            db.insert_entry(self.dst, entry);
+        }
+        if self.nx {
+            RespFrame::Integer(1)
+        } else {
+            RESP_OK.clone()
+        }
+    }
+}
+
+impl TryFrom<RespArray> for Rename {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let nx = validate_command(&value, &["rename"], 2)
+            .map(|_| false)
+            .or_else(|_| validate_command(&value, &["renamenx"], 2).map(|_| true))?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(Rename {
+            src: extract_string(args.next())?,
+            dst: extract_string(args.next())?,
+            nx,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{BulkString, RespDecode};
+
+    use super::*;
+
+    fn rename(backend: &Backend, src: &str, dst: &str, nx: bool) -> RespFrame {
+        Rename {
+            src: src.to_string(),
+            dst: dst.to_string(),
+            nx,
+        }
+        .execute(backend)
+    }
+
+    #[test]
+    fn test_rename_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*3\r\n$6\r\nrename\r\n$1\r\na\r\n$1\r\nb\r\n");
+        let cmd: Rename = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(
+            (cmd.src.as_str(), cmd.dst.as_str(), cmd.nx),
+            ("a", "b", false)
+        );
+
+        let mut buf = BytesMut::from("*3\r\n$8\r\nRENAMENX\r\n$1\r\na\r\n$1\r\nb\r\n");
+        let cmd: Rename = RespArray::decode(&mut buf)?.try_into()?;
+        assert!(cmd.nx);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rename_overwrites_dst() {
+        let backend = Backend::new();
+        backend.set("src".to_string(), BulkString::new("1"));
+        backend.set("dst".to_string(), BulkString::new("2"));
+
+        assert_eq!(rename(&backend, "src", "dst", false), RESP_OK.clone());
+        assert_eq!(backend.get("src"), None);
+        assert_eq!(backend.get("dst"), Some(BulkString::new("1").into()));
+    }
+
+    #[test]
+    fn test_rename_missing_src() {
+        let backend = Backend::new();
+        let err: RespFrame = SimpleError::new("ERR no such key").into();
+        assert_eq!(rename(&backend, "src", "dst", false), err);
+        assert_eq!(rename(&backend, "src", "dst", true), err);
+        assert_eq!(backend.get("dst"), None);
+    }
+
+    #[test]
+    fn test_renamenx() {
+        let backend = Backend::new();
+        backend.set("src".to_string(), BulkString::new("1"));
+        backend.set("dst".to_string(), BulkString::new("2"));
+
+        assert_eq!(rename(&backend, "src", "dst", true), RespFrame::Integer(0));
+        assert_eq!(backend.get("src"), Some(BulkString::new("1").into()));
+        assert_eq!(backend.get("dst"), Some(BulkString::new("2").into()));
+
+        assert_eq!(rename(&backend, "src", "new", true), RespFrame::Integer(1));
+        assert_eq!(backend.get("src"), None);
+        assert_eq!(backend.get("new"), Some(BulkString::new("1").into()));
+    }
+}
@@ -0,0 +1,160 @@
+use crate::{network::ConnectionState, Backend, RespArray, RespFrame, SimpleError};
+
+use super::{
+    extract_args, extract_string, parse_number, validate_variadic_command, CommandError,
+    CommandExecutor, RESP_NO_CONNECTION, RESP_OK,
+};
+
+/// REPLCONF option value [option value ...], how a replica configures its link to the master
+#[derive(Debug, PartialEq)]
+pub enum ReplConf {
+    // listening-port, ip-address and capa, kept for PSYNC
+    Options(Vec<(String, String)>),
+    // ACK offset, the replica received the stream up to `offset`
+    Ack(u64),
+    // GETACK *, only a master asks for an ACK
+    GetAck,
+}
+
+impl CommandExecutor for ReplConf {
+    fn execute(self, _backend: &Backend) -> RespFrame {
+        RESP_NO_CONNECTION.clone()
+    }
+
+    fn execute_on(self, backend: &Backend, conn: &mut ConnectionState) -> RespFrame {
+        self.reply_to(backend, conn)
+            .unwrap_or_else(|| RESP_OK.clone())
+    }
+}
+
+impl ReplConf {
+    /// None for acknowledgements, which are not answered
+    pub fn reply_to(self, backend: &Backend, conn: &mut ConnectionState) -> Option<RespFrame> {
+        let options = match self {
+            ReplConf::Options(options) => options,
+            ReplConf::Ack(offset) => {
+                backend.replication().ack(conn.id, offset);
+                return None;
+            }
+            ReplConf::GetAck => return None,
+        };
+        let replica = &mut conn.replica_options;
+        for (name, value) in options {
+            match name.as_str() {
+                "listening-port" => match value.parse() {
+                    Ok(port) => replica.listening_port = Some(port),
+                    Err(_) => {
+                        return Some(SimpleError::new("ERR value is out of range").into());
+                    }
+                },
+                "ip-address" => replica.ip_address = Some(value),
+                "capa" => {
+                    if !replica.capa.contains(&value) {
+                        replica.capa.push(value);
+                    }
+                }
+                _ => {
+                    return Some(
+                        SimpleError::new(format!("ERR Unrecognized REPLCONF option: {}", name))
+                            .into(),
+                    );
+                }
+            }
+        }
+        Some(RESP_OK.clone())
+    }
+}
+
+impl TryFrom<RespArray> for ReplConf {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["replconf"], 0)?;
+        if value.len().is_multiple_of(2) {
+            return Err(CommandError::InvalidArgument(
+                "replconf takes option value pairs".to_string(),
+            ));
+        }
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let mut options = Vec::new();
+        while let Some(name) = args.next() {
+            let name = extract_string(Some(name))?.to_ascii_lowercase();
+            match name.as_str() {
+                "ack" => return Ok(ReplConf::Ack(parse_number(args.next())?)),
+                "getack" => return Ok(ReplConf::GetAck),
+                _ => options.push((name, extract_string(args.next())?.to_ascii_lowercase())),
+            }
+        }
+        Ok(ReplConf::Options(options))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_replconf_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from(
+            "*5\r\n$8\r\nreplconf\r\n$4\r\ncapa\r\n$3\r\neof\r\n$4\r\ncapa\r\n$6\r\npsync2\r\n",
+        );
+        let cmd: ReplConf = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(
+            cmd,
+            ReplConf::Options(vec![
+                ("capa".to_string(), "eof".to_string()),
+                ("capa".to_string(), "psync2".to_string()),
+            ])
+        );
+
+        let mut buf = BytesMut::from("*3\r\n$8\r\nreplconf\r\n$3\r\nACK\r\n$3\r\n120\r\n");
+        let cmd: ReplConf = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd, ReplConf::Ack(120));
+
+        let mut buf = BytesMut::from("*2\r\n$8\r\nreplconf\r\n$4\r\ncapa\r\n");
+        let ret: Result<ReplConf, _> = RespArray::decode(&mut buf)?.try_into();
+        assert!(ret.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_replconf_options_and_acks() {
+        let backend = Backend::new();
+        let mut conn = ConnectionState::new();
+        let options = |pairs: &[(&str, &str)]| {
+            ReplConf::Options(
+                pairs
+                    .iter()
+                    .map(|(n, v)| (n.to_string(), v.to_string()))
+                    .collect(),
+            )
+        };
+
+        let cmd = options(&[("listening-port", "6380"), ("capa", "psync2")]);
+        assert_eq!(cmd.reply_to(&backend, &mut conn), Some(RESP_OK.clone()));
+        assert_eq!(conn.replica_options.listening_port, Some(6380));
+        assert_eq!(conn.replica_options.capa, vec!["psync2".to_string()]);
+        let cmd = options(&[("listening-port", "70000")]);
+        assert!(matches!(
+            cmd.reply_to(&backend, &mut conn),
+            Some(RespFrame::Error(_))
+        ));
+        let cmd = options(&[("unknown", "1")]);
+        assert!(matches!(
+            cmd.reply_to(&backend, &mut conn),
+            Some(RespFrame::Error(_))
+        ));
+
+        // once a replica, its acknowledgements count for WAIT
+        let replication = backend.replication();
+        let _stream = replication.add_replica(conn.id, conn.replica_options.clone(), vec![], 64);
+        let offset = replication.advance(10);
+        assert_eq!(ReplConf::Ack(offset).reply_to(&backend, &mut conn), None);
+        assert_eq!(replication.acked(offset), 1);
+    }
+}
@@ -0,0 +1,113 @@
+use tokio::runtime::Handle;
+
+use crate::{Backend, RespArray, RespFrame, SimpleError, SimpleString};
+
+use super::{
+    extract_args, extract_string, parse_number, validate_command, CommandError, CommandExecutor,
+    RESP_OK,
+};
+
+/// REPLICAOF host port | NO ONE, SLAVEOF is the same. following a master replaces the
+/// dataset with the master's once it arrived, NO ONE keeps what was replicated so far
+#[derive(Debug)]
+pub struct ReplicaOf {
+    pub master: Option<(String, u16)>,
+}
+
+impl CommandExecutor for ReplicaOf {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let replication = backend.replication();
+        let Some((host, port)) = self.master else {
+            replication.promote();
+            return RESP_OK.clone();
+        };
+        if replication
+            .master()
+            .is_some_and(|master| (&master.host, master.port) == (&host, port))
+        {
+            return SimpleString::new("OK Already connected to specified master").into();
+        }
+        let Ok(handle) = Handle::try_current() else {
+            return SimpleError::new("ERR Replication needs a runtime").into();
+        };
+        replication.replicate(backend, host, port, &handle);
+        RESP_OK.clone()
+    }
+}
+
+impl TryFrom<RespArray> for ReplicaOf {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["replicaof"], 2)
+            .or_else(|_| validate_command(&value, &["slaveof"], 2))?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let host = extract_string(args.next())?;
+        let port = args.next();
+        if host.eq_ignore_ascii_case("no") {
+            if extract_string(port)?.eq_ignore_ascii_case("one") {
+                return Ok(ReplicaOf { master: None });
+            }
+            return Err(CommandError::InvalidArgument(
+                "replicaof takes a host and port or NO ONE".to_string(),
+            ));
+        }
+        Ok(ReplicaOf {
+            master: Some((host, parse_number(port)?)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_replicaof_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*3\r\n$7\r\nslaveof\r\n$9\r\n127.0.0.1\r\n$4\r\n6380\r\n");
+        let cmd: ReplicaOf = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.master, Some(("127.0.0.1".to_string(), 6380)));
+
+        let mut buf = BytesMut::from("*3\r\n$9\r\nreplicaof\r\n$2\r\nNO\r\n$3\r\nONE\r\n");
+        let cmd: ReplicaOf = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.master, None);
+
+        let mut buf = BytesMut::from("*3\r\n$9\r\nreplicaof\r\n$2\r\nno\r\n$3\r\ntwo\r\n");
+        let ret: Result<ReplicaOf, _> = RespArray::decode(&mut buf)?.try_into();
+        assert!(ret.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_replicaof_and_promote() {
+        let backend = Backend::new();
+        let cmd = |master: Option<(&str, u16)>| ReplicaOf {
+            master: master.map(|(host, port)| (host.to_string(), port)),
+        };
+        assert_eq!(cmd(None).execute(&backend), RESP_OK.clone());
+        assert!(!backend.replication().is_replica());
+
+        // nothing listens there, the replica keeps trying
+        assert_eq!(
+            cmd(Some(("127.0.0.1", 1))).execute(&backend),
+            RESP_OK.clone()
+        );
+        let master = backend.replication().master().unwrap();
+        assert_eq!(
+            (master.host.as_str(), master.port, master.link_up),
+            ("127.0.0.1", 1, false)
+        );
+        assert_eq!(
+            cmd(Some(("127.0.0.1", 1))).execute(&backend),
+            SimpleString::new("OK Already connected to specified master").into()
+        );
+
+        assert_eq!(cmd(None).execute(&backend), RESP_OK.clone());
+        assert_eq!(backend.replication().master(), None);
+    }
+}
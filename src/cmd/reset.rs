@@ -0,0 +1,109 @@
+use lazy_static::lazy_static;
+
+use crate::{network::ConnectionState, Backend, RespArray, RespFrame, SimpleString};
+
+use super::{
+    multi::end_transaction, validate_command, watch::unwatch_all, CommandError, CommandExecutor,
+    RESP_NO_CONNECTION,
+};
+
+lazy_static! {
+    static ref RESP_RESET: RespFrame = SimpleString::new("RESET").into();
+}
+
+/// RESET, puts the connection back the way it was right after connecting
+#[derive(Debug)]
+pub struct Reset;
+
+impl CommandExecutor for Reset {
+    fn execute(self, _backend: &Backend) -> RespFrame {
+        RESP_NO_CONNECTION.clone()
+    }
+
+    fn execute_on(self, backend: &Backend, conn: &mut ConnectionState) -> RespFrame {
+        end_transaction(conn);
+        unwatch_all(backend, conn);
+        conn.unsubscribe_all(backend.pubsub());
+        conn.monitor = None;
+        conn.client_name = None;
+        conn.selected_db = 0;
+        conn.resp_version = 2;
+        // back to the default user, which needs the password again if one is set
+        conn.authenticated = backend.config().requirepass.is_none();
+        RESP_RESET.clone()
+    }
+}
+
+impl TryFrom<RespArray> for Reset {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["reset"], 0)?;
+        Ok(Reset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{
+        cmd::{MonitorCmd, Multi, Set, Watch},
+        network::{ConnectionFlags, Subscription},
+        BulkString, RespDecode,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_reset_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*1\r\n$5\r\nRESET\r\n");
+        let _: Reset = RespArray::decode(&mut buf)?.try_into()?;
+
+        let mut buf = BytesMut::from("*2\r\n$5\r\nreset\r\n$3\r\nall\r\n");
+        let ret: Result<Reset, _> = RespArray::decode(&mut buf)?.try_into();
+        assert!(ret.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_reset_clears_connection_state() {
+        let backend = Backend::new();
+        backend.config_mut().requirepass = Some("secret".to_string());
+        let mut conn = ConnectionState::new();
+        conn.authenticated = true;
+        conn.client_name = Some("pooled".to_string());
+        conn.selected_db = 3;
+        conn.resp_version = 3;
+        Watch {
+            keys: vec!["key".to_string()],
+        }
+        .execute_on(&backend, &mut conn);
+        Multi.execute_on(&backend, &mut conn);
+        conn.flags.insert(ConnectionFlags::DIRTY_EXEC);
+        conn.queued.push(
+            Set {
+                key: "key".to_string(),
+                value: BulkString::new("value"),
+            }
+            .into(),
+        );
+        conn.subscribe(backend.pubsub(), Subscription::Channel("news".to_string()));
+        conn.subscribe(backend.pubsub(), Subscription::Pattern("n*".to_string()));
+        MonitorCmd.execute_on(&backend, &mut conn);
+
+        assert_eq!(Reset.execute_on(&backend, &mut conn), RESP_RESET.clone());
+        assert_eq!(conn.flags, ConnectionFlags::default());
+        assert!(conn.queued.is_empty());
+        assert!(conn.watched.is_empty());
+        assert!(conn.subscriptions.is_empty());
+        assert_eq!(backend.pubsub().numsub("news"), 0);
+        assert_eq!(backend.pubsub().numpat(), 0);
+        assert!(conn.monitor.is_none());
+        assert!(!backend.monitor().is_active());
+        assert_eq!(conn.client_name, None);
+        assert_eq!(conn.selected_db, 0);
+        assert_eq!(conn.resp_version, 2);
+        assert!(!conn.authenticated);
+    }
+}
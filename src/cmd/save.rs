@@ -0,0 +1,204 @@
+use lazy_static::lazy_static;
+use tokio::runtime::Handle;
+use tracing::{info, warn};
+
+use crate::{
+    persistence::{self, aof},
+    Backend, RespArray, RespFrame, SimpleError, SimpleString,
+};
+
+use super::{validate_command, CommandError, CommandExecutor, RESP_OK};
+
+lazy_static! {
+    pub(super) static ref RESP_SAVE_IN_PROGRESS: RespFrame =
+        SimpleError::new("ERR Background save already in progress").into();
+    static ref RESP_REWRITE_STARTED: RespFrame =
+        SimpleString::new("Background append only file rewriting started").into();
+}
+
+/// SAVE, writes the dataset to the RDB file before replying. writes wait until it is done
+#[derive(Debug)]
+pub struct Save;
+
+/// BGSAVE, writes a copy of the dataset to the RDB file while clients are served
+#[derive(Debug)]
+pub struct BgSave;
+
+/// BGREWRITEAOF, replaces the AOF with the commands recreating the dataset. the caller holds
+/// the exec guard, so that the copy taken matches what was logged so far
+#[derive(Debug)]
+pub struct BgRewriteAof;
+
+impl CommandExecutor for Save {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let Some(guard) = backend.begin_save() else {
+            return RESP_SAVE_IN_PROGRESS.clone();
+        };
+        let path = backend.config().rdb_path();
+        match persistence::save(&path, backend) {
+            Ok(()) => {
+                guard.saved();
+                info!("DB saved on disk");
+                RESP_OK.clone()
+            }
+            Err(e) => SimpleError::new(format!("ERR {}", e)).into(),
+        }
+    }
+}
+
+impl CommandExecutor for BgSave {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let Some(guard) = backend.begin_save() else {
+            return RESP_SAVE_IN_PROGRESS.clone();
+        };
+        // the copy is taken now, changes made while it is written are not part of it
+        let dbs = backend.snapshot();
+        let path = backend.config().rdb_path();
+        let save = move || {
+            match persistence::save_snapshot(&path, &dbs) {
+                Ok(()) => {
+                    guard.saved();
+                    info!("Background saving terminated with success");
+                }
+                Err(e) => warn!("Background saving error: {}", e),
+            }
+            drop(guard);
+        };
+        match Handle::try_current() {
+            Ok(handle) => drop(handle.spawn_blocking(save)),
+            Err(_) => save(),
+        }
+        SimpleString::new("Background saving started").into()
+    }
+}
+
+impl CommandExecutor for BgRewriteAof {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let dbs = backend.snapshot();
+        // while the AOF is on its writer rewrites it, in between two appends
+        let Err(dbs) = backend.persistence().aof().rewrite(dbs) else {
+            return RESP_REWRITE_STARTED.clone();
+        };
+        let Ok(handle) = Handle::try_current() else {
+            return SimpleError::new("ERR Background AOF rewrite needs a runtime").into();
+        };
+        let path = backend.config().aof_path();
+        drop(
+            handle.spawn_blocking(move || match aof::rewrite(&path, &dbs) {
+                Ok(()) => info!("Background AOF rewrite finished successfully"),
+                Err(e) => warn!("Background AOF rewrite error: {}", e),
+            }),
+        );
+        RESP_REWRITE_STARTED.clone()
+    }
+}
+
+impl TryFrom<RespArray> for Save {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["save"], 0)?;
+        Ok(Save)
+    }
+}
+
+impl TryFrom<RespArray> for BgRewriteAof {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["bgrewriteaof"], 0)?;
+        Ok(BgRewriteAof)
+    }
+}
+
+impl TryFrom<RespArray> for BgSave {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["bgsave"], 0)?;
+        Ok(BgSave)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf, time::Duration};
+
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{cmd::Set, BulkString, RespDecode};
+
+    use super::*;
+
+    // a backend saving to a directory of its own
+    fn backend_in_temp_dir(name: &str) -> Result<(Backend, PathBuf)> {
+        let dir =
+            std::env::temp_dir().join(format!("simple-redis-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir)?;
+        let backend = Backend::new();
+        backend.config_mut().dir = dir.to_string_lossy().into_owned();
+        Ok((backend, dir))
+    }
+
+    fn set(backend: &Backend, key: &str, value: &str) {
+        Set {
+            key: key.to_string(),
+            value: BulkString::new(value),
+        }
+        .execute(backend);
+    }
+
+    #[test]
+    fn test_save_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*1\r\n$4\r\nSAVE\r\n");
+        let _: Save = RespArray::decode(&mut buf)?.try_into()?;
+
+        let mut buf = BytesMut::from("*2\r\n$6\r\nbgsave\r\n$8\r\nSCHEDULE\r\n");
+        let ret: Result<BgSave, _> = RespArray::decode(&mut buf)?.try_into();
+        assert!(ret.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_and_load() -> Result<()> {
+        let (backend, dir) = backend_in_temp_dir("save")?;
+        set(&backend, "key", "value");
+        set(&backend.select(5).unwrap(), "other", "42");
+
+        assert_eq!(Save.execute(&backend), RESP_OK.clone());
+        let loaded = persistence::load(&dir.join("dump.rdb"))?;
+        assert_eq!(loaded.get("key"), Some(BulkString::new("value").into()));
+        assert_eq!(
+            loaded.select(5).unwrap().get("other"),
+            Some(BulkString::new("42").into())
+        );
+
+        // a save in progress turns away others
+        let guard = backend.begin_save();
+        assert_eq!(Save.execute(&backend), RESP_SAVE_IN_PROGRESS.clone());
+        assert_eq!(BgSave.execute(&backend), RESP_SAVE_IN_PROGRESS.clone());
+        drop(guard);
+
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_bgsave() -> Result<()> {
+        let (backend, dir) = backend_in_temp_dir("bgsave")?;
+        set(&backend, "key", "before");
+        assert_eq!(
+            BgSave.execute(&backend),
+            SimpleString::new("Background saving started").into()
+        );
+        // written after the snapshot was taken
+        set(&backend, "key", "after");
+
+        while backend.persistence().is_saving() {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        let loaded = persistence::load(&dir.join("dump.rdb"))?;
+        assert_eq!(loaded.get("key"), Some(BulkString::new("before").into()));
+
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+}
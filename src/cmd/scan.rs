@@ -0,0 +1,171 @@
+use crate::{Backend, BulkString, RespArray, RespFrame};
+
+use super::{
+    extract_args, extract_bytes, extract_string, keys::glob_match, parse_number,
+    validate_variadic_command, CommandError, CommandExecutor,
+};
+
+const DEFAULT_SCAN_COUNT: usize = 10;
+
+#[derive(Debug)]
+pub struct Scan {
+    pub cursor: u64,
+    pub pattern: Option<Vec<u8>>,
+    pub count: usize,
+    pub type_name: Option<String>,
+}
+
+impl CommandExecutor for Scan {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        // the cursor is an index into the sorted key set: a full iteration over an unchanged
+        // keyspace visits every key exactly once
+        let db = backend.read();
+        let mut keys: Vec<&String> = db.keys().collect();
+        keys.sort_unstable();
+
+        let start = (self.cursor as usize).min(keys.len());
+        let end = start.saturating_add(self.count).min(keys.len());
+        let next = if end == keys.len() { 0 } else { end as u64 };
+
+        // MATCH and TYPE filter the visited window, so a page may come back empty
+        let found: Vec<RespFrame> = keys[start..end]
+            .iter()
+            .filter(|key| match &self.pattern {
+                Some(pattern) => glob_match(pattern, key.as_bytes()),
+                None => true,
+            })
+            .filter(|key| match &self.type_name {
+                Some(type_name) => db
+                    .get(key)
+                    .is_some_and(|value| value.type_name() == type_name),
+                None => true,
+            })
+            .map(|key| BulkString::new(key.as_bytes()).into())
+            .collect();
+        RespArray::new(vec![
+            BulkString::new(next.to_string()).into(),
+            RespArray::new(found).into(),
+        ])
+        .into()
+    }
+}
+
+impl TryFrom<RespArray> for Scan {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["scan"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let mut cmd = Scan {
+            cursor: parse_number(args.next())?,
+            pattern: None,
+            count: DEFAULT_SCAN_COUNT,
+            type_name: None,
+        };
+        while let Some(arg) = args.next() {
+            match extract_string(Some(arg))?.to_ascii_lowercase().as_str() {
+                "match" => cmd.pattern = Some(extract_bytes(args.next())?),
+                "count" => {
+                    cmd.count = parse_number(args.next())?;
+                    if cmd.count == 0 {
+                        return Err(CommandError::InvalidArgument(
+                            "COUNT must be positive".to_string(),
+                        ));
+                    }
+                }
+                "type" => cmd.type_name = Some(extract_string(args.next())?.to_ascii_lowercase()),
+                v => {
+                    return Err(CommandError::InvalidArgument(format!(
+                        "Invalid option: {}",
+                        v
+                    )))
+                }
+            }
+        }
+        Ok(cmd)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{RespDecode, ZSet};
+
+    use super::*;
+
+    // run SCAN until the cursor comes back to 0, returning every key seen
+    fn scan_all(backend: &Backend, pattern: Option<&str>, type_name: Option<&str>) -> Vec<String> {
+        let mut cursor = 0;
+        let mut seen = vec![];
+        loop {
+            let cmd = Scan {
+                cursor,
+                pattern: pattern.map(|p| p.as_bytes().to_vec()),
+                count: 3,
+                type_name: type_name.map(|t| t.to_string()),
+            };
+            let RespFrame::Array(ret) = cmd.execute(backend) else {
+                panic!("expected an array");
+            };
+            let (RespFrame::BulkString(next), RespFrame::Array(keys)) = (&ret[0], &ret[1]) else {
+                panic!("expected [cursor, keys]");
+            };
+            for key in keys.iter() {
+                let RespFrame::BulkString(key) = key else {
+                    panic!("expected a bulk string");
+                };
+                seen.push(String::from_utf8_lossy(key).to_string());
+            }
+            cursor = String::from_utf8_lossy(next).parse().unwrap();
+            if cursor == 0 {
+                return seen;
+            }
+        }
+    }
+
+    #[test]
+    fn test_scan_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from(
+            "*8\r\n$4\r\nscan\r\n$2\r\n10\r\n$5\r\nMATCH\r\n$3\r\nk:*\r\n$5\r\nCOUNT\r\n$2\r\n20\r\n$4\r\nTYPE\r\n$6\r\nstring\r\n",
+        );
+        let cmd: Scan = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.cursor, 10);
+        assert_eq!(cmd.pattern, Some(b"k:*".to_vec()));
+        assert_eq!(cmd.count, 20);
+        assert_eq!(cmd.type_name, Some("string".to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_scan_visits_every_key_once() {
+        let backend = Backend::new();
+        for i in 0..20 {
+            backend.set(format!("key:{}", i), BulkString::new("value"));
+        }
+
+        let seen = scan_all(&backend, None, None);
+        assert_eq!(seen.len(), 20);
+        let unique: HashSet<_> = seen.iter().collect();
+        assert_eq!(unique.len(), 20);
+    }
+
+    #[test]
+    fn test_scan_match_and_type() {
+        let backend = Backend::new();
+        for i in 0..5 {
+            backend.set(format!("str:{}", i), BulkString::new("value"));
+            backend.set(format!("zset:{}", i), ZSet::new());
+        }
+
+        let mut seen = scan_all(&backend, Some("str:*"), None);
+        seen.sort();
+        assert_eq!(seen, vec!["str:0", "str:1", "str:2", "str:3", "str:4"]);
+
+        assert_eq!(scan_all(&backend, None, Some("zset")).len(), 5);
+        assert_eq!(scan_all(&backend, Some("str:*"), Some("zset")).len(), 0);
+    }
+}
@@ -0,0 +1,99 @@
+use crate::{network::ConnectionState, Backend, RespArray, RespFrame, SimpleError, DB_COUNT};
+
+use super::{extract_args, parse_number, validate_command, CommandError, CommandExecutor, RESP_OK};
+
+#[derive(Debug)]
+pub struct Select {
+    pub index: i64,
+}
+
+impl Select {
+    /// the database to switch to, None if the index is out of range
+    pub fn db_index(&self) -> Option<usize> {
+        usize::try_from(self.index)
+            .ok()
+            .filter(|index| *index < DB_COUNT)
+    }
+}
+
+// the switch itself is per connection state, without a connection this only validates the index
+impl CommandExecutor for Select {
+    fn execute(self, _backend: &Backend) -> RespFrame {
+        match self.db_index() {
+            Some(_) => RESP_OK.clone(),
+            None => SimpleError::new("ERR DB index is out of range").into(),
+        }
+    }
+
+    fn execute_on(self, backend: &Backend, conn: &mut ConnectionState) -> RespFrame {
+        if let Some(index) = self.db_index() {
+            conn.selected_db = index;
+        }
+        self.execute(backend)
+    }
+}
+
+impl TryFrom<RespArray> for Select {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["select"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(Select {
+            index: parse_number(args.next())?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{
+        cmd::{Get, Set},
+        BulkString, RespDecode, RespNull,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_select_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*2\r\n$6\r\nselect\r\n$1\r\n3\r\n");
+        let cmd: Select = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.index, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_select_out_of_range() {
+        let backend = Backend::new();
+        let err: RespFrame = SimpleError::new("ERR DB index is out of range").into();
+        assert_eq!(Select { index: 16 }.execute(&backend), err);
+        assert_eq!(Select { index: -1 }.execute(&backend), err);
+        assert_eq!(Select { index: 15 }.execute(&backend), RESP_OK.clone());
+        assert!(backend.select(16).is_none());
+    }
+
+    #[test]
+    fn test_databases_are_independent() {
+        let db0 = Backend::new();
+        let db1 = db0.select(1).unwrap();
+
+        let cmd = Set {
+            key: "key".to_string(),
+            value: BulkString::new("value"),
+        };
+        cmd.execute(&db0);
+
+        let get = || Get {
+            key: "key".to_string(),
+        };
+        assert_eq!(get().execute(&db1), RespFrame::Null(RespNull));
+        assert_eq!(get().execute(&db0), BulkString::new("value").into());
+        assert_eq!(
+            get().execute(&db1.select(0).unwrap()),
+            BulkString::new("value").into()
+        );
+    }
+}
@@ -0,0 +1,149 @@
+use crate::{Backend, BackendValue, BulkString, RespArray, RespFrame, SimpleError};
+
+use super::{
+    extract_args, extract_bytes, extract_string, parse_number, validate_command, CommandError,
+    CommandExecutor, RESP_WRONGTYPE,
+};
+
+pub(super) const MAX_STRING_SIZE: usize = 512 * 1024 * 1024;
+
+#[derive(Debug)]
+pub struct SetRange {
+    pub key: String,
+    pub offset: i64,
+    pub value: Vec<u8>,
+}
+
+impl CommandExecutor for SetRange {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        if self.offset < 0 {
+            return SimpleError::new("ERR offset is out of range").into();
+        }
+        let offset = self.offset as usize;
+        if offset + self.value.len() > MAX_STRING_SIZE {
+            return SimpleError::new("ERR string exceeds maximum allowed size (512MB)").into();
+        }
+        // an empty value never creates the key
+        let mut db = backend.write();
+        if self.value.is_empty() && !db.contains_key(&self.key) {
+            return RespFrame::Integer(0);
+        }
+
+        let s = match db.get_or_insert_with(self.key.clone(), || BulkString::new(vec![]).into()) {
+            BackendValue::String(s) => s,
+            _ => return RESP_WRONGTYPE.clone(),
+        };
+        if self.value.is_empty() {
+            return RespFrame::Integer(s.len() as i64);
+        }
+        let end = offset + self.value.len();
+        if s.0.len() < end {
+            s.0.resize(end, 0);
+        }
+        s.0[offset..end].copy_from_slice(&self.value);
+        let len = s.len();
+        db.signal_modified(&self.key);
+        RespFrame::Integer(len as i64)
+    }
+}
+
+impl TryFrom<RespArray> for SetRange {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["setrange"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(SetRange {
+            key: extract_string(args.next())?,
+            offset: parse_number(args.next())?,
+            value: extract_bytes(args.next())?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    fn setrange(backend: &Backend, key: &str, offset: i64, value: &[u8]) -> RespFrame {
+        SetRange {
+            key: key.to_string(),
+            offset,
+            value: value.to_vec(),
+        }
+        .execute(backend)
+    }
+
+    #[test]
+    fn test_setrange_try_from_resp_array() -> Result<()> {
+        let mut buf =
+            BytesMut::from("*4\r\n$8\r\nsetrange\r\n$3\r\nkey\r\n$1\r\n6\r\n$5\r\nRedis\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+        let cmd: SetRange = frame.try_into()?;
+        assert_eq!(cmd.key, "key");
+        assert_eq!(cmd.offset, 6);
+        assert_eq!(cmd.value, b"Redis");
+        Ok(())
+    }
+
+    #[test]
+    fn test_setrange_overwrite() {
+        let backend = Backend::new();
+        backend.set("key".to_string(), BulkString::new("Hello World"));
+
+        assert_eq!(
+            setrange(&backend, "key", 6, b"Redis"),
+            RespFrame::Integer(11)
+        );
+        assert_eq!(
+            backend.get("key"),
+            Some(BulkString::new("Hello Redis").into())
+        );
+    }
+
+    #[test]
+    fn test_setrange_extend() {
+        let backend = Backend::new();
+        backend.set("key".to_string(), BulkString::new("Hello"));
+
+        assert_eq!(
+            setrange(&backend, "key", 3, b"p me!"),
+            RespFrame::Integer(8)
+        );
+        assert_eq!(backend.get("key"), Some(BulkString::new("Help me!").into()));
+    }
+
+    #[test]
+    fn test_setrange_zero_padding() {
+        let backend = Backend::new();
+
+        assert_eq!(setrange(&backend, "key", 4, b"ab"), RespFrame::Integer(6));
+        assert_eq!(
+            backend.get("key"),
+            Some(BulkString::new(b"\0\0\0\0ab".to_vec()).into())
+        );
+
+        assert_eq!(setrange(&backend, "empty", 10, b""), RespFrame::Integer(0));
+        assert_eq!(backend.get("empty"), None);
+    }
+
+    #[test]
+    fn test_setrange_invalid_offset() {
+        let backend = Backend::new();
+
+        assert_eq!(
+            setrange(&backend, "key", -1, b"a"),
+            SimpleError::new("ERR offset is out of range").into()
+        );
+        assert_eq!(
+            setrange(&backend, "key", MAX_STRING_SIZE as i64, b"a"),
+            SimpleError::new("ERR string exceeds maximum allowed size (512MB)").into()
+        );
+    }
+}
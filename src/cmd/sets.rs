@@ -0,0 +1,545 @@
+use std::collections::HashSet;
+
+use rand::{seq::IteratorRandom, Rng};
+
+use crate::{Backend, BackendValue, BulkString, RespArray, RespFrame, RespNullBulkString};
+
+use super::{
+    extract_args, extract_bytes, extract_string, parse_number, validate_command,
+    validate_variadic_command, CommandError, CommandExecutor, RESP_WRONGTYPE,
+};
+
+#[derive(Debug)]
+pub struct SAdd {
+    pub key: String,
+    pub members: Vec<Vec<u8>>,
+}
+
+#[derive(Debug)]
+pub struct SRem {
+    pub key: String,
+    pub members: Vec<Vec<u8>>,
+}
+
+#[derive(Debug)]
+pub struct SMembers {
+    pub key: String,
+}
+
+#[derive(Debug)]
+pub struct SIsMember {
+    pub key: String,
+    pub member: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub struct SMIsMember {
+    pub key: String,
+    pub members: Vec<Vec<u8>>,
+}
+
+#[derive(Debug)]
+pub struct SCard {
+    pub key: String,
+}
+
+#[derive(Debug)]
+pub struct SPop {
+    pub key: String,
+    // None replies with a single member, Some with an array of up to `count` members
+    pub count: Option<usize>,
+}
+
+#[derive(Debug)]
+pub struct SRandMember {
+    pub key: String,
+    // None replies with a single member, Some with an array: a positive count picks distinct
+    // members, a negative one allows the same member to be picked again
+    pub count: Option<i64>,
+}
+
+impl CommandExecutor for SAdd {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let mut db = backend.write();
+        let added =
+            match db.get_or_insert_with(self.key.clone(), || BackendValue::Set(HashSet::new())) {
+                BackendValue::Set(set) => self
+                    .members
+                    .into_iter()
+                    .filter(|member| set.insert(member.clone()))
+                    .count(),
+                _ => return RESP_WRONGTYPE.clone(),
+            };
+        if added > 0 {
+            db.signal_modified(&self.key);
+        }
+        RespFrame::Integer(added as i64)
+    }
+}
+
+impl CommandExecutor for SRem {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        write_set(backend, &self.key, RespFrame::Integer(0), |set| {
+            let removed = self.members.iter().filter(|m| set.remove(*m)).count();
+            (RespFrame::Integer(removed as i64), removed > 0)
+        })
+    }
+}
+
+impl CommandExecutor for SMembers {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        read_set(backend, &self.key, RespArray::new(vec![]).into(), |set| {
+            members_array(set.iter())
+        })
+    }
+}
+
+impl CommandExecutor for SIsMember {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        read_set(backend, &self.key, RespFrame::Integer(0), |set| {
+            RespFrame::Integer(set.contains(&self.member) as i64)
+        })
+    }
+}
+
+impl CommandExecutor for SMIsMember {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let empty = HashSet::new();
+        let reply = |set: &HashSet<Vec<u8>>| -> RespFrame {
+            let ret: Vec<RespFrame> = self
+                .members
+                .iter()
+                .map(|m| RespFrame::Integer(set.contains(m) as i64))
+                .collect();
+            RespArray::new(ret).into()
+        };
+        read_set(backend, &self.key, reply(&empty), reply)
+    }
+}
+
+impl CommandExecutor for SCard {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        read_set(backend, &self.key, RespFrame::Integer(0), |set| {
+            RespFrame::Integer(set.len() as i64)
+        })
+    }
+}
+
+impl CommandExecutor for SPop {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let missing = match self.count {
+            Some(_) => RespArray::new(vec![]).into(),
+            None => RespNullBulkString.into(),
+        };
+        write_set(backend, &self.key, missing, |set| {
+            let mut rng = rand::thread_rng();
+            let picked: Vec<Vec<u8>> = set
+                .iter()
+                .choose_multiple(&mut rng, self.count.unwrap_or(1))
+                .into_iter()
+                .cloned()
+                .collect();
+            for member in picked.iter() {
+                set.remove(member);
+            }
+            let modified = !picked.is_empty();
+            let ret = match self.count {
+                Some(_) => members_array(picked.iter()),
+                None => match picked.into_iter().next() {
+                    Some(member) => BulkString::new(member).into(),
+                    None => RespNullBulkString.into(),
+                },
+            };
+            (ret, modified)
+        })
+    }
+}
+
+impl CommandExecutor for SRandMember {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let missing = match self.count {
+            Some(_) => RespArray::new(vec![]).into(),
+            None => RespNullBulkString.into(),
+        };
+        read_set(backend, &self.key, missing, |set| {
+            let mut rng = rand::thread_rng();
+            match self.count {
+                None => match set.iter().choose(&mut rng) {
+                    Some(member) => BulkString::new(member.as_slice()).into(),
+                    None => RespNullBulkString.into(),
+                },
+                Some(count) if count >= 0 => members_array(
+                    set.iter()
+                        .choose_multiple(&mut rng, count as usize)
+                        .into_iter(),
+                ),
+                Some(_) if set.is_empty() => RespArray::new(vec![]).into(),
+                Some(count) => {
+                    let members: Vec<_> = set.iter().collect();
+                    members_array(
+                        (0..count.unsigned_abs()).map(|_| members[rng.gen_range(0..members.len())]),
+                    )
+                }
+            }
+        })
+    }
+}
+
+pub(super) fn members_array<'a>(members: impl Iterator<Item = &'a Vec<u8>>) -> RespFrame {
+    let members: Vec<RespFrame> = members
+        .map(|member| BulkString::new(member.as_slice()).into())
+        .collect();
+    RespArray::new(members).into()
+}
+
+// run `f` on the set at `key` under the read lock
+pub(super) fn read_set(
+    backend: &Backend,
+    key: &str,
+    missing: RespFrame,
+    f: impl FnOnce(&HashSet<Vec<u8>>) -> RespFrame,
+) -> RespFrame {
+    match backend.read().get(key) {
+        Some(BackendValue::Set(set)) => f(set),
+        Some(_) => RESP_WRONGTYPE.clone(),
+        None => missing,
+    }
+}
+
+// run `f` on the set at `key` under the write lock, it replies and tells whether it changed
+// the set. the key is removed if `f` empties the set
+fn write_set(
+    backend: &Backend,
+    key: &str,
+    missing: RespFrame,
+    f: impl FnOnce(&mut HashSet<Vec<u8>>) -> (RespFrame, bool),
+) -> RespFrame {
+    let mut db = backend.write();
+    let (ret, modified) = match db.get_mut(key) {
+        Some(BackendValue::Set(set)) => f(set),
+        Some(_) => return RESP_WRONGTYPE.clone(),
+        None => return missing,
+    };
+    if modified {
+        db.signal_modified(key);
+    }
+    if matches!(db.get(key), Some(BackendValue::Set(set)) if set.is_empty()) {
+        db.remove(key);
+    }
+    ret
+}
+
+// - "<cmd> key member [member ...]"
+fn parse_members(
+    value: RespArray,
+    name: &'static str,
+) -> Result<(String, Vec<Vec<u8>>), CommandError> {
+    validate_variadic_command(&value, &[name], 2)?;
+
+    let mut args = extract_args(value, 1)?.into_iter();
+    let key = extract_string(args.next())?;
+    let members = args
+        .map(|arg| extract_bytes(Some(arg)))
+        .collect::<Result<_, _>>()?;
+    Ok((key, members))
+}
+
+// - "<cmd> key [count]"
+fn parse_count<T: std::str::FromStr>(
+    value: RespArray,
+    name: &'static str,
+) -> Result<(String, Option<T>), CommandError> {
+    validate_variadic_command(&value, &[name], 1)?;
+
+    let mut args = extract_args(value, 1)?.into_iter();
+    let key = extract_string(args.next())?;
+    let count = match args.next() {
+        Some(arg) => Some(parse_number(Some(arg))?),
+        None => None,
+    };
+    if args.next().is_some() {
+        return Err(CommandError::InvalidArgument(
+            "Too many arguments".to_string(),
+        ));
+    }
+    Ok((key, count))
+}
+
+impl TryFrom<RespArray> for SAdd {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let (key, members) = parse_members(value, "sadd")?;
+        Ok(SAdd { key, members })
+    }
+}
+
+impl TryFrom<RespArray> for SRem {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let (key, members) = parse_members(value, "srem")?;
+        Ok(SRem { key, members })
+    }
+}
+
+impl TryFrom<RespArray> for SMIsMember {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let (key, members) = parse_members(value, "smismember")?;
+        Ok(SMIsMember { key, members })
+    }
+}
+
+impl TryFrom<RespArray> for SMembers {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["smembers"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(SMembers {
+            key: extract_string(args.next())?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for SIsMember {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["sismember"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(SIsMember {
+            key: extract_string(args.next())?,
+            member: extract_bytes(args.next())?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for SCard {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["scard"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(SCard {
+            key: extract_string(args.next())?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for SPop {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let (key, count) = parse_count(value, "spop")?;
+        Ok(SPop { key, count })
+    }
+}
+
+impl TryFrom<RespArray> for SRandMember {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let (key, count) = parse_count(value, "srandmember")?;
+        Ok(SRandMember { key, count })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    fn sadd(backend: &Backend, members: &[&str]) -> RespFrame {
+        SAdd {
+            key: "set".to_string(),
+            members: members.iter().map(|m| m.as_bytes().to_vec()).collect(),
+        }
+        .execute(backend)
+    }
+
+    fn strings(frame: RespFrame) -> Vec<String> {
+        let RespFrame::Array(array) = frame else {
+            panic!("expected an array");
+        };
+        array
+            .iter()
+            .map(|frame| {
+                let RespFrame::BulkString(s) = frame else {
+                    panic!("expected a bulk string");
+                };
+                String::from_utf8_lossy(s).to_string()
+            })
+            .collect()
+    }
+
+    fn sorted(frame: RespFrame) -> Vec<String> {
+        let mut ret = strings(frame);
+        ret.sort();
+        ret
+    }
+
+    #[test]
+    fn test_sets_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*4\r\n$4\r\nsadd\r\n$3\r\nset\r\n$1\r\na\r\n$1\r\nb\r\n");
+        let cmd: SAdd = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.key, "set");
+        assert_eq!(cmd.members, vec![b"a".to_vec(), b"b".to_vec()]);
+
+        let mut buf = BytesMut::from("*3\r\n$11\r\nsrandmember\r\n$3\r\nset\r\n$2\r\n-3\r\n");
+        let cmd: SRandMember = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.count, Some(-3));
+
+        let mut buf = BytesMut::from("*2\r\n$4\r\nspop\r\n$3\r\nset\r\n");
+        let cmd: SPop = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.count, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sadd_srem_smembers() {
+        let backend = Backend::new();
+        assert_eq!(sadd(&backend, &["a", "b", "a"]), RespFrame::Integer(2));
+        assert_eq!(sadd(&backend, &["b", "c"]), RespFrame::Integer(1));
+
+        let smembers = || {
+            SMembers {
+                key: "set".to_string(),
+            }
+            .execute(&backend)
+        };
+        assert_eq!(sorted(smembers()), vec!["a", "b", "c"]);
+
+        let cmd = SRem {
+            key: "set".to_string(),
+            members: vec![b"a".to_vec(), b"z".to_vec()],
+        };
+        assert_eq!(cmd.execute(&backend), RespFrame::Integer(1));
+        let cmd = SCard {
+            key: "set".to_string(),
+        };
+        assert_eq!(cmd.execute(&backend), RespFrame::Integer(2));
+
+        // removing the last members removes the key
+        let cmd = SRem {
+            key: "set".to_string(),
+            members: vec![b"b".to_vec(), b"c".to_vec()],
+        };
+        assert_eq!(cmd.execute(&backend), RespFrame::Integer(2));
+        assert_eq!(backend.get("set"), None);
+        assert!(strings(smembers()).is_empty());
+    }
+
+    #[test]
+    fn test_sismember_smismember() {
+        let backend = Backend::new();
+        sadd(&backend, &["a", "b"]);
+
+        let sismember = |member: &str| {
+            SIsMember {
+                key: "set".to_string(),
+                member: member.as_bytes().to_vec(),
+            }
+            .execute(&backend)
+        };
+        assert_eq!(sismember("a"), RespFrame::Integer(1));
+        assert_eq!(sismember("z"), RespFrame::Integer(0));
+
+        let smismember = |key: &str| {
+            SMIsMember {
+                key: key.to_string(),
+                members: vec![b"a".to_vec(), b"z".to_vec(), b"b".to_vec()],
+            }
+            .execute(&backend)
+        };
+        let expected = |flags: [i64; 3]| -> RespFrame {
+            RespArray::new(
+                flags
+                    .iter()
+                    .map(|f| RespFrame::Integer(*f))
+                    .collect::<Vec<_>>(),
+            )
+            .into()
+        };
+        assert_eq!(smismember("set"), expected([1, 0, 1]));
+        assert_eq!(smismember("missing"), expected([0, 0, 0]));
+    }
+
+    #[test]
+    fn test_spop() {
+        let backend = Backend::new();
+        sadd(&backend, &["a", "b", "c"]);
+
+        let spop = |count| {
+            SPop {
+                key: "set".to_string(),
+                count,
+            }
+            .execute(&backend)
+        };
+        let RespFrame::BulkString(first) = spop(None) else {
+            panic!("expected a bulk string");
+        };
+        let mut popped = strings(spop(Some(5)));
+        popped.push(String::from_utf8_lossy(&first).to_string());
+        popped.sort();
+        assert_eq!(popped, vec!["a", "b", "c"]);
+
+        assert_eq!(backend.get("set"), None);
+        assert_eq!(spop(None), RespNullBulkString.into());
+        assert!(strings(spop(Some(1))).is_empty());
+    }
+
+    #[test]
+    fn test_srandmember() {
+        let backend = Backend::new();
+        sadd(&backend, &["a", "b", "c"]);
+
+        let srandmember = |count| {
+            SRandMember {
+                key: "set".to_string(),
+                count,
+            }
+            .execute(&backend)
+        };
+        let distinct = strings(srandmember(Some(2)));
+        assert_eq!(distinct.len(), 2);
+        assert_ne!(distinct[0], distinct[1]);
+        assert_eq!(sorted(srandmember(Some(10))), vec!["a", "b", "c"]);
+
+        let repeated = strings(srandmember(Some(-10)));
+        assert_eq!(repeated.len(), 10);
+        assert!(repeated
+            .iter()
+            .all(|m| ["a", "b", "c"].contains(&m.as_str())));
+
+        // SRANDMEMBER doesn't remove anything
+        let cmd = SCard {
+            key: "set".to_string(),
+        };
+        assert_eq!(cmd.execute(&backend), RespFrame::Integer(3));
+    }
+
+    #[test]
+    fn test_sets_wrongtype() {
+        let backend = Backend::new();
+        backend.set("set".to_string(), BulkString::new("value"));
+
+        assert_eq!(sadd(&backend, &["a"]), RESP_WRONGTYPE.clone());
+        let cmd = SCard {
+            key: "set".to_string(),
+        };
+        assert_eq!(cmd.execute(&backend), RESP_WRONGTYPE.clone());
+        let cmd = SPop {
+            key: "set".to_string(),
+            count: None,
+        };
+        assert_eq!(cmd.execute(&backend), RESP_WRONGTYPE.clone());
+        let cmd = SRem {
+            key: "set".to_string(),
+            members: vec![b"a".to_vec()],
+        };
+        assert_eq!(cmd.execute(&backend), RESP_WRONGTYPE.clone());
+    }
+}
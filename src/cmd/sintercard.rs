@@ -0,0 +1,171 @@
+use std::collections::HashSet;
+
+use crate::{Backend, BackendValue, RespArray, RespFrame};
+
+use super::{
+    extract_args, extract_string, parse_number, validate_variadic_command, CommandError,
+    CommandExecutor, RESP_WRONGTYPE,
+};
+
+#[derive(Debug)]
+pub struct SInterCard {
+    pub keys: Vec<String>,
+    // stop counting once this many common members are found, 0 for no limit
+    pub limit: usize,
+}
+
+impl CommandExecutor for SInterCard {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let db = backend.read();
+        let mut sets: Vec<&HashSet<Vec<u8>>> = Vec::with_capacity(self.keys.len());
+        for key in self.keys.iter() {
+            match db.get(key) {
+                Some(BackendValue::Set(set)) => sets.push(set),
+                Some(_) => return RESP_WRONGTYPE.clone(),
+                // any missing key makes the intersection empty
+                None => return RespFrame::Integer(0),
+            }
+        }
+        let limit = match self.limit {
+            0 => usize::MAX,
+            n => n,
+        };
+        // count over the smallest set without building the intersection
+        sets.sort_by_key(|set| set.len());
+        let Some((smallest, rest)) = sets.split_first() else {
+            return RespFrame::Integer(0);
+        };
+        let count = smallest
+            .iter()
+            .filter(|m| rest.iter().all(|set| set.contains(*m)))
+            .take(limit)
+            .count();
+        RespFrame::Integer(count as i64)
+    }
+}
+
+impl TryFrom<RespArray> for SInterCard {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["sintercard"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let numkeys: usize = parse_number(args.next())?;
+        if numkeys == 0 || numkeys > args.len() {
+            return Err(CommandError::InvalidArgument(
+                "numkeys must be positive and at most the number of keys given".to_string(),
+            ));
+        }
+        let keys = args
+            .by_ref()
+            .take(numkeys)
+            .map(|arg| extract_string(Some(arg)))
+            .collect::<Result<_, _>>()?;
+        let mut cmd = SInterCard { keys, limit: 0 };
+        while let Some(arg) = args.next() {
+            match extract_string(Some(arg))?.to_ascii_lowercase().as_str() {
+                "limit" => cmd.limit = parse_number(args.next())?,
+                v => {
+                    return Err(CommandError::InvalidArgument(format!(
+                        "Invalid option: {}",
+                        v
+                    )))
+                }
+            }
+        }
+        Ok(cmd)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{cmd::SAdd, BulkString, RespDecode};
+
+    use super::*;
+
+    fn setup() -> Backend {
+        let backend = Backend::new();
+        let sadd = |key: &str, members: std::ops::Range<i32>| {
+            let cmd = SAdd {
+                key: key.to_string(),
+                members: members.map(|m| m.to_string().into_bytes()).collect(),
+            };
+            cmd.execute(&backend);
+        };
+        // 10 common members: 10..20
+        sadd("a", 0..20);
+        sadd("b", 10..30);
+        sadd("c", 5..25);
+        backend
+    }
+
+    fn sintercard(backend: &Backend, keys: &[&str], limit: usize) -> RespFrame {
+        SInterCard {
+            keys: keys.iter().map(|k| k.to_string()).collect(),
+            limit,
+        }
+        .execute(backend)
+    }
+
+    #[test]
+    fn test_sintercard_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from(
+            "*6\r\n$10\r\nsintercard\r\n$1\r\n2\r\n$1\r\na\r\n$1\r\nb\r\n$5\r\nLIMIT\r\n$1\r\n5\r\n",
+        );
+        let cmd: SInterCard = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.keys, vec!["a", "b"]);
+        assert_eq!(cmd.limit, 5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sintercard_numkeys_mismatch() -> Result<()> {
+        // more keys announced than given
+        let mut buf =
+            BytesMut::from("*4\r\n$10\r\nsintercard\r\n$1\r\n3\r\n$1\r\na\r\n$1\r\nb\r\n");
+        let ret: Result<SInterCard, _> = RespArray::decode(&mut buf)?.try_into();
+        assert!(matches!(ret, Err(CommandError::InvalidArgument(_))));
+
+        // fewer keys announced than given, the extra key is not an option
+        let mut buf =
+            BytesMut::from("*4\r\n$10\r\nsintercard\r\n$1\r\n1\r\n$1\r\na\r\n$1\r\nb\r\n");
+        let ret: Result<SInterCard, _> = RespArray::decode(&mut buf)?.try_into();
+        assert!(matches!(ret, Err(CommandError::InvalidArgument(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_sintercard_limit() {
+        let backend = setup();
+        assert_eq!(
+            sintercard(&backend, &["a", "b", "c"], 0),
+            RespFrame::Integer(10)
+        );
+        assert_eq!(
+            sintercard(&backend, &["a", "b", "c"], 3),
+            RespFrame::Integer(3)
+        );
+        assert_eq!(
+            sintercard(&backend, &["a", "b", "c"], 50),
+            RespFrame::Integer(10)
+        );
+        assert_eq!(sintercard(&backend, &["a", "c"], 0), RespFrame::Integer(15));
+    }
+
+    #[test]
+    fn test_sintercard_missing_and_wrongtype() {
+        let backend = setup();
+        assert_eq!(
+            sintercard(&backend, &["a", "missing"], 0),
+            RespFrame::Integer(0)
+        );
+        backend.set("str".to_string(), BulkString::new("value"));
+        assert_eq!(
+            sintercard(&backend, &["a", "str"], 0),
+            RESP_WRONGTYPE.clone()
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use crate::{monitoring::SlowLogEntry, Backend, BulkString, RespArray, RespFrame};
+
+use super::{
+    extract_args, parse_number, validate_command, validate_variadic_command, CommandError,
+    CommandExecutor, RESP_OK,
+};
+
+// entries SLOWLOG GET returns without a count
+const DEFAULT_COUNT: usize = 10;
+
+/// SLOWLOG GET [count], -1 for every entry
+#[derive(Debug)]
+pub struct SlowLogGet {
+    pub count: usize,
+}
+
+#[derive(Debug)]
+pub struct SlowLogLen;
+
+#[derive(Debug)]
+pub struct SlowLogReset;
+
+impl CommandExecutor for SlowLogGet {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let entries = backend.slowlog().get(self.count);
+        RespArray::new(entries.into_iter().map(entry_frame).collect::<Vec<_>>()).into()
+    }
+}
+
+// [id, timestamp, duration in microseconds, [arg ...]]
+fn entry_frame(entry: SlowLogEntry) -> RespFrame {
+    let args = entry
+        .args
+        .into_iter()
+        .map(|arg| BulkString::new(arg).into())
+        .collect::<Vec<RespFrame>>();
+    RespArray::new(vec![
+        RespFrame::Integer(entry.id as i64),
+        RespFrame::Integer(entry.timestamp),
+        RespFrame::Integer(entry.duration.as_micros() as i64),
+        RespArray::new(args).into(),
+    ])
+    .into()
+}
+
+impl CommandExecutor for SlowLogLen {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        RespFrame::Integer(backend.slowlog().len() as i64)
+    }
+}
+
+impl CommandExecutor for SlowLogReset {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        backend.slowlog().reset();
+        RESP_OK.clone()
+    }
+}
+
+impl TryFrom<RespArray> for SlowLogGet {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["slowlog", "get"], 0)?;
+        if value.len() > 3 {
+            return Err(CommandError::InvalidArgument(
+                "slowlog get command must have at most 1 argument".to_string(),
+            ));
+        }
+
+        let count = match extract_args(value, 2)?.into_iter().next() {
+            None => DEFAULT_COUNT,
+            Some(arg) => match parse_number::<i64>(Some(arg))? {
+                -1 => usize::MAX,
+                n if n < 0 => {
+                    return Err(CommandError::InvalidArgument(
+                        "count should be greater than or equal to -1".to_string(),
+                    ))
+                }
+                n => n as usize,
+            },
+        };
+        Ok(SlowLogGet { count })
+    }
+}
+
+impl TryFrom<RespArray> for SlowLogLen {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["slowlog", "len"], 0)?;
+        Ok(SlowLogLen)
+    }
+}
+
+impl TryFrom<RespArray> for SlowLogReset {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["slowlog", "reset"], 0)?;
+        Ok(SlowLogReset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_slowlog_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*2\r\n$7\r\nslowlog\r\n$3\r\nget\r\n");
+        let cmd: SlowLogGet = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.count, DEFAULT_COUNT);
+
+        let mut buf = BytesMut::from("*3\r\n$7\r\nslowlog\r\n$3\r\nget\r\n$2\r\n-1\r\n");
+        let cmd: SlowLogGet = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.count, usize::MAX);
+
+        let mut buf = BytesMut::from("*3\r\n$7\r\nslowlog\r\n$3\r\nget\r\n$2\r\n-2\r\n");
+        let ret: Result<SlowLogGet, _> = RespArray::decode(&mut buf)?.try_into();
+        assert!(ret.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_slowlog_commands() {
+        let backend = Backend::new();
+        for key in ["a", "b", "c"] {
+            let args = vec![b"get".to_vec(), key.as_bytes().to_vec()];
+            backend
+                .slowlog()
+                .record(args, Duration::from_millis(20), 10_000, 128);
+        }
+        assert_eq!(SlowLogLen.execute(&backend), RespFrame::Integer(3));
+
+        let RespFrame::Array(entries) = SlowLogGet { count: 2 }.execute(&backend) else {
+            panic!("expected an array");
+        };
+        assert_eq!(entries.len(), 2);
+        let RespFrame::Array(ref newest) = entries[0] else {
+            panic!("expected an array");
+        };
+        assert_eq!(newest[0], RespFrame::Integer(2));
+        assert_eq!(newest[2], RespFrame::Integer(20_000));
+        assert_eq!(
+            newest[3],
+            RespArray::new(vec![
+                BulkString::new("get").into(),
+                BulkString::new("c").into()
+            ])
+            .into()
+        );
+
+        assert_eq!(SlowLogReset.execute(&backend), RESP_OK.clone());
+        assert_eq!(SlowLogLen.execute(&backend), RespFrame::Integer(0));
+    }
+}
@@ -0,0 +1,340 @@
+use std::collections::HashSet;
+
+use crate::{Backend, BackendValue, Db, RespArray, RespFrame};
+
+use super::{
+    extract_args, extract_bytes, extract_string, sets::members_array, validate_command,
+    validate_variadic_command, CommandError, CommandExecutor, RESP_WRONGTYPE,
+};
+
+#[derive(Debug)]
+pub struct SMove {
+    pub src: String,
+    pub dst: String,
+    pub member: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetOp {
+    Diff,
+    Union,
+    Inter,
+}
+
+/// SDIFF, SUNION, SINTER and their STORE variants
+#[derive(Debug)]
+pub struct SCombine {
+    pub op: SetOp,
+    pub keys: Vec<String>,
+    // the *STORE variants write the result here instead of replying with it
+    pub dst: Option<String>,
+}
+
+impl CommandExecutor for SMove {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let mut db = backend.write();
+        match db.get(&self.dst) {
+            Some(BackendValue::Set(_)) | None => {}
+            Some(_) => return RESP_WRONGTYPE.clone(),
+        }
+        let Some(value) = db.get_mut(&self.src) else {
+            return RespFrame::Integer(0);
+        };
+        let BackendValue::Set(src) = value else {
+            return RESP_WRONGTYPE.clone();
+        };
+        if !src.remove(&self.member) {
+            return RespFrame::Integer(0);
+        }
+        let now_empty = src.is_empty();
+        db.signal_modified(&self.src);
+        if now_empty {
+            db.remove(&self.src);
+        }
+        if let BackendValue::Set(dst) =
+            db.get_or_insert_with(self.dst.clone(), || BackendValue::Set(HashSet::new()))
+        {
+            dst.insert(self.member);
+        }
+        db.signal_modified(&self.dst);
+        RespFrame::Integer(1)
+    }
+}
+
+impl CommandExecutor for SCombine {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match self.dst {
+            None => match combine(&backend.read(), self.op, &self.keys) {
+                Ok(set) => members_array(set.iter()),
+                Err(e) => e,
+            },
+            Some(dst) => {
+                // one write lock covers reading the sources and storing the result
+                let mut db = backend.write();
+                let set = match combine(&db, self.op, &self.keys) {
+                    Ok(set) => set,
+                    Err(e) => return e,
+                };
+                let len = set.len();
+                // an empty result deletes the destination, like any emptied set
+                if set.is_empty() {
+                    db.remove(&dst);
+                } else {
+                    db.insert(dst, BackendValue::Set(set));
+                }
+                RespFrame::Integer(len as i64)
+            }
+        }
+    }
+}
+
+// missing keys count as empty sets
+fn combine(db: &Db, op: SetOp, keys: &[String]) -> Result<HashSet<Vec<u8>>, RespFrame> {
+    let empty = HashSet::new();
+    let mut sets = Vec::with_capacity(keys.len());
+    for key in keys {
+        match db.get(key) {
+            Some(BackendValue::Set(set)) => sets.push(set),
+            Some(_) => return Err(RESP_WRONGTYPE.clone()),
+            None => sets.push(&empty),
+        }
+    }
+    let Some((first, rest)) = sets.split_first() else {
+        return Ok(HashSet::new());
+    };
+    let ret = match op {
+        SetOp::Diff => first
+            .iter()
+            .filter(|m| !rest.iter().any(|set| set.contains(*m)))
+            .cloned()
+            .collect(),
+        SetOp::Union => sets.iter().flat_map(|set| set.iter()).cloned().collect(),
+        SetOp::Inter => {
+            // walk the smallest set, probing the others
+            let smallest = sets.iter().min_by_key(|set| set.len()).unwrap_or(first);
+            smallest
+                .iter()
+                .filter(|m| sets.iter().all(|set| set.contains(*m)))
+                .cloned()
+                .collect()
+        }
+    };
+    Ok(ret)
+}
+
+impl TryFrom<RespArray> for SMove {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["smove"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(SMove {
+            src: extract_string(args.next())?,
+            dst: extract_string(args.next())?,
+            member: extract_bytes(args.next())?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for SCombine {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        const COMMANDS: [(&str, SetOp, bool); 6] = [
+            ("sdiff", SetOp::Diff, false),
+            ("sunion", SetOp::Union, false),
+            ("sinter", SetOp::Inter, false),
+            ("sdiffstore", SetOp::Diff, true),
+            ("sunionstore", SetOp::Union, true),
+            ("sinterstore", SetOp::Inter, true),
+        ];
+        let (op, store) = COMMANDS
+            .iter()
+            .find_map(|(name, op, store)| {
+                let min_args = if *store { 2 } else { 1 };
+                validate_variadic_command(&value, &[name], min_args)
+                    .ok()
+                    .map(|_| (*op, *store))
+            })
+            .ok_or_else(|| {
+                CommandError::InvalidCommand("Invalid set operation or arguments".to_string())
+            })?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let dst = if store {
+            Some(extract_string(args.next())?)
+        } else {
+            None
+        };
+        let keys = args
+            .map(|arg| extract_string(Some(arg)))
+            .collect::<Result<_, _>>()?;
+        Ok(SCombine { op, keys, dst })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{
+        cmd::{SAdd, SMembers},
+        BulkString, RespDecode,
+    };
+
+    use super::*;
+
+    fn sadd(backend: &Backend, key: &str, members: &[&str]) {
+        let cmd = SAdd {
+            key: key.to_string(),
+            members: members.iter().map(|m| m.as_bytes().to_vec()).collect(),
+        };
+        cmd.execute(backend);
+    }
+
+    fn sorted(frame: RespFrame) -> Vec<String> {
+        let RespFrame::Array(array) = frame else {
+            panic!("expected an array, got {:?}", frame);
+        };
+        let mut ret: Vec<String> = array
+            .iter()
+            .map(|frame| {
+                let RespFrame::BulkString(s) = frame else {
+                    panic!("expected a bulk string");
+                };
+                String::from_utf8_lossy(s).to_string()
+            })
+            .collect();
+        ret.sort();
+        ret
+    }
+
+    fn combine(backend: &Backend, op: SetOp, keys: &[&str]) -> Vec<String> {
+        let cmd = SCombine {
+            op,
+            keys: keys.iter().map(|k| k.to_string()).collect(),
+            dst: None,
+        };
+        sorted(cmd.execute(backend))
+    }
+
+    // a: 1 2 3 4, b: 3 4 5, c: 4 5 6
+    fn setup() -> Backend {
+        let backend = Backend::new();
+        sadd(&backend, "a", &["1", "2", "3", "4"]);
+        sadd(&backend, "b", &["3", "4", "5"]);
+        sadd(&backend, "c", &["4", "5", "6"]);
+        backend
+    }
+
+    #[test]
+    fn test_scombine_try_from_resp_array() -> Result<()> {
+        let mut buf =
+            BytesMut::from("*4\r\n$11\r\nsinterstore\r\n$3\r\ndst\r\n$1\r\na\r\n$1\r\nb\r\n");
+        let cmd: SCombine = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.op, SetOp::Inter);
+        assert_eq!(cmd.dst, Some("dst".to_string()));
+        assert_eq!(cmd.keys, vec!["a", "b"]);
+
+        let mut buf = BytesMut::from("*3\r\n$5\r\nsdiff\r\n$1\r\na\r\n$1\r\nb\r\n");
+        let cmd: SCombine = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.op, SetOp::Diff);
+        assert_eq!(cmd.dst, None);
+        assert_eq!(cmd.keys, vec!["a", "b"]);
+
+        let mut buf = BytesMut::from("*2\r\n$11\r\nsunionstore\r\n$3\r\ndst\r\n");
+        let ret: Result<SCombine, _> = RespArray::decode(&mut buf)?.try_into();
+        assert!(ret.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_three_way_operations() {
+        let backend = setup();
+        assert_eq!(
+            combine(&backend, SetOp::Diff, &["a", "b", "c"]),
+            vec!["1", "2"]
+        );
+        assert_eq!(
+            combine(&backend, SetOp::Union, &["a", "b", "c"]),
+            vec!["1", "2", "3", "4", "5", "6"]
+        );
+        assert_eq!(combine(&backend, SetOp::Inter, &["a", "b", "c"]), vec!["4"]);
+    }
+
+    #[test]
+    fn test_operations_with_empty_sets() {
+        let backend = setup();
+        assert_eq!(
+            combine(&backend, SetOp::Diff, &["a", "missing"]),
+            vec!["1", "2", "3", "4"]
+        );
+        assert!(combine(&backend, SetOp::Diff, &["missing", "a"]).is_empty());
+        assert_eq!(
+            combine(&backend, SetOp::Union, &["missing", "b"]),
+            vec!["3", "4", "5"]
+        );
+        assert!(combine(&backend, SetOp::Inter, &["a", "missing", "b"]).is_empty());
+        assert!(combine(&backend, SetOp::Diff, &["a", "a"]).is_empty());
+    }
+
+    #[test]
+    fn test_store_variants() {
+        let backend = setup();
+        let store = |op, keys: &[&str]| {
+            SCombine {
+                op,
+                keys: keys.iter().map(|k| k.to_string()).collect(),
+                dst: Some("dst".to_string()),
+            }
+            .execute(&backend)
+        };
+        let members = || {
+            sorted(
+                SMembers {
+                    key: "dst".to_string(),
+                }
+                .execute(&backend),
+            )
+        };
+
+        assert_eq!(store(SetOp::Union, &["b", "c"]), RespFrame::Integer(4));
+        assert_eq!(members(), vec!["3", "4", "5", "6"]);
+        // the destination may be one of the sources
+        assert_eq!(store(SetOp::Inter, &["dst", "a"]), RespFrame::Integer(2));
+        assert_eq!(members(), vec!["3", "4"]);
+
+        // an empty result removes the destination, whatever it held
+        backend.set("dst".to_string(), BulkString::new("value"));
+        assert_eq!(store(SetOp::Diff, &["b", "a", "c"]), RespFrame::Integer(0));
+        assert_eq!(backend.get("dst"), None);
+    }
+
+    #[test]
+    fn test_smove() {
+        let backend = setup();
+        let smove = |src: &str, dst: &str, member: &str| {
+            SMove {
+                src: src.to_string(),
+                dst: dst.to_string(),
+                member: member.as_bytes().to_vec(),
+            }
+            .execute(&backend)
+        };
+        assert_eq!(smove("a", "new", "1"), RespFrame::Integer(1));
+        assert_eq!(smove("a", "new", "1"), RespFrame::Integer(0));
+        assert_eq!(smove("missing", "new", "1"), RespFrame::Integer(0));
+        assert_eq!(combine(&backend, SetOp::Union, &["new"]), vec!["1"]);
+
+        backend.set("str".to_string(), BulkString::new("value"));
+        assert_eq!(smove("a", "str", "2"), RESP_WRONGTYPE.clone());
+        assert_eq!(smove("str", "a", "2"), RESP_WRONGTYPE.clone());
+        assert_eq!(combine(&backend, SetOp::Union, &["a"]), vec!["2", "3", "4"]);
+        let cmd = SCombine {
+            op: SetOp::Union,
+            keys: vec!["a".to_string(), "str".to_string()],
+            dst: None,
+        };
+        assert_eq!(cmd.execute(&backend), RESP_WRONGTYPE.clone());
+    }
+}
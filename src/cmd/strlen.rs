@@ -0,0 +1,81 @@
+use crate::{Backend, RespArray, RespFrame};
+
+use super::{
+    extract_args, extract_string, get_string, validate_command, CommandError, CommandExecutor,
+};
+
+#[derive(Debug)]
+pub struct StrLen {
+    pub key: String,
+}
+
+impl CommandExecutor for StrLen {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match get_string(backend, &self.key) {
+            Ok(Some(value)) => RespFrame::Integer(value.len() as i64),
+            Ok(None) => RespFrame::Integer(0),
+            Err(e) => e,
+        }
+    }
+}
+
+impl TryFrom<RespArray> for StrLen {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["strlen"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(StrLen {
+            key: extract_string(args.next())?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{cmd::RESP_WRONGTYPE, BulkString, RespDecode, ZSet};
+
+    use super::*;
+
+    fn strlen(backend: &Backend, key: &str) -> RespFrame {
+        StrLen {
+            key: key.to_string(),
+        }
+        .execute(backend)
+    }
+
+    #[test]
+    fn test_strlen_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*2\r\n$6\r\nstrlen\r\n$3\r\nkey\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+        let cmd: StrLen = frame.try_into()?;
+        assert_eq!(cmd.key, "key");
+        Ok(())
+    }
+
+    #[test]
+    fn test_strlen_command() {
+        let backend = Backend::new();
+        assert_eq!(strlen(&backend, "missing"), RespFrame::Integer(0));
+
+        backend.set("empty".to_string(), BulkString::new(""));
+        assert_eq!(strlen(&backend, "empty"), RespFrame::Integer(0));
+
+        backend.set("utf8".to_string(), BulkString::new("héllo wörld"));
+        assert_eq!(strlen(&backend, "utf8"), RespFrame::Integer(13));
+
+        backend.set("binary".to_string(), BulkString::new("\x00a\x00\x00b"));
+        assert_eq!(strlen(&backend, "binary"), RespFrame::Integer(5));
+    }
+
+    #[test]
+    fn test_strlen_wrong_type() {
+        let backend = Backend::new();
+        backend.set("zset".to_string(), ZSet::new());
+        assert_eq!(strlen(&backend, "zset"), RESP_WRONGTYPE.clone());
+    }
+}
@@ -0,0 +1,322 @@
+use crate::{
+    network::{ConnectionState, Subscription},
+    Backend, BulkString, RespArray, RespFrame, RespNullBulkString,
+};
+
+use super::{
+    extract_args, extract_bytes, extract_string, validate_command, validate_variadic_command,
+    CommandError, CommandExecutor, RESP_NO_CONNECTION,
+};
+
+/// SUBSCRIBE channel [channel ...] / PSUBSCRIBE pattern [pattern ...]
+#[derive(Debug)]
+pub struct Subscribe {
+    pub channels: Vec<String>,
+    pub pattern: bool,
+}
+
+/// UNSUBSCRIBE [channel ...] / PUNSUBSCRIBE [pattern ...], every channel or pattern when none
+/// is given
+#[derive(Debug)]
+pub struct Unsubscribe {
+    pub channels: Vec<String>,
+    pub pattern: bool,
+}
+
+#[derive(Debug)]
+pub struct Publish {
+    pub channel: String,
+    pub message: Vec<u8>,
+}
+
+// ["subscribe", channel, subscription count] and the like
+fn confirmation(kind: &str, channel: Option<&str>, count: usize) -> RespFrame {
+    let channel = match channel {
+        Some(channel) => BulkString::new(channel).into(),
+        None => RespNullBulkString.into(),
+    };
+    RespArray::new(vec![
+        BulkString::new(kind).into(),
+        channel,
+        RespFrame::Integer(count as i64),
+    ])
+    .into()
+}
+
+fn subscription(name: String, pattern: bool) -> Subscription {
+    if pattern {
+        Subscription::Pattern(name)
+    } else {
+        Subscription::Channel(name)
+    }
+}
+
+impl Subscribe {
+    /// subscribe, replying with one confirmation per channel
+    pub fn execute_each(self, backend: &Backend, conn: &mut ConnectionState) -> Vec<RespFrame> {
+        let kind = if self.pattern {
+            "psubscribe"
+        } else {
+            "subscribe"
+        };
+        self.channels
+            .into_iter()
+            .map(|channel| {
+                let count = conn.subscribe(
+                    backend.pubsub(),
+                    subscription(channel.clone(), self.pattern),
+                );
+                confirmation(kind, Some(&channel), count)
+            })
+            .collect()
+    }
+}
+
+impl Unsubscribe {
+    /// unsubscribe, replying with one confirmation per channel
+    pub fn execute_each(self, backend: &Backend, conn: &mut ConnectionState) -> Vec<RespFrame> {
+        let kind = if self.pattern {
+            "punsubscribe"
+        } else {
+            "unsubscribe"
+        };
+        let channels = match (self.channels.is_empty(), self.pattern) {
+            (false, _) => self.channels,
+            (true, false) => conn.subscribed_channels(),
+            (true, true) => conn.subscribed_patterns(),
+        };
+        if channels.is_empty() {
+            return vec![confirmation(kind, None, conn.subscriptions.len())];
+        }
+        channels
+            .into_iter()
+            .map(|channel| {
+                let subscription = subscription(channel, self.pattern);
+                let count = conn.unsubscribe(backend.pubsub(), &subscription);
+                let (Subscription::Channel(channel) | Subscription::Pattern(channel)) =
+                    subscription;
+                confirmation(kind, Some(&channel), count)
+            })
+            .collect()
+    }
+}
+
+// the network layer sends the confirmations one by one, in a transaction they come as an array
+impl CommandExecutor for Subscribe {
+    fn execute(self, _backend: &Backend) -> RespFrame {
+        RESP_NO_CONNECTION.clone()
+    }
+
+    fn execute_on(self, backend: &Backend, conn: &mut ConnectionState) -> RespFrame {
+        RespArray::new(self.execute_each(backend, conn)).into()
+    }
+}
+
+impl CommandExecutor for Unsubscribe {
+    fn execute(self, _backend: &Backend) -> RespFrame {
+        RESP_NO_CONNECTION.clone()
+    }
+
+    fn execute_on(self, backend: &Backend, conn: &mut ConnectionState) -> RespFrame {
+        RespArray::new(self.execute_each(backend, conn)).into()
+    }
+}
+
+impl CommandExecutor for Publish {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let receivers = backend.pubsub().publish(&self.channel, &self.message);
+        RespFrame::Integer(receivers as i64)
+    }
+}
+
+impl TryFrom<RespArray> for Subscribe {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let pattern = validate_variadic_command(&value, &["subscribe"], 1)
+            .map(|_| false)
+            .or_else(|_| validate_variadic_command(&value, &["psubscribe"], 1).map(|_| true))?;
+        Ok(Subscribe {
+            channels: extract_channels(value)?,
+            pattern,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for Unsubscribe {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let pattern = validate_variadic_command(&value, &["unsubscribe"], 0)
+            .map(|_| false)
+            .or_else(|_| validate_variadic_command(&value, &["punsubscribe"], 0).map(|_| true))?;
+        Ok(Unsubscribe {
+            channels: extract_channels(value)?,
+            pattern,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for Publish {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["publish"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(Publish {
+            channel: extract_string(args.next())?,
+            message: extract_bytes(args.next())?,
+        })
+    }
+}
+
+fn extract_channels(value: RespArray) -> Result<Vec<String>, CommandError> {
+    extract_args(value, 1)?
+        .into_iter()
+        .map(|arg| extract_string(Some(arg)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+    use tokio_stream::StreamExt;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    fn subscribe(
+        backend: &Backend,
+        conn: &mut ConnectionState,
+        channels: &[&str],
+    ) -> Vec<RespFrame> {
+        Subscribe {
+            channels: channels.iter().map(|c| c.to_string()).collect(),
+            pattern: false,
+        }
+        .execute_each(backend, conn)
+    }
+
+    fn unsubscribe(
+        backend: &Backend,
+        conn: &mut ConnectionState,
+        channels: &[&str],
+    ) -> Vec<RespFrame> {
+        Unsubscribe {
+            channels: channels.iter().map(|c| c.to_string()).collect(),
+            pattern: false,
+        }
+        .execute_each(backend, conn)
+    }
+
+    fn publish(backend: &Backend, channel: &str, message: &str) -> RespFrame {
+        Publish {
+            channel: channel.to_string(),
+            message: message.as_bytes().to_vec(),
+        }
+        .execute(backend)
+    }
+
+    #[test]
+    fn test_publish_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*3\r\n$7\r\npublish\r\n$4\r\nnews\r\n$5\r\nhello\r\n");
+        let cmd: Publish = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.channel, "news");
+        assert_eq!(cmd.message, b"hello");
+
+        let mut buf = BytesMut::from("*1\r\n$11\r\nunsubscribe\r\n");
+        let cmd: Unsubscribe = RespArray::decode(&mut buf)?.try_into()?;
+        assert!(cmd.channels.is_empty());
+        assert!(!cmd.pattern);
+
+        let mut buf = BytesMut::from("*2\r\n$10\r\npsubscribe\r\n$5\r\nh?llo\r\n");
+        let cmd: Subscribe = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.channels, vec!["h?llo".to_string()]);
+        assert!(cmd.pattern);
+        Ok(())
+    }
+
+    #[test]
+    fn test_subscribe_confirmations() {
+        let backend = Backend::new();
+        let mut conn = ConnectionState::new();
+        assert_eq!(
+            subscribe(&backend, &mut conn, &["a", "b", "a"]),
+            vec![
+                confirmation("subscribe", Some("a"), 1),
+                confirmation("subscribe", Some("b"), 2),
+                confirmation("subscribe", Some("a"), 2),
+            ]
+        );
+        assert_eq!(
+            unsubscribe(&backend, &mut conn, &["b", "c"]),
+            vec![
+                confirmation("unsubscribe", Some("b"), 1),
+                confirmation("unsubscribe", Some("c"), 1),
+            ]
+        );
+        assert_eq!(
+            unsubscribe(&backend, &mut conn, &[]),
+            vec![confirmation("unsubscribe", Some("a"), 0)]
+        );
+        assert_eq!(
+            unsubscribe(&backend, &mut conn, &[]),
+            vec![confirmation("unsubscribe", None, 0)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_publish() {
+        let backend = Backend::new();
+        let (mut a, mut b) = (ConnectionState::new(), ConnectionState::new());
+        subscribe(&backend, &mut a, &["news"]);
+        subscribe(&backend, &mut b, &["news", "sports"]);
+
+        assert_eq!(publish(&backend, "news", "hello"), RespFrame::Integer(2));
+        assert_eq!(publish(&backend, "sports", "goal"), RespFrame::Integer(1));
+        assert_eq!(publish(&backend, "weather", "rain"), RespFrame::Integer(0));
+
+        let message = |channel: &str, message: &str| -> RespFrame {
+            RespArray::new(vec![
+                BulkString::new("message").into(),
+                BulkString::new(channel).into(),
+                BulkString::new(message).into(),
+            ])
+            .into()
+        };
+        let (_, received) = a.subscriptions.next().await.unwrap();
+        assert_eq!(received.unwrap(), message("news", "hello"));
+
+        unsubscribe(&backend, &mut a, &["news"]);
+        assert_eq!(publish(&backend, "news", "again"), RespFrame::Integer(1));
+    }
+
+    #[test]
+    fn test_pattern_subscriptions() {
+        let backend = Backend::new();
+        let mut conn = ConnectionState::new();
+        subscribe(&backend, &mut conn, &["news"]);
+        let cmd = Subscribe {
+            channels: vec!["h?llo".to_string()],
+            pattern: true,
+        };
+        assert_eq!(
+            cmd.execute_each(&backend, &mut conn),
+            vec![confirmation("psubscribe", Some("h?llo"), 2)]
+        );
+        assert_eq!(publish(&backend, "hello", "hi"), RespFrame::Integer(1));
+        assert_eq!(publish(&backend, "hallo", "hi"), RespFrame::Integer(1));
+
+        // PUNSUBSCRIBE without arguments leaves the channels alone
+        let cmd = Unsubscribe {
+            channels: vec![],
+            pattern: true,
+        };
+        assert_eq!(
+            cmd.execute_each(&backend, &mut conn),
+            vec![confirmation("punsubscribe", Some("h?llo"), 1)]
+        );
+        assert_eq!(publish(&backend, "hello", "hi"), RespFrame::Integer(0));
+        assert_eq!(conn.subscribed_channels(), vec!["news".to_string()]);
+    }
+}
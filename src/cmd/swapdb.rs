@@ -0,0 +1,121 @@
+use crate::{Backend, RespArray, RespFrame, SimpleError};
+
+use super::{extract_args, parse_number, validate_command, CommandError, CommandExecutor, RESP_OK};
+
+#[derive(Debug)]
+pub struct SwapDb {
+    pub index1: i64,
+    pub index2: i64,
+}
+
+impl CommandExecutor for SwapDb {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let swapped = match (usize::try_from(self.index1), usize::try_from(self.index2)) {
+            (Ok(a), Ok(b)) => backend.swap_db(a, b),
+            _ => false,
+        };
+        if swapped {
+            RESP_OK.clone()
+        } else {
+            SimpleError::new("ERR DB index is out of range").into()
+        }
+    }
+}
+
+impl TryFrom<RespArray> for SwapDb {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["swapdb"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(SwapDb {
+            index1: parse_number(args.next())?,
+            index2: parse_number(args.next())?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{BulkString, RespDecode};
+
+    use super::*;
+
+    fn sorted_keys(backend: &Backend) -> Vec<String> {
+        let mut keys: Vec<String> = backend.read().keys().cloned().collect();
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn test_swapdb_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*3\r\n$6\r\nswapdb\r\n$1\r\n0\r\n$1\r\n1\r\n");
+        let cmd: SwapDb = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!((cmd.index1, cmd.index2), (0, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_swapdb_command() {
+        let db0 = Backend::new();
+        let db1 = db0.select(1).unwrap();
+        db0.set("a".to_string(), BulkString::new("0"));
+        db1.set("b".to_string(), BulkString::new("1"));
+
+        let cmd = SwapDb {
+            index1: 1,
+            index2: 0,
+        };
+        assert_eq!(cmd.execute(&db0), RESP_OK.clone());
+        assert_eq!(sorted_keys(&db0), vec!["b"]);
+        assert_eq!(sorted_keys(&db1), vec!["a"]);
+
+        let cmd = SwapDb {
+            index1: 0,
+            index2: 16,
+        };
+        assert_eq!(
+            cmd.execute(&db0),
+            SimpleError::new("ERR DB index is out of range").into()
+        );
+        assert_eq!(sorted_keys(&db0), vec!["b"]);
+    }
+
+    #[test]
+    fn test_swapdb_has_no_intermediate_state() {
+        let db0 = Backend::new();
+        let db1 = db0.select(1).unwrap();
+        db0.set("a".to_string(), BulkString::new("0"));
+        db0.set("b".to_string(), BulkString::new("0"));
+        db1.set("c".to_string(), BulkString::new("1"));
+
+        let readers: Vec<_> = [db0.clone(), db1.clone()]
+            .into_iter()
+            .map(|backend| {
+                thread::spawn(move || {
+                    for _ in 0..1000 {
+                        let keys = sorted_keys(&backend);
+                        assert!(keys == ["a", "b"] || keys == ["c"], "{:?}", keys);
+                    }
+                })
+            })
+            .collect();
+        for _ in 0..1000 {
+            let cmd = SwapDb {
+                index1: 0,
+                index2: 1,
+            };
+            cmd.execute(&db0);
+        }
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        // an even number of swaps brings everything back
+        assert_eq!(sorted_keys(&db0), vec!["a", "b"]);
+    }
+}
@@ -0,0 +1,134 @@
+use crate::{clock, Backend, RespArray, RespFrame};
+
+use super::{extract_args, extract_string, validate_command, CommandError, CommandExecutor};
+
+/// TTL and PTTL
+#[derive(Debug)]
+pub struct Ttl {
+    pub key: String,
+    pub millis: bool,
+}
+
+impl CommandExecutor for Ttl {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        // write lock: an expired key is removed when it is looked up
+        let mut db = backend.write();
+        let ttl = match db.entry_mut(&self.key) {
+            None => -2,
+            Some(entry) => match entry.expires_at {
+                None => -1,
+                Some(at) => {
+                    let ms = at
+                        .duration_since(clock::now())
+                        .unwrap_or_default()
+                        .as_millis() as i64;
+                    if self.millis {
+                        ms
+                    } else {
+                        // rounded to the nearest second, as redis does
+                        (ms + 500) / 1000
+                    }
+                }
+            },
+        };
+        RespFrame::Integer(ttl)
+    }
+}
+
+impl TryFrom<RespArray> for Ttl {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let millis = validate_command(&value, &["ttl"], 1)
+            .map(|_| false)
+            .or_else(|_| validate_command(&value, &["pttl"], 1).map(|_| true))?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(Ttl {
+            key: extract_string(args.next())?,
+            millis,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{
+        cmd::{Expire, Expiry},
+        BulkString, RespDecode,
+    };
+
+    use super::*;
+
+    fn ttl(backend: &Backend, key: &str, millis: bool) -> RespFrame {
+        Ttl {
+            key: key.to_string(),
+            millis,
+        }
+        .execute(backend)
+    }
+
+    fn set_with_expire(backend: &Backend, key: &str, ms: i64) {
+        backend.set(key.to_string(), BulkString::new("value"));
+        Expire {
+            key: key.to_string(),
+            time: Expiry::In(ms),
+        }
+        .execute(backend);
+    }
+
+    #[test]
+    fn test_ttl_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*2\r\n$3\r\nttl\r\n$3\r\nkey\r\n");
+        let cmd: Ttl = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.key, "key");
+        assert!(!cmd.millis);
+
+        let mut buf = BytesMut::from("*2\r\n$4\r\nPTTL\r\n$3\r\nkey\r\n");
+        let cmd: Ttl = RespArray::decode(&mut buf)?.try_into()?;
+        assert!(cmd.millis);
+        Ok(())
+    }
+
+    #[test]
+    fn test_ttl_without_expiry() {
+        let backend = Backend::new();
+        assert_eq!(ttl(&backend, "key", false), RespFrame::Integer(-2));
+        assert_eq!(ttl(&backend, "key", true), RespFrame::Integer(-2));
+
+        backend.set("key".to_string(), BulkString::new("value"));
+        assert_eq!(ttl(&backend, "key", false), RespFrame::Integer(-1));
+        assert_eq!(ttl(&backend, "key", true), RespFrame::Integer(-1));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_ttl_expired_key() {
+        let backend = Backend::new();
+        set_with_expire(&backend, "key", 1000);
+
+        tokio::time::advance(Duration::from_millis(1000)).await;
+        assert_eq!(ttl(&backend, "key", true), RespFrame::Integer(-2));
+        assert_eq!(ttl(&backend, "key", false), RespFrame::Integer(-2));
+        assert_eq!(backend.get("key"), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_ttl_mid_life() {
+        let backend = Backend::new();
+        set_with_expire(&backend, "key", 10_000);
+        assert_eq!(ttl(&backend, "key", true), RespFrame::Integer(10_000));
+        assert_eq!(ttl(&backend, "key", false), RespFrame::Integer(10));
+
+        tokio::time::advance(Duration::from_millis(3_250)).await;
+        assert_eq!(ttl(&backend, "key", true), RespFrame::Integer(6_750));
+        assert_eq!(ttl(&backend, "key", false), RespFrame::Integer(7));
+
+        tokio::time::advance(Duration::from_millis(6_749)).await;
+        assert_eq!(ttl(&backend, "key", true), RespFrame::Integer(1));
+        assert_eq!(ttl(&backend, "key", false), RespFrame::Integer(0));
+    }
+}
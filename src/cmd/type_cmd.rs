@@ -0,0 +1,77 @@
+use crate::{Backend, RespArray, RespFrame, SimpleString};
+
+use super::{extract_args, extract_string, validate_command, CommandError, CommandExecutor};
+
+#[derive(Debug)]
+pub struct Type {
+    pub key: String,
+}
+
+impl CommandExecutor for Type {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let name = match backend.read().get(&self.key) {
+            Some(value) => value.type_name(),
+            None => "none",
+        };
+        SimpleString::new(name).into()
+    }
+}
+
+impl TryFrom<RespArray> for Type {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["type"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(Type {
+            key: extract_string(args.next())?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet, VecDeque};
+
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{BackendValue, BulkString, RespDecode, ZSet};
+
+    use super::*;
+
+    fn type_of(backend: &Backend, key: &str) -> RespFrame {
+        Type {
+            key: key.to_string(),
+        }
+        .execute(backend)
+    }
+
+    #[test]
+    fn test_type_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*2\r\n$4\r\ntype\r\n$3\r\nkey\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+        let cmd: Type = frame.try_into()?;
+        assert_eq!(cmd.key, "key");
+        Ok(())
+    }
+
+    #[test]
+    fn test_type_command() {
+        let backend = Backend::new();
+        backend.set("string".to_string(), BulkString::new("value"));
+        backend.set("list".to_string(), BackendValue::List(VecDeque::new()));
+        backend.set("hash".to_string(), BackendValue::Hash(HashMap::new()));
+        backend.set("set".to_string(), BackendValue::Set(HashSet::new()));
+        backend.set("zset".to_string(), ZSet::new());
+
+        for name in ["string", "list", "hash", "set", "zset"] {
+            assert_eq!(type_of(&backend, name), SimpleString::new(name).into());
+        }
+        assert_eq!(
+            type_of(&backend, "missing"),
+            SimpleString::new("none").into()
+        );
+    }
+}
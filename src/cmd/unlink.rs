@@ -0,0 +1,84 @@
+use crate::{Backend, RespArray, RespFrame};
+
+use super::{
+    extract_args, extract_string, validate_variadic_command, CommandError, CommandExecutor,
+};
+
+#[derive(Debug)]
+pub struct Unlink {
+    pub keys: Vec<String>,
+}
+
+impl CommandExecutor for Unlink {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        // keys disappear under the write lock, their values are freed in the background
+        let removed: Vec<_> = {
+            let mut db = backend.write();
+            self.keys.iter().filter_map(|key| db.remove(key)).collect()
+        };
+        let count = removed.len();
+        backend.lazy_free(removed);
+        RespFrame::Integer(count as i64)
+    }
+}
+
+impl TryFrom<RespArray> for Unlink {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["unlink"], 1)?;
+
+        let keys = extract_args(value, 1)?
+            .into_iter()
+            .map(|arg| extract_string(Some(arg)))
+            .collect::<Result<_, _>>()?;
+        Ok(Unlink { keys })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{cmd::Exists, BulkString, RespDecode};
+
+    use super::*;
+
+    #[test]
+    fn test_unlink_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*3\r\n$6\r\nunlink\r\n$1\r\na\r\n$1\r\nb\r\n");
+        let cmd: Unlink = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.keys, vec!["a", "b"]);
+        Ok(())
+    }
+
+    // the current thread runtime never polls the lazy free task until the test yields
+    #[tokio::test]
+    async fn test_unlink_is_immediately_visible() {
+        let backend = Backend::new();
+        backend.set("a".to_string(), BulkString::new("1"));
+        backend.set("b".to_string(), BulkString::new("2"));
+
+        let cmd = Unlink {
+            keys: vec!["a".to_string(), "missing".to_string(), "b".to_string()],
+        };
+        assert_eq!(cmd.execute(&backend), RespFrame::Integer(2));
+
+        let cmd = Exists {
+            keys: vec!["a".to_string(), "b".to_string()],
+        };
+        assert_eq!(cmd.execute(&backend), RespFrame::Integer(0));
+        tokio::task::yield_now().await;
+    }
+
+    #[test]
+    fn test_unlink_without_runtime() {
+        let backend = Backend::new();
+        backend.set("a".to_string(), BulkString::new("1"));
+        let cmd = Unlink {
+            keys: vec!["a".to_string()],
+        };
+        assert_eq!(cmd.execute(&backend), RespFrame::Integer(1));
+        assert_eq!(backend.get("a"), None);
+    }
+}
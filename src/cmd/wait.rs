@@ -0,0 +1,128 @@
+use std::time::Duration;
+
+use crate::{Backend, RespArray, RespFrame};
+
+use super::{extract_args, parse_number, validate_command, CommandError, CommandExecutor};
+
+/// WAIT numreplicas timeout, the timeout in milliseconds and 0 to wait forever
+#[derive(Debug)]
+pub struct Wait {
+    pub replicas: usize,
+    pub timeout: Option<Duration>,
+}
+
+impl CommandExecutor for Wait {
+    // without a connection to block, e.g. inside a transaction, only count the replicas that
+    // are already there
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let replication = backend.replication();
+        RespFrame::Integer(replication.acked(replication.offset()) as i64)
+    }
+}
+
+impl Wait {
+    /// wait for the replicas to acknowledge every write made so far
+    pub async fn execute_blocking(self, backend: &Backend) -> RespFrame {
+        let replication = backend.replication();
+        let offset = replication.offset();
+        let acked = replication
+            .wait_for(self.replicas, offset, self.timeout)
+            .await;
+        RespFrame::Integer(acked as i64)
+    }
+}
+
+impl TryFrom<RespArray> for Wait {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["wait"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let replicas = parse_number(args.next())?;
+        let timeout: u64 = parse_number(args.next())?;
+        Ok(Wait {
+            replicas,
+            timeout: (timeout > 0).then(|| Duration::from_millis(timeout)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{network::ReplicaOptions, RespDecode};
+
+    use super::*;
+
+    fn wait(replicas: usize, timeout_ms: u64) -> Wait {
+        Wait {
+            replicas,
+            timeout: (timeout_ms > 0).then(|| Duration::from_millis(timeout_ms)),
+        }
+    }
+
+    #[test]
+    fn test_wait_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*3\r\n$4\r\nwait\r\n$1\r\n2\r\n$3\r\n500\r\n");
+        let cmd: Wait = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.replicas, 2);
+        assert_eq!(cmd.timeout, Some(Duration::from_millis(500)));
+
+        let mut buf = BytesMut::from("*3\r\n$4\r\nwait\r\n$1\r\n0\r\n$2\r\n-1\r\n");
+        let ret: Result<Wait, _> = RespArray::decode(&mut buf)?.try_into();
+        assert!(ret.is_err());
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_without_replicas() {
+        let backend = Backend::new();
+        let start = tokio::time::Instant::now();
+        assert_eq!(
+            wait(0, 0).execute_blocking(&backend).await,
+            RespFrame::Integer(0)
+        );
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        assert_eq!(
+            wait(1, 100).execute_blocking(&backend).await,
+            RespFrame::Integer(0)
+        );
+        assert_eq!(start.elapsed(), Duration::from_millis(100));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_for_acks() {
+        let backend = Backend::new();
+        let replication = backend.replication();
+        let _streams = [
+            replication.add_replica(1, ReplicaOptions::default(), Vec::new(), 1024),
+            replication.add_replica(2, ReplicaOptions::default(), Vec::new(), 1024),
+        ];
+        let offset = replication.advance(64);
+
+        let acker = backend.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            acker.replication().ack(1, offset);
+        });
+        let start = tokio::time::Instant::now();
+        assert_eq!(
+            wait(1, 0).execute_blocking(&backend).await,
+            RespFrame::Integer(1)
+        );
+        assert_eq!(start.elapsed(), Duration::from_millis(10));
+
+        // the second replica never catches up
+        assert_eq!(
+            wait(2, 50).execute_blocking(&backend).await,
+            RespFrame::Integer(1)
+        );
+        assert_eq!(wait(2, 0).execute(&backend), RespFrame::Integer(1));
+
+        replication.remove_replica(1);
+        assert_eq!(wait(0, 0).execute(&backend), RespFrame::Integer(0));
+    }
+}
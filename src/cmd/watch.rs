@@ -0,0 +1,252 @@
+use crate::{
+    network::{ConnectionFlags, ConnectionState},
+    Backend, RespArray, RespFrame, SimpleError,
+};
+
+use super::{
+    extract_args, extract_string, validate_command, validate_variadic_command, CommandError,
+    CommandExecutor, RESP_NO_CONNECTION, RESP_OK,
+};
+
+/// WATCH key [key ...], EXEC aborts if any of them is written to in the meantime
+#[derive(Debug)]
+pub struct Watch {
+    pub keys: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct Unwatch;
+
+impl CommandExecutor for Watch {
+    fn execute(self, _backend: &Backend) -> RespFrame {
+        RESP_NO_CONNECTION.clone()
+    }
+
+    fn execute_on(self, backend: &Backend, conn: &mut ConnectionState) -> RespFrame {
+        if conn.flags.contains(ConnectionFlags::MULTI) {
+            return SimpleError::new("ERR WATCH inside MULTI is not allowed").into();
+        }
+        let mut db = backend.write();
+        for key in self.keys {
+            db.watch(&key, conn.id, conn.watch_tx.clone());
+            conn.watched.push((backend.db_index(), key));
+        }
+        RESP_OK.clone()
+    }
+}
+
+impl CommandExecutor for Unwatch {
+    fn execute(self, _backend: &Backend) -> RespFrame {
+        RESP_NO_CONNECTION.clone()
+    }
+
+    fn execute_on(self, backend: &Backend, conn: &mut ConnectionState) -> RespFrame {
+        unwatch_all(backend, conn);
+        RESP_OK.clone()
+    }
+}
+
+/// whether a key watched by `conn` was written to since WATCH
+pub(super) fn watched_keys_modified(conn: &mut ConnectionState) -> bool {
+    while conn.watch_rx.try_recv().is_ok() {
+        conn.watched_keys_modified = true;
+    }
+    conn.watched_keys_modified
+}
+
+/// forget every key watched by `conn`, as UNWATCH, EXEC and DISCARD do
+pub(super) fn unwatch_all(backend: &Backend, conn: &mut ConnectionState) {
+    for (index, key) in std::mem::take(&mut conn.watched) {
+        if let Some(backend) = backend.select(index) {
+            backend.write().unwatch(&key, conn.id);
+        }
+    }
+    // drop notifications about keys no longer watched
+    while conn.watch_rx.try_recv().is_ok() {}
+    conn.watched_keys_modified = false;
+}
+
+impl TryFrom<RespArray> for Watch {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["watch"], 1)?;
+
+        let keys = extract_args(value, 1)?
+            .into_iter()
+            .map(|arg| extract_string(Some(arg)))
+            .collect::<Result<_, _>>()?;
+        Ok(Watch { keys })
+    }
+}
+
+impl TryFrom<RespArray> for Unwatch {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["unwatch"], 0)?;
+        Ok(Unwatch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        cmd::{Exec, Multi, Persist, Push, SAdd, SRem, Set, Ttl},
+        BackendValue, BulkString, RespNullArray,
+    };
+
+    use super::*;
+
+    fn watch(backend: &Backend, conn: &mut ConnectionState, keys: &[&str]) -> RespFrame {
+        Watch {
+            keys: keys.iter().map(|k| k.to_string()).collect(),
+        }
+        .execute_on(backend, conn)
+    }
+
+    fn set(key: &str, value: &str) -> Set {
+        Set {
+            key: key.to_string(),
+            value: BulkString::new(value),
+        }
+    }
+
+    fn transaction(backend: &Backend, conn: &mut ConnectionState) -> RespFrame {
+        Multi.execute_on(backend, conn);
+        conn.queued.push(set("key", "from exec").into());
+        Exec.execute_on(backend, conn)
+    }
+
+    #[test]
+    fn test_exec_aborts_on_watched_key_write() {
+        let backend = Backend::new();
+        let mut conn = ConnectionState::new();
+        assert_eq!(
+            watch(&backend, &mut conn, &["key", "other"]),
+            RESP_OK.clone()
+        );
+
+        // another client writes the key
+        set("key", "elsewhere").execute(&backend);
+
+        assert_eq!(
+            transaction(&backend, &mut conn),
+            RespFrame::NullArray(RespNullArray)
+        );
+        assert_eq!(
+            backend.get("key"),
+            Some(BulkString::new("elsewhere").into())
+        );
+
+        // EXEC unwatched everything, the next transaction goes through
+        assert!(matches!(
+            transaction(&backend, &mut conn),
+            RespFrame::Array(_)
+        ));
+        assert_eq!(
+            backend.get("key"),
+            Some(BulkString::new("from exec").into())
+        );
+    }
+
+    #[test]
+    fn test_exec_runs_when_watched_keys_are_untouched() {
+        let backend = Backend::new();
+        let mut conn = ConnectionState::new();
+        watch(&backend, &mut conn, &["key"]);
+        set("unrelated", "value").execute(&backend);
+        // the same key in another database is another key
+        set("key", "value").execute(&backend.select(1).unwrap());
+
+        assert!(matches!(
+            transaction(&backend, &mut conn),
+            RespFrame::Array(_)
+        ));
+    }
+
+    #[test]
+    fn test_exec_runs_when_watched_key_is_only_looked_up() {
+        let backend = Backend::new();
+        let mut conn = ConnectionState::new();
+        let members = || BackendValue::Set([b"a".to_vec()].into());
+        backend.set("key".to_string(), members());
+        watch(&backend, &mut conn, &["key"]);
+        // a member that isn't there or already is, a TTL lookup, no expiry to drop and a write
+        // to the wrong type change nothing
+        let srem = |member: &str| SRem {
+            key: "key".to_string(),
+            members: vec![member.as_bytes().to_vec()],
+        };
+        srem("b").execute(&backend);
+        SAdd {
+            key: "key".to_string(),
+            members: vec![b"a".to_vec()],
+        }
+        .execute(&backend);
+        let push = Push {
+            key: "key".to_string(),
+            elements: vec![b"a".to_vec()],
+            left: true,
+        };
+        assert!(matches!(push.execute(&backend), RespFrame::Error(_)));
+        Ttl {
+            key: "key".to_string(),
+            millis: false,
+        }
+        .execute(&backend);
+        Persist {
+            key: "key".to_string(),
+        }
+        .execute(&backend);
+        assert!(matches!(
+            transaction(&backend, &mut conn),
+            RespFrame::Array(_)
+        ));
+
+        backend.set("key".to_string(), members());
+        watch(&backend, &mut conn, &["key"]);
+        srem("a").execute(&backend);
+        assert_eq!(
+            transaction(&backend, &mut conn),
+            RespFrame::NullArray(RespNullArray)
+        );
+    }
+
+    #[test]
+    fn test_unwatch() {
+        let backend = Backend::new();
+        let mut conn = ConnectionState::new();
+        watch(&backend, &mut conn, &["key"]);
+        assert_eq!(Unwatch.execute_on(&backend, &mut conn), RESP_OK.clone());
+        set("key", "elsewhere").execute(&backend);
+
+        assert!(matches!(
+            transaction(&backend, &mut conn),
+            RespFrame::Array(_)
+        ));
+    }
+
+    #[test]
+    fn test_flush_touches_watched_keys() {
+        let backend = Backend::new();
+        let mut conn = ConnectionState::new();
+        set("key", "value").execute(&backend);
+        watch(&backend, &mut conn, &["key"]);
+        backend.flush();
+
+        assert_eq!(
+            transaction(&backend, &mut conn),
+            RespFrame::NullArray(RespNullArray)
+        );
+    }
+
+    #[test]
+    fn test_watch_inside_multi() {
+        let backend = Backend::new();
+        let mut conn = ConnectionState::new();
+        Multi.execute_on(&backend, &mut conn);
+        assert!(matches!(
+            watch(&backend, &mut conn, &["key"]),
+            RespFrame::Error(_)
+        ));
+    }
+}
@@ -0,0 +1,151 @@
+use crate::{Backend, LexBound, RespArray, RespFrame, ScoreBound};
+
+use super::{
+    extract_args, extract_string, validate_command,
+    zset::{parse_lex_bound, parse_score_bound, read_zset},
+    CommandError, CommandExecutor,
+};
+
+#[derive(Debug)]
+pub struct ZCount {
+    pub key: String,
+    pub min: ScoreBound,
+    pub max: ScoreBound,
+}
+
+#[derive(Debug)]
+pub struct ZLexCount {
+    pub key: String,
+    pub min: LexBound,
+    pub max: LexBound,
+}
+
+impl CommandExecutor for ZCount {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        read_zset(backend, &self.key, RespFrame::Integer(0), |zset| {
+            RespFrame::Integer(zset.range_by_score(self.min, self.max).count() as i64)
+        })
+    }
+}
+
+impl CommandExecutor for ZLexCount {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        read_zset(backend, &self.key, RespFrame::Integer(0), |zset| {
+            RespFrame::Integer(zset.range_by_lex(&self.min, &self.max).count() as i64)
+        })
+    }
+}
+
+impl TryFrom<RespArray> for ZCount {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["zcount"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(ZCount {
+            key: extract_string(args.next())?,
+            min: parse_score_bound(args.next())?,
+            max: parse_score_bound(args.next())?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for ZLexCount {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["zlexcount"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(ZLexCount {
+            key: extract_string(args.next())?,
+            min: parse_lex_bound(args.next())?,
+            max: parse_lex_bound(args.next())?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{
+        cmd::{ZAdd, ZAddOptions, RESP_WRONGTYPE},
+        BulkString, RespDecode,
+    };
+
+    use super::*;
+
+    fn decode(cmd: &str) -> RespArray {
+        let args: Vec<&str> = cmd.split(' ').collect();
+        let mut buf = BytesMut::from(format!("*{}\r\n", args.len()).as_str());
+        for arg in args {
+            buf.extend_from_slice(format!("${}\r\n{}\r\n", arg.len(), arg).as_bytes());
+        }
+        RespArray::decode(&mut buf).unwrap()
+    }
+
+    fn zadd(backend: &Backend, members: &[(f64, &str)]) {
+        let cmd = ZAdd {
+            key: "key".to_string(),
+            members: members
+                .iter()
+                .map(|(s, m)| (*s, m.as_bytes().to_vec()))
+                .collect(),
+            options: ZAddOptions::default(),
+        };
+        cmd.execute(backend);
+    }
+
+    fn zcount(backend: &Backend, min: &str, max: &str) -> Result<RespFrame> {
+        let cmd: ZCount = decode(&format!("zcount key {} {}", min, max)).try_into()?;
+        Ok(cmd.execute(backend))
+    }
+
+    fn zlexcount(backend: &Backend, min: &str, max: &str) -> Result<RespFrame> {
+        let cmd: ZLexCount = decode(&format!("zlexcount key {} {}", min, max)).try_into()?;
+        Ok(cmd.execute(backend))
+    }
+
+    #[test]
+    fn test_zcount() -> Result<()> {
+        let backend = Backend::new();
+        zadd(
+            &backend,
+            &[(1.0, "a"), (2.5, "b"), (3.0, "c"), (f64::INFINITY, "d")],
+        );
+        assert_eq!(zcount(&backend, "1", "3")?, RespFrame::Integer(3));
+        assert_eq!(zcount(&backend, "(1", "3")?, RespFrame::Integer(2));
+        assert_eq!(zcount(&backend, "1", "(3")?, RespFrame::Integer(2));
+        assert_eq!(zcount(&backend, "1.5", "2.5")?, RespFrame::Integer(1));
+        assert_eq!(zcount(&backend, "(2.5", "+inf")?, RespFrame::Integer(2));
+        assert_eq!(zcount(&backend, "-inf", "(+inf")?, RespFrame::Integer(3));
+        assert_eq!(zcount(&backend, "-inf", "+inf")?, RespFrame::Integer(4));
+        assert_eq!(zcount(&backend, "3", "1")?, RespFrame::Integer(0));
+        assert!(zcount(&backend, "a", "1").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_zlexcount() -> Result<()> {
+        let backend = Backend::new();
+        zadd(&backend, &[(0.0, "a"), (0.0, "b"), (0.0, "c"), (0.0, "d")]);
+        assert_eq!(zlexcount(&backend, "-", "+")?, RespFrame::Integer(4));
+        assert_eq!(zlexcount(&backend, "[b", "[c")?, RespFrame::Integer(2));
+        assert_eq!(zlexcount(&backend, "(b", "+")?, RespFrame::Integer(2));
+        assert_eq!(zlexcount(&backend, "-", "(c")?, RespFrame::Integer(2));
+        assert_eq!(zlexcount(&backend, "[c", "[b")?, RespFrame::Integer(0));
+        assert!(zlexcount(&backend, "b", "+").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_zcount_missing_and_wrongtype() -> Result<()> {
+        let backend = Backend::new();
+        assert_eq!(zcount(&backend, "-inf", "+inf")?, RespFrame::Integer(0));
+        assert_eq!(zlexcount(&backend, "-", "+")?, RespFrame::Integer(0));
+        backend.set("key".to_string(), BulkString::new("value"));
+        assert_eq!(zcount(&backend, "-inf", "+inf")?, RESP_WRONGTYPE.clone());
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+use crate::{Backend, BackendValue, RespArray, RespFrame};
+
+use super::{
+    extract_args, extract_string, parse_number, validate_variadic_command, zsetops::Input,
+    CommandError, CommandExecutor, RESP_WRONGTYPE,
+};
+
+#[derive(Debug)]
+pub struct ZInterCard {
+    pub keys: Vec<String>,
+    // stop counting once this many common members are found, 0 for no limit
+    pub limit: usize,
+}
+
+impl CommandExecutor for ZInterCard {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let db = backend.read();
+        let mut inputs = Vec::with_capacity(self.keys.len());
+        for key in self.keys.iter() {
+            match db.get(key) {
+                Some(BackendValue::ZSet(zset)) => inputs.push(Input::ZSet(zset)),
+                Some(BackendValue::Set(set)) => inputs.push(Input::Set(set)),
+                Some(_) => return RESP_WRONGTYPE.clone(),
+                // any missing key makes the intersection empty
+                None => return RespFrame::Integer(0),
+            }
+        }
+        let limit = match self.limit {
+            0 => usize::MAX,
+            n => n,
+        };
+        // count over the smallest input without building the intersection
+        inputs.sort_by_key(|input| input.len());
+        let Some((smallest, rest)) = inputs.split_first() else {
+            return RespFrame::Integer(0);
+        };
+        let count = smallest
+            .iter()
+            .filter(|(m, _)| rest.iter().all(|input| input.score(m).is_some()))
+            .take(limit)
+            .count();
+        RespFrame::Integer(count as i64)
+    }
+}
+
+impl TryFrom<RespArray> for ZInterCard {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["zintercard"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let numkeys: usize = parse_number(args.next())?;
+        if numkeys == 0 || numkeys > args.len() {
+            return Err(CommandError::InvalidArgument(
+                "numkeys must be positive and at most the number of keys given".to_string(),
+            ));
+        }
+        let keys = args
+            .by_ref()
+            .take(numkeys)
+            .map(|arg| extract_string(Some(arg)))
+            .collect::<Result<_, _>>()?;
+        let mut cmd = ZInterCard { keys, limit: 0 };
+        while let Some(arg) = args.next() {
+            match extract_string(Some(arg))?.to_ascii_lowercase().as_str() {
+                "limit" => cmd.limit = parse_number(args.next())?,
+                v => {
+                    return Err(CommandError::InvalidArgument(format!(
+                        "Invalid option: {}",
+                        v
+                    )))
+                }
+            }
+        }
+        Ok(cmd)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{
+        cmd::{ZAdd, ZAddOptions},
+        BulkString, RespDecode,
+    };
+
+    use super::*;
+
+    fn setup() -> Backend {
+        let backend = Backend::new();
+        let zadd = |key: &str, members: std::ops::Range<i32>| {
+            let cmd = ZAdd {
+                key: key.to_string(),
+                members: members
+                    .map(|m| (m as f64, m.to_string().into_bytes()))
+                    .collect(),
+                options: ZAddOptions::default(),
+            };
+            cmd.execute(&backend);
+        };
+        // 10 common members: 10..20
+        zadd("a", 0..20);
+        zadd("b", 10..30);
+        zadd("c", 5..25);
+        backend
+    }
+
+    fn zintercard(backend: &Backend, keys: &[&str], limit: usize) -> RespFrame {
+        ZInterCard {
+            keys: keys.iter().map(|k| k.to_string()).collect(),
+            limit,
+        }
+        .execute(backend)
+    }
+
+    #[test]
+    fn test_zintercard_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from(
+            "*6\r\n$10\r\nzintercard\r\n$1\r\n2\r\n$1\r\na\r\n$1\r\nb\r\n$5\r\nLIMIT\r\n$1\r\n5\r\n",
+        );
+        let cmd: ZInterCard = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.keys, vec!["a", "b"]);
+        assert_eq!(cmd.limit, 5);
+
+        let mut buf =
+            BytesMut::from("*4\r\n$10\r\nzintercard\r\n$1\r\n3\r\n$1\r\na\r\n$1\r\nb\r\n");
+        let ret: Result<ZInterCard, _> = RespArray::decode(&mut buf)?.try_into();
+        assert!(matches!(ret, Err(CommandError::InvalidArgument(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_zintercard_limit() {
+        let backend = setup();
+        assert_eq!(
+            zintercard(&backend, &["a", "b", "c"], 0),
+            RespFrame::Integer(10)
+        );
+        assert_eq!(
+            zintercard(&backend, &["a", "b", "c"], 3),
+            RespFrame::Integer(3)
+        );
+        assert_eq!(
+            zintercard(&backend, &["a", "b", "c"], 50),
+            RespFrame::Integer(10)
+        );
+        assert_eq!(zintercard(&backend, &["a", "c"], 0), RespFrame::Integer(15));
+    }
+
+    #[test]
+    fn test_zintercard_missing_and_wrongtype() {
+        let backend = setup();
+        assert_eq!(
+            zintercard(&backend, &["a", "missing"], 0),
+            RespFrame::Integer(0)
+        );
+        backend.set("str".to_string(), BulkString::new("value"));
+        assert_eq!(
+            zintercard(&backend, &["a", "str"], 0),
+            RESP_WRONGTYPE.clone()
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use crate::{Backend, BulkString, RespArray, RespFrame, RespNullBulkString, ZSet};
+
+use super::{
+    extract_args, extract_bytes, extract_string, validate_variadic_command,
+    zset::{format_score, read_zset},
+    CommandError, CommandExecutor,
+};
+
+#[derive(Debug)]
+pub struct ZMScore {
+    pub key: String,
+    pub members: Vec<Vec<u8>>,
+}
+
+impl CommandExecutor for ZMScore {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let empty = ZSet::new();
+        let reply = |zset: &ZSet| -> RespFrame {
+            let ret: Vec<RespFrame> = self
+                .members
+                .iter()
+                .map(|m| match zset.score(m) {
+                    Some(score) => BulkString::new(format_score(score)).into(),
+                    None => RespNullBulkString.into(),
+                })
+                .collect();
+            RespArray::new(ret).into()
+        };
+        read_zset(backend, &self.key, reply(&empty), reply)
+    }
+}
+
+impl TryFrom<RespArray> for ZMScore {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["zmscore"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = extract_string(args.next())?;
+        let members = args
+            .map(|arg| extract_bytes(Some(arg)))
+            .collect::<Result<_, _>>()?;
+        Ok(ZMScore { key, members })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{
+        cmd::{ZAdd, ZAddOptions, RESP_WRONGTYPE},
+        RespDecode,
+    };
+
+    use super::*;
+
+    fn zmscore(backend: &Backend, members: &[&str]) -> RespFrame {
+        ZMScore {
+            key: "key".to_string(),
+            members: members.iter().map(|m| m.as_bytes().to_vec()).collect(),
+        }
+        .execute(backend)
+    }
+
+    #[test]
+    fn test_zmscore_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*4\r\n$7\r\nzmscore\r\n$3\r\nkey\r\n$1\r\na\r\n$1\r\nb\r\n");
+        let cmd: ZMScore = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.key, "key");
+        assert_eq!(cmd.members, vec![b"a".to_vec(), b"b".to_vec()]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_zmscore() {
+        let backend = Backend::new();
+        let cmd = ZAdd {
+            key: "key".to_string(),
+            members: vec![(1.5, b"a".to_vec()), (f64::NEG_INFINITY, b"b".to_vec())],
+            options: ZAddOptions::default(),
+        };
+        cmd.execute(&backend);
+
+        assert_eq!(
+            zmscore(&backend, &["b", "missing", "a", "a"]),
+            RespArray::new(vec![
+                BulkString::new("-inf").into(),
+                RespNullBulkString.into(),
+                BulkString::new("1.5").into(),
+                BulkString::new("1.5").into(),
+            ])
+            .into()
+        );
+    }
+
+    #[test]
+    fn test_zmscore_missing_and_wrongtype() {
+        let backend = Backend::new();
+        assert_eq!(
+            zmscore(&backend, &["a", "b"]),
+            RespArray::new(vec![RespNullBulkString.into(), RespNullBulkString.into()]).into()
+        );
+        backend.set("key".to_string(), BulkString::new("value"));
+        assert_eq!(zmscore(&backend, &["a"]), RESP_WRONGTYPE.clone());
+    }
+}
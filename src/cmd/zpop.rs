@@ -0,0 +1,182 @@
+use crate::{Backend, BackendValue, BulkString, Db, RespArray, RespFrame};
+
+use super::{
+    extract_args, extract_string, parse_number, validate_variadic_command, zset::format_score,
+    CommandError, CommandExecutor, RESP_WRONGTYPE,
+};
+
+#[derive(Debug)]
+pub struct ZPop {
+    pub key: String,
+    pub count: usize,
+    // ZPOPMAX: pop the highest scores instead of the lowest
+    pub max: bool,
+}
+
+impl CommandExecutor for ZPop {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let mut db = backend.write();
+        match zpop(&mut db, &self.key, self.count, self.max) {
+            Ok(popped) => {
+                let ret: Vec<RespFrame> = popped
+                    .into_iter()
+                    .flat_map(|(member, score)| {
+                        [
+                            BulkString::new(member).into(),
+                            BulkString::new(format_score(score)).into(),
+                        ]
+                    })
+                    .collect();
+                RespArray::new(ret).into()
+            }
+            Err(e) => e,
+        }
+    }
+}
+
+// pop up to `count` members from one end of the sorted set at `key`, removing the key once
+// it is empty. a missing key pops nothing
+pub(super) fn zpop(
+    db: &mut Db,
+    key: &str,
+    count: usize,
+    max: bool,
+) -> Result<Vec<(Vec<u8>, f64)>, RespFrame> {
+    let zset = match db.get_mut(key) {
+        Some(BackendValue::ZSet(zset)) => zset,
+        Some(_) => return Err(RESP_WRONGTYPE.clone()),
+        None => return Ok(vec![]),
+    };
+    let popped: Vec<_> = std::iter::from_fn(|| if max { zset.pop_max() } else { zset.pop_min() })
+        .take(count)
+        .collect();
+    let now_empty = zset.is_empty();
+    if !popped.is_empty() {
+        db.signal_modified(key);
+    }
+    if now_empty {
+        db.remove(key);
+    }
+    Ok(popped)
+}
+
+impl TryFrom<RespArray> for ZPop {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let max = validate_variadic_command(&value, &["zpopmin"], 1)
+            .map(|_| false)
+            .or_else(|_| validate_variadic_command(&value, &["zpopmax"], 1).map(|_| true))?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = extract_string(args.next())?;
+        let count = match args.next() {
+            Some(arg) => parse_number(Some(arg))?,
+            None => 1,
+        };
+        if args.next().is_some() {
+            return Err(CommandError::InvalidArgument(
+                "Too many arguments".to_string(),
+            ));
+        }
+        Ok(ZPop { key, count, max })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{
+        cmd::{ZAdd, ZAddOptions},
+        RespDecode,
+    };
+
+    use super::*;
+
+    fn array(items: &[&str]) -> RespFrame {
+        let items: Vec<RespFrame> = items.iter().map(|i| BulkString::new(*i).into()).collect();
+        RespArray::new(items).into()
+    }
+
+    fn zpop(backend: &Backend, count: usize, max: bool) -> RespFrame {
+        ZPop {
+            key: "key".to_string(),
+            count,
+            max,
+        }
+        .execute(backend)
+    }
+
+    #[test]
+    fn test_zpop_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from("*3\r\n$7\r\nzpopmax\r\n$3\r\nkey\r\n$1\r\n2\r\n");
+        let cmd: ZPop = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.key, "key");
+        assert_eq!(cmd.count, 2);
+        assert!(cmd.max);
+
+        let mut buf = BytesMut::from("*2\r\n$7\r\nzpopmin\r\n$3\r\nkey\r\n");
+        let cmd: ZPop = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.count, 1);
+        assert!(!cmd.max);
+        Ok(())
+    }
+
+    #[test]
+    fn test_zpop() {
+        let backend = Backend::new();
+        let cmd = ZAdd {
+            key: "key".to_string(),
+            members: vec![
+                (1.0, b"a".to_vec()),
+                (2.0, b"b".to_vec()),
+                (3.0, b"c".to_vec()),
+            ],
+            options: ZAddOptions::default(),
+        };
+        cmd.execute(&backend);
+
+        assert_eq!(zpop(&backend, 1, false), array(&["a", "1"]));
+        assert_eq!(zpop(&backend, 5, true), array(&["c", "3", "b", "2"]));
+        assert_eq!(backend.get("key"), None);
+        assert_eq!(zpop(&backend, 1, true), array(&[]));
+
+        backend.set("key".to_string(), BulkString::new("value"));
+        assert_eq!(zpop(&backend, 1, true), RESP_WRONGTYPE.clone());
+    }
+
+    #[test]
+    fn test_zpop_concurrent() {
+        let backend = Backend::new();
+        let cmd = ZAdd {
+            key: "key".to_string(),
+            members: (0..1000)
+                .map(|i| (i as f64, i.to_string().into_bytes()))
+                .collect(),
+            options: ZAddOptions::default(),
+        };
+        cmd.execute(&backend);
+
+        // every member is popped by exactly one thread
+        let popped: Vec<usize> = std::thread::scope(|s| {
+            let handles: Vec<_> = (0..4)
+                .map(|i| {
+                    let backend = &backend;
+                    s.spawn(move || {
+                        let mut n = 0;
+                        while let RespFrame::Array(ret) = zpop(backend, 3, i % 2 == 0) {
+                            if ret.is_empty() {
+                                break;
+                            }
+                            n += ret.len() / 2;
+                        }
+                        n
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        assert_eq!(popped.iter().sum::<usize>(), 1000);
+    }
+}
@@ -0,0 +1,220 @@
+use rand::{seq::IteratorRandom, Rng};
+
+use crate::{Backend, BulkString, RespArray, RespFrame, RespNullBulkString};
+
+use super::{
+    extract_args, extract_string, parse_number, validate_variadic_command,
+    zset::{format_score, read_zset},
+    CommandError, CommandExecutor,
+};
+
+#[derive(Debug)]
+pub struct ZRandMember {
+    pub key: String,
+    // None replies with a single member, Some with an array: a positive count picks distinct
+    // members, a negative one allows the same member to be picked again
+    pub count: Option<i64>,
+    pub with_scores: bool,
+}
+
+impl CommandExecutor for ZRandMember {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let missing = match self.count {
+            Some(_) => RespArray::new(vec![]).into(),
+            None => RespNullBulkString.into(),
+        };
+        read_zset(backend, &self.key, missing, |zset| {
+            let mut rng = rand::thread_rng();
+            let Some(count) = self.count else {
+                return match zset.iter().choose(&mut rng) {
+                    Some((member, _)) => BulkString::new(member).into(),
+                    None => RespNullBulkString.into(),
+                };
+            };
+
+            let len = zset.len();
+            let picks = count.unsigned_abs() as usize;
+            let picked: Vec<(&[u8], f64)> = if count >= 0 {
+                // reservoir sampling: one pass, only the picked members are kept
+                zset.iter().choose_multiple(&mut rng, picks)
+            } else if len == 0 {
+                vec![]
+            } else if picks <= len / 8 {
+                // a few picks from a large set: walk to each one rather than index the set
+                (0..picks)
+                    .filter_map(|_| zset.iter().nth(rng.gen_range(0..len)))
+                    .collect()
+            } else {
+                let members: Vec<_> = zset.iter().collect();
+                (0..picks).map(|_| members[rng.gen_range(0..len)]).collect()
+            };
+
+            let mut ret = Vec::with_capacity(picked.len() * if self.with_scores { 2 } else { 1 });
+            for (member, score) in picked {
+                ret.push(BulkString::new(member).into());
+                if self.with_scores {
+                    ret.push(BulkString::new(format_score(score)).into());
+                }
+            }
+            RespArray::new(ret).into()
+        })
+    }
+}
+
+impl TryFrom<RespArray> for ZRandMember {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["zrandmember"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = extract_string(args.next())?;
+        let count = match args.next() {
+            Some(arg) => Some(parse_number(Some(arg))?),
+            None => None,
+        };
+        let with_scores = match args.next() {
+            Some(arg) => match extract_string(Some(arg))?.to_ascii_lowercase().as_str() {
+                "withscores" => true,
+                v => {
+                    return Err(CommandError::InvalidArgument(format!(
+                        "Invalid option: {}",
+                        v
+                    )))
+                }
+            },
+            None => false,
+        };
+        if args.next().is_some() {
+            return Err(CommandError::InvalidArgument(
+                "Too many arguments".to_string(),
+            ));
+        }
+        Ok(ZRandMember {
+            key,
+            count,
+            with_scores,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{
+        cmd::{ZAdd, ZAddOptions},
+        RespDecode,
+    };
+
+    use super::*;
+
+    fn setup(n: usize) -> Backend {
+        let backend = Backend::new();
+        let cmd = ZAdd {
+            key: "key".to_string(),
+            members: (0..n)
+                .map(|i| (i as f64, format!("m{}", i).into_bytes()))
+                .collect(),
+            options: ZAddOptions::default(),
+        };
+        cmd.execute(&backend);
+        backend
+    }
+
+    fn zrandmember(backend: &Backend, count: Option<i64>, with_scores: bool) -> Vec<String> {
+        let ret = ZRandMember {
+            key: "key".to_string(),
+            count,
+            with_scores,
+        }
+        .execute(backend);
+        let frames = match ret {
+            RespFrame::Array(array) => array.0,
+            frame => vec![frame],
+        };
+        frames
+            .into_iter()
+            .map(|frame| {
+                let RespFrame::BulkString(s) = frame else {
+                    panic!("expected a bulk string");
+                };
+                String::from_utf8_lossy(&s).to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_zrandmember_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from(
+            "*4\r\n$11\r\nzrandmember\r\n$3\r\nkey\r\n$2\r\n-3\r\n$10\r\nWITHSCORES\r\n",
+        );
+        let cmd: ZRandMember = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(cmd.key, "key");
+        assert_eq!(cmd.count, Some(-3));
+        assert!(cmd.with_scores);
+        Ok(())
+    }
+
+    #[test]
+    fn test_zrandmember_distinct_and_repeated() {
+        let backend = setup(5);
+        let picked = zrandmember(&backend, Some(3), false);
+        assert_eq!(picked.len(), 3);
+        assert_eq!(picked.iter().collect::<HashSet<_>>().len(), 3);
+        assert_eq!(zrandmember(&backend, Some(10), false).len(), 5);
+
+        let picked = zrandmember(&backend, Some(-20), false);
+        assert_eq!(picked.len(), 20);
+        assert!(picked.iter().collect::<HashSet<_>>().len() <= 5);
+    }
+
+    #[test]
+    fn test_zrandmember_with_scores() {
+        let backend = setup(5);
+        let picked = zrandmember(&backend, Some(-8), true);
+        assert_eq!(picked.len(), 16);
+        for pair in picked.chunks(2) {
+            assert_eq!(pair[0][1..], pair[1]);
+        }
+    }
+
+    #[test]
+    fn test_zrandmember_distribution() {
+        // 4000 draws over 4 members: each is expected 1000 times, far outside this window
+        // only with negligible probability
+        let backend = setup(4);
+        let mut single: HashMap<String, usize> = HashMap::new();
+        for _ in 0..4000 {
+            *single
+                .entry(zrandmember(&backend, None, false).remove(0))
+                .or_default() += 1;
+        }
+        let mut repeated: HashMap<String, usize> = HashMap::new();
+        for member in zrandmember(&backend, Some(-4000), false) {
+            *repeated.entry(member).or_default() += 1;
+        }
+        for counts in [single, repeated] {
+            assert_eq!(counts.len(), 4);
+            assert!(
+                counts.values().all(|n| (800..1200).contains(n)),
+                "{:?}",
+                counts
+            );
+        }
+    }
+
+    #[test]
+    fn test_zrandmember_missing_key() {
+        let backend = Backend::new();
+        let cmd = ZRandMember {
+            key: "key".to_string(),
+            count: None,
+            with_scores: false,
+        };
+        assert_eq!(cmd.execute(&backend), RespNullBulkString.into());
+        assert!(zrandmember(&backend, Some(3), false).is_empty());
+    }
+}
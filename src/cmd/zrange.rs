@@ -0,0 +1,317 @@
+use crate::{Backend, BulkString, LexBound, RespArray, RespFrame, ScoreBound, ZSet};
+
+use super::{
+    extract_args, extract_string,
+    list::list_range,
+    parse_number, validate_variadic_command,
+    zset::{format_score, parse_lex_bound, parse_score_bound, read_zset},
+    CommandError, CommandExecutor,
+};
+
+#[derive(Debug, PartialEq)]
+pub enum ZRangeBy {
+    // 0-based, possibly negative ranks, both inclusive
+    Rank(i64, i64),
+    Score(ScoreBound, ScoreBound),
+    Lex(LexBound, LexBound),
+}
+
+/// ZRANGE key start stop [BYSCORE | BYLEX] [REV] [LIMIT offset count] [WITHSCORES]
+#[derive(Debug)]
+pub struct ZRange {
+    pub key: String,
+    // score and lex ranges are always stored as (min, max), whatever order REV takes them in
+    pub by: ZRangeBy,
+    pub rev: bool,
+    // offset and count, a negative count returns everything past the offset
+    pub limit: Option<(usize, i64)>,
+    pub with_scores: bool,
+}
+
+impl CommandExecutor for ZRange {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        read_zset(backend, &self.key, RespArray::new(vec![]).into(), |zset| {
+            let mut ret = Vec::new();
+            for (member, score) in self.query(zset) {
+                ret.push(BulkString::new(member).into());
+                if self.with_scores {
+                    ret.push(BulkString::new(format_score(score)).into());
+                }
+            }
+            RespArray::new(ret).into()
+        })
+    }
+}
+
+impl ZRange {
+    /// The selected members in reply order, with LIMIT applied.
+    pub(super) fn query<'a>(&self, zset: &'a ZSet) -> Vec<(&'a [u8], f64)> {
+        let members: Vec<(&[u8], f64)> = match &self.by {
+            ZRangeBy::Rank(start, stop) => match list_range(zset.len(), *start, *stop) {
+                Some((start, stop)) => {
+                    let n = stop - start + 1;
+                    if self.rev {
+                        zset.iter().rev().skip(start).take(n).collect()
+                    } else {
+                        zset.iter().skip(start).take(n).collect()
+                    }
+                }
+                None => vec![],
+            },
+            ZRangeBy::Score(min, max) => ordered(zset.range_by_score(*min, *max), self.rev),
+            ZRangeBy::Lex(min, max) => ordered(zset.range_by_lex(min, max), self.rev),
+        };
+
+        let (offset, count) = self.limit.unwrap_or((0, -1));
+        let count = usize::try_from(count).unwrap_or(usize::MAX);
+        members.into_iter().skip(offset).take(count).collect()
+    }
+}
+
+fn ordered<'a>(members: impl Iterator<Item = (&'a [u8], f64)>, rev: bool) -> Vec<(&'a [u8], f64)> {
+    let mut members: Vec<_> = members.collect();
+    if rev {
+        members.reverse();
+    }
+    members
+}
+
+impl TryFrom<RespArray> for ZRange {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["zrange"], 3)?;
+        parse_range(extract_args(value, 1)?.into_iter())
+    }
+}
+
+// - "key start stop [BYSCORE | BYLEX] [REV] [LIMIT offset count] [WITHSCORES]"
+pub(super) fn parse_range(
+    mut args: impl Iterator<Item = RespFrame>,
+) -> Result<ZRange, CommandError> {
+    let key = extract_string(args.next())?;
+    let (start, stop) = (args.next(), args.next());
+
+    let (mut by_score, mut by_lex, mut rev, mut limit, mut with_scores) =
+        (false, false, false, None, false);
+    while let Some(arg) = args.next() {
+        match extract_string(Some(arg))?.to_ascii_lowercase().as_str() {
+            "byscore" => by_score = true,
+            "bylex" => by_lex = true,
+            "rev" => rev = true,
+            "limit" => limit = Some((parse_number(args.next())?, parse_number(args.next())?)),
+            "withscores" => with_scores = true,
+            v => {
+                return Err(CommandError::InvalidArgument(format!(
+                    "Invalid option: {}",
+                    v
+                )))
+            }
+        }
+    }
+    if by_score && by_lex {
+        return Err(CommandError::InvalidArgument(
+            "BYSCORE and BYLEX options at the same time are not compatible".to_string(),
+        ));
+    }
+    if limit.is_some() && !by_score && !by_lex {
+        return Err(CommandError::InvalidArgument(
+            "LIMIT is only supported in combination with either BYSCORE or BYLEX".to_string(),
+        ));
+    }
+    if with_scores && by_lex {
+        return Err(CommandError::InvalidArgument(
+            "WITHSCORES not supported in combination with BYLEX".to_string(),
+        ));
+    }
+
+    // REV takes score and lex ranges as max then min
+    let (start, stop) = if rev && (by_score || by_lex) {
+        (stop, start)
+    } else {
+        (start, stop)
+    };
+    let by = if by_score {
+        ZRangeBy::Score(parse_score_bound(start)?, parse_score_bound(stop)?)
+    } else if by_lex {
+        ZRangeBy::Lex(parse_lex_bound(start)?, parse_lex_bound(stop)?)
+    } else {
+        ZRangeBy::Rank(parse_number(start)?, parse_number(stop)?)
+    };
+    Ok(ZRange {
+        key,
+        by,
+        rev,
+        limit,
+        with_scores,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{
+        cmd::{ZAdd, ZAddOptions},
+        RespDecode,
+    };
+
+    use super::*;
+
+    // a:1 b:2 c:3 d:4 e:5
+    fn setup() -> Backend {
+        let backend = Backend::new();
+        let cmd = ZAdd {
+            key: "key".to_string(),
+            members: ["a", "b", "c", "d", "e"]
+                .iter()
+                .enumerate()
+                .map(|(i, m)| (i as f64 + 1.0, m.as_bytes().to_vec()))
+                .collect(),
+            options: ZAddOptions::default(),
+        };
+        cmd.execute(&backend);
+        backend
+    }
+
+    fn zrange(
+        backend: &Backend,
+        by: ZRangeBy,
+        rev: bool,
+        limit: Option<(usize, i64)>,
+    ) -> RespFrame {
+        ZRange {
+            key: "key".to_string(),
+            by,
+            rev,
+            limit,
+            with_scores: false,
+        }
+        .execute(backend)
+    }
+
+    fn array(items: &[&str]) -> RespFrame {
+        let items: Vec<RespFrame> = items.iter().map(|i| BulkString::new(*i).into()).collect();
+        RespArray::new(items).into()
+    }
+
+    fn parse(cmd: &str) -> Result<ZRange, CommandError> {
+        let args: Vec<&str> = cmd.split(' ').collect();
+        let mut buf = BytesMut::from(format!("*{}\r\n", args.len()).as_str());
+        for arg in args {
+            buf.extend_from_slice(format!("${}\r\n{}\r\n", arg.len(), arg).as_bytes());
+        }
+        RespArray::decode(&mut buf).unwrap().try_into()
+    }
+
+    #[test]
+    fn test_zrange_try_from_resp_array() -> Result<()> {
+        let cmd = parse("zrange key 0 -1")?;
+        assert_eq!(cmd.by, ZRangeBy::Rank(0, -1));
+        assert!(!cmd.rev);
+
+        let cmd = parse("zrange key (5 1 BYSCORE REV LIMIT 1 2 WITHSCORES")?;
+        assert_eq!(
+            cmd.by,
+            ZRangeBy::Score(ScoreBound::Inclusive(1.0), ScoreBound::Exclusive(5.0))
+        );
+        assert!(cmd.rev);
+        assert_eq!(cmd.limit, Some((1, 2)));
+        assert!(cmd.with_scores);
+
+        let cmd = parse("zrange key [a (c BYLEX")?;
+        assert_eq!(
+            cmd.by,
+            ZRangeBy::Lex(
+                LexBound::Inclusive(b"a".to_vec()),
+                LexBound::Exclusive(b"c".to_vec())
+            )
+        );
+
+        assert!(parse("zrange key 0 -1 LIMIT 0 1").is_err());
+        assert!(parse("zrange key - + BYLEX WITHSCORES").is_err());
+        assert!(parse("zrange key 0 1 BYSCORE BYLEX").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_zrange_by_rank() {
+        let backend = setup();
+        assert_eq!(
+            zrange(&backend, ZRangeBy::Rank(0, -1), false, None),
+            array(&["a", "b", "c", "d", "e"])
+        );
+        assert_eq!(
+            zrange(&backend, ZRangeBy::Rank(1, 2), false, None),
+            array(&["b", "c"])
+        );
+        assert_eq!(
+            zrange(&backend, ZRangeBy::Rank(0, 1), true, None),
+            array(&["e", "d"])
+        );
+        assert_eq!(
+            zrange(&backend, ZRangeBy::Rank(-2, -1), true, None),
+            array(&["b", "a"])
+        );
+        assert_eq!(
+            zrange(&backend, ZRangeBy::Rank(3, 1), false, None),
+            array(&[])
+        );
+    }
+
+    #[test]
+    fn test_zrange_by_score() {
+        let backend = setup();
+        let by = || ZRangeBy::Score(ScoreBound::Exclusive(1.0), ScoreBound::Inclusive(4.0));
+        assert_eq!(zrange(&backend, by(), false, None), array(&["b", "c", "d"]));
+        assert_eq!(zrange(&backend, by(), true, None), array(&["d", "c", "b"]));
+        assert_eq!(zrange(&backend, by(), false, Some((1, 1))), array(&["c"]));
+        assert_eq!(
+            zrange(&backend, by(), true, Some((1, -1))),
+            array(&["c", "b"])
+        );
+
+        let cmd = ZRange {
+            key: "key".to_string(),
+            by: ZRangeBy::Score(
+                ScoreBound::Inclusive(4.0),
+                ScoreBound::Inclusive(f64::INFINITY),
+            ),
+            rev: false,
+            limit: None,
+            with_scores: true,
+        };
+        assert_eq!(cmd.execute(&backend), array(&["d", "4", "e", "5"]));
+    }
+
+    #[test]
+    fn test_zrange_by_lex() {
+        let backend = Backend::new();
+        let cmd = ZAdd {
+            key: "key".to_string(),
+            members: ["a", "b", "c", "d"]
+                .iter()
+                .map(|m| (0.0, m.as_bytes().to_vec()))
+                .collect(),
+            options: ZAddOptions::default(),
+        };
+        cmd.execute(&backend);
+
+        let by = || ZRangeBy::Lex(LexBound::Inclusive(b"b".to_vec()), LexBound::PosInf);
+        assert_eq!(zrange(&backend, by(), false, None), array(&["b", "c", "d"]));
+        assert_eq!(zrange(&backend, by(), true, None), array(&["d", "c", "b"]));
+        assert_eq!(
+            zrange(&backend, by(), true, Some((0, 2))),
+            array(&["d", "c"])
+        );
+    }
+
+    #[test]
+    fn test_zrange_missing_key() {
+        let backend = Backend::new();
+        assert_eq!(
+            zrange(&backend, ZRangeBy::Rank(0, -1), false, None),
+            array(&[])
+        );
+    }
+}
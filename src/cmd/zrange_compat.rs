@@ -0,0 +1,211 @@
+use crate::{Backend, RespArray, RespFrame};
+
+use super::{
+    extract_args, extract_string, parse_number, validate_variadic_command,
+    zset::{parse_lex_bound, parse_score_bound},
+    CommandError, CommandExecutor, ZRange, ZRangeBy,
+};
+
+/// ZRANGEBYSCORE and ZREVRANGEBYSCORE, the pre 6.2 spelling of ZRANGE ... BYSCORE
+#[derive(Debug)]
+pub struct ZRangeByScore(pub ZRange);
+
+/// ZRANGEBYLEX and ZREVRANGEBYLEX, the pre 6.2 spelling of ZRANGE ... BYLEX
+#[derive(Debug)]
+pub struct ZRangeByLex(pub ZRange);
+
+impl CommandExecutor for ZRangeByScore {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        self.0.execute(backend)
+    }
+}
+
+impl CommandExecutor for ZRangeByLex {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        self.0.execute(backend)
+    }
+}
+
+impl TryFrom<RespArray> for ZRangeByScore {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let rev = validate_variadic_command(&value, &["zrangebyscore"], 3)
+            .map(|_| false)
+            .or_else(|_| {
+                validate_variadic_command(&value, &["zrevrangebyscore"], 3).map(|_| true)
+            })?;
+        translate(value, rev, true).map(ZRangeByScore)
+    }
+}
+
+impl TryFrom<RespArray> for ZRangeByLex {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let rev = validate_variadic_command(&value, &["zrangebylex"], 3)
+            .map(|_| false)
+            .or_else(|_| validate_variadic_command(&value, &["zrevrangebylex"], 3).map(|_| true))?;
+        translate(value, rev, false).map(ZRangeByLex)
+    }
+}
+
+// - "<cmd> key min max [WITHSCORES] [LIMIT offset count]", max before min for the REV variants
+fn translate(value: RespArray, rev: bool, by_score: bool) -> Result<ZRange, CommandError> {
+    let mut args = extract_args(value, 1)?.into_iter();
+    let key = extract_string(args.next())?;
+    let (start, stop) = (args.next(), args.next());
+    let (min, max) = if rev { (stop, start) } else { (start, stop) };
+    let by = if by_score {
+        ZRangeBy::Score(parse_score_bound(min)?, parse_score_bound(max)?)
+    } else {
+        ZRangeBy::Lex(parse_lex_bound(min)?, parse_lex_bound(max)?)
+    };
+
+    let mut cmd = ZRange {
+        key,
+        by,
+        rev,
+        limit: None,
+        with_scores: false,
+    };
+    while let Some(arg) = args.next() {
+        match extract_string(Some(arg))?.to_ascii_lowercase().as_str() {
+            "withscores" if by_score => cmd.with_scores = true,
+            "limit" => cmd.limit = Some((parse_number(args.next())?, parse_number(args.next())?)),
+            v => {
+                return Err(CommandError::InvalidArgument(format!(
+                    "Invalid option: {}",
+                    v
+                )))
+            }
+        }
+    }
+    Ok(cmd)
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{
+        cmd::{ZAdd, ZAddOptions},
+        BulkString, LexBound, RespDecode, ScoreBound,
+    };
+
+    use super::*;
+
+    fn decode(cmd: &str) -> RespArray {
+        let args: Vec<&str> = cmd.split(' ').collect();
+        let mut buf = BytesMut::from(format!("*{}\r\n", args.len()).as_str());
+        for arg in args {
+            buf.extend_from_slice(format!("${}\r\n{}\r\n", arg.len(), arg).as_bytes());
+        }
+        RespArray::decode(&mut buf).unwrap()
+    }
+
+    fn zadd(backend: &Backend, members: &[(f64, &str)]) {
+        let cmd = ZAdd {
+            key: "key".to_string(),
+            members: members
+                .iter()
+                .map(|(s, m)| (*s, m.as_bytes().to_vec()))
+                .collect(),
+            options: ZAddOptions::default(),
+        };
+        cmd.execute(backend);
+    }
+
+    fn array(items: &[&str]) -> RespFrame {
+        let items: Vec<RespFrame> = items.iter().map(|i| BulkString::new(*i).into()).collect();
+        RespArray::new(items).into()
+    }
+
+    #[test]
+    fn test_zrangebyscore_try_from_resp_array() -> Result<()> {
+        let ZRangeByScore(cmd) = decode("zrangebyscore key (1.0 +inf").try_into()?;
+        assert_eq!(cmd.key, "key");
+        assert_eq!(
+            cmd.by,
+            ZRangeBy::Score(
+                ScoreBound::Exclusive(1.0),
+                ScoreBound::Inclusive(f64::INFINITY)
+            )
+        );
+        assert!(!cmd.rev);
+        assert!(!cmd.with_scores);
+
+        let ZRangeByScore(cmd) =
+            decode("zrevrangebyscore key 10 (2 WITHSCORES LIMIT 1 5").try_into()?;
+        assert_eq!(
+            cmd.by,
+            ZRangeBy::Score(ScoreBound::Exclusive(2.0), ScoreBound::Inclusive(10.0))
+        );
+        assert!(cmd.rev);
+        assert!(cmd.with_scores);
+        assert_eq!(cmd.limit, Some((1, 5)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_zrangebylex_try_from_resp_array() -> Result<()> {
+        let ZRangeByLex(cmd) = decode("zrangebylex key - (d").try_into()?;
+        assert_eq!(cmd.key, "key");
+        assert_eq!(
+            cmd.by,
+            ZRangeBy::Lex(LexBound::NegInf, LexBound::Exclusive(b"d".to_vec()))
+        );
+
+        let ZRangeByLex(cmd) = decode("zrevrangebylex key + [b LIMIT 0 2").try_into()?;
+        assert_eq!(
+            cmd.by,
+            ZRangeBy::Lex(LexBound::Inclusive(b"b".to_vec()), LexBound::PosInf)
+        );
+        assert!(cmd.rev);
+        assert_eq!(cmd.limit, Some((0, 2)));
+
+        let ret: Result<ZRangeByLex, _> = decode("zrangebylex key - + WITHSCORES").try_into();
+        assert!(ret.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_zrangebyscore() -> Result<()> {
+        let backend = Backend::new();
+        zadd(&backend, &[(1.0, "one"), (2.0, "two"), (3.0, "three")]);
+
+        let cmd: ZRangeByScore = decode("zrangebyscore key -inf +inf").try_into()?;
+        assert_eq!(cmd.execute(&backend), array(&["one", "two", "three"]));
+
+        let cmd: ZRangeByScore = decode("zrangebyscore key (1 3 WITHSCORES").try_into()?;
+        assert_eq!(cmd.execute(&backend), array(&["two", "2", "three", "3"]));
+
+        let cmd: ZRangeByScore = decode("zrevrangebyscore key +inf -inf LIMIT 1 1").try_into()?;
+        assert_eq!(cmd.execute(&backend), array(&["two"]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_zrangebylex() -> Result<()> {
+        let backend = Backend::new();
+        zadd(
+            &backend,
+            &[
+                (0.0, "apple"),
+                (0.0, "banana"),
+                (0.0, "cherry"),
+                (0.0, "zucchini"),
+            ],
+        );
+
+        // members starting with a to y
+        let cmd: ZRangeByLex = decode("zrangebylex key [a [z").try_into()?;
+        assert_eq!(cmd.execute(&backend), array(&["apple", "banana", "cherry"]));
+
+        let cmd: ZRangeByLex = decode("zrevrangebylex key + (banana").try_into()?;
+        assert_eq!(cmd.execute(&backend), array(&["zucchini", "cherry"]));
+
+        let cmd: ZRangeByLex = decode("zrangebylex key - + LIMIT 1 2").try_into()?;
+        assert_eq!(cmd.execute(&backend), array(&["banana", "cherry"]));
+        Ok(())
+    }
+}
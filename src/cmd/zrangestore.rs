@@ -0,0 +1,177 @@
+use crate::{Backend, BackendValue, RespArray, RespFrame, ZSet};
+
+use super::{
+    extract_args, extract_string, validate_variadic_command, zrange::parse_range, CommandError,
+    CommandExecutor, ZRange, RESP_WRONGTYPE,
+};
+
+/// ZRANGESTORE dst src min max [BYSCORE | BYLEX] [REV] [LIMIT offset count]
+#[derive(Debug)]
+pub struct ZRangeStore {
+    pub dst: String,
+    pub range: ZRange,
+}
+
+impl CommandExecutor for ZRangeStore {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let mut db = backend.write();
+        // copy the range out first, dst may be the source itself
+        let zset = match db.get(&self.range.key) {
+            Some(BackendValue::ZSet(src)) => {
+                let mut zset = ZSet::new();
+                for (member, score) in self.range.query(src) {
+                    zset.insert(member.to_vec(), score);
+                }
+                zset
+            }
+            Some(_) => return RESP_WRONGTYPE.clone(),
+            None => ZSet::new(),
+        };
+        let len = zset.len();
+        if zset.is_empty() {
+            db.remove(&self.dst);
+        } else {
+            db.insert(self.dst.clone(), zset.into());
+            backend.signal_key(&self.dst);
+        }
+        RespFrame::Integer(len as i64)
+    }
+}
+
+impl TryFrom<RespArray> for ZRangeStore {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["zrangestore"], 4)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let dst = extract_string(args.next())?;
+        let range = parse_range(args)?;
+        if range.with_scores {
+            return Err(CommandError::InvalidArgument(
+                "Invalid option: withscores".to_string(),
+            ));
+        }
+        Ok(ZRangeStore { dst, range })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{
+        cmd::{ZAdd, ZAddOptions, ZRangeBy},
+        BulkString, RespDecode, ScoreBound,
+    };
+
+    use super::*;
+
+    // a:1 b:2 c:3 d:4 e:5
+    fn setup() -> Backend {
+        let backend = Backend::new();
+        let cmd = ZAdd {
+            key: "src".to_string(),
+            members: ["a", "b", "c", "d", "e"]
+                .iter()
+                .enumerate()
+                .map(|(i, m)| (i as f64 + 1.0, m.as_bytes().to_vec()))
+                .collect(),
+            options: ZAddOptions::default(),
+        };
+        cmd.execute(&backend);
+        backend
+    }
+
+    fn parse(cmd: &str) -> Result<ZRangeStore, CommandError> {
+        let args: Vec<&str> = cmd.split(' ').collect();
+        let mut buf = BytesMut::from(format!("*{}\r\n", args.len()).as_str());
+        for arg in args {
+            buf.extend_from_slice(format!("${}\r\n{}\r\n", arg.len(), arg).as_bytes());
+        }
+        RespArray::decode(&mut buf).unwrap().try_into()
+    }
+
+    fn members(backend: &Backend, key: &str) -> Vec<(Vec<u8>, f64)> {
+        match backend.read().get(key) {
+            Some(BackendValue::ZSet(zset)) => zset.iter().map(|(m, s)| (m.to_vec(), s)).collect(),
+            _ => panic!("{} is not a sorted set", key),
+        }
+    }
+
+    #[test]
+    fn test_zrangestore_try_from_resp_array() -> Result<()> {
+        let cmd = parse("zrangestore dst src (5 1 BYSCORE REV LIMIT 1 2")?;
+        assert_eq!(cmd.dst, "dst");
+        assert_eq!(cmd.range.key, "src");
+        assert_eq!(
+            cmd.range.by,
+            ZRangeBy::Score(ScoreBound::Inclusive(1.0), ScoreBound::Exclusive(5.0))
+        );
+        assert!(cmd.range.rev);
+        assert_eq!(cmd.range.limit, Some((1, 2)));
+
+        assert!(parse("zrangestore dst src 0 -1 WITHSCORES").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_zrangestore() -> Result<()> {
+        let backend = setup();
+        assert_eq!(
+            parse("zrangestore dst src 1 3")?.execute(&backend),
+            RespFrame::Integer(3)
+        );
+        assert_eq!(
+            members(&backend, "dst"),
+            vec![
+                (b"b".to_vec(), 2.0),
+                (b"c".to_vec(), 3.0),
+                (b"d".to_vec(), 4.0)
+            ]
+        );
+
+        // overwrites a destination of another type
+        backend.set("str".to_string(), BulkString::new("value"));
+        assert_eq!(
+            parse("zrangestore str src 5 1 BYSCORE REV LIMIT 0 2")?.execute(&backend),
+            RespFrame::Integer(2)
+        );
+        assert_eq!(
+            members(&backend, "str"),
+            vec![(b"d".to_vec(), 4.0), (b"e".to_vec(), 5.0)]
+        );
+
+        // an empty range removes the destination
+        assert_eq!(
+            parse("zrangestore dst src 10 20")?.execute(&backend),
+            RespFrame::Integer(0)
+        );
+        assert_eq!(backend.get("dst"), None);
+
+        assert_eq!(
+            parse("zrangestore dst str2 0 -1")?.execute(&backend),
+            RespFrame::Integer(0)
+        );
+        backend.set("str2".to_string(), BulkString::new("value"));
+        assert_eq!(
+            parse("zrangestore dst str2 0 -1")?.execute(&backend),
+            RESP_WRONGTYPE.clone()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_zrangestore_same_key() -> Result<()> {
+        let backend = setup();
+        assert_eq!(
+            parse("zrangestore src src -2 -1")?.execute(&backend),
+            RespFrame::Integer(2)
+        );
+        assert_eq!(
+            members(&backend, "src"),
+            vec![(b"d".to_vec(), 4.0), (b"e".to_vec(), 5.0)]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,678 @@
+use crate::{
+    Backend, BackendValue, BulkString, LexBound, RespArray, RespFrame, RespNullArray,
+    RespNullBulkString, ScoreBound, SimpleError, ZSet,
+};
+
+use super::{
+    extract_args, extract_bytes, extract_string, parse_number, validate_command,
+    validate_variadic_command, CommandError, CommandExecutor, RESP_WRONGTYPE,
+};
+
+#[derive(Debug)]
+pub struct ZAdd {
+    pub key: String,
+    pub members: Vec<(f64, Vec<u8>)>,
+    pub options: ZAddOptions,
+}
+
+// ZADD flags, NX/XX and GT/LT/NX are mutually exclusive
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ZAddOptions {
+    // only add new members
+    pub nx: bool,
+    // only update existing members
+    pub xx: bool,
+    // only update when the new score is greater
+    pub gt: bool,
+    // only update when the new score is less
+    pub lt: bool,
+    // count changed scores along with added members
+    pub ch: bool,
+    // increment the score of a single member and reply with the new score, like ZINCRBY
+    pub incr: bool,
+}
+
+#[derive(Debug)]
+pub struct ZScore {
+    pub key: String,
+    pub member: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub struct ZCard {
+    pub key: String,
+}
+
+#[derive(Debug)]
+pub struct ZRank {
+    pub key: String,
+    pub member: Vec<u8>,
+    // ZREVRANK: rank by descending score
+    pub rev: bool,
+    pub with_score: bool,
+}
+
+#[derive(Debug)]
+pub struct ZIncrBy {
+    pub key: String,
+    pub increment: f64,
+    pub member: Vec<u8>,
+}
+
+impl CommandExecutor for ZAdd {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        zadd(backend, self.key, self.members, self.options)
+    }
+}
+
+impl CommandExecutor for ZScore {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        read_zset(
+            backend,
+            &self.key,
+            RespNullBulkString.into(),
+            |zset| match zset.score(&self.member) {
+                Some(score) => BulkString::new(format_score(score)).into(),
+                None => RespNullBulkString.into(),
+            },
+        )
+    }
+}
+
+impl CommandExecutor for ZCard {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        read_zset(backend, &self.key, RespFrame::Integer(0), |zset| {
+            RespFrame::Integer(zset.len() as i64)
+        })
+    }
+}
+
+impl CommandExecutor for ZRank {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let missing = || -> RespFrame {
+            if self.with_score {
+                RespNullArray.into()
+            } else {
+                RespNullBulkString.into()
+            }
+        };
+        read_zset(backend, &self.key, missing(), |zset| {
+            let Some(rank) = zset.rank(&self.member) else {
+                return missing();
+            };
+            let rank = if self.rev {
+                zset.len() - 1 - rank
+            } else {
+                rank
+            };
+            let rank = RespFrame::Integer(rank as i64);
+            match zset.score(&self.member) {
+                Some(score) if self.with_score => {
+                    RespArray::new(vec![rank, BulkString::new(format_score(score)).into()]).into()
+                }
+                _ => rank,
+            }
+        })
+    }
+}
+
+impl CommandExecutor for ZIncrBy {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let options = ZAddOptions {
+            incr: true,
+            ..Default::default()
+        };
+        zadd(
+            backend,
+            self.key,
+            vec![(self.increment, self.member)],
+            options,
+        )
+    }
+}
+
+impl TryFrom<RespArray> for ZAdd {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_variadic_command(&value, &["zadd"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter().peekable();
+        let key = extract_string(args.next())?;
+        let mut options = ZAddOptions::default();
+        // flags come before the first score
+        while let Some(RespFrame::BulkString(arg)) = args.peek() {
+            let flag = match arg.to_ascii_lowercase().as_slice() {
+                b"nx" => &mut options.nx,
+                b"xx" => &mut options.xx,
+                b"gt" => &mut options.gt,
+                b"lt" => &mut options.lt,
+                b"ch" => &mut options.ch,
+                b"incr" => &mut options.incr,
+                _ => break,
+            };
+            *flag = true;
+            args.next();
+        }
+        if options.nx && options.xx {
+            return Err(CommandError::InvalidArgument(
+                "XX and NX options at the same time are not compatible".to_string(),
+            ));
+        }
+        if [options.gt, options.lt, options.nx]
+            .iter()
+            .filter(|f| **f)
+            .count()
+            > 1
+        {
+            return Err(CommandError::InvalidArgument(
+                "GT, LT, and/or NX options at the same time are not compatible".to_string(),
+            ));
+        }
+
+        let args: Vec<RespFrame> = args.collect();
+        if args.is_empty() || !args.len().is_multiple_of(2) {
+            return Err(CommandError::InvalidArgument(
+                "Expected score and member pairs".to_string(),
+            ));
+        }
+        if options.incr && args.len() != 2 {
+            return Err(CommandError::InvalidArgument(
+                "INCR option supports a single increment-element pair".to_string(),
+            ));
+        }
+        let mut args = args.into_iter();
+        let mut members = Vec::with_capacity(args.len() / 2);
+        while args.len() > 0 {
+            let score = parse_score(args.next())?;
+            let member = extract_bytes(args.next())?;
+            members.push((score, member));
+        }
+        Ok(ZAdd {
+            key,
+            members,
+            options,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for ZScore {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["zscore"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(ZScore {
+            key: extract_string(args.next())?,
+            member: extract_bytes(args.next())?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for ZCard {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["zcard"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(ZCard {
+            key: extract_string(args.next())?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for ZRank {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let rev = validate_variadic_command(&value, &["zrank"], 2)
+            .map(|_| false)
+            .or_else(|_| validate_variadic_command(&value, &["zrevrank"], 2).map(|_| true))?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = extract_string(args.next())?;
+        let member = extract_bytes(args.next())?;
+        let with_score = match args.next() {
+            Some(arg) => match extract_string(Some(arg))?.to_ascii_lowercase().as_str() {
+                "withscore" => true,
+                v => {
+                    return Err(CommandError::InvalidArgument(format!(
+                        "Invalid option: {}",
+                        v
+                    )))
+                }
+            },
+            None => false,
+        };
+        if args.next().is_some() {
+            return Err(CommandError::InvalidArgument(
+                "Too many arguments".to_string(),
+            ));
+        }
+        Ok(ZRank {
+            key,
+            member,
+            rev,
+            with_score,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for ZIncrBy {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["zincrby"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(ZIncrBy {
+            key: extract_string(args.next())?,
+            increment: parse_score(args.next())?,
+            member: extract_bytes(args.next())?,
+        })
+    }
+}
+
+// shared by ZADD, ZINCRBY and GEOADD. replies with the number of new (or, with CH, changed)
+// members, or with INCR the new score, null if the flags prevented the update
+pub(super) fn zadd(
+    backend: &Backend,
+    key: String,
+    members: Vec<(f64, Vec<u8>)>,
+    options: ZAddOptions,
+) -> RespFrame {
+    let mut db = backend.write();
+    // a missing key is only stored once a member is added, XX must not leave an empty set
+    let mut created = None;
+    let zset = match db.get_mut(&key) {
+        Some(BackendValue::ZSet(zset)) => zset,
+        Some(_) => return RESP_WRONGTYPE.clone(),
+        None => created.insert(ZSet::new()),
+    };
+
+    let mut added = 0;
+    let mut changed = 0;
+    let mut incremented = None;
+    for (score, member) in members {
+        let old = zset.score(&member);
+        let score = match (options.incr, old) {
+            (true, Some(old)) => old + score,
+            _ => score,
+        };
+        if score.is_nan() {
+            return SimpleError::new("ERR resulting score is not a number (NaN)").into();
+        }
+        let allowed = match old {
+            None => !options.xx,
+            Some(old) => {
+                !(options.nx || (options.gt && score <= old) || (options.lt && score >= old))
+            }
+        };
+        if !allowed {
+            continue;
+        }
+        match old {
+            None => added += 1,
+            Some(old) if old != score => changed += 1,
+            Some(_) => {}
+        }
+        zset.insert(member, score);
+        incremented = Some(score);
+    }
+    match created {
+        Some(zset) if !zset.is_empty() => {
+            db.insert(key.clone(), zset.into());
+        }
+        _ if added + changed > 0 => db.signal_modified(&key),
+        _ => {}
+    }
+    if added > 0 {
+        backend.signal_key(&key);
+    }
+
+    if options.incr {
+        return match incremented {
+            Some(score) => BulkString::new(format_score(score)).into(),
+            None => RespNullBulkString.into(),
+        };
+    }
+    if options.ch {
+        RespFrame::Integer(added + changed)
+    } else {
+        RespFrame::Integer(added)
+    }
+}
+
+// run `f` on the sorted set at `key` under the read lock
+pub(super) fn read_zset(
+    backend: &Backend,
+    key: &str,
+    missing: RespFrame,
+    f: impl FnOnce(&ZSet) -> RespFrame,
+) -> RespFrame {
+    match backend.read().get(key) {
+        Some(BackendValue::ZSet(zset)) => f(zset),
+        Some(_) => RESP_WRONGTYPE.clone(),
+        None => missing,
+    }
+}
+
+fn parse_score(frame: Option<RespFrame>) -> Result<f64, CommandError> {
+    let score: f64 = parse_number(frame)?;
+    if score.is_nan() {
+        return Err(CommandError::InvalidArgument(
+            "Score is not a valid float".to_string(),
+        ));
+    }
+    Ok(score)
+}
+
+// - score bound: "1.5" (inclusive), "(1.5" (exclusive), "-inf", "+inf"
+pub(super) fn parse_score_bound(frame: Option<RespFrame>) -> Result<ScoreBound, CommandError> {
+    let s = extract_string(frame)?;
+    let (exclusive, v) = match s.strip_prefix('(') {
+        Some(v) => (true, v),
+        None => (false, s.as_str()),
+    };
+    let v: f64 = v
+        .parse()
+        .map_err(|_| CommandError::InvalidArgument("Min or max is not a float".to_string()))?;
+    if v.is_nan() {
+        return Err(CommandError::InvalidArgument(
+            "Min or max is not a float".to_string(),
+        ));
+    }
+    Ok(if exclusive {
+        ScoreBound::Exclusive(v)
+    } else {
+        ScoreBound::Inclusive(v)
+    })
+}
+
+// - lex bound: "[a" (inclusive), "(a" (exclusive), "-", "+"
+pub(super) fn parse_lex_bound(frame: Option<RespFrame>) -> Result<LexBound, CommandError> {
+    let mut v = extract_bytes(frame)?;
+    match v.first() {
+        Some(b'-') if v.len() == 1 => Ok(LexBound::NegInf),
+        Some(b'+') if v.len() == 1 => Ok(LexBound::PosInf),
+        Some(b'[') => Ok(LexBound::Inclusive(v.split_off(1))),
+        Some(b'(') => Ok(LexBound::Exclusive(v.split_off(1))),
+        _ => Err(CommandError::InvalidArgument(
+            "Min or max not valid string range item".to_string(),
+        )),
+    }
+}
+
+// as "%.17g" would, but with the fewest digits that read back as the same score: an exponent
+// below -4 or from 17 on is written out, e.g. 1e+300 and 1e-07
+pub(super) fn format_score(score: f64) -> String {
+    if score.is_infinite() {
+        return if score > 0.0 { "inf" } else { "-inf" }.to_string();
+    }
+    let scientific = format!("{:e}", score);
+    let (mantissa, exponent) = scientific
+        .split_once('e')
+        .expect("{:e} always has an exponent");
+    let exponent: i32 = exponent.parse().expect("{:e} exponents are integers");
+    if (-4..17).contains(&exponent) {
+        score.to_string()
+    } else {
+        let sign = if exponent < 0 { '-' } else { '+' };
+        format!("{}e{}{:02}", mantissa, sign, exponent.abs())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_zadd_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from(
+            "*6\r\n$4\r\nzadd\r\n$3\r\nkey\r\n$1\r\n1\r\n$1\r\na\r\n$3\r\n2.5\r\n$1\r\nb\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+        let zadd: ZAdd = frame.try_into()?;
+        assert_eq!(zadd.key, "key");
+        assert_eq!(
+            zadd.members,
+            vec![(1.0, b"a".to_vec()), (2.5, b"b".to_vec())]
+        );
+        Ok(())
+    }
+
+    fn zadd_with(backend: &Backend, options: ZAddOptions, members: &[(f64, &str)]) -> RespFrame {
+        let cmd = ZAdd {
+            key: "key".to_string(),
+            members: members
+                .iter()
+                .map(|(s, m)| (*s, m.as_bytes().to_vec()))
+                .collect(),
+            options,
+        };
+        cmd.execute(backend)
+    }
+
+    fn zscore(backend: &Backend, member: &str) -> RespFrame {
+        ZScore {
+            key: "key".to_string(),
+            member: member.as_bytes().to_vec(),
+        }
+        .execute(backend)
+    }
+
+    #[test]
+    fn test_zadd_flags_try_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::from(
+            "*7\r\n$4\r\nzadd\r\n$3\r\nkey\r\n$2\r\nXX\r\n$2\r\nGT\r\n$2\r\nch\r\n$1\r\n1\r\n$1\r\na\r\n",
+        );
+        let cmd: ZAdd = RespArray::decode(&mut buf)?.try_into()?;
+        assert_eq!(
+            cmd.options,
+            ZAddOptions {
+                xx: true,
+                gt: true,
+                ch: true,
+                ..Default::default()
+            }
+        );
+        assert_eq!(cmd.members, vec![(1.0, b"a".to_vec())]);
+
+        let mut buf = BytesMut::from(
+            "*6\r\n$4\r\nzadd\r\n$3\r\nkey\r\n$2\r\nNX\r\n$2\r\nGT\r\n$1\r\n1\r\n$1\r\na\r\n",
+        );
+        let ret: Result<ZAdd, _> = RespArray::decode(&mut buf)?.try_into();
+        assert!(ret.is_err());
+
+        let mut buf = BytesMut::from(
+            "*7\r\n$4\r\nzadd\r\n$3\r\nkey\r\n$4\r\nINCR\r\n$1\r\n1\r\n$1\r\na\r\n$1\r\n2\r\n$1\r\nb\r\n",
+        );
+        let ret: Result<ZAdd, _> = RespArray::decode(&mut buf)?.try_into();
+        assert!(ret.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_zadd_nx_xx() {
+        let backend = Backend::new();
+        zadd_with(&backend, ZAddOptions::default(), &[(1.0, "a")]);
+
+        let nx = ZAddOptions {
+            nx: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            zadd_with(&backend, nx, &[(5.0, "a"), (2.0, "b")]),
+            RespFrame::Integer(1)
+        );
+        assert_eq!(zscore(&backend, "a"), BulkString::new("1").into());
+
+        let xx = ZAddOptions {
+            xx: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            zadd_with(&backend, xx, &[(5.0, "a"), (3.0, "c")]),
+            RespFrame::Integer(0)
+        );
+        assert_eq!(zscore(&backend, "a"), BulkString::new("5").into());
+        assert_eq!(zscore(&backend, "c"), RespNullBulkString.into());
+
+        // XX on a missing key doesn't create it
+        let cmd = ZAdd {
+            key: "missing".to_string(),
+            members: vec![(1.0, b"a".to_vec())],
+            options: xx,
+        };
+        assert_eq!(cmd.execute(&backend), RespFrame::Integer(0));
+        assert_eq!(backend.get("missing"), None);
+    }
+
+    #[test]
+    fn test_zadd_gt_lt_ch() {
+        let backend = Backend::new();
+        zadd_with(&backend, ZAddOptions::default(), &[(5.0, "a"), (5.0, "b")]);
+
+        let gt_ch = ZAddOptions {
+            gt: true,
+            ch: true,
+            ..Default::default()
+        };
+        // a goes up, b would go down, c is new
+        assert_eq!(
+            zadd_with(&backend, gt_ch, &[(6.0, "a"), (4.0, "b"), (1.0, "c")]),
+            RespFrame::Integer(2)
+        );
+        assert_eq!(zscore(&backend, "a"), BulkString::new("6").into());
+        assert_eq!(zscore(&backend, "b"), BulkString::new("5").into());
+
+        let lt = ZAddOptions {
+            lt: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            zadd_with(&backend, lt, &[(7.0, "a"), (4.0, "b")]),
+            RespFrame::Integer(0)
+        );
+        assert_eq!(zscore(&backend, "a"), BulkString::new("6").into());
+        assert_eq!(zscore(&backend, "b"), BulkString::new("4").into());
+    }
+
+    #[test]
+    fn test_zadd_incr_and_zincrby() {
+        let backend = Backend::new();
+        let incr = ZAddOptions {
+            incr: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            zadd_with(&backend, incr, &[(1.5, "a")]),
+            BulkString::new("1.5").into()
+        );
+        assert_eq!(
+            zadd_with(&backend, incr, &[(1.0, "a")]),
+            BulkString::new("2.5").into()
+        );
+
+        let nx_incr = ZAddOptions { nx: true, ..incr };
+        assert_eq!(
+            zadd_with(&backend, nx_incr, &[(1.0, "a")]),
+            RespNullBulkString.into()
+        );
+
+        let cmd = ZIncrBy {
+            key: "key".to_string(),
+            increment: -0.5,
+            member: b"a".to_vec(),
+        };
+        assert_eq!(cmd.execute(&backend), BulkString::new("2").into());
+
+        let cmd = ZIncrBy {
+            key: "key".to_string(),
+            increment: f64::INFINITY,
+            member: b"inf".to_vec(),
+        };
+        assert_eq!(cmd.execute(&backend), BulkString::new("inf").into());
+        let cmd = ZIncrBy {
+            key: "key".to_string(),
+            increment: f64::NEG_INFINITY,
+            member: b"inf".to_vec(),
+        };
+        assert_eq!(
+            cmd.execute(&backend),
+            SimpleError::new("ERR resulting score is not a number (NaN)").into()
+        );
+    }
+
+    #[test]
+    fn test_zcard_zrank() {
+        let backend = Backend::new();
+        zadd_with(
+            &backend,
+            ZAddOptions::default(),
+            &[(3.0, "c"), (1.0, "a"), (2.0, "b")],
+        );
+
+        let cmd = ZCard {
+            key: "key".to_string(),
+        };
+        assert_eq!(cmd.execute(&backend), RespFrame::Integer(3));
+
+        let zrank = |member: &str, rev, with_score| {
+            ZRank {
+                key: "key".to_string(),
+                member: member.as_bytes().to_vec(),
+                rev,
+                with_score,
+            }
+            .execute(&backend)
+        };
+        assert_eq!(zrank("a", false, false), RespFrame::Integer(0));
+        assert_eq!(zrank("c", false, false), RespFrame::Integer(2));
+        assert_eq!(zrank("a", true, false), RespFrame::Integer(2));
+        assert_eq!(
+            zrank("b", true, true),
+            RespArray::new(vec![RespFrame::Integer(1), BulkString::new("2").into()]).into()
+        );
+        assert_eq!(zrank("z", false, false), RespNullBulkString.into());
+        assert_eq!(zrank("z", false, true), RespNullArray.into());
+    }
+
+    #[test]
+    fn test_zset_wrongtype() {
+        let backend = Backend::new();
+        backend.set("key".to_string(), BulkString::new("value"));
+        assert_eq!(
+            zadd_with(&backend, ZAddOptions::default(), &[(1.0, "a")]),
+            RESP_WRONGTYPE.clone()
+        );
+        assert_eq!(zscore(&backend, "a"), RESP_WRONGTYPE.clone());
+        let cmd = ZCard {
+            key: "key".to_string(),
+        };
+        assert_eq!(cmd.execute(&backend), RESP_WRONGTYPE.clone());
+    }
+
+    #[test]
+    fn test_format_score() {
+        assert_eq!(format_score(1.0), "1");
+        assert_eq!(format_score(-2.5), "-2.5");
+        assert_eq!(format_score(0.1), "0.1");
+        assert_eq!(format_score(0.0001), "0.0001");
+        assert_eq!(format_score(1e16), "10000000000000000");
+        assert_eq!(format_score(1e17), "1e+17");
+        assert_eq!(format_score(1e300), "1e+300");
+        assert_eq!(format_score(-1.5e300), "-1.5e+300");
+        assert_eq!(format_score(1e-7), "1e-07");
+        assert_eq!(format_score(1.25e-5), "1.25e-05");
+        assert_eq!(format_score(f64::INFINITY), "inf");
+        assert_eq!(format_score(f64::NEG_INFINITY), "-inf");
+        // whatever is written reads back as the same score
+        for score in [1e300, 1e-7, 0.1 + 0.2, f64::MAX, f64::MIN_POSITIVE] {
+            assert_eq!(format_score(score).parse::<f64>(), Ok(score));
+        }
+    }
+}
@@ -0,0 +1,437 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{Backend, BackendValue, BulkString, Db, RespArray, RespFrame, ZSet};
+
+use super::{
+    extract_args, extract_string, parse_number, validate_variadic_command, zset::format_score,
+    CommandError, CommandExecutor, SetOp, RESP_WRONGTYPE,
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregate {
+    #[default]
+    Sum,
+    Min,
+    Max,
+}
+
+/// ZDIFF, ZUNION, ZINTER and their STORE variants
+#[derive(Debug)]
+pub struct ZCombine {
+    pub op: SetOp,
+    pub keys: Vec<String>,
+    // one multiplier per key, ZUNION and ZINTER only
+    pub weights: Option<Vec<f64>>,
+    pub aggregate: Aggregate,
+    pub with_scores: bool,
+    // the *STORE variants write the result here instead of replying with it
+    pub dst: Option<String>,
+}
+
+// a plain set takes part in ZUNION and ZINTER with every score at 1
+pub(super) enum Input<'a> {
+    ZSet(&'a ZSet),
+    Set(&'a HashSet<Vec<u8>>),
+    Empty,
+}
+
+impl CommandExecutor for ZCombine {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match &self.dst {
+            None => match self.combine(&backend.read()) {
+                Ok(zset) => {
+                    let mut ret = Vec::new();
+                    for (member, score) in zset.iter() {
+                        ret.push(BulkString::new(member).into());
+                        if self.with_scores {
+                            ret.push(BulkString::new(format_score(score)).into());
+                        }
+                    }
+                    RespArray::new(ret).into()
+                }
+                Err(e) => e,
+            },
+            Some(dst) => {
+                // one write lock covers reading the sources and storing the result
+                let mut db = backend.write();
+                let zset = match self.combine(&db) {
+                    Ok(zset) => zset,
+                    Err(e) => return e,
+                };
+                let len = zset.len();
+                if zset.is_empty() {
+                    db.remove(dst);
+                } else {
+                    db.insert(dst.clone(), zset.into());
+                    backend.signal_key(dst);
+                }
+                RespFrame::Integer(len as i64)
+            }
+        }
+    }
+}
+
+impl ZCombine {
+    fn combine(&self, db: &Db) -> Result<ZSet, RespFrame> {
+        let mut inputs = Vec::with_capacity(self.keys.len());
+        for key in self.keys.iter() {
+            inputs.push(match db.get(key) {
+                Some(BackendValue::ZSet(zset)) => Input::ZSet(zset),
+                Some(BackendValue::Set(set)) if self.op != SetOp::Diff => Input::Set(set),
+                Some(_) => return Err(RESP_WRONGTYPE.clone()),
+                None => Input::Empty,
+            });
+        }
+        let weight = |i: usize| self.weights.as_ref().map_or(1.0, |w| w[i]);
+
+        let mut ret = ZSet::new();
+        let Some((first, rest)) = inputs.split_first() else {
+            return Ok(ret);
+        };
+        match self.op {
+            SetOp::Diff => {
+                for (member, score) in first.iter() {
+                    if rest.iter().all(|input| input.score(member).is_none()) {
+                        ret.insert(member.to_vec(), score);
+                    }
+                }
+            }
+            SetOp::Union => {
+                let mut scores: HashMap<&[u8], f64> = HashMap::new();
+                for (i, input) in inputs.iter().enumerate() {
+                    for (member, score) in input.iter() {
+                        let score = weighted(score, weight(i));
+                        scores
+                            .entry(member)
+                            .and_modify(|acc| *acc = self.aggregate.apply(*acc, score))
+                            .or_insert(score);
+                    }
+                }
+                for (member, score) in scores {
+                    ret.insert(member.to_vec(), score);
+                }
+            }
+            SetOp::Inter => {
+                // walk the smallest input, probing the others
+                let smallest = inputs
+                    .iter()
+                    .min_by_key(|input| input.len())
+                    .unwrap_or(first);
+                'members: for (member, _) in smallest.iter() {
+                    let mut acc: Option<f64> = None;
+                    for (i, input) in inputs.iter().enumerate() {
+                        let Some(score) = input.score(member) else {
+                            continue 'members;
+                        };
+                        let score = weighted(score, weight(i));
+                        acc = Some(acc.map_or(score, |acc| self.aggregate.apply(acc, score)));
+                    }
+                    if let Some(score) = acc {
+                        ret.insert(member.to_vec(), score);
+                    }
+                }
+            }
+        }
+        Ok(ret)
+    }
+}
+
+// 0 * inf is taken as 0, as in redis
+fn weighted(score: f64, weight: f64) -> f64 {
+    let score = score * weight;
+    if score.is_nan() {
+        0.0
+    } else {
+        score
+    }
+}
+
+impl Aggregate {
+    fn apply(self, acc: f64, score: f64) -> f64 {
+        match self {
+            // inf + -inf is taken as 0, as in redis
+            Aggregate::Sum => {
+                let sum = acc + score;
+                if sum.is_nan() {
+                    0.0
+                } else {
+                    sum
+                }
+            }
+            Aggregate::Min => acc.min(score),
+            Aggregate::Max => acc.max(score),
+        }
+    }
+}
+
+impl Input<'_> {
+    pub(super) fn len(&self) -> usize {
+        match self {
+            Input::ZSet(zset) => zset.len(),
+            Input::Set(set) => set.len(),
+            Input::Empty => 0,
+        }
+    }
+
+    pub(super) fn score(&self, member: &[u8]) -> Option<f64> {
+        match self {
+            Input::ZSet(zset) => zset.score(member),
+            Input::Set(set) => set.contains(member).then_some(1.0),
+            Input::Empty => None,
+        }
+    }
+
+    pub(super) fn iter(&self) -> Box<dyn Iterator<Item = (&[u8], f64)> + '_> {
+        match self {
+            Input::ZSet(zset) => Box::new(zset.iter()),
+            Input::Set(set) => Box::new(set.iter().map(|m| (m.as_slice(), 1.0))),
+            Input::Empty => Box::new(std::iter::empty()),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for ZCombine {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        const COMMANDS: [(&str, SetOp, bool); 6] = [
+            ("zdiff", SetOp::Diff, false),
+            ("zunion", SetOp::Union, false),
+            ("zinter", SetOp::Inter, false),
+            ("zdiffstore", SetOp::Diff, true),
+            ("zunionstore", SetOp::Union, true),
+            ("zinterstore", SetOp::Inter, true),
+        ];
+        let (op, store) = COMMANDS
+            .iter()
+            .find_map(|(name, op, store)| {
+                let min_args = if *store { 3 } else { 2 };
+                validate_variadic_command(&value, &[name], min_args)
+                    .ok()
+                    .map(|_| (*op, *store))
+            })
+            .ok_or_else(|| {
+                CommandError::InvalidCommand(
+                    "Invalid sorted set operation or arguments".to_string(),
+                )
+            })?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let dst = if store {
+            Some(extract_string(args.next())?)
+        } else {
+            None
+        };
+        let numkeys: usize = parse_number(args.next())?;
+        if numkeys == 0 || numkeys > args.len() {
+            return Err(CommandError::InvalidArgument(
+                "numkeys must be positive and at most the number of keys given".to_string(),
+            ));
+        }
+        let keys = args
+            .by_ref()
+            .take(numkeys)
+            .map(|arg| extract_string(Some(arg)))
+            .collect::<Result<_, _>>()?;
+
+        let mut cmd = ZCombine {
+            op,
+            keys,
+            weights: None,
+            aggregate: Aggregate::Sum,
+            with_scores: false,
+            dst,
+        };
+        while let Some(arg) = args.next() {
+            match extract_string(Some(arg))?.to_ascii_lowercase().as_str() {
+                "withscores" if !store => cmd.with_scores = true,
+                "weights" if op != SetOp::Diff => {
+                    let weights = (0..numkeys)
+                        .map(|_| parse_number(args.next()))
+                        .collect::<Result<Vec<f64>, _>>()?;
+                    cmd.weights = Some(weights);
+                }
+                "aggregate" if op != SetOp::Diff => {
+                    cmd.aggregate = match extract_string(args.next())?.to_ascii_lowercase().as_str()
+                    {
+                        "sum" => Aggregate::Sum,
+                        "min" => Aggregate::Min,
+                        "max" => Aggregate::Max,
+                        v => {
+                            return Err(CommandError::InvalidArgument(format!(
+                                "Invalid aggregate: {}",
+                                v
+                            )))
+                        }
+                    }
+                }
+                v => {
+                    return Err(CommandError::InvalidArgument(format!(
+                        "Invalid option: {}",
+                        v
+                    )))
+                }
+            }
+        }
+        Ok(cmd)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{
+        cmd::{SAdd, ZAdd, ZAddOptions},
+        RespDecode,
+    };
+
+    use super::*;
+
+    fn decode(cmd: &str) -> RespArray {
+        let args: Vec<&str> = cmd.split(' ').collect();
+        let mut buf = BytesMut::from(format!("*{}\r\n", args.len()).as_str());
+        for arg in args {
+            buf.extend_from_slice(format!("${}\r\n{}\r\n", arg.len(), arg).as_bytes());
+        }
+        RespArray::decode(&mut buf).unwrap()
+    }
+
+    fn zadd(backend: &Backend, key: &str, members: &[(f64, &str)]) {
+        let cmd = ZAdd {
+            key: key.to_string(),
+            members: members
+                .iter()
+                .map(|(s, m)| (*s, m.as_bytes().to_vec()))
+                .collect(),
+            options: ZAddOptions::default(),
+        };
+        cmd.execute(backend);
+    }
+
+    // a: x1 y2 z3, b: y10 z20, c: z100 w5
+    fn setup() -> Backend {
+        let backend = Backend::new();
+        zadd(&backend, "a", &[(1.0, "x"), (2.0, "y"), (3.0, "z")]);
+        zadd(&backend, "b", &[(10.0, "y"), (20.0, "z")]);
+        zadd(&backend, "c", &[(100.0, "z"), (5.0, "w")]);
+        backend
+    }
+
+    fn run(backend: &Backend, cmd: &str) -> RespFrame {
+        let cmd: ZCombine = decode(cmd).try_into().unwrap();
+        cmd.execute(backend)
+    }
+
+    fn array(items: &[&str]) -> RespFrame {
+        let items: Vec<RespFrame> = items.iter().map(|i| BulkString::new(*i).into()).collect();
+        RespArray::new(items).into()
+    }
+
+    #[test]
+    fn test_zcombine_try_from_resp_array() -> Result<()> {
+        let cmd: ZCombine =
+            decode("zunionstore dst 2 a b WEIGHTS 2 0.5 AGGREGATE MAX").try_into()?;
+        assert_eq!(cmd.op, SetOp::Union);
+        assert_eq!(cmd.dst, Some("dst".to_string()));
+        assert_eq!(cmd.keys, vec!["a", "b"]);
+        assert_eq!(cmd.weights, Some(vec![2.0, 0.5]));
+        assert_eq!(cmd.aggregate, Aggregate::Max);
+
+        let cmd: ZCombine = decode("zdiff 2 a b WITHSCORES").try_into()?;
+        assert_eq!(cmd.op, SetOp::Diff);
+        assert!(cmd.with_scores);
+
+        let ret: Result<ZCombine, _> = decode("zdiff 3 a b").try_into();
+        assert!(ret.is_err());
+        let ret: Result<ZCombine, _> = decode("zdiff 2 a b AGGREGATE MIN").try_into();
+        assert!(ret.is_err());
+        let ret: Result<ZCombine, _> = decode("zinterstore dst 2 a b WITHSCORES").try_into();
+        assert!(ret.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_zdiff() {
+        let backend = setup();
+        assert_eq!(
+            run(&backend, "zdiff 3 a b c WITHSCORES"),
+            array(&["x", "1"])
+        );
+        assert_eq!(run(&backend, "zdiff 2 a missing"), array(&["x", "y", "z"]));
+        assert_eq!(run(&backend, "zdiff 2 missing a"), array(&[]));
+    }
+
+    #[test]
+    fn test_zunion() {
+        let backend = setup();
+        assert_eq!(
+            run(&backend, "zunion 3 a b c WITHSCORES"),
+            array(&["x", "1", "w", "5", "y", "12", "z", "123"])
+        );
+        assert_eq!(
+            run(&backend, "zunion 2 a b AGGREGATE MIN WITHSCORES"),
+            array(&["x", "1", "y", "2", "z", "3"])
+        );
+        assert_eq!(
+            run(
+                &backend,
+                "zunion 2 a b WEIGHTS 10 1 AGGREGATE MAX WITHSCORES"
+            ),
+            array(&["x", "10", "y", "20", "z", "30"])
+        );
+        assert_eq!(run(&backend, "zunion 2 missing missing2"), array(&[]));
+    }
+
+    #[test]
+    fn test_zinter() {
+        let backend = setup();
+        assert_eq!(
+            run(&backend, "zinter 3 a b c WITHSCORES"),
+            array(&["z", "123"])
+        );
+        assert_eq!(
+            run(&backend, "zinter 2 a b AGGREGATE MAX WITHSCORES"),
+            array(&["y", "10", "z", "20"])
+        );
+        assert_eq!(run(&backend, "zinter 3 a b missing"), array(&[]));
+
+        // plain sets count with a score of 1
+        let cmd = SAdd {
+            key: "set".to_string(),
+            members: vec![b"y".to_vec(), b"q".to_vec()],
+        };
+        cmd.execute(&backend);
+        assert_eq!(
+            run(&backend, "zinter 2 a set WITHSCORES"),
+            array(&["y", "3"])
+        );
+    }
+
+    #[test]
+    fn test_zcombine_store() {
+        let backend = setup();
+        assert_eq!(
+            run(&backend, "zinterstore dst 2 a b"),
+            RespFrame::Integer(2)
+        );
+        assert!(matches!(backend.get("dst"), Some(BackendValue::ZSet(_))));
+        assert_eq!(
+            run(&backend, "zunion 1 dst WITHSCORES"),
+            array(&["y", "12", "z", "23"])
+        );
+
+        // the destination may be a source, an empty result removes it
+        assert_eq!(
+            run(&backend, "zdiffstore dst 2 dst b"),
+            RespFrame::Integer(0)
+        );
+        assert_eq!(backend.get("dst"), None);
+
+        backend.set("str".to_string(), BulkString::new("value"));
+        assert_eq!(
+            run(&backend, "zunionstore dst 2 a str"),
+            RESP_WRONGTYPE.clone()
+        );
+    }
+}
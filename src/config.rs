@@ -0,0 +1,571 @@
+use std::{fmt, path::PathBuf};
+
+use anyhow::{anyhow, Result};
+
+use crate::DB_COUNT;
+
+/// names CONFIG GET knows, in the order it lists them
+pub const PARAMETERS: [&str; 27] = [
+    "bind",
+    "port",
+    "unixsocket",
+    "tls-port",
+    "tls-cert-file",
+    "tls-key-file",
+    "tls-ca-file",
+    "requirepass",
+    "tcp-read-timeout-ms",
+    "tcp-write-timeout-ms",
+    "maxclients",
+    "databases",
+    "maxmemory",
+    "maxmemory-policy",
+    "hz",
+    "save",
+    "loglevel",
+    "notify-keyspace-events",
+    "slowlog-log-slower-than",
+    "slowlog-max-len",
+    "latency-monitor-threshold",
+    "dir",
+    "dbfilename",
+    "appendonly",
+    "appendfilename",
+    "appendfsync",
+    "repl-backlog-size",
+];
+
+// only given on the command line, the server is already listening by the time CONFIG SET runs
+const STARTUP_ONLY: [&str; 10] = [
+    "bind",
+    "port",
+    "unixsocket",
+    "tls-port",
+    "tls-cert-file",
+    "tls-key-file",
+    "tls-ca-file",
+    "maxclients",
+    "databases",
+    "appendfilename",
+];
+
+const MAXMEMORY_POLICIES: [&str; 8] = [
+    "noeviction",
+    "allkeys-lru",
+    "allkeys-lfu",
+    "allkeys-random",
+    "volatile-lru",
+    "volatile-lfu",
+    "volatile-random",
+    "volatile-ttl",
+];
+
+const LOGLEVELS: [&str; 4] = ["debug", "verbose", "notice", "warning"];
+
+const APPENDFSYNC: [&str; 3] = ["always", "everysec", "no"];
+
+/// settings of the running server, given on the command line and changed live by CONFIG SET
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerConfig {
+    pub bind_addr: String,
+    pub port: u16,
+    // also listen on a unix socket at this path
+    pub unixsocket: Option<String>,
+    // also accept TLS connections on this port, 0 for none, with this certificate and key.
+    // clients must present a certificate signed by the CA if one is given
+    pub tls_port: u16,
+    pub tls_cert_file: Option<String>,
+    pub tls_key_file: Option<String>,
+    pub tls_ca_file: Option<String>,
+    // clients must AUTH with this password before running any other command
+    pub requirepass: Option<String>,
+    // a connection is closed when a request takes longer to arrive, subscribers and monitors
+    // excepted, or a reply longer to write
+    pub tcp_read_timeout_ms: Option<u64>,
+    pub tcp_write_timeout_ms: Option<u64>,
+    // connections beyond this many are turned away, the permits are handed out at startup
+    pub maxclients: usize,
+    pub databases: usize,
+    // in bytes, 0 for no limit
+    pub maxmemory: u64,
+    pub maxmemory_policy: String,
+    // background tasks run this many times per second
+    pub hz: u32,
+    // snapshot after (seconds, changes) when both are reached
+    pub save_intervals: Vec<(u64, u64)>,
+    pub loglevel: String,
+    pub notify_keyspace_events: NotificationConfig,
+    // in microseconds, negative to disable the slow log and 0 to log every command
+    pub slowlog_log_slower_than: i64,
+    pub slowlog_max_len: usize,
+    // in milliseconds, 0 to disable the latency monitor
+    pub latency_monitor_threshold: u64,
+    // the RDB file is `dbfilename` in directory `dir`
+    pub dir: String,
+    pub dbfilename: String,
+    // log every write to `appendfilename` in `dir`, fsynced as `appendfsync` says
+    pub appendonly: bool,
+    pub appendfilename: String,
+    pub appendfsync: String,
+    // bytes of the replication stream kept for replicas that reconnect
+    pub repl_backlog_size: u64,
+}
+
+/// which keyspace events are published, in the letters notify-keyspace-events takes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NotificationConfig(u16);
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            bind_addr: "0.0.0.0".to_string(),
+            port: 6379,
+            unixsocket: None,
+            tls_port: 0,
+            tls_cert_file: None,
+            tls_key_file: None,
+            tls_ca_file: None,
+            requirepass: None,
+            tcp_read_timeout_ms: None,
+            tcp_write_timeout_ms: None,
+            maxclients: 10000,
+            databases: DB_COUNT,
+            maxmemory: 0,
+            maxmemory_policy: "noeviction".to_string(),
+            hz: 10,
+            save_intervals: vec![(3600, 1), (300, 100), (60, 10000)],
+            loglevel: "notice".to_string(),
+            notify_keyspace_events: NotificationConfig::default(),
+            slowlog_log_slower_than: 10_000,
+            slowlog_max_len: 128,
+            latency_monitor_threshold: 0,
+            dir: ".".to_string(),
+            dbfilename: "dump.rdb".to_string(),
+            appendonly: false,
+            appendfilename: "appendonly.aof".to_string(),
+            appendfsync: "everysec".to_string(),
+            repl_backlog_size: 1024 * 1024,
+        }
+    }
+}
+
+impl ServerConfig {
+    /// parse `--name value` pairs, the names are the ones CONFIG SET takes
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut config = ServerConfig::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let name = arg
+                .strip_prefix("--")
+                .ok_or_else(|| anyhow!("expected an option, got '{}'", arg))?;
+            let value = args
+                .next()
+                .ok_or_else(|| anyhow!("missing value for option '{}'", name))?;
+            config.set(name, &value)?;
+        }
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// change one setting by name, as the command line does
+    pub fn set(&mut self, name: &str, value: &str) -> Result<()> {
+        match name.to_ascii_lowercase().as_str() {
+            "bind" => self.bind_addr = value.to_string(),
+            "port" => self.port = parse(name, value)?,
+            "unixsocket" => self.unixsocket = (!value.is_empty()).then(|| value.to_string()),
+            "tls-port" => self.tls_port = parse(name, value)?,
+            "tls-cert-file" => self.tls_cert_file = (!value.is_empty()).then(|| value.to_string()),
+            "tls-key-file" => self.tls_key_file = (!value.is_empty()).then(|| value.to_string()),
+            "tls-ca-file" => self.tls_ca_file = (!value.is_empty()).then(|| value.to_string()),
+            // an empty password turns authentication off
+            "requirepass" => {
+                self.requirepass = (!value.is_empty()).then(|| value.to_string());
+            }
+            // 0 turns the timeout off
+            "tcp-read-timeout-ms" => {
+                self.tcp_read_timeout_ms = Some(parse(name, value)?).filter(|&ms| ms > 0);
+            }
+            "tcp-write-timeout-ms" => {
+                self.tcp_write_timeout_ms = Some(parse(name, value)?).filter(|&ms| ms > 0);
+            }
+            "maxclients" => {
+                self.maxclients = parse(name, value)?;
+                if self.maxclients == 0 {
+                    return Err(anyhow!("maxclients must be at least 1"));
+                }
+            }
+            // the keyspaces are allocated up front
+            "databases" => {
+                if parse::<usize>(name, value)? != DB_COUNT {
+                    return Err(anyhow!("only {} databases are supported", DB_COUNT));
+                }
+            }
+            "maxmemory" => self.maxmemory = parse_memory(value)?,
+            "maxmemory-policy" => self.maxmemory_policy = one_of(name, value, &MAXMEMORY_POLICIES)?,
+            "hz" => {
+                let hz = parse(name, value)?;
+                if !(1..=500).contains(&hz) {
+                    return Err(anyhow!("argument must be between 1 and 500 inclusive"));
+                }
+                self.hz = hz;
+            }
+            "save" => self.save_intervals = parse_save(value)?,
+            "loglevel" => self.loglevel = one_of(name, value, &LOGLEVELS)?,
+            "notify-keyspace-events" => {
+                self.notify_keyspace_events = value.parse()?;
+            }
+            "slowlog-log-slower-than" => self.slowlog_log_slower_than = parse(name, value)?,
+            "slowlog-max-len" => self.slowlog_max_len = parse(name, value)?,
+            "latency-monitor-threshold" => self.latency_monitor_threshold = parse(name, value)?,
+            "dir" => self.dir = value.to_string(),
+            "dbfilename" => {
+                if value.is_empty() || value.contains(['/', '\\']) {
+                    return Err(anyhow!("dbfilename can't be a path, just a filename"));
+                }
+                self.dbfilename = value.to_string();
+            }
+            "appendonly" => self.appendonly = parse_bool(name, value)?,
+            "appendfilename" => {
+                if value.is_empty() || value.contains(['/', '\\']) {
+                    return Err(anyhow!("appendfilename can't be a path, just a filename"));
+                }
+                self.appendfilename = value.to_string();
+            }
+            "appendfsync" => self.appendfsync = one_of(name, value, &APPENDFSYNC)?,
+            "repl-backlog-size" => {
+                self.repl_backlog_size = parse_memory(value)?;
+                if self.repl_backlog_size == 0 {
+                    return Err(anyhow!("repl-backlog-size must be at least 1"));
+                }
+            }
+            _ => return Err(anyhow!("Unknown option '{}'", name)),
+        }
+        Ok(())
+    }
+
+    /// the startup settings that don't fit together
+    pub fn validate(&self) -> Result<()> {
+        if self.tls_port != 0 && (self.tls_cert_file.is_none() || self.tls_key_file.is_none()) {
+            return Err(anyhow!("tls-port needs tls-cert-file and tls-key-file"));
+        }
+        Ok(())
+    }
+
+    /// change one setting by name at runtime, as CONFIG SET does
+    pub fn set_live(&mut self, name: &str, value: &str) -> Result<()> {
+        let lower = name.to_ascii_lowercase();
+        if STARTUP_ONLY.contains(&lower.as_str()) {
+            return Err(anyhow!("can't set immutable config '{}'", name));
+        }
+        self.set(name, value)
+    }
+
+    /// the value of a setting as CONFIG GET shows it, None for an unknown name
+    pub fn get(&self, name: &str) -> Option<String> {
+        let value = match name.to_ascii_lowercase().as_str() {
+            "bind" => self.bind_addr.clone(),
+            "port" => self.port.to_string(),
+            "unixsocket" => self.unixsocket.clone().unwrap_or_default(),
+            "tls-port" => self.tls_port.to_string(),
+            "tls-cert-file" => self.tls_cert_file.clone().unwrap_or_default(),
+            "tls-key-file" => self.tls_key_file.clone().unwrap_or_default(),
+            "tls-ca-file" => self.tls_ca_file.clone().unwrap_or_default(),
+            "requirepass" => self.requirepass.clone().unwrap_or_default(),
+            "tcp-read-timeout-ms" => self.tcp_read_timeout_ms.unwrap_or_default().to_string(),
+            "tcp-write-timeout-ms" => self.tcp_write_timeout_ms.unwrap_or_default().to_string(),
+            "maxclients" => self.maxclients.to_string(),
+            "databases" => self.databases.to_string(),
+            "maxmemory" => self.maxmemory.to_string(),
+            "maxmemory-policy" => self.maxmemory_policy.clone(),
+            "hz" => self.hz.to_string(),
+            "save" => self
+                .save_intervals
+                .iter()
+                .map(|(seconds, changes)| format!("{} {}", seconds, changes))
+                .collect::<Vec<_>>()
+                .join(" "),
+            "loglevel" => self.loglevel.clone(),
+            "notify-keyspace-events" => self.notify_keyspace_events.to_string(),
+            "slowlog-log-slower-than" => self.slowlog_log_slower_than.to_string(),
+            "slowlog-max-len" => self.slowlog_max_len.to_string(),
+            "latency-monitor-threshold" => self.latency_monitor_threshold.to_string(),
+            "dir" => self.dir.clone(),
+            "dbfilename" => self.dbfilename.clone(),
+            "appendonly" => if self.appendonly { "yes" } else { "no" }.to_string(),
+            "appendfilename" => self.appendfilename.clone(),
+            "appendfsync" => self.appendfsync.clone(),
+            "repl-backlog-size" => self.repl_backlog_size.to_string(),
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    /// where SAVE writes the dataset and the server loads it from at startup
+    pub fn rdb_path(&self) -> PathBuf {
+        PathBuf::from(&self.dir).join(&self.dbfilename)
+    }
+
+    /// where writes are logged while appendonly is on
+    pub fn aof_path(&self) -> PathBuf {
+        PathBuf::from(&self.dir).join(&self.appendfilename)
+    }
+}
+
+fn parse<T: std::str::FromStr>(name: &str, value: &str) -> Result<T> {
+    value
+        .parse()
+        .map_err(|_| anyhow!("Invalid value '{}' for '{}'", value, name))
+}
+
+fn one_of(name: &str, value: &str, allowed: &[&str]) -> Result<String> {
+    let value = value.to_ascii_lowercase();
+    if !allowed.contains(&value.as_str()) {
+        return Err(anyhow!("Invalid value '{}' for '{}'", value, name));
+    }
+    Ok(value)
+}
+
+fn parse_bool(name: &str, value: &str) -> Result<bool> {
+    match one_of(name, value, &["yes", "no"])?.as_str() {
+        "yes" => Ok(true),
+        _ => Ok(false),
+    }
+}
+
+// a byte count with an optional unit, k is 1000 and kb is 1024 as in redis.conf
+fn parse_memory(value: &str) -> Result<u64> {
+    let lower = value.to_ascii_lowercase();
+    let digits = lower.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let multiplier = match &lower[digits.len()..] {
+        "" | "b" => 1,
+        "k" => 1000,
+        "kb" => 1024,
+        "m" => 1000 * 1000,
+        "mb" => 1024 * 1024,
+        "g" => 1000 * 1000 * 1000,
+        "gb" => 1024 * 1024 * 1024,
+        _ => return Err(anyhow!("Invalid memory size '{}'", value)),
+    };
+    let n: u64 = parse("maxmemory", digits)?;
+    n.checked_mul(multiplier)
+        .ok_or_else(|| anyhow!("Invalid memory size '{}'", value))
+}
+
+// "seconds changes [seconds changes ...]", empty to never save
+fn parse_save(value: &str) -> Result<Vec<(u64, u64)>> {
+    let numbers = value
+        .split_whitespace()
+        .map(|n| parse("save", n))
+        .collect::<Result<Vec<u64>>>()?;
+    if !numbers.len().is_multiple_of(2) {
+        return Err(anyhow!("Invalid save parameters"));
+    }
+    Ok(numbers.chunks(2).map(|pair| (pair[0], pair[1])).collect())
+}
+
+impl NotificationConfig {
+    /// publish to `__keyspace@<db>__:<key>`
+    pub const KEYSPACE: NotificationConfig = NotificationConfig(1);
+    /// publish to `__keyevent@<db>__:<event>`
+    pub const KEYEVENT: NotificationConfig = NotificationConfig(1 << 1);
+    /// commands that apply to any type, DEL, EXPIRE, RENAME...
+    pub const GENERIC: NotificationConfig = NotificationConfig(1 << 2);
+    pub const STRING: NotificationConfig = NotificationConfig(1 << 3);
+    pub const LIST: NotificationConfig = NotificationConfig(1 << 4);
+    pub const SET: NotificationConfig = NotificationConfig(1 << 5);
+    pub const HASH: NotificationConfig = NotificationConfig(1 << 6);
+    pub const ZSET: NotificationConfig = NotificationConfig(1 << 7);
+    pub const EXPIRED: NotificationConfig = NotificationConfig(1 << 8);
+    pub const EVICTED: NotificationConfig = NotificationConfig(1 << 9);
+    /// every event class, the `A` letter
+    pub const ALL: NotificationConfig = NotificationConfig(0b11_1111_1100);
+
+    // event classes by letter, the channel kinds are K and E
+    const CLASSES: [(char, NotificationConfig); 8] = [
+        ('g', Self::GENERIC),
+        ('$', Self::STRING),
+        ('l', Self::LIST),
+        ('s', Self::SET),
+        ('h', Self::HASH),
+        ('z', Self::ZSET),
+        ('x', Self::EXPIRED),
+        ('e', Self::EVICTED),
+    ];
+
+    pub fn contains(self, flags: NotificationConfig) -> bool {
+        self.0 & flags.0 == flags.0
+    }
+
+    /// whether events of `class` are published to at least one kind of channel
+    pub fn is_enabled(self, class: NotificationConfig) -> bool {
+        (self.contains(Self::KEYSPACE) || self.contains(Self::KEYEVENT)) && self.contains(class)
+    }
+}
+
+impl std::str::FromStr for NotificationConfig {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut flags = 0;
+        for c in s.chars() {
+            flags |= match c {
+                'K' => Self::KEYSPACE.0,
+                'E' => Self::KEYEVENT.0,
+                'A' => Self::ALL.0,
+                c => match Self::CLASSES.iter().find(|(letter, _)| *letter == c) {
+                    Some((_, class)) => class.0,
+                    None => return Err(anyhow!("Invalid event class character '{}'", c)),
+                },
+            };
+        }
+        Ok(NotificationConfig(flags))
+    }
+}
+
+// the canonical form, as CONFIG GET shows it
+impl fmt::Display for NotificationConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.contains(Self::ALL) {
+            write!(f, "A")?;
+        } else {
+            for (letter, class) in Self::CLASSES {
+                if self.contains(class) {
+                    write!(f, "{}", letter)?;
+                }
+            }
+        }
+        if self.contains(Self::KEYSPACE) {
+            write!(f, "K")?;
+        }
+        if self.contains(Self::KEYEVENT) {
+            write!(f, "E")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_server_config_from_args() -> Result<()> {
+        assert_eq!(ServerConfig::from_args(vec![])?, ServerConfig::default());
+
+        let config = ServerConfig::from_args(args(&["--requirepass", "secret", "--port", "7000"]))?;
+        assert_eq!(config.requirepass.as_deref(), Some("secret"));
+        assert_eq!(config.port, 7000);
+
+        assert!(ServerConfig::from_args(args(&["--requirepass"])).is_err());
+        assert!(ServerConfig::from_args(args(&["requirepass", "secret"])).is_err());
+        assert!(ServerConfig::from_args(args(&["--unknown", "1"])).is_err());
+        // a TLS port needs a certificate and its key
+        let config = ServerConfig::from_args(args(&["--tls-cert-file", "redis.crt"]))?;
+        assert_eq!(config.get("tls-cert-file").as_deref(), Some("redis.crt"));
+        assert!(ServerConfig::from_args(args(&["--tls-port", "6380"])).is_err());
+        let tls = [
+            "--tls-port",
+            "6380",
+            "--tls-cert-file",
+            "a.crt",
+            "--tls-key-file",
+            "a.key",
+        ];
+        let config = ServerConfig::from_args(args(&tls))?;
+        assert_eq!(config.tls_port, 6380);
+        assert_eq!(config.tls_key_file.as_deref(), Some("a.key"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_server_config_set() -> Result<()> {
+        let mut config = ServerConfig::default();
+        config.set("REQUIREPASS", "secret")?;
+        assert_eq!(config.requirepass.as_deref(), Some("secret"));
+        config.set("requirepass", "")?;
+        assert_eq!(config.requirepass, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_notification_config() -> Result<()> {
+        let config: NotificationConfig = "KEA".parse()?;
+        assert!(config.is_enabled(NotificationConfig::STRING));
+        assert!(config.is_enabled(NotificationConfig::EXPIRED));
+        assert_eq!(config.to_string(), "AKE");
+
+        let config: NotificationConfig = "Eg$".parse()?;
+        assert!(config.is_enabled(NotificationConfig::GENERIC));
+        assert!(!config.is_enabled(NotificationConfig::LIST));
+        assert_eq!(config.to_string(), "g$E");
+
+        // classes without a channel kind publish nothing
+        let config: NotificationConfig = "A".parse()?;
+        assert!(!config.is_enabled(NotificationConfig::STRING));
+        assert_eq!(
+            "".parse::<NotificationConfig>()?,
+            NotificationConfig::default()
+        );
+        assert!("KEQ".parse::<NotificationConfig>().is_err());
+
+        let mut config = ServerConfig::default();
+        config.set("notify-keyspace-events", "KEA")?;
+        assert!(config
+            .notify_keyspace_events
+            .is_enabled(NotificationConfig::GENERIC));
+        Ok(())
+    }
+
+    #[test]
+    fn test_server_config_get() -> Result<()> {
+        let mut config = ServerConfig::default();
+        config.set("maxmemory", "100mb")?;
+        config.set("save", "900 1 300 10")?;
+        config.set("MAXMEMORY-POLICY", "ALLKEYS-LRU")?;
+        assert_eq!(config.get("maxmemory").as_deref(), Some("104857600"));
+        assert_eq!(config.get("save").as_deref(), Some("900 1 300 10"));
+        assert_eq!(
+            config.get("maxmemory-policy").as_deref(),
+            Some("allkeys-lru")
+        );
+        assert_eq!(config.get("requirepass").as_deref(), Some(""));
+        assert_eq!(config.get("unknown"), None);
+        // every listed parameter can be read
+        assert!(PARAMETERS.iter().all(|name| config.get(name).is_some()));
+
+        assert!(config.set("hz", "0").is_err());
+        assert!(config.set("loglevel", "loud").is_err());
+        assert!(config.set("maxmemory", "1xb").is_err());
+        assert!(config.set("save", "900").is_err());
+        assert!(config.set("databases", "32").is_err());
+        config.set("save", "")?;
+        assert!(config.save_intervals.is_empty());
+
+        assert!(config.set_live("port", "7000").is_err());
+        assert!(config.set_live("unixsocket", "/tmp/redis.sock").is_err());
+        assert!(config.set_live("tls-cert-file", "redis.crt").is_err());
+        config.set_live("hz", "20")?;
+        config.set_live("tcp-read-timeout-ms", "1500")?;
+        assert_eq!(config.tcp_read_timeout_ms, Some(1500));
+        config.set_live("tcp-read-timeout-ms", "0")?;
+        assert_eq!(config.tcp_read_timeout_ms, None);
+        assert_eq!(config.get("hz").as_deref(), Some("20"));
+
+        config.set_live("dir", "/var/lib/redis")?;
+        assert!(config.set("dbfilename", "../dump.rdb").is_err());
+        assert!(config.set("appendonly", "maybe").is_err());
+        assert!(config.set("appendfsync", "sometimes").is_err());
+        config.set_live("repl-backlog-size", "2mb")?;
+        assert_eq!(config.repl_backlog_size, 2 * 1024 * 1024);
+        assert!(config.set("repl-backlog-size", "0").is_err());
+        assert_eq!(
+            config.rdb_path(),
+            std::path::Path::new("/var/lib/redis/dump.rdb")
+        );
+        Ok(())
+    }
+}
@@ -1,10 +1,14 @@
 mod backend;
 pub mod cmd;
+mod config;
 mod resp;
 mod respv2;
 
+pub mod monitoring;
 pub mod network;
+pub mod persistence;
 
 pub use backend::*;
+pub use config::{NotificationConfig, ServerConfig};
 pub use resp::*;
 pub use respv2::*;
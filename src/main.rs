@@ -1,29 +1,69 @@
 use anyhow::Result;
-use simple_redis::Backend;
-use tokio::net::TcpListener;
+use simple_redis::{
+    network::{run_server, tls_acceptor, Listener},
+    persistence::{self, aof, AppendFsync},
+    Backend, ServerConfig,
+};
+use tokio::{net::TcpListener, signal};
 use tracing::{info, warn};
 
 #[tokio::main]
 async fn main() -> Result<()> {
     tracing_subscriber::fmt::init();
+    let config = ServerConfig::from_args(std::env::args().skip(1))?;
 
-    let addr = "0.0.0.0:6379";
+    let addr = format!("{}:{}", config.bind_addr, config.port);
     info!("Simple-Redis_server is Listening on {}", addr);
-    let listener = TcpListener::bind(addr).await?;
+    let mut listeners = vec![Listener::Tcp(TcpListener::bind(&addr).await?)];
+    if let Some(path) = &config.unixsocket {
+        info!("Simple-Redis_server is Listening on unix socket {}", path);
+        listeners.push(Listener::bind_unix(path)?);
+    }
+    if config.tls_port != 0 {
+        let acceptor = tls_acceptor(&config)?;
+        let addr = format!("{}:{}", config.bind_addr, config.tls_port);
+        info!("Simple-Redis_server is Listening for TLS on {}", addr);
+        listeners.push(Listener::Tls(TcpListener::bind(&addr).await?, acceptor));
+    }
 
+    let (rdb_path, aof_path) = (config.rdb_path(), config.aof_path());
+    let (appendonly, fsync) = (
+        config.appendonly,
+        AppendFsync::from_config(&config.appendfsync),
+    );
     let backend = Backend::new();
+    *backend.config_mut() = config;
+    // the AOF is more up to date than a snapshot when there is one
+    if appendonly && aof_path.exists() {
+        aof::load(&aof_path, &backend)?;
+        info!("DB loaded from append only file {}", aof_path.display());
+    } else if rdb_path.exists() {
+        persistence::load_into(&rdb_path, &backend)?;
+        info!("DB loaded from {}", rdb_path.display());
+    }
+    if appendonly {
+        let base = (!aof_path.exists()).then(|| backend.snapshot());
+        backend.persistence().aof().start(aof_path, fsync, base);
+    }
 
-    loop {
-        let (socket, raddr) = listener.accept().await?;
-        info!("Accepted connection from: {}", raddr);
-        let cloned_backend = backend.clone();
-        tokio::spawn(async move {
-            match simple_redis::network::stream_handler(socket, cloned_backend).await {
-                Ok(_) => {
-                    info!("Connection from {} is handled successfully", raddr);
-                }
-                Err(e) => warn!("Error: {:?}", e),
+    run_server(listeners, backend, shutdown_signal()).await
+}
+
+// Ctrl-C, or SIGTERM as sent by service managers
+async fn shutdown_signal() {
+    let terminate = async {
+        match signal::unix::signal(signal::unix::SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(e) => {
+                warn!("Can't listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
             }
-        });
+        }
+    };
+    tokio::select! {
+        _ = signal::ctrl_c() => info!("Received SIGINT scheduling shutdown..."),
+        _ = terminate => info!("Received SIGTERM scheduling shutdown..."),
     }
 }
@@ -0,0 +1,118 @@
+use std::{collections::HashMap, collections::VecDeque, time::Duration};
+
+use parking_lot::Mutex;
+
+use crate::clock;
+
+// samples kept per event, one per second at most
+const MAX_SAMPLES: usize = 160;
+
+/// latency spikes of the server's critical paths, by event name
+#[derive(Debug, Default)]
+pub struct LatencyMonitor {
+    events: Mutex<HashMap<String, LatencyEvent>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LatencyEvent {
+    // (unix time in seconds, milliseconds), oldest first
+    pub samples: VecDeque<(i64, u64)>,
+    // the highest latency ever recorded
+    pub max: u64,
+}
+
+impl LatencyMonitor {
+    /// record that `event` took `duration`, if it reached `threshold_ms`. a threshold of 0
+    /// disables monitoring. spikes within the same second are merged, keeping the highest
+    pub fn record(&self, event: &str, duration: Duration, threshold_ms: u64) {
+        let ms = duration.as_millis() as u64;
+        if threshold_ms == 0 || ms < threshold_ms {
+            return;
+        }
+        let now = clock::unix_time_ms(clock::now()) / 1000;
+        let mut events = self.events.lock();
+        let event = events.entry(event.to_string()).or_default();
+        event.max = event.max.max(ms);
+        match event.samples.back_mut() {
+            Some((at, latency)) if *at == now => *latency = (*latency).max(ms),
+            _ => {
+                if event.samples.len() == MAX_SAMPLES {
+                    event.samples.pop_front();
+                }
+                event.samples.push_back((now, ms));
+            }
+        }
+    }
+
+    pub fn history(&self, event: &str) -> Vec<(i64, u64)> {
+        self.events
+            .lock()
+            .get(event)
+            .map(|e| e.samples.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// every event with its latest sample, by name
+    pub fn latest(&self) -> Vec<(String, LatencyEvent)> {
+        let mut events: Vec<_> = self
+            .events
+            .lock()
+            .iter()
+            .map(|(name, e)| (name.clone(), e.clone()))
+            .collect();
+        events.sort_by(|a, b| a.0.cmp(&b.0));
+        events
+    }
+
+    /// forget the given events, every event when none is given. returns how many were reset
+    pub fn reset(&self, events: &[String]) -> usize {
+        let mut all = self.events.lock();
+        if events.is_empty() {
+            let n = all.len();
+            all.clear();
+            return n;
+        }
+        events.iter().filter(|e| all.remove(*e).is_some()).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_latency_record() {
+        let monitor = LatencyMonitor::default();
+        let ms = Duration::from_millis;
+        monitor.record("command", ms(50), 0);
+        monitor.record("command", ms(5), 10);
+        assert!(monitor.history("command").is_empty());
+
+        monitor.record("command", ms(20), 10);
+        monitor.record("command", ms(30), 10);
+        monitor.record("command", ms(15), 10);
+        // one sample per second, the highest
+        let history = monitor.history("command");
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].1, 30);
+
+        tokio::time::advance(Duration::from_secs(1)).await;
+        monitor.record("command", ms(12), 10);
+        let history = monitor.history("command");
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].0, history[0].0 + 1);
+
+        monitor.record("expire-cycle", ms(12), 10);
+        let latest = monitor.latest();
+        assert_eq!(latest.len(), 2);
+        assert_eq!(latest[0].0, "command");
+        assert_eq!(latest[0].1.max, 30);
+
+        assert_eq!(
+            monitor.reset(&["command".to_string(), "unknown".to_string()]),
+            1
+        );
+        assert_eq!(monitor.reset(&[]), 1);
+        assert!(monitor.latest().is_empty());
+    }
+}
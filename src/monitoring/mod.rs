@@ -0,0 +1,5 @@
+mod latency;
+mod slowlog;
+
+pub use latency::{LatencyEvent, LatencyMonitor};
+pub use slowlog::{command_args, SlowLog, SlowLogEntry};
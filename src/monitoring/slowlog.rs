@@ -0,0 +1,158 @@
+use std::{
+    collections::VecDeque,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use parking_lot::Mutex;
+
+use crate::{clock, RespFrame};
+
+// arguments kept per entry, and bytes kept per argument, like redis does
+const MAX_ARGS: usize = 32;
+const MAX_ARG_LEN: usize = 128;
+
+/// commands that took longer than slowlog-log-slower-than, newest first
+#[derive(Debug, Default)]
+pub struct SlowLog {
+    entries: Mutex<VecDeque<SlowLogEntry>>,
+    next_id: AtomicU64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlowLogEntry {
+    pub id: u64,
+    // unix time in seconds
+    pub timestamp: i64,
+    pub duration: Duration,
+    pub args: Vec<Vec<u8>>,
+}
+
+impl SlowLog {
+    /// log a command if it ran for at least `threshold_us` microseconds, a negative threshold
+    /// logs nothing. the oldest entries are dropped beyond `max_len`
+    pub fn record(
+        &self,
+        args: Vec<Vec<u8>>,
+        duration: Duration,
+        threshold_us: i64,
+        max_len: usize,
+    ) {
+        if threshold_us < 0 || duration.as_micros() < threshold_us as u128 {
+            return;
+        }
+        let entry = SlowLogEntry {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            timestamp: clock::unix_time_ms(clock::now()) / 1000,
+            duration,
+            args,
+        };
+        let mut entries = self.entries.lock();
+        entries.push_front(entry);
+        entries.truncate(max_len);
+    }
+
+    /// the `count` most recent entries, newest first
+    pub fn get(&self, count: usize) -> Vec<SlowLogEntry> {
+        self.entries.lock().iter().take(count).cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// forget every entry, ids keep increasing
+    pub fn reset(&self) {
+        self.entries.lock().clear();
+    }
+}
+
+/// the arguments of a request as the slow log keeps them, long ones are cut short
+pub fn command_args(frame: &RespFrame) -> Vec<Vec<u8>> {
+    let RespFrame::Array(array) = frame else {
+        return vec![];
+    };
+    let mut args: Vec<Vec<u8>> = array
+        .iter()
+        .take(if array.len() > MAX_ARGS {
+            MAX_ARGS - 1
+        } else {
+            MAX_ARGS
+        })
+        .map(|arg| match arg {
+            RespFrame::BulkString(s) if s.len() > MAX_ARG_LEN => {
+                let mut arg = s[..MAX_ARG_LEN].to_vec();
+                arg.extend_from_slice(
+                    format!("... ({} more bytes)", s.len() - MAX_ARG_LEN).as_bytes(),
+                );
+                arg
+            }
+            RespFrame::BulkString(s) => s.to_vec(),
+            _ => vec![],
+        })
+        .collect();
+    if array.len() > MAX_ARGS {
+        let more = array.len() - (MAX_ARGS - 1);
+        args.push(format!("... ({} more arguments)", more).into_bytes());
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BulkString, RespArray};
+
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<Vec<u8>> {
+        args.iter().map(|a| a.as_bytes().to_vec()).collect()
+    }
+
+    #[test]
+    fn test_slowlog_record() {
+        let slowlog = SlowLog::default();
+        let ms = Duration::from_millis;
+        slowlog.record(args(&["get", "a"]), ms(5), 10_000, 128);
+        slowlog.record(args(&["get", "b"]), ms(10), 10_000, 128);
+        slowlog.record(args(&["get", "c"]), ms(10), -1, 128);
+        slowlog.record(args(&["get", "d"]), ms(0), 0, 128);
+        assert_eq!(slowlog.len(), 2);
+
+        let entries = slowlog.get(10);
+        assert_eq!(entries[0].args, args(&["get", "d"]));
+        assert_eq!(entries[0].id, 1);
+        assert_eq!(entries[1].args, args(&["get", "b"]));
+        assert_eq!(entries[1].duration, ms(10));
+        assert_eq!(slowlog.get(1).len(), 1);
+
+        // a shorter max length drops the oldest entries
+        slowlog.record(args(&["get", "e"]), ms(10), 0, 2);
+        let ids: Vec<_> = slowlog.get(10).iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![2, 1]);
+
+        slowlog.reset();
+        assert!(slowlog.is_empty());
+    }
+
+    #[test]
+    fn test_command_args_are_truncated() {
+        let long = "x".repeat(MAX_ARG_LEN + 10);
+        let frame: RespFrame = RespArray::new(
+            (0..40)
+                .map(|i| BulkString::new(if i == 1 { long.clone() } else { i.to_string() }).into())
+                .collect::<Vec<RespFrame>>(),
+        )
+        .into();
+        let args = command_args(&frame);
+        assert_eq!(args.len(), MAX_ARGS);
+        assert_eq!(
+            args[1],
+            format!("{}... (10 more bytes)", "x".repeat(MAX_ARG_LEN)).into_bytes()
+        );
+        assert_eq!(args[MAX_ARGS - 1], b"... (9 more arguments)".to_vec());
+    }
+}
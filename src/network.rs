@@ -1,73 +0,0 @@
-use futures::SinkExt;
-use tokio::net::TcpStream;
-use tokio_stream::StreamExt;
-use tokio_util::codec::{Decoder, Encoder, Framed};
-use tracing::info;
-
-use crate::{
-    cmd::{Command, CommandExecutor},
-    Backend, RespDecodeV2, RespEncode, RespError, RespFrame,
-};
-
-#[derive(Debug)]
-struct RespFrameCodec;
-
-#[derive(Debug)]
-struct RedisRequest {
-    frame: RespFrame,
-    backend: Backend,
-}
-
-#[derive(Debug)]
-struct RedisResponse {
-    frame: RespFrame,
-}
-
-pub async fn stream_handler(stream: TcpStream, backend: Backend) -> anyhow::Result<()> {
-    let mut framed = Framed::new(stream, RespFrameCodec);
-    loop {
-        match framed.next().await {
-            Some(Ok(frame)) => {
-                info!("Received frame: {:?}", frame);
-                let request = RedisRequest {
-                    frame,
-                    backend: backend.clone(),
-                };
-                let response = request_handler(request).await?;
-                info!("Sending response: {:?}", response.frame);
-                framed.send(response.frame).await?;
-            }
-            Some(Err(e)) => return Err(e),
-            None => return Ok(()),
-        }
-    }
-}
-
-async fn request_handler(request: RedisRequest) -> anyhow::Result<RedisResponse> {
-    let (frame, backend) = (request.frame, request.backend);
-    let cmd: Command = frame.try_into()?;
-    info!("Executing command: {:?}", cmd);
-    let frame = cmd.execute(&backend);
-    Ok(RedisResponse { frame })
-}
-
-impl Encoder<RespFrame> for RespFrameCodec {
-    type Error = anyhow::Error;
-    fn encode(&mut self, item: RespFrame, dst: &mut bytes::BytesMut) -> Result<(), Self::Error> {
-        let encodecd = item.encode();
-        dst.extend_from_slice(&encodecd);
-        Ok(())
-    }
-}
-
-impl Decoder for RespFrameCodec {
-    type Item = RespFrame;
-    type Error = anyhow::Error;
-    fn decode(&mut self, src: &mut bytes::BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        match RespFrame::decode(src) {
-            Ok(frame) => Ok(Some(frame)),
-            Err(RespError::NotComplete) => Ok(None),
-            Err(e) => Err(e.into()),
-        }
-    }
-}
@@ -0,0 +1,100 @@
+use std::{fmt, io, path::PathBuf, time::Duration};
+
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::{TcpListener, TcpStream, UnixListener},
+};
+use tokio_rustls::TlsAcceptor;
+
+// a client that doesn't complete its TLS handshake by then is dropped
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// a byte stream a client talks RESP over
+pub trait Connection: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Connection for T {}
+
+/// where the server accepts clients
+pub enum Listener {
+    Tcp(TcpListener),
+    // the path is kept to show as the client address and to remove the socket file
+    Unix(UnixListener, PathBuf),
+    Tls(TcpListener, TlsAcceptor),
+}
+
+/// a client just accepted. the TLS handshake is left to `establish`, so that a slow client
+/// doesn't hold up the others
+pub enum Accepted {
+    Plain(Box<dyn Connection>),
+    Tls(TcpStream, TlsAcceptor),
+}
+
+impl Listener {
+    /// listen on a unix socket at `path`, replacing a stale socket file left by a previous run
+    pub fn bind_unix(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+        Ok(Listener::Unix(UnixListener::bind(&path)?, path))
+    }
+
+    /// the next client and its address as CLIENT LIST shows it
+    pub async fn accept(&self) -> io::Result<(Accepted, String)> {
+        match self {
+            Listener::Tcp(listener) => {
+                let (stream, addr) = listener.accept().await?;
+                Ok((Accepted::Plain(Box::new(stream)), addr.to_string()))
+            }
+            // unix socket clients have no address of their own
+            Listener::Unix(listener, path) => {
+                let (stream, _) = listener.accept().await?;
+                Ok((
+                    Accepted::Plain(Box::new(stream)),
+                    format!("{}:0", path.display()),
+                ))
+            }
+            Listener::Tls(listener, acceptor) => {
+                let (stream, addr) = listener.accept().await?;
+                Ok((Accepted::Tls(stream, acceptor.clone()), addr.to_string()))
+            }
+        }
+    }
+}
+
+impl Accepted {
+    /// the stream to talk RESP over, once the TLS handshake is done for TLS clients
+    pub async fn establish(self) -> io::Result<Box<dyn Connection>> {
+        match self {
+            Accepted::Plain(stream) => Ok(stream),
+            Accepted::Tls(stream, acceptor) => {
+                let handshake = acceptor.accept(stream);
+                match tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, handshake).await {
+                    Ok(stream) => Ok(Box::new(stream?)),
+                    Err(_) => Err(io::ErrorKind::TimedOut.into()),
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Listener {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Listener::Tcp(listener) => f.debug_tuple("Tcp").field(listener).finish(),
+            Listener::Unix(listener, path) => {
+                f.debug_tuple("Unix").field(listener).field(path).finish()
+            }
+            Listener::Tls(listener, _) => f.debug_tuple("Tls").field(listener).finish(),
+        }
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        if let Listener::Unix(_, path) = self {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
@@ -0,0 +1,715 @@
+mod listener;
+mod monitor;
+mod notify;
+mod pause;
+mod pubsub;
+mod replication;
+mod shutdown;
+mod tls;
+
+use std::{
+    future::Future,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use bytes::{Bytes, BytesMut};
+use futures::SinkExt;
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    sync::{
+        mpsc::{self, UnboundedReceiver, UnboundedSender},
+        oneshot,
+    },
+    time::Instant,
+};
+use tokio_stream::{wrappers::BroadcastStream, StreamExt, StreamMap};
+use tokio_util::codec::{Decoder, Encoder, Framed};
+use tracing::{info, warn};
+
+use crate::{
+    cmd::{Command, CommandExecutor},
+    monitoring::command_args,
+    persistence::aof,
+    Backend, ConnectionInfo, RespDecodeV2, RespEncode, RespError, RespFrame, SimpleError,
+    SimpleString,
+};
+
+pub use listener::{Accepted, Connection, Listener};
+pub use monitor::Monitor;
+pub use notify::execute_command;
+pub use pause::{PauseGate, PauseMode, PauseState};
+pub use pubsub::{PubSub, Subscription};
+pub use replication::{MasterStatus, ReplicaOptions, ReplicaStatus, ReplicationState};
+pub use shutdown::{run_server, Shutdown, SHUTDOWN_DEADLINE};
+pub use tls::tls_acceptor;
+
+// connection ids are never reused for the lifetime of the process
+static NEXT_CONNECTION_ID: AtomicU64 = AtomicU64::new(1);
+
+// the only commands a connection in pub/sub mode may send
+const SUBSCRIBER_COMMANDS: &[&str] = &[
+    "subscribe",
+    "unsubscribe",
+    "psubscribe",
+    "punsubscribe",
+    "ping",
+    "reset",
+    "quit",
+];
+
+#[derive(Debug)]
+struct RespFrameCodec;
+
+/// what the server knows about one client connection, owned by its `stream_handler`
+#[derive(Debug)]
+pub struct ConnectionState {
+    // every connection starts on database 0 until it sends a SELECT
+    pub selected_db: usize,
+    pub resp_version: u8,
+    pub client_name: Option<String>,
+    pub id: u64,
+    pub flags: ConnectionFlags,
+    // whether a password is configured, refreshed before every command
+    pub auth_required: bool,
+    pub authenticated: bool,
+    // commands sent after MULTI, run by EXEC
+    pub queued: Vec<Command>,
+    // the request of each queued command if it is a write or SELECT, for the AOF and replicas
+    pub queued_frames: Vec<Option<RespFrame>>,
+    // WATCHed keys by database index, the backend tells `watch_tx` when one is written to
+    pub watched: Vec<(usize, String)>,
+    pub watch_tx: UnboundedSender<()>,
+    pub watch_rx: UnboundedReceiver<()>,
+    pub watched_keys_modified: bool,
+    // messages of the channels and patterns this connection is (P)SUBSCRIBEd to
+    pub subscriptions: StreamMap<Subscription, BroadcastStream<RespFrame>>,
+    // the feed of every processed command, once MONITOR was sent
+    pub monitor: Option<BroadcastStream<RespFrame>>,
+    // peer address, empty for connections not made over the network
+    pub addr: String,
+    // what a replica sent with REPLCONF, the dataset and then the replication stream once it
+    // sent PSYNC
+    pub replica_options: ReplicaOptions,
+    pub replica_stream: Option<UnboundedReceiver<Bytes>>,
+}
+
+/// on/off switches of a connection, as a bit set
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectionFlags(u32);
+
+#[derive(Debug)]
+struct RedisRequest {
+    frame: RespFrame,
+    backend: Backend,
+}
+
+#[derive(Debug)]
+struct RedisResponse {
+    // usually one reply, SUBSCRIBE and UNSUBSCRIBE confirm each channel separately
+    frames: Vec<RespFrame>,
+}
+
+// keeps a connection listed in the client registry for as long as its handler runs
+struct ClientRegistration<'a> {
+    backend: &'a Backend,
+    id: u64,
+}
+
+/// serve one client connected from `addr` until it disconnects or is killed
+pub async fn stream_handler<S>(stream: S, addr: String, backend: Backend) -> anyhow::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut framed = Framed::new(stream, RespFrameCodec);
+    let mut state = ConnectionState::new();
+    state.addr.clone_from(&addr);
+    // clients that connect while no password is set stay authenticated if one is set later
+    state.authenticated = backend.config().requirepass.is_none();
+    let (_registration, killed) =
+        ClientRegistration::new(&backend, ConnectionInfo::new(state.id, addr));
+    let ret = serve(&mut framed, &backend, &mut state, killed).await;
+    state.unsubscribe_all(backend.pubsub());
+    if state.replica_stream.is_some() {
+        backend.replication().remove_replica(state.id);
+    }
+    ret
+}
+
+async fn serve<S: AsyncRead + AsyncWrite + Unpin>(
+    framed: &mut Framed<S, RespFrameCodec>,
+    backend: &Backend,
+    state: &mut ConnectionState,
+    mut killed: oneshot::Receiver<()>,
+) -> anyhow::Result<()> {
+    loop {
+        let (read_timeout, write_timeout) = {
+            let config = backend.config();
+            (config.tcp_read_timeout_ms, config.tcp_write_timeout_ms)
+        };
+        // subscribers, monitors and replicas wait for messages, not for their own requests
+        let read_timeout = read_timeout
+            .filter(|_| {
+                state.subscriptions.is_empty()
+                    && state.monitor.is_none()
+                    && state.replica_stream.is_none()
+            })
+            .map(Duration::from_millis);
+        let write_timeout = write_timeout.map(Duration::from_millis);
+        let frame = tokio::select! {
+            biased;
+            // CLIENT KILL, dropping the socket closes it
+            _ = &mut killed => return Ok(()),
+            Some((_, message)) = state.subscriptions.next(), if !state.subscriptions.is_empty() => {
+                // a subscriber too slow to keep up misses messages
+                if let Ok(message) = message {
+                    send(framed, vec![message], true, write_timeout, &state.addr).await?;
+                }
+                continue;
+            }
+            Some(line) = async { state.monitor.as_mut()?.next().await }, if state.monitor.is_some() => {
+                // so does a monitor
+                if let Ok(line) = line {
+                    send(framed, vec![line], true, write_timeout, &state.addr).await?;
+                }
+                continue;
+            }
+            Some(data) = async { state.replica_stream.as_mut()?.recv().await }, if state.replica_stream.is_some() => {
+                send(framed, vec![data], true, write_timeout, &state.addr).await?;
+                continue;
+            }
+            // only between requests, the one in flight is answered first
+            _ = backend.shutdown().wait() => {
+                return send(framed, Vec::<RespFrame>::new(), true, write_timeout, &state.addr).await;
+            }
+            frame = within(read_timeout, framed.next()) => match frame {
+                Some(frame) => frame,
+                None => {
+                    warn!("Client {} sent no request in time, closing the connection", state.addr);
+                    backend.kill_clients(|info| info.id == state.id);
+                    return Ok(());
+                }
+            },
+        };
+        match frame {
+            Some(Ok(frame)) => {
+                info!("Received frame: {:?}", frame);
+                let cmd = command_name(&frame);
+                let request = RedisRequest {
+                    frame,
+                    backend: backend
+                        .select(state.selected_db)
+                        .expect("validated by SELECT"),
+                };
+                // a blocking command may be killed while it waits, a client killing itself gets
+                // its reply first
+                let response = tokio::select! {
+                    biased;
+                    response = request_handler(request, state) => response?,
+                    _ = &mut killed => return Ok(()),
+                };
+                backend.update_client(state.id, |info| {
+                    info.name.clone_from(&state.client_name);
+                    info.db = state.selected_db;
+                    info.cmd = cmd;
+                    info.last_interaction = Instant::now();
+                });
+                info!("Sending response: {:?}", response.frames);
+                let close = state.flags.contains(ConnectionFlags::CLOSE_AFTER_REPLY);
+                // the replies to pipelined requests go out together, once no further request
+                // is buffered
+                let flush = close || !request_pending(framed.read_buffer());
+                send(framed, response.frames, flush, write_timeout, &state.addr).await?;
+                if close {
+                    return Ok(());
+                }
+            }
+            Some(Err(e)) => return Err(e),
+            None => return Ok(()),
+        }
+    }
+}
+
+async fn request_handler(
+    request: RedisRequest,
+    state: &mut ConnectionState,
+) -> anyhow::Result<RedisResponse> {
+    let (frame, backend) = (request.frame, request.backend);
+    let name = command_name(&frame);
+    // kept for the slow log, unless it is disabled
+    let args = (backend.config().slowlog_log_slower_than >= 0).then(|| command_args(&frame));
+    let in_multi = state.flags.contains(ConnectionFlags::MULTI);
+    let monitor_line = backend
+        .monitor()
+        .line(state.selected_db, &state.addr, &frame);
+    // writes are logged and replicated once they succeeded
+    let raw = (backend.persistence().aof().is_enabled() || backend.replication().is_active())
+        .then(|| frame.clone());
+    let cmd: Command = match frame.try_into() {
+        Ok(cmd) => cmd,
+        // a command that can't be queued dooms the whole transaction
+        Err(e) if in_multi => {
+            state.flags.insert(ConnectionFlags::DIRTY_EXEC);
+            return Ok(RedisResponse::new(
+                SimpleError::new(format!("ERR {}", e)).into(),
+            ));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    backend.stats().command_processed();
+    state.auth_required = backend.config().requirepass.is_some();
+    if state.auth_required
+        && !state.authenticated
+        && !matches!(cmd, Command::Auth(_) | Command::Reset(_) | Command::Quit(_))
+    {
+        return Ok(RedisResponse::new(
+            SimpleError::new("NOAUTH Authentication required.").into(),
+        ));
+    }
+    if let Some(line) = monitor_line {
+        backend.monitor().feed(line);
+    }
+    if !state.subscriptions.is_empty() && !SUBSCRIBER_COMMANDS.contains(&name.as_str()) {
+        return Ok(RedisResponse::new(
+            SimpleError::new(format!(
+                "ERR Can't execute '{}': only (P)SUBSCRIBE / (P)UNSUBSCRIBE / PING / QUIT / RESET \
+                 are allowed in this context",
+                name
+            ))
+            .into(),
+        ));
+    }
+    if pause::is_write(&cmd) && backend.replication().is_replica() {
+        if in_multi {
+            state.flags.insert(ConnectionFlags::DIRTY_EXEC);
+        }
+        return Ok(RedisResponse::new(
+            SimpleError::new("READONLY You can't write against a read only replica.").into(),
+        ));
+    }
+    if in_multi
+        && !matches!(
+            cmd,
+            Command::Multi(_)
+                | Command::Exec(_)
+                | Command::Discard(_)
+                | Command::Reset(_)
+                | Command::Quit(_)
+        )
+    {
+        state
+            .queued_frames
+            .push(raw.filter(|_| pause::is_write(&cmd) || matches!(cmd, Command::Select(_))));
+        state.queued.push(cmd);
+        return Ok(RedisResponse::new(SimpleString::new("QUEUED").into()));
+    }
+    if !pause::bypasses_pause(&cmd) {
+        // EXEC writes if one of the queued commands does
+        let write = match cmd {
+            Command::Exec(_) => state.queued.iter().any(pause::is_write),
+            _ => pause::is_write(&cmd),
+        };
+        backend.pause_gate().wait(write).await;
+    }
+    info!("Executing command: {:?}", cmd);
+    let start = std::time::Instant::now();
+    let frame = match cmd {
+        Command::Subscribe(cmd) => {
+            return Ok(RedisResponse {
+                frames: cmd.execute_each(&backend, state),
+            })
+        }
+        Command::Unsubscribe(cmd) => {
+            return Ok(RedisResponse {
+                frames: cmd.execute_each(&backend, state),
+            })
+        }
+        // acknowledgements are not answered
+        Command::ReplConf(cmd) => {
+            return Ok(RedisResponse {
+                frames: cmd.reply_to(&backend, state).into_iter().collect(),
+            })
+        }
+        // blocking commands wait for other clients without holding up the backend, they are
+        // logged as what they popped once served
+        Command::BPop(cmd) => cmd.execute_blocking(&backend, raw).await,
+        Command::BLMove(cmd) => cmd.execute_blocking(&backend, raw).await,
+        Command::BZPop(cmd) => cmd.execute_blocking(&backend, raw).await,
+        // not timed as most of it is spent waiting for replicas
+        Command::Wait(cmd) => return Ok(RedisResponse::new(cmd.execute_blocking(&backend).await)),
+        // only this connection waits, the slow log sees the whole sleep
+        Command::DebugSleep(cmd) => cmd.execute_sleeping().await,
+        // takes the exec guard exclusively
+        Command::Exec(cmd) => cmd.execute_on(&backend, state),
+        // they may copy the dataset for the AOF or a replica, which must not miss a write
+        // already done or see one not yet logged
+        cmd @ (Command::BgRewriteAof(_) | Command::ConfigSet(_) | Command::Psync(_)) => {
+            let _guard = backend.exec_guard();
+            execute_command(cmd, &backend, state)
+        }
+        cmd => {
+            let write = pause::is_write(&cmd);
+            let _guard = backend.command_guard();
+            let frame = execute_command(cmd, &backend, state);
+            // logged before another command may run, in the order they were executed
+            if let Some(raw) = raw.filter(|_| write) {
+                propagate(&backend, raw, &frame);
+            }
+            frame
+        }
+    };
+    let elapsed = start.elapsed();
+    backend.record_latency("command", elapsed);
+    if let Some(args) = args {
+        let (threshold, max_len) = {
+            let config = backend.config();
+            (config.slowlog_log_slower_than, config.slowlog_max_len)
+        };
+        backend.slowlog().record(args, elapsed, threshold, max_len);
+    }
+    Ok(RedisResponse::new(frame))
+}
+
+/// log and replicate a write that replied `reply` on the database `backend` is bound to, in a
+/// form that replays the same. called under the command guard, so writes go out in execution
+/// order
+pub(crate) fn propagate(backend: &Backend, frame: RespFrame, reply: &RespFrame) {
+    if matches!(reply, RespFrame::Error(_)) {
+        return;
+    }
+    if let Some(frame) = aof::propagated(frame, reply, backend) {
+        let db = backend.db_index();
+        backend.replication().feed(db, frame.clone());
+        backend.persistence().aof().feed(db, frame);
+    }
+}
+
+// None once `timeout` passed first
+async fn within<T>(timeout: Option<Duration>, fut: impl Future<Output = T>) -> Option<T> {
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, fut).await.ok(),
+        None => Some(fut.await),
+    }
+}
+
+// whether a whole request is already read, the parser would fail on the rest
+fn request_pending(buf: &BytesMut) -> bool {
+    RespFrame::expect_length(buf).is_ok()
+}
+
+// queue replies, and write them out along with earlier ones if `flush`. a client that doesn't
+// take its replies in time is given up on
+async fn send<S, I>(
+    framed: &mut Framed<S, RespFrameCodec>,
+    frames: Vec<I>,
+    flush: bool,
+    timeout: Option<Duration>,
+    addr: &str,
+) -> anyhow::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+    RespFrameCodec: Encoder<I, Error = anyhow::Error>,
+{
+    let write = async {
+        for frame in frames {
+            framed.feed(frame).await?;
+        }
+        if flush {
+            framed.flush().await?;
+        }
+        Ok(())
+    };
+    match within(timeout, write).await {
+        Some(sent) => sent,
+        None => {
+            warn!(
+                "Client {} didn't take its reply in time, closing the connection",
+                addr
+            );
+            Err(anyhow::anyhow!("write timed out"))
+        }
+    }
+}
+
+// lowercase command name of a request, for the client registry
+fn command_name(frame: &RespFrame) -> String {
+    match frame {
+        RespFrame::Array(array) => match array.first() {
+            Some(RespFrame::BulkString(name)) => {
+                String::from_utf8_lossy(&name.to_ascii_lowercase()).into_owned()
+            }
+            _ => "NULL".to_string(),
+        },
+        _ => "NULL".to_string(),
+    }
+}
+
+impl<'a> ClientRegistration<'a> {
+    fn new(backend: &'a Backend, info: ConnectionInfo) -> (Self, oneshot::Receiver<()>) {
+        let id = info.id;
+        let killed = backend.register_client(info);
+        (ClientRegistration { backend, id }, killed)
+    }
+}
+
+impl Drop for ClientRegistration<'_> {
+    fn drop(&mut self) {
+        self.backend.unregister_client(self.id);
+    }
+}
+
+impl RedisResponse {
+    fn new(frame: RespFrame) -> Self {
+        RedisResponse {
+            frames: vec![frame],
+        }
+    }
+}
+
+impl ConnectionState {
+    pub fn new() -> Self {
+        let (watch_tx, watch_rx) = mpsc::unbounded_channel();
+        ConnectionState {
+            selected_db: 0,
+            resp_version: 2,
+            client_name: None,
+            id: NEXT_CONNECTION_ID.fetch_add(1, Ordering::Relaxed),
+            flags: ConnectionFlags::default(),
+            auth_required: false,
+            authenticated: false,
+            queued: Vec::new(),
+            queued_frames: Vec::new(),
+            watched: Vec::new(),
+            watch_tx,
+            watch_rx,
+            watched_keys_modified: false,
+            subscriptions: StreamMap::new(),
+            monitor: None,
+            addr: String::new(),
+            replica_options: ReplicaOptions::default(),
+            replica_stream: None,
+        }
+    }
+}
+
+impl Default for ConnectionState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConnectionFlags {
+    /// close the connection once the reply to the current command is sent
+    pub const CLOSE_AFTER_REPLY: ConnectionFlags = ConnectionFlags(1);
+    /// in a transaction, commands are queued until EXEC
+    pub const MULTI: ConnectionFlags = ConnectionFlags(1 << 1);
+    /// a command failed to queue, EXEC will discard the transaction
+    pub const DIRTY_EXEC: ConnectionFlags = ConnectionFlags(1 << 2);
+
+    pub fn contains(self, flag: ConnectionFlags) -> bool {
+        self.0 & flag.0 == flag.0
+    }
+
+    pub fn insert(&mut self, flag: ConnectionFlags) {
+        self.0 |= flag.0;
+    }
+
+    pub fn remove(&mut self, flag: ConnectionFlags) {
+        self.0 &= !flag.0;
+    }
+}
+
+impl Encoder<RespFrame> for RespFrameCodec {
+    type Error = anyhow::Error;
+    fn encode(&mut self, item: RespFrame, dst: &mut bytes::BytesMut) -> Result<(), Self::Error> {
+        let encodecd = item.encode();
+        dst.extend_from_slice(&encodecd);
+        Ok(())
+    }
+}
+
+// already encoded, e.g. the replication stream
+impl Encoder<Bytes> for RespFrameCodec {
+    type Error = anyhow::Error;
+    fn encode(&mut self, item: Bytes, dst: &mut bytes::BytesMut) -> Result<(), Self::Error> {
+        dst.extend_from_slice(&item);
+        Ok(())
+    }
+}
+
+impl Decoder for RespFrameCodec {
+    type Item = RespFrame;
+    type Error = anyhow::Error;
+    fn decode(&mut self, src: &mut bytes::BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        match RespFrame::decode(src) {
+            Ok(frame) => Ok(Some(frame)),
+            Err(RespError::NotComplete) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::{BulkString, RespArray};
+
+    use super::*;
+
+    fn request(backend: &Backend, state: &ConnectionState, args: &[&str]) -> RedisRequest {
+        let args: Vec<RespFrame> = args.iter().map(|a| BulkString::new(*a).into()).collect();
+        RedisRequest {
+            frame: RespArray::new(args).into(),
+            backend: backend.select(state.selected_db).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_connection_ids_are_unique() {
+        let (a, b) = (ConnectionState::new(), ConnectionState::new());
+        assert_ne!(a.id, b.id);
+        assert_eq!(a.selected_db, 0);
+        assert_eq!(a.resp_version, 2);
+    }
+
+    #[test]
+    fn test_connection_flags() {
+        let mut flags = ConnectionFlags::default();
+        assert!(!flags.contains(ConnectionFlags::CLOSE_AFTER_REPLY));
+        flags.insert(ConnectionFlags::CLOSE_AFTER_REPLY);
+        assert!(flags.contains(ConnectionFlags::CLOSE_AFTER_REPLY));
+        flags.remove(ConnectionFlags::CLOSE_AFTER_REPLY);
+        assert_eq!(flags, ConnectionFlags::default());
+    }
+
+    #[tokio::test]
+    async fn test_select_updates_connection_state() -> Result<()> {
+        let backend = Backend::new();
+        let mut state = ConnectionState::new();
+
+        let req = request(&backend, &state, &["select", "3"]);
+        request_handler(req, &mut state).await?;
+        assert_eq!(state.selected_db, 3);
+
+        // an out of range index leaves the selection alone
+        let req = request(&backend, &state, &["select", "1000"]);
+        let response = request_handler(req, &mut state).await?;
+        assert!(matches!(&response.frames[..], [RespFrame::Error(_)]));
+        assert_eq!(state.selected_db, 3);
+
+        let req = request(&backend, &state, &["set", "key", "value"]);
+        request_handler(req, &mut state).await?;
+        assert!(backend.select(3).unwrap().get("key").is_some());
+        assert!(backend.get("key").is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_transaction() -> Result<()> {
+        let backend = Backend::new();
+        let mut state = ConnectionState::new();
+
+        for args in [
+            &["multi"][..],
+            &["set", "key", "value"],
+            &["select", "1"],
+            &["set", "key", "other"],
+        ] {
+            let req = request(&backend, &state, args);
+            request_handler(req, &mut state).await?;
+        }
+        assert_eq!(state.queued.len(), 3);
+        assert_eq!(backend.get("key"), None);
+
+        let req = request(&backend, &state, &["exec"]);
+        let response = request_handler(req, &mut state).await?;
+        assert!(matches!(&response.frames[..], [RespFrame::Array(a)] if a.len() == 3));
+        assert_eq!(backend.get("key"), Some(BulkString::new("value").into()));
+        assert_eq!(
+            backend.select(1).unwrap().get("key"),
+            Some(BulkString::new("other").into())
+        );
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_exec_with_writes_waits_for_write_pause() -> Result<()> {
+        let backend = Backend::new();
+        let mut state = ConnectionState::new();
+        for args in [&["multi"][..], &["set", "key", "value"]] {
+            let req = request(&backend, &state, args);
+            request_handler(req, &mut state).await?;
+        }
+        backend
+            .pause_gate()
+            .pause(PauseMode::Write, Duration::from_secs(10));
+
+        let start = tokio::time::Instant::now();
+        let req = request(&backend, &state, &["exec"]);
+        let response = request_handler(req, &mut state).await?;
+        assert!(matches!(&response.frames[..], [RespFrame::Array(a)] if a.len() == 1));
+        assert_eq!(start.elapsed(), Duration::from_secs(10));
+        assert_eq!(backend.get("key"), Some(BulkString::new("value").into()));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_transaction_queuing_error() -> Result<()> {
+        let backend = Backend::new();
+        let mut state = ConnectionState::new();
+
+        for args in [&["multi"][..], &["set", "key", "value"], &["get"]] {
+            let req = request(&backend, &state, args);
+            request_handler(req, &mut state).await?;
+        }
+        let req = request(&backend, &state, &["exec"]);
+        let response = request_handler(req, &mut state).await?;
+        assert!(
+            matches!(&response.frames[..], [RespFrame::Error(e)] if e.starts_with("EXECABORT"))
+        );
+        assert_eq!(backend.get("key"), None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_commands_require_auth() -> Result<()> {
+        let backend = Backend::new();
+        backend.config_mut().requirepass = Some("secret".to_string());
+        let mut state = ConnectionState::new();
+
+        let req = request(&backend, &state, &["set", "key", "value"]);
+        let response = request_handler(req, &mut state).await?;
+        assert_eq!(
+            response.frames,
+            vec![SimpleError::new("NOAUTH Authentication required.").into()]
+        );
+        assert!(backend.get("key").is_none());
+
+        let req = request(&backend, &state, &["auth", "secret"]);
+        request_handler(req, &mut state).await?;
+        let req = request(&backend, &state, &["set", "key", "value"]);
+        request_handler(req, &mut state).await?;
+        assert!(backend.get("key").is_some());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_slow_commands_are_logged() -> Result<()> {
+        let backend = Backend::new();
+        let mut state = ConnectionState::new();
+
+        // the default threshold lets quick commands through
+        let req = request(&backend, &state, &["set", "key", "value"]);
+        request_handler(req, &mut state).await?;
+        assert!(backend.slowlog().is_empty());
+
+        backend.config_mut().slowlog_log_slower_than = 0;
+        let req = request(&backend, &state, &["get", "key"]);
+        request_handler(req, &mut state).await?;
+        let entries = backend.slowlog().get(10);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].args, vec![b"get".to_vec(), b"key".to_vec()]);
+        Ok(())
+    }
+}
@@ -0,0 +1,151 @@
+use std::{fmt::Write, time::SystemTime};
+
+use tokio::sync::broadcast;
+
+use crate::{clock, cmd::command_metadata, RespFrame, SimpleString};
+
+// lines a slow MONITOR connection may fall behind by before it starts missing some
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// the feed of processed commands MONITOR connections listen to. formatting a line costs
+/// every command some time, which is only spent while a monitor is connected
+#[derive(Debug)]
+pub struct Monitor {
+    tx: broadcast::Sender<RespFrame>,
+}
+
+impl Default for Monitor {
+    fn default() -> Self {
+        let (tx, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Monitor { tx }
+    }
+}
+
+impl Monitor {
+    pub fn subscribe(&self) -> broadcast::Receiver<RespFrame> {
+        self.tx.subscribe()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.tx.receiver_count() > 0
+    }
+
+    /// the line monitors get for a request, None if nobody listens or the command is not
+    /// shown. administrative commands are left out and passwords redacted
+    pub fn line(&self, db: usize, addr: &str, frame: &RespFrame) -> Option<String> {
+        if !self.is_active() {
+            return None;
+        }
+        let RespFrame::Array(args) = frame else {
+            return None;
+        };
+        let args: Vec<&[u8]> = args
+            .iter()
+            .map(|arg| match arg {
+                RespFrame::BulkString(s) => s.as_slice(),
+                _ => &[],
+            })
+            .collect();
+        let name = String::from_utf8_lossy(args.first()?).to_ascii_lowercase();
+        if command_metadata(&name).is_some_and(|meta| meta.flags.contains(&"admin")) {
+            return None;
+        }
+        Some(format_line(clock::now(), db, addr, &name, &args))
+    }
+
+    pub fn feed(&self, line: String) {
+        // every monitor may have gone in the meantime
+        let _ = self.tx.send(SimpleString::new(line).into());
+    }
+}
+
+// 1339518083.107412 [0 127.0.0.1:60866] "keys" "*"
+fn format_line(at: SystemTime, db: usize, addr: &str, name: &str, args: &[&[u8]]) -> String {
+    let since_epoch = at
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+    let mut line = format!(
+        "{}.{:06} [{} {}]",
+        since_epoch.as_secs(),
+        since_epoch.subsec_micros(),
+        db,
+        addr
+    );
+    for (i, arg) in args.iter().enumerate() {
+        line.push(' ');
+        if i > 0 && name == "auth" {
+            line.push_str("\"(redacted)\"");
+        } else {
+            quote(&mut line, arg);
+        }
+    }
+    line
+}
+
+// a double quoted, escaped argument, so that the line stays on one line
+fn quote(line: &mut String, arg: &[u8]) {
+    line.push('"');
+    for &c in arg {
+        match c {
+            b'\\' => line.push_str("\\\\"),
+            b'"' => line.push_str("\\\""),
+            b'\n' => line.push_str("\\n"),
+            b'\r' => line.push_str("\\r"),
+            b'\t' => line.push_str("\\t"),
+            c if c.is_ascii_graphic() || c == b' ' => line.push(c as char),
+            // writing to a String can't fail
+            c => {
+                let _ = write!(line, "\\x{:02x}", c);
+            }
+        }
+    }
+    line.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{BulkString, RespArray};
+
+    use super::*;
+
+    fn request(args: &[&str]) -> RespFrame {
+        RespArray::new(
+            args.iter()
+                .map(|arg| BulkString::new(*arg).into())
+                .collect::<Vec<RespFrame>>(),
+        )
+        .into()
+    }
+
+    #[test]
+    fn test_format_line() {
+        let at = SystemTime::UNIX_EPOCH + Duration::from_micros(1_339_518_083_107_412);
+        let args: [&[u8]; 3] = [b"set", b"k", b"say \"hi\"\n\x01"];
+        assert_eq!(
+            format_line(at, 2, "127.0.0.1:60866", "set", &args),
+            r#"1339518083.107412 [2 127.0.0.1:60866] "set" "k" "say \"hi\"\n\x01""#
+        );
+
+        let args: [&[u8]; 2] = [b"AUTH", b"secret"];
+        assert!(format_line(at, 0, "a", "auth", &args).ends_with(r#""AUTH" "(redacted)""#));
+    }
+
+    #[test]
+    fn test_monitor_feed() {
+        let monitor = Monitor::default();
+        assert_eq!(monitor.line(0, "a", &request(&["get", "k"])), None);
+
+        let mut rx = monitor.subscribe();
+        let line = monitor.line(0, "a", &request(&["get", "k"])).unwrap();
+        assert!(line.ends_with(r#"[0 a] "get" "k""#), "{}", line);
+        assert_eq!(
+            monitor.line(0, "a", &request(&["config", "get", "*"])),
+            None
+        );
+
+        monitor.feed(line.clone());
+        assert_eq!(rx.try_recv().unwrap(), SimpleString::new(line).into());
+    }
+}
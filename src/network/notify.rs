@@ -0,0 +1,193 @@
+use crate::{
+    cmd::{Command, CommandExecutor},
+    Backend, NotificationConfig, RespFrame,
+};
+
+use super::ConnectionState;
+
+// the keyspace event a command causes if it succeeds
+struct KeyEvent {
+    class: NotificationConfig,
+    event: &'static str,
+    keys: Vec<String>,
+}
+
+/// run a command for a connection, then publish the keyspace events it caused
+pub fn execute_command(cmd: Command, backend: &Backend, conn: &mut ConnectionState) -> RespFrame {
+    let config = backend.config().notify_keyspace_events;
+    let event = key_event(&cmd, backend).filter(|event| config.is_enabled(event.class));
+    let frame = cmd.execute_on(backend, conn);
+    if let Some(event) = event {
+        if succeeded(&frame) {
+            for key in &event.keys {
+                backend.notify_keyspace_event(event.class, event.event, key);
+            }
+        }
+    }
+    frame
+}
+
+// decided before the command runs, while the keys it removes can still be seen
+fn key_event(cmd: &Command, backend: &Backend) -> Option<KeyEvent> {
+    let (class, event, keys) = match cmd {
+        Command::Set(cmd) => (NotificationConfig::STRING, "set", vec![cmd.key.clone()]),
+        // only the keys that exist are deleted
+        Command::Del(cmd) => (
+            NotificationConfig::GENERIC,
+            "del",
+            existing(backend, &cmd.keys),
+        ),
+        Command::Unlink(cmd) => (
+            NotificationConfig::GENERIC,
+            "del",
+            existing(backend, &cmd.keys),
+        ),
+        // a deadline in the past deletes the key
+        Command::Expire(cmd) => match cmd.time.deadline() {
+            Some(_) => (NotificationConfig::GENERIC, "expire", vec![cmd.key.clone()]),
+            None => (NotificationConfig::GENERIC, "del", vec![cmd.key.clone()]),
+        },
+        _ => return None,
+    };
+    Some(KeyEvent { class, event, keys })
+}
+
+fn existing(backend: &Backend, keys: &[String]) -> Vec<String> {
+    let db = backend.read();
+    keys.iter()
+        .filter(|key| db.contains_key(key))
+        .cloned()
+        .collect()
+}
+
+// errors change nothing, EXPIRE replies 0 when the key doesn't exist
+fn succeeded(frame: &RespFrame) -> bool {
+    !matches!(frame, RespFrame::Error(_) | RespFrame::Integer(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::FutureExt;
+    use tokio_stream::StreamExt;
+
+    use crate::{
+        backend::clock,
+        cmd::{Del, Set},
+        network::Subscription,
+        BulkString, RespArray,
+    };
+
+    use super::*;
+
+    fn message(channel: &str, message: &str) -> RespFrame {
+        RespArray::new(vec![
+            BulkString::new("message").into(),
+            BulkString::new(channel).into(),
+            BulkString::new(message).into(),
+        ])
+        .into()
+    }
+
+    async fn received(conn: &mut ConnectionState) -> RespFrame {
+        let (_, frame) = conn.subscriptions.next().await.unwrap();
+        frame.unwrap()
+    }
+
+    fn set(backend: &Backend, conn: &mut ConnectionState, key: &str) -> RespFrame {
+        let cmd = Command::Set(Set {
+            key: key.to_string(),
+            value: BulkString::new("value"),
+        });
+        execute_command(cmd, backend, conn)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_keyspace_notifications() {
+        let backend = Backend::default();
+        let mut subscriber = ConnectionState::new();
+        let mut conn = ConnectionState::new();
+        subscriber.subscribe(
+            backend.pubsub(),
+            Subscription::Channel("__keyspace@0__:foo".to_string()),
+        );
+        subscriber.subscribe(
+            backend.pubsub(),
+            Subscription::Channel("__keyevent@0__:del".to_string()),
+        );
+
+        // nothing is published until notify-keyspace-events is set
+        set(&backend, &mut conn, "foo");
+        backend
+            .config_mut()
+            .set("notify-keyspace-events", "KEA")
+            .unwrap();
+        set(&backend, &mut conn, "foo");
+        assert_eq!(
+            received(&mut subscriber).await,
+            message("__keyspace@0__:foo", "set")
+        );
+
+        // only keys that existed are reported deleted
+        let cmd = Command::Del(Del {
+            keys: vec!["foo".to_string(), "missing".to_string()],
+        });
+        assert_eq!(
+            execute_command(cmd, &backend, &mut conn),
+            RespFrame::Integer(1)
+        );
+        let mut messages = vec![
+            received(&mut subscriber).await,
+            received(&mut subscriber).await,
+        ];
+        messages.sort_by_key(|frame| format!("{:?}", frame));
+        assert_eq!(
+            messages,
+            vec![
+                message("__keyevent@0__:del", "foo"),
+                message("__keyspace@0__:foo", "del"),
+            ]
+        );
+        assert!(subscriber.subscriptions.next().now_or_never().is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_expired_notifications() {
+        let backend = Backend::default();
+        backend
+            .config_mut()
+            .set("notify-keyspace-events", "Ex")
+            .unwrap();
+        let mut subscriber = ConnectionState::new();
+        subscriber.subscribe(
+            backend.pubsub(),
+            Subscription::Channel("__keyevent@0__:expired".to_string()),
+        );
+        let mut conn = ConnectionState::new();
+        set(&backend, &mut conn, "reaped");
+        set(&backend, &mut conn, "lazy");
+        for key in ["reaped", "lazy"] {
+            backend
+                .write()
+                .expire(key, clock::now() + Duration::from_secs(1));
+        }
+
+        tokio::time::advance(Duration::from_secs(1)).await;
+        // a write to an expired key removes it, the next cycle tells about it
+        assert!(backend.write().remove_if_expired("lazy"));
+        assert_eq!(backend.active_expire_cycle(), 1);
+        let mut messages = vec![
+            received(&mut subscriber).await,
+            received(&mut subscriber).await,
+        ];
+        messages.sort_by_key(|frame| format!("{:?}", frame));
+        assert_eq!(
+            messages,
+            vec![
+                message("__keyevent@0__:expired", "lazy"),
+                message("__keyevent@0__:expired", "reaped"),
+            ]
+        );
+    }
+}
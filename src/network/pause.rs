@@ -0,0 +1,198 @@
+use std::time::Duration;
+
+use parking_lot::RwLock;
+use tokio::{sync::Notify, time::Instant};
+
+use crate::cmd::Command;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PauseMode {
+    // only commands that may change the keyspace wait
+    Write,
+    All,
+}
+
+#[derive(Debug, Default)]
+pub struct PauseState {
+    // what is paused and until when, a past deadline means not paused
+    paused: Option<(PauseMode, Instant)>,
+}
+
+/// CLIENT PAUSE, shared by every connection: commands wait for it before they execute
+#[derive(Debug, Default)]
+pub struct PauseGate {
+    state: RwLock<PauseState>,
+    unpaused: Notify,
+}
+
+impl PauseState {
+    // the deadline a command has to wait for, None if it may run now
+    fn blocks(&self, write: bool) -> Option<Instant> {
+        match self.paused {
+            Some((mode, until)) if until > Instant::now() && (write || mode == PauseMode::All) => {
+                Some(until)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl PauseGate {
+    /// pause for `timeout`. pausing again while paused keeps the later deadline and the
+    /// stricter mode, as in redis
+    pub fn pause(&self, mode: PauseMode, timeout: Duration) {
+        let mut state = self.state.write();
+        let until = Instant::now() + timeout;
+        state.paused = match state.paused {
+            Some((old_mode, old_until)) if old_until > Instant::now() => {
+                let mode = if old_mode == PauseMode::All {
+                    old_mode
+                } else {
+                    mode
+                };
+                Some((mode, until.max(old_until)))
+            }
+            _ => Some((mode, until)),
+        };
+    }
+
+    pub fn unpause(&self) {
+        self.state.write().paused = None;
+        self.unpaused.notify_waiters();
+    }
+
+    /// return once a command of this kind may run
+    pub async fn wait(&self, write: bool) {
+        loop {
+            // register interest before looking at the state, so that an unpause landing in
+            // between still wakes us up
+            let notified = self.unpaused.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            let Some(until) = self.state.read().blocks(write) else {
+                return;
+            };
+            tokio::select! {
+                _ = tokio::time::sleep_until(until) => {}
+                _ = notified => {}
+            }
+        }
+    }
+}
+
+/// whether a command waits for CLIENT PAUSE WRITE
+pub fn is_write(cmd: &Command) -> bool {
+    match cmd {
+        Command::SCombine(cmd) => cmd.dst.is_some(),
+        Command::ZCombine(cmd) => cmd.dst.is_some(),
+        Command::Set(_)
+        | Command::Del(_)
+        | Command::Unlink(_)
+        | Command::Rename(_)
+        | Command::Expire(_)
+        | Command::Persist(_)
+        | Command::Copy(_)
+        | Command::Flush(_)
+        | Command::SwapDb(_)
+        | Command::SetRange(_)
+        | Command::HSet(_)
+        | Command::HDel(_)
+        | Command::HMSet(_)
+        | Command::HIncrBy(_)
+        | Command::HIncrByFloat(_)
+        | Command::HSetNx(_)
+        | Command::Push(_)
+        | Command::Pop(_)
+        | Command::LSet(_)
+        | Command::LInsert(_)
+        | Command::LRem(_)
+        | Command::LTrim(_)
+        | Command::LMove(_)
+        | Command::LMPop(_)
+        | Command::BPop(_)
+        | Command::BLMove(_)
+        | Command::SAdd(_)
+        | Command::SRem(_)
+        | Command::SPop(_)
+        | Command::SMove(_)
+        | Command::ZAdd(_)
+        | Command::ZIncrBy(_)
+        | Command::ZPop(_)
+        | Command::BZPop(_)
+        | Command::ZRangeStore(_)
+        | Command::GeoAdd(_)
+        | Command::PfAdd(_)
+        | Command::PfMerge(_)
+        | Command::SetBit(_)
+        | Command::BitOp(_) => true,
+        _ => false,
+    }
+}
+
+/// whether a command runs even while clients are paused, so that CLIENT UNPAUSE gets through
+pub fn bypasses_pause(cmd: &Command) -> bool {
+    matches!(
+        cmd,
+        Command::ClientId(_)
+            | Command::ClientSetName(_)
+            | Command::ClientGetName(_)
+            | Command::ClientList(_)
+            | Command::ClientKill(_)
+            | Command::ClientPause(_)
+            | Command::ClientUnpause(_)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_pause_times_out() {
+        let pause = PauseGate::default();
+        pause.pause(PauseMode::All, Duration::from_secs(1));
+        let start = Instant::now();
+        pause.wait(false).await;
+        assert_eq!(start.elapsed(), Duration::from_secs(1));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_write_pause_lets_reads_through() {
+        let pause = PauseGate::default();
+        pause.pause(PauseMode::Write, Duration::from_secs(1));
+        let start = Instant::now();
+        pause.wait(false).await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        pause.wait(true).await;
+        assert_eq!(start.elapsed(), Duration::from_secs(1));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_unpause_wakes_waiters() {
+        let pause = Arc::new(PauseGate::default());
+        pause.pause(PauseMode::All, Duration::from_secs(10));
+        let waiter = tokio::spawn({
+            let pause = pause.clone();
+            async move { pause.wait(true).await }
+        });
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert!(!waiter.is_finished());
+
+        let start = Instant::now();
+        pause.unpause();
+        waiter.await.unwrap();
+        assert_eq!(start.elapsed(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_pause_keeps_later_deadline_and_stricter_mode() {
+        let pause = PauseGate::default();
+        pause.pause(PauseMode::All, Duration::from_secs(10));
+        pause.pause(PauseMode::Write, Duration::from_secs(1));
+        let state = pause.state.read();
+        assert!(state.blocks(false).is_some());
+        assert!(state.blocks(false).unwrap() > Instant::now() + Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+
+use parking_lot::{RwLock, RwLockWriteGuard};
+use tokio::sync::broadcast;
+
+use tokio_stream::wrappers::BroadcastStream;
+
+use crate::{cmd::glob_match, BulkString, RespArray, RespFrame};
+
+use super::ConnectionState;
+
+// messages a slow subscriber may fall behind by before it starts missing some
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// what a connection listens to, a channel name or a glob pattern of channel names
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Subscription {
+    Channel(String),
+    Pattern(String),
+}
+
+/// the pub/sub registry shared by every connection: one broadcast channel per subscribed
+/// channel name or pattern, carrying ready to send message frames
+#[derive(Debug, Default)]
+pub struct PubSub {
+    channels: RwLock<HashMap<String, broadcast::Sender<RespFrame>>>,
+    patterns: RwLock<HashMap<String, broadcast::Sender<RespFrame>>>,
+}
+
+impl PubSub {
+    pub fn subscribe(&self, subscription: &Subscription) -> broadcast::Receiver<RespFrame> {
+        let (mut senders, name) = self.senders_mut(subscription);
+        match senders.get(name) {
+            Some(tx) => tx.subscribe(),
+            None => {
+                let (tx, rx) = broadcast::channel(CHANNEL_CAPACITY);
+                senders.insert(name.to_string(), tx);
+                rx
+            }
+        }
+    }
+
+    /// call once a receiver from `subscribe` is dropped, forgets the channel or pattern when
+    /// nobody listens anymore
+    pub fn unsubscribed(&self, subscription: &Subscription) {
+        let (mut senders, name) = self.senders_mut(subscription);
+        if senders.get(name).is_some_and(|tx| tx.receiver_count() == 0) {
+            senders.remove(name);
+        }
+    }
+
+    /// send `message` to every subscriber of `channel` and of the patterns matching it,
+    /// returns how many received it
+    pub fn publish(&self, channel: &str, message: &[u8]) -> usize {
+        let mut receivers = 0;
+        if let Some(tx) = self.channels.read().get(channel) {
+            let frame = RespArray::new(vec![
+                BulkString::new("message").into(),
+                BulkString::new(channel).into(),
+                BulkString::new(message).into(),
+            ])
+            .into();
+            receivers += tx.send(frame).unwrap_or(0);
+        }
+        for (pattern, tx) in self.patterns.read().iter() {
+            if !glob_match(pattern.as_bytes(), channel.as_bytes()) {
+                continue;
+            }
+            let frame = RespArray::new(vec![
+                BulkString::new("pmessage").into(),
+                BulkString::new(pattern.as_str()).into(),
+                BulkString::new(channel).into(),
+                BulkString::new(message).into(),
+            ])
+            .into();
+            receivers += tx.send(frame).unwrap_or(0);
+        }
+        receivers
+    }
+
+    /// channels with at least one subscriber, optionally only those matching `pattern`
+    pub fn channels(&self, pattern: Option<&str>) -> Vec<String> {
+        self.channels
+            .read()
+            .iter()
+            .filter(|(_, tx)| tx.receiver_count() > 0)
+            .map(|(channel, _)| channel)
+            .filter(|channel| pattern.is_none_or(|p| glob_match(p.as_bytes(), channel.as_bytes())))
+            .cloned()
+            .collect()
+    }
+
+    /// subscribers of `channel`, not counting pattern subscribers
+    pub fn numsub(&self, channel: &str) -> usize {
+        self.channels
+            .read()
+            .get(channel)
+            .map_or(0, |tx| tx.receiver_count())
+    }
+
+    /// distinct patterns with at least one subscriber
+    pub fn numpat(&self) -> usize {
+        self.patterns
+            .read()
+            .values()
+            .filter(|tx| tx.receiver_count() > 0)
+            .count()
+    }
+
+    fn senders_mut<'a>(
+        &self,
+        subscription: &'a Subscription,
+    ) -> (
+        RwLockWriteGuard<'_, HashMap<String, broadcast::Sender<RespFrame>>>,
+        &'a str,
+    ) {
+        match subscription {
+            Subscription::Channel(name) => (self.channels.write(), name),
+            Subscription::Pattern(name) => (self.patterns.write(), name),
+        }
+    }
+}
+
+impl ConnectionState {
+    /// start receiving the messages of a channel or pattern, returns the number of
+    /// subscriptions
+    pub fn subscribe(&mut self, pubsub: &PubSub, subscription: Subscription) -> usize {
+        if !self.subscriptions.contains_key(&subscription) {
+            let rx = pubsub.subscribe(&subscription);
+            self.subscriptions
+                .insert(subscription, BroadcastStream::new(rx));
+        }
+        self.subscriptions.len()
+    }
+
+    /// stop receiving the messages of a channel or pattern, returns the number of
+    /// subscriptions left
+    pub fn unsubscribe(&mut self, pubsub: &PubSub, subscription: &Subscription) -> usize {
+        if self.subscriptions.remove(subscription).is_some() {
+            pubsub.unsubscribed(subscription);
+        }
+        self.subscriptions.len()
+    }
+
+    /// subscribed channels, in no particular order
+    pub fn subscribed_channels(&self) -> Vec<String> {
+        self.subscriptions
+            .keys()
+            .filter_map(|s| match s {
+                Subscription::Channel(name) => Some(name.clone()),
+                Subscription::Pattern(_) => None,
+            })
+            .collect()
+    }
+
+    /// subscribed patterns, in no particular order
+    pub fn subscribed_patterns(&self) -> Vec<String> {
+        self.subscriptions
+            .keys()
+            .filter_map(|s| match s {
+                Subscription::Pattern(name) => Some(name.clone()),
+                Subscription::Channel(_) => None,
+            })
+            .collect()
+    }
+
+    pub fn unsubscribe_all(&mut self, pubsub: &PubSub) {
+        let subscriptions: Vec<_> = self.subscriptions.keys().cloned().collect();
+        for subscription in subscriptions {
+            self.unsubscribe(pubsub, &subscription);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_publish() {
+        let pubsub = PubSub::default();
+        assert_eq!(pubsub.publish("news", b"hello"), 0);
+
+        let news = Subscription::Channel("news".to_string());
+        let mut rx1 = pubsub.subscribe(&news);
+        let rx2 = pubsub.subscribe(&news);
+        assert_eq!(pubsub.publish("news", b"hello"), 2);
+        assert_eq!(pubsub.publish("other", b"hello"), 0);
+        assert_eq!(
+            rx1.try_recv().unwrap(),
+            RespArray::new(vec![
+                BulkString::new("message").into(),
+                BulkString::new("news").into(),
+                BulkString::new("hello").into(),
+            ])
+            .into()
+        );
+
+        drop(rx2);
+        pubsub.unsubscribed(&news);
+        assert_eq!(pubsub.publish("news", b"hello"), 1);
+        drop(rx1);
+        pubsub.unsubscribed(&news);
+        assert!(pubsub.channels.read().is_empty());
+    }
+
+    #[test]
+    fn test_publish_to_patterns() {
+        let pubsub = PubSub::default();
+        let pattern = Subscription::Pattern("h?llo".to_string());
+        let mut rx = pubsub.subscribe(&pattern);
+
+        assert_eq!(pubsub.publish("hello", b"1"), 1);
+        assert_eq!(pubsub.publish("hallo", b"2"), 1);
+        assert_eq!(pubsub.publish("heello", b"3"), 0);
+
+        // same glob rules as KEYS, `?` is any one character so a class is needed to skip hxllo
+        let class = Subscription::Pattern("h[ae]llo".to_string());
+        let _class_rx = pubsub.subscribe(&class);
+        assert_eq!(pubsub.publish("hxllo", b"4"), 1);
+        assert_eq!(pubsub.publish("hallo", b"5"), 2);
+
+        let pmessage = |channel: &str, message: &str| -> RespFrame {
+            RespArray::new(vec![
+                BulkString::new("pmessage").into(),
+                BulkString::new("h?llo").into(),
+                BulkString::new(channel).into(),
+                BulkString::new(message).into(),
+            ])
+            .into()
+        };
+        assert_eq!(rx.try_recv().unwrap(), pmessage("hello", "1"));
+        assert_eq!(rx.try_recv().unwrap(), pmessage("hallo", "2"));
+
+        // a channel subscriber and a pattern subscriber both count
+        let _channel_rx = pubsub.subscribe(&Subscription::Channel("hello".to_string()));
+        assert_eq!(pubsub.publish("hello", b"6"), 3);
+
+        drop(rx);
+        pubsub.unsubscribed(&pattern);
+        assert_eq!(pubsub.patterns.read().len(), 1);
+    }
+}
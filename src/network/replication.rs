@@ -0,0 +1,696 @@
+//! replication: a master sends the replicas connected to it a copy of its dataset as an RDB
+//! file, then every write command it executes. a replica is a client of its master that sent
+//! PSYNC, and a server becomes one with REPLICAOF. the latest part of the stream is kept in a
+//! backlog, so that a replica that lost its connection only needs what it missed
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail, Result};
+use bytes::{Buf, Bytes, BytesMut};
+use parking_lot::Mutex;
+use rand::Rng;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+    runtime::Handle,
+    sync::{
+        mpsc::{self, UnboundedReceiver, UnboundedSender},
+        Notify,
+    },
+    task::JoinHandle,
+    time::Instant,
+};
+use tracing::{info, warn};
+
+use crate::{
+    persistence::{self, aof},
+    Backend, ConnectionId, RespDecodeV2, RespEncode, RespError, RespFrame,
+};
+
+// how long a replica waits before connecting to its master again
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+// how often a replica tells its master how much of the stream it received
+const ACK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// both sides of replication: the replicas of this server and how much of the replication
+/// stream each has acknowledged, and the master this server replicates if any
+#[derive(Debug)]
+pub struct ReplicationState {
+    // 40 random hex digits naming the history of this dataset
+    replid: String,
+    // bytes of write commands sent to replicas so far
+    offset: AtomicU64,
+    stream: Mutex<Stream>,
+    // woken whenever an acknowledgement arrives
+    acked: Notify,
+    // None while this server is a master
+    master: Mutex<Option<MasterLink>>,
+}
+
+/// the master a replica follows, as INFO reports it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MasterStatus {
+    pub host: String,
+    pub port: u16,
+    // whether the dataset was received and the master's writes are being applied
+    pub link_up: bool,
+}
+
+/// a replica connected to this server, as INFO reports it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplicaStatus {
+    pub ip: String,
+    pub port: u16,
+    // the offset acknowledged last, and how long ago
+    pub offset: u64,
+    pub lag: Duration,
+}
+
+/// what a replica told its master about itself with REPLCONF, before PSYNC
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReplicaOptions {
+    // where the replica itself listens for clients
+    pub listening_port: Option<u16>,
+    pub ip_address: Option<String>,
+    // what the replica understands, e.g. psync2 for a CONTINUE naming the replication id
+    pub capa: Vec<String>,
+}
+
+/// the last bytes of the replication stream, in a ring buffer of a fixed size
+#[derive(Debug)]
+pub struct Backlog {
+    buf: Vec<u8>,
+    size: usize,
+    // where the oldest byte is once the buffer is full, 0 until then
+    pos: usize,
+    // the stream offset after the last byte pushed
+    end: u64,
+}
+
+#[derive(Debug, Default)]
+struct Stream {
+    // the database the stream selected last, None until a SELECT was sent to every replica
+    db: Option<usize>,
+    replicas: HashMap<ConnectionId, Replica>,
+    // created for the first replica, and kept from then on
+    backlog: Option<Backlog>,
+}
+
+#[derive(Debug)]
+struct Replica {
+    options: ReplicaOptions,
+    // to the connection of the replica, which writes it out
+    tx: UnboundedSender<Bytes>,
+    // the latest offset the replica acknowledged, and when
+    ack: u64,
+    ack_time: Instant,
+}
+
+#[derive(Debug)]
+struct MasterLink {
+    host: String,
+    port: u16,
+    link_up: Arc<AtomicBool>,
+    // connects to the master and applies its stream, until the link is dropped
+    task: JoinHandle<()>,
+}
+
+// where a replica is in its master's stream, kept across reconnects to continue from there
+#[derive(Debug, Default)]
+struct Progress {
+    // None until the first full resync
+    replid: Option<String>,
+    offset: u64,
+    replay: aof::Replay,
+}
+
+// a replica's connection to its master, read from directly as the RDB file is not RESP
+struct MasterConnection {
+    stream: TcpStream,
+    buf: BytesMut,
+}
+
+impl Default for ReplicationState {
+    fn default() -> Self {
+        ReplicationState {
+            replid: random_id(),
+            offset: AtomicU64::new(0),
+            stream: Mutex::new(Stream::default()),
+            acked: Notify::new(),
+            master: Mutex::new(None),
+        }
+    }
+}
+
+impl ReplicationState {
+    pub fn replid(&self) -> &str {
+        &self.replid
+    }
+
+    pub fn offset(&self) -> u64 {
+        self.offset.load(Ordering::Relaxed)
+    }
+
+    /// account for `bytes` more of the replication stream, returns the new offset
+    pub fn advance(&self, bytes: u64) -> u64 {
+        self.offset.fetch_add(bytes, Ordering::Relaxed) + bytes
+    }
+
+    /// make connection `id` a replica, which is sent `rdb` and then the stream from the
+    /// returned offset on. the caller holds the exec guard, so that no write is missing from
+    /// both or in both. the first replica starts a backlog of `backlog_size` bytes
+    pub fn add_replica(
+        &self,
+        id: ConnectionId,
+        options: ReplicaOptions,
+        rdb: Vec<u8>,
+        backlog_size: usize,
+    ) -> (u64, UnboundedReceiver<Bytes>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let _ = tx.send(rdb_payload(&options, rdb).into());
+
+        let mut stream = self.stream.lock();
+        let offset = self.offset();
+        stream
+            .replicas
+            .insert(id, Replica::new(options, tx, offset));
+        // the new replica doesn't know which database the stream is on
+        stream.db = None;
+        stream
+            .backlog
+            .get_or_insert_with(|| Backlog::new(backlog_size, offset));
+        (offset, rx)
+    }
+
+    /// make connection `id` a replica that already has the stream up to `offset`, if the
+    /// backlog still holds the rest of it
+    pub fn continue_replica(
+        &self,
+        id: ConnectionId,
+        options: ReplicaOptions,
+        offset: u64,
+    ) -> Option<UnboundedReceiver<Bytes>> {
+        let mut stream = self.stream.lock();
+        let missed = self.offset().checked_sub(offset)?;
+        let missed = stream.backlog.as_ref()?.range(offset, missed as usize)?;
+        let (tx, rx) = mpsc::unbounded_channel();
+        let _ = tx.send(missed.into());
+        stream
+            .replicas
+            .insert(id, Replica::new(options, tx, offset));
+        Some(rx)
+    }
+
+    /// keep `size` bytes of the stream from now on, the latest of what is kept already
+    pub fn resize_backlog(&self, size: usize) {
+        if let Some(backlog) = self.stream.lock().backlog.as_mut() {
+            backlog.resize(size);
+        }
+    }
+
+    /// a replica confirmed it received the stream up to `offset`
+    pub fn ack(&self, replica: ConnectionId, offset: u64) {
+        if let Some(replica) = self.stream.lock().replicas.get_mut(&replica) {
+            replica.ack = offset;
+            replica.ack_time = Instant::now();
+        }
+        self.acked.notify_waiters();
+    }
+
+    pub fn remove_replica(&self, replica: ConnectionId) {
+        self.stream.lock().replicas.remove(&replica);
+    }
+
+    /// the replicas by connection id, i.e. in the order they connected
+    pub fn replicas(&self) -> Vec<ReplicaStatus> {
+        let stream = self.stream.lock();
+        let mut ids: Vec<_> = stream.replicas.keys().collect();
+        ids.sort();
+        ids.into_iter()
+            .map(|id| {
+                let replica = &stream.replicas[id];
+                ReplicaStatus {
+                    ip: replica.options.ip_address.clone().unwrap_or_default(),
+                    port: replica.options.listening_port.unwrap_or_default(),
+                    offset: replica.ack,
+                    lag: replica.ack_time.elapsed(),
+                }
+            })
+            .collect()
+    }
+
+    pub fn replica_count(&self) -> usize {
+        self.stream.lock().replicas.len()
+    }
+
+    /// bytes of the stream held in the backlog
+    pub fn backlog_len(&self) -> usize {
+        self.stream
+            .lock()
+            .backlog
+            .as_ref()
+            .map_or(0, |backlog| backlog.buf.len())
+    }
+
+    /// whether writes are fed to the stream, which they are from the first replica on
+    pub fn is_active(&self) -> bool {
+        self.stream.lock().backlog.is_some()
+    }
+
+    /// number of replicas that acknowledged at least `offset`
+    pub fn acked(&self, offset: u64) -> usize {
+        self.stream
+            .lock()
+            .replicas
+            .values()
+            .filter(|replica| replica.ack >= offset)
+            .count()
+    }
+
+    /// wait until `replicas` replicas acknowledged `offset` or the timeout expires, None waits
+    /// forever. returns the number of replicas that acknowledged
+    pub async fn wait_for(&self, replicas: usize, offset: u64, timeout: Option<Duration>) -> usize {
+        let deadline = timeout.map(|timeout| tokio::time::Instant::now() + timeout);
+        loop {
+            // registered before checking, so that an ack in between isn't missed
+            let notified = self.acked.notified();
+            let acked = self.acked(offset);
+            if acked >= replicas {
+                return acked;
+            }
+            match deadline {
+                Some(deadline) => {
+                    if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                        return self.acked(offset);
+                    }
+                }
+                None => notified.await,
+            }
+        }
+    }
+
+    /// send a write command executed on database `db` to the replicas
+    pub fn feed(&self, db: usize, frame: RespFrame) {
+        self.propagate(db, vec![frame]);
+    }
+
+    /// send the writes of a transaction started on database `db`, so that replicas apply them
+    /// as one
+    pub fn feed_transaction(&self, db: usize, frames: Vec<RespFrame>) {
+        let mut wrapped = Vec::with_capacity(frames.len() + 2);
+        wrapped.push(aof::command(&["multi"]));
+        wrapped.extend(frames);
+        wrapped.push(aof::command(&["exec"]));
+        self.propagate(db, wrapped);
+    }
+
+    fn propagate(&self, db: usize, frames: Vec<RespFrame>) {
+        let mut guard = self.stream.lock();
+        let stream = &mut *guard;
+        let Some(backlog) = stream.backlog.as_mut() else {
+            return;
+        };
+        let mut buf = Vec::new();
+        if stream.db != Some(db) {
+            buf.extend(aof::command(&["select", &db.to_string()]).encode());
+            stream.db = Some(db);
+        }
+        for frame in frames {
+            // a SELECT inside a transaction leaves the database unknown
+            if aof::is_select(&frame) {
+                stream.db = None;
+            }
+            buf.extend(frame.encode());
+        }
+        backlog.push(&buf);
+        self.advance(buf.len() as u64);
+        let data = Bytes::from(buf);
+        // a replica that went away is removed once its connection is closed
+        for replica in stream.replicas.values() {
+            let _ = replica.tx.send(data.clone());
+        }
+    }
+
+    /// the master this server replicates, None while it is a master
+    pub fn master(&self) -> Option<MasterStatus> {
+        self.master.lock().as_ref().map(|link| MasterStatus {
+            host: link.host.clone(),
+            port: link.port,
+            link_up: link.link_up.load(Ordering::Relaxed),
+        })
+    }
+
+    pub fn is_replica(&self) -> bool {
+        self.master.lock().is_some()
+    }
+
+    /// start replicating `host:port` in a task of `handle`, in place of the master followed
+    /// so far. the dataset is replaced once the master's copy arrived
+    pub fn replicate(&self, backend: &Backend, host: String, port: u16, handle: &Handle) {
+        let link_up = Arc::new(AtomicBool::new(false));
+        let task = handle.spawn(follow_master(
+            backend.clone(),
+            host.clone(),
+            port,
+            link_up.clone(),
+        ));
+        *self.master.lock() = Some(MasterLink {
+            host,
+            port,
+            link_up,
+            task,
+        });
+    }
+
+    /// stop replicating, the dataset is kept as it is
+    pub fn promote(&self) {
+        self.master.lock().take();
+    }
+}
+
+impl Replica {
+    fn new(options: ReplicaOptions, tx: UnboundedSender<Bytes>, offset: u64) -> Self {
+        Replica {
+            options,
+            tx,
+            ack: offset,
+            ack_time: Instant::now(),
+        }
+    }
+}
+
+impl Backlog {
+    /// an empty backlog of `size` bytes, the stream being at `offset`
+    pub fn new(size: usize, offset: u64) -> Self {
+        Backlog {
+            buf: Vec::new(),
+            size: size.max(1),
+            pos: 0,
+            end: offset,
+        }
+    }
+
+    /// the offset of the oldest byte kept
+    pub fn start(&self) -> u64 {
+        self.end - self.buf.len() as u64
+    }
+
+    /// append the next bytes of the stream, pushing out the oldest once full
+    pub fn push(&mut self, data: &[u8]) {
+        self.end += data.len() as u64;
+        let mut data = &data[data.len().saturating_sub(self.size)..];
+        let fill = (self.size - self.buf.len()).min(data.len());
+        self.buf.extend_from_slice(&data[..fill]);
+        data = &data[fill..];
+        while !data.is_empty() {
+            let n = (self.size - self.pos).min(data.len());
+            self.buf[self.pos..self.pos + n].copy_from_slice(&data[..n]);
+            self.pos = (self.pos + n) % self.size;
+            data = &data[n..];
+        }
+    }
+
+    /// `len` bytes of the stream from offset `start`, None unless they are all kept
+    pub fn range(&self, start: u64, len: usize) -> Option<Vec<u8>> {
+        if start < self.start() || start + len as u64 > self.end {
+            return None;
+        }
+        if len == 0 {
+            return Some(Vec::new());
+        }
+        let first = (self.pos + (start - self.start()) as usize) % self.buf.len();
+        let head = (self.buf.len() - first).min(len);
+        let mut ret = Vec::with_capacity(len);
+        ret.extend_from_slice(&self.buf[first..first + head]);
+        ret.extend_from_slice(&self.buf[..len - head]);
+        Some(ret)
+    }
+
+    pub fn resize(&mut self, size: usize) {
+        let kept = self.buf.len().min(size.max(1));
+        let data = self
+            .range(self.end - kept as u64, kept)
+            .expect("the latest bytes are kept");
+        *self = Backlog::new(size, self.end - kept as u64);
+        self.push(&data);
+    }
+}
+
+// 40 random hex digits, as replication ids and EOF marks are
+fn random_id() -> String {
+    rand::thread_rng()
+        .gen::<[u8; 20]>()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+// a replica that knows the EOF capability is sent the RDB file between two random marks, as
+// masters that don't know its length up front do. the others get its length first
+fn rdb_payload(options: &ReplicaOptions, rdb: Vec<u8>) -> Vec<u8> {
+    if !options.capa.iter().any(|capa| capa == "eof") {
+        let mut payload = format!("${}\r\n", rdb.len()).into_bytes();
+        payload.extend(rdb);
+        return payload;
+    }
+    let mark = random_id();
+    let mut payload = format!("$EOF:{}\r\n", mark).into_bytes();
+    payload.extend(rdb);
+    payload.extend(mark.as_bytes());
+    payload
+}
+
+impl Drop for MasterLink {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+// reconnects whenever the link breaks, continuing where it stopped if the master can
+async fn follow_master(backend: Backend, host: String, port: u16, link_up: Arc<AtomicBool>) {
+    let mut progress = Progress::default();
+    loop {
+        match sync_with_master(&backend, &host, port, &link_up, &mut progress).await {
+            Ok(()) => info!("Connection with master {}:{} lost", host, port),
+            Err(e) => warn!("Replication from master {}:{} failed: {}", host, port, e),
+        }
+        link_up.store(false, Ordering::Relaxed);
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+async fn sync_with_master(
+    backend: &Backend,
+    host: &str,
+    port: u16,
+    link_up: &AtomicBool,
+    progress: &mut Progress,
+) -> Result<()> {
+    info!("Connecting to MASTER {}:{}", host, port);
+    let mut master = MasterConnection {
+        stream: TcpStream::connect((host, port)).await?,
+        buf: BytesMut::new(),
+    };
+    // any reply but an error shows the master is there
+    if let RespFrame::Error(e) = master.call(&["ping"]).await? {
+        bail!("master replied to ping with {:?}", e);
+    }
+    // masters that don't know these options can still replicate
+    let listening_port = backend.config().port.to_string();
+    for args in [
+        &["replconf", "listening-port", &listening_port][..],
+        &["replconf", "capa", "eof", "capa", "psync2"],
+    ] {
+        if let RespFrame::Error(e) = master.call(args).await? {
+            warn!("Master does not understand {}: {:?}", args.join(" "), e);
+        }
+    }
+
+    // the offset asked for is the one of the first byte missing, counting from 1
+    let (replid, offset) = match &progress.replid {
+        Some(replid) => (replid.clone(), (progress.offset + 1).to_string()),
+        None => ("?".to_string(), "-1".to_string()),
+    };
+    let reply = master.call(&["psync", &replid, &offset]).await?;
+    let RespFrame::SimpleString(reply) = reply else {
+        bail!("unexpected reply to psync: {:?}", reply);
+    };
+    match reply.split(' ').collect::<Vec<_>>()[..] {
+        ["FULLRESYNC", replid, offset] => {
+            info!("Full resync from master: {}:{}", replid, offset);
+            let offset = offset.parse()?;
+            let rdb = master.payload().await?;
+            backend.restore(persistence::parse(&rdb)?);
+            *progress = Progress {
+                replid: Some(replid.to_string()),
+                offset,
+                replay: aof::Replay::default(),
+            };
+        }
+        // the master may have taken over another history, which continues this one
+        ["CONTINUE", ref new_replid @ ..] => {
+            info!("Successful partial resynchronization with master");
+            if let [replid] = new_replid {
+                progress.replid = Some(replid.to_string());
+            }
+        }
+        _ => bail!("unexpected reply to psync: {}", *reply),
+    }
+    link_up.store(true, Ordering::Relaxed);
+    info!("MASTER <-> REPLICA sync: Finished with success");
+
+    let mut acks = tokio::time::interval(ACK_INTERVAL);
+    loop {
+        tokio::select! {
+            frame = master.frame() => {
+                let Some((frame, len)) = frame? else {
+                    return Ok(());
+                };
+                // asked for by the master, not applied
+                let getack = is_getack(&frame);
+                if !getack {
+                    progress.replay.apply(frame, backend)?;
+                }
+                progress.offset += len as u64;
+                if getack {
+                    master.ack(progress.offset).await?;
+                }
+            }
+            _ = acks.tick() => master.ack(progress.offset).await?,
+        }
+    }
+}
+
+// REPLCONF GETACK, sent by masters that need an ACK now
+fn is_getack(frame: &RespFrame) -> bool {
+    let RespFrame::Array(args) = frame else {
+        return false;
+    };
+    let arg = |i: usize| match args.get(i) {
+        Some(RespFrame::BulkString(arg)) => arg.to_ascii_lowercase(),
+        _ => Vec::new(),
+    };
+    arg(0) == b"replconf" && arg(1) == b"getack"
+}
+
+impl MasterConnection {
+    // send a command, the reply is the next frame
+    async fn call(&mut self, args: &[&str]) -> Result<RespFrame> {
+        self.stream.write_all(&aof::command(args).encode()).await?;
+        let (frame, _) = self
+            .frame()
+            .await?
+            .ok_or_else(|| anyhow!("master closed the connection"))?;
+        Ok(frame)
+    }
+
+    // REPLCONF ACK, which the master doesn't answer
+    async fn ack(&mut self, offset: u64) -> Result<()> {
+        let ack = aof::command(&["replconf", "ack", &offset.to_string()]);
+        self.stream.write_all(&ack.encode()).await?;
+        Ok(())
+    }
+
+    // the next frame and its length in bytes, None once the master closed the connection
+    async fn frame(&mut self) -> Result<Option<(RespFrame, usize)>> {
+        loop {
+            let available = self.buf.len();
+            match RespFrame::decode(&mut self.buf) {
+                Ok(frame) => return Ok(Some((frame, available - self.buf.len()))),
+                Err(RespError::NotComplete) => {}
+                Err(e) => return Err(e.into()),
+            }
+            if !self.fill().await? {
+                return Ok(None);
+            }
+        }
+    }
+
+    // the RDB file following FULLRESYNC: `$<length>\r\n` and the data, or `$EOF:<mark>\r\n`
+    // and the data up to the 40 byte mark, which the stream may follow right away. newlines
+    // before it keep the connection alive
+    async fn payload(&mut self) -> Result<Vec<u8>> {
+        let header = loop {
+            while self.buf.first() == Some(&b'\n') {
+                self.buf.advance(1);
+            }
+            if let Some(end) = self.buf.windows(2).position(|w| w == b"\r\n") {
+                let header = self.buf.split_to(end + 2);
+                break String::from_utf8_lossy(&header[..end]).into_owned();
+            }
+            self.fill_or_fail().await?;
+        };
+        let Some(header) = header.strip_prefix('$') else {
+            bail!("unexpected reply instead of the dataset: {}", header);
+        };
+        if let Some(mark) = header.strip_prefix("EOF:") {
+            let mark = mark.as_bytes();
+            let mut searched = 0;
+            loop {
+                if let Some(at) = self.buf[searched..]
+                    .windows(mark.len())
+                    .position(|w| w == mark)
+                {
+                    let data = self.buf.split_to(searched + at);
+                    self.buf.advance(mark.len());
+                    return Ok(data.to_vec());
+                }
+                // a mark cut in two is found once the rest of it arrived
+                searched = self.buf.len().saturating_sub(mark.len() - 1);
+                self.fill_or_fail().await?;
+            }
+        }
+        let len: usize = header.parse()?;
+        while self.buf.len() < len {
+            self.fill_or_fail().await?;
+        }
+        Ok(self.buf.split_to(len).to_vec())
+    }
+
+    // false once the master closed the connection
+    async fn fill(&mut self) -> Result<bool> {
+        Ok(self.stream.read_buf(&mut self.buf).await? > 0)
+    }
+
+    async fn fill_or_fail(&mut self) -> Result<()> {
+        if !self.fill().await? {
+            bail!("master closed the connection");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backlog_keeps_the_latest_bytes() {
+        let mut backlog = Backlog::new(8, 100);
+        assert_eq!(backlog.range(100, 0), Some(vec![]));
+        backlog.push(b"abcde");
+        assert_eq!(backlog.range(101, 3), Some(b"bcd".to_vec()));
+        assert_eq!(backlog.range(103, 3), None);
+
+        // wraps around, the oldest bytes are gone
+        backlog.push(b"fghij");
+        assert_eq!(backlog.start(), 102);
+        assert_eq!(backlog.range(102, 8), Some(b"cdefghij".to_vec()));
+        assert_eq!(backlog.range(101, 2), None);
+        backlog.push(b"0123456789");
+        assert_eq!(backlog.range(112, 8), Some(b"23456789".to_vec()));
+
+        backlog.resize(4);
+        assert_eq!(backlog.start(), 116);
+        assert_eq!(backlog.range(116, 4), Some(b"6789".to_vec()));
+        backlog.push(b"xy");
+        assert_eq!(backlog.range(118, 4), Some(b"89xy".to_vec()));
+    }
+}
@@ -0,0 +1,137 @@
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use anyhow::Result;
+use futures::future;
+use tokio::{
+    io::AsyncWriteExt,
+    sync::{Notify, Semaphore},
+    task::JoinSet,
+    time::Instant,
+};
+use tracing::{info, warn};
+
+use crate::Backend;
+
+use super::{stream_handler, Listener};
+
+/// how long connections get to finish the request they are serving once the server shuts down
+pub const SHUTDOWN_DEADLINE: Duration = Duration::from_secs(5);
+
+const RESP_MAX_CLIENTS: &[u8] = b"-ERR max number of clients reached\r\n";
+
+/// set once the server starts shutting down, connections close as soon as they are idle
+#[derive(Debug, Default)]
+pub struct Shutdown {
+    requested: AtomicBool,
+    notify: Notify,
+}
+
+impl Shutdown {
+    pub fn trigger(&self) {
+        self.requested.store(true, Ordering::Release);
+        self.notify.notify_waiters();
+    }
+
+    pub fn is_requested(&self) -> bool {
+        self.requested.load(Ordering::Acquire)
+    }
+
+    /// return once shutdown is requested
+    pub async fn wait(&self) {
+        loop {
+            // registered before checking the flag, so that a trigger in between isn't missed
+            let notified = self.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            if self.is_requested() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// accept connections until `signal` completes, then stop accepting and give the connections
+/// `SHUTDOWN_DEADLINE` to answer the requests in flight. ongoing saves are waited for within
+/// the same deadline, and the AOF is flushed
+pub async fn run_server(
+    listeners: Vec<Listener>,
+    backend: Backend,
+    signal: impl Future<Output = ()>,
+) -> Result<()> {
+    let mut connections = JoinSet::new();
+    // a permit per connection, held until its handler returns
+    let permits = Arc::new(Semaphore::new(backend.config().maxclients));
+    tokio::pin!(signal);
+    loop {
+        // whichever listener has a client first, accepting is cancel safe
+        let accept = future::select_all(listeners.iter().map(|l| Box::pin(l.accept())));
+        tokio::select! {
+            _ = &mut signal => break,
+            (accepted, _, _) = accept => {
+                let (accepted, raddr) = accepted?;
+                let Ok(permit) = permits.clone().try_acquire_owned() else {
+                    warn!("Rejected connection from {}: max number of clients reached", raddr);
+                    backend.stats().connection_rejected();
+                    // told why, then closed. written from a task of its own, as the client may
+                    // be slow to read
+                    tokio::spawn(async move {
+                        if let Ok(mut socket) = accepted.establish().await {
+                            let _ = socket.write_all(RESP_MAX_CLIENTS).await;
+                            let _ = socket.shutdown().await;
+                        }
+                    });
+                    continue;
+                };
+                info!("Accepted connection from: {}", raddr);
+                let backend = backend.clone();
+                connections.spawn(async move {
+                    let _permit = permit;
+                    let socket = match accepted.establish().await {
+                        Ok(socket) => socket,
+                        Err(e) => {
+                            warn!("TLS handshake with {} failed: {}", raddr, e);
+                            return;
+                        }
+                    };
+                    match stream_handler(socket, raddr.clone(), backend).await {
+                        Ok(_) => info!("Connection from {} is handled successfully", raddr),
+                        Err(e) => warn!("Error: {:?}", e),
+                    }
+                });
+            }
+            // reap finished connections as they go
+            Some(_) = connections.join_next(), if !connections.is_empty() => {}
+        }
+    }
+
+    info!("Shutting down, no longer accepting connections");
+    drop(listeners);
+    backend.shutdown().trigger();
+    let deadline = Instant::now() + SHUTDOWN_DEADLINE;
+    let drained = tokio::time::timeout_at(deadline, async {
+        while connections.join_next().await.is_some() {}
+        while backend.persistence().is_saving() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await;
+    if drained.is_err() {
+        warn!(
+            "{} connections still busy after {:?}, closing them",
+            connections.len(),
+            SHUTDOWN_DEADLINE
+        );
+        connections.shutdown().await;
+    }
+    backend.persistence().aof().flush().await;
+    info!("Server is now ready to exit, bye bye...");
+    Ok(())
+}
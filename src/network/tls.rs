@@ -0,0 +1,54 @@
+use std::{fs::File, io::BufReader, sync::Arc};
+
+use anyhow::{anyhow, Context, Result};
+use tokio_rustls::{
+    rustls::{
+        crypto::ring, pki_types::CertificateDer, server::WebPkiClientVerifier, RootCertStore,
+        ServerConfig as TlsConfig,
+    },
+    TlsAcceptor,
+};
+
+use crate::ServerConfig;
+
+/// the acceptor for tls-port, from the PEM files named by tls-cert-file and tls-key-file. with a
+/// tls-ca-file, clients must present a certificate signed by one of its authorities
+pub fn tls_acceptor(config: &ServerConfig) -> Result<TlsAcceptor> {
+    let (Some(cert_file), Some(key_file)) = (&config.tls_cert_file, &config.tls_key_file) else {
+        return Err(anyhow!("tls-port needs tls-cert-file and tls-key-file"));
+    };
+    let certs = load_certs(cert_file)?;
+    let key = rustls_pemfile::private_key(&mut open(key_file)?)?
+        .ok_or_else(|| anyhow!("no private key in {}", key_file))?;
+
+    let provider = Arc::new(ring::default_provider());
+    let builder =
+        TlsConfig::builder_with_provider(provider.clone()).with_safe_default_protocol_versions()?;
+    let builder = match &config.tls_ca_file {
+        Some(ca_file) => {
+            let mut roots = RootCertStore::empty();
+            for cert in load_certs(ca_file)? {
+                roots.add(cert)?;
+            }
+            let verifier =
+                WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider).build()?;
+            builder.with_client_cert_verifier(verifier)
+        }
+        None => builder.with_no_client_auth(),
+    };
+    let tls = builder.with_single_cert(certs, key)?;
+    Ok(TlsAcceptor::from(Arc::new(tls)))
+}
+
+fn load_certs(path: &str) -> Result<Vec<CertificateDer<'static>>> {
+    let certs = rustls_pemfile::certs(&mut open(path)?).collect::<Result<Vec<_>, _>>()?;
+    if certs.is_empty() {
+        return Err(anyhow!("no certificate in {}", path));
+    }
+    Ok(certs)
+}
+
+fn open(path: &str) -> Result<BufReader<File>> {
+    let file = File::open(path).with_context(|| format!("can't open {}", path))?;
+    Ok(BufReader::new(file))
+}
@@ -0,0 +1,603 @@
+//! the append-only file: every write command in RESP, as sent or rewritten to replay the same
+//! later, with a SELECT in front whenever the database changes. a rewrite replaces it with the
+//! commands that recreate the dataset as it is
+
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::{anyhow, Result};
+use bytes::BytesMut;
+use parking_lot::Mutex;
+use tokio::{
+    fs::{File, OpenOptions},
+    io::AsyncWriteExt,
+    sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
+    task::JoinHandle,
+};
+use tracing::{info, warn};
+
+use crate::{
+    clock,
+    cmd::Command,
+    network::{execute_command, ConnectionState},
+    Backend, BackendValue, BulkString, Db, RespArray, RespDecodeV2, RespEncode, RespError,
+    RespFrame,
+};
+
+// collections are rewritten with at most this many elements per command
+const ITEMS_PER_COMMAND: usize = 64;
+
+/// when appended commands are flushed to disk, as appendfsync says
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppendFsync {
+    // after every write
+    Always,
+    // once a second, at most a second of writes is lost on a crash
+    EverySec,
+    // whenever the operating system sees fit
+    No,
+}
+
+/// appends commands to the AOF
+#[derive(Debug)]
+pub struct AofWriter {
+    file: File,
+    fsync: AppendFsync,
+    // the database the commands written last apply to, None until a SELECT is written
+    db: Option<usize>,
+    // written since the last fsync
+    dirty: bool,
+}
+
+/// the AOF of a server, on while appendonly is. writes are handed over to a task that owns the
+/// `AofWriter`, in the order they were executed
+#[derive(Debug, Default)]
+pub struct Aof {
+    tx: Mutex<Option<UnboundedSender<AofMessage>>>,
+    // the writer task, which finishes once `tx` is dropped and everything sent was written
+    writer: Mutex<Option<JoinHandle<()>>>,
+}
+
+/// applies logged or replicated write commands one by one, a transaction once its EXEC
+/// arrived
+#[derive(Debug, Default)]
+pub struct Replay {
+    conn: ConnectionState,
+    transaction: Option<Vec<Command>>,
+}
+
+#[derive(Debug)]
+enum AofMessage {
+    Append { db: usize, frames: Vec<RespFrame> },
+    // a copy of the dataset taken after every write appended before it
+    Rewrite(Vec<Db>),
+    Fsync(AppendFsync),
+}
+
+impl AppendFsync {
+    /// the policy of an appendfsync setting, which CONFIG SET already validated
+    pub fn from_config(policy: &str) -> Self {
+        match policy {
+            "always" => AppendFsync::Always,
+            "no" => AppendFsync::No,
+            _ => AppendFsync::EverySec,
+        }
+    }
+}
+
+impl AofWriter {
+    pub async fn open(path: &Path, fsync: AppendFsync) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await?;
+        Ok(AofWriter {
+            file,
+            fsync,
+            db: None,
+            dirty: false,
+        })
+    }
+
+    /// append the commands of a request run on database `db`
+    pub async fn append(&mut self, db: usize, frames: Vec<RespFrame>) -> Result<()> {
+        let mut buf = Vec::new();
+        if self.db != Some(db) {
+            buf.extend(command(&["select", &db.to_string()]).encode());
+            self.db = Some(db);
+        }
+        for frame in frames {
+            // a SELECT inside a transaction leaves the database unknown
+            if is_select(&frame) {
+                self.db = None;
+            }
+            buf.extend(frame.encode());
+        }
+        self.file.write_all(&buf).await?;
+        self.file.flush().await?;
+        self.dirty = true;
+        if self.fsync == AppendFsync::Always {
+            self.sync().await?;
+        }
+        Ok(())
+    }
+
+    /// fsync what was appended since the last time
+    pub async fn sync(&mut self) -> Result<()> {
+        if self.dirty {
+            self.file.sync_data().await?;
+            self.dirty = false;
+        }
+        Ok(())
+    }
+}
+
+impl Aof {
+    pub fn is_enabled(&self) -> bool {
+        self.tx.lock().is_some()
+    }
+
+    /// log a write command run on database `db`
+    pub fn feed(&self, db: usize, frame: RespFrame) {
+        self.send(AofMessage::Append {
+            db,
+            frames: vec![frame],
+        });
+    }
+
+    /// log the commands of a transaction started on database `db`, so that it is replayed as
+    /// one
+    pub fn feed_transaction(&self, db: usize, frames: Vec<RespFrame>) {
+        let mut wrapped = Vec::with_capacity(frames.len() + 2);
+        wrapped.push(command(&["multi"]));
+        wrapped.extend(frames);
+        wrapped.push(command(&["exec"]));
+        self.send(AofMessage::Append {
+            db,
+            frames: wrapped,
+        });
+    }
+
+    /// start logging to `path`, rewriting it from `base` first if given. spawns the writer
+    /// task, so it must be called within a tokio runtime
+    pub fn start(&self, path: PathBuf, fsync: AppendFsync, base: Option<Vec<Db>>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        if let Some(dbs) = base {
+            let _ = tx.send(AofMessage::Rewrite(dbs));
+        }
+        *self.writer.lock() = Some(tokio::spawn(write_aof(path, fsync, rx)));
+        *self.tx.lock() = Some(tx);
+    }
+
+    /// stop logging, what was fed so far is still written
+    pub fn stop(&self) {
+        self.tx.lock().take();
+    }
+
+    /// stop logging and return once what was fed so far is written and fsynced
+    pub async fn flush(&self) {
+        self.stop();
+        let writer = self.writer.lock().take();
+        if let Some(writer) = writer {
+            let _ = writer.await;
+        }
+    }
+
+    /// replace the file with the commands recreating `dbs`, handing them back if the AOF is
+    /// off
+    pub fn rewrite(&self, dbs: Vec<Db>) -> Result<(), Vec<Db>> {
+        match &*self.tx.lock() {
+            Some(tx) => {
+                let _ = tx.send(AofMessage::Rewrite(dbs));
+                Ok(())
+            }
+            None => Err(dbs),
+        }
+    }
+
+    pub fn set_fsync(&self, fsync: AppendFsync) {
+        self.send(AofMessage::Fsync(fsync));
+    }
+
+    // the writer only goes away when it fails, which it logged
+    fn send(&self, message: AofMessage) {
+        if let Some(tx) = &*self.tx.lock() {
+            let _ = tx.send(message);
+        }
+    }
+}
+
+async fn write_aof(path: PathBuf, fsync: AppendFsync, mut rx: UnboundedReceiver<AofMessage>) {
+    if let Err(e) = run_writer(&path, fsync, &mut rx).await {
+        warn!(
+            "Writing to the append only file {} failed: {}",
+            path.display(),
+            e
+        );
+    }
+}
+
+async fn run_writer(
+    path: &Path,
+    fsync: AppendFsync,
+    rx: &mut UnboundedReceiver<AofMessage>,
+) -> Result<()> {
+    let mut writer = AofWriter::open(path, fsync).await?;
+    let mut every_second = tokio::time::interval(Duration::from_secs(1));
+    loop {
+        tokio::select! {
+            message = rx.recv() => match message {
+                Some(AofMessage::Append { db, frames }) => writer.append(db, frames).await?,
+                Some(AofMessage::Rewrite(dbs)) => {
+                    writer.sync().await?;
+                    let target = path.to_path_buf();
+                    tokio::task::spawn_blocking(move || rewrite(&target, &dbs)).await??;
+                    info!("Background AOF rewrite finished successfully");
+                    // the old file was replaced, appending goes on in the new one
+                    writer = AofWriter::open(path, writer.fsync).await?;
+                }
+                Some(AofMessage::Fsync(fsync)) => writer.fsync = fsync,
+                // appendonly was turned off
+                None => return writer.sync().await,
+            },
+            _ = every_second.tick() => {
+                if writer.fsync == AppendFsync::EverySec {
+                    writer.sync().await?;
+                }
+            }
+        }
+    }
+}
+
+/// replace the file at `path` with the commands recreating `dbs`
+pub fn rewrite(path: &Path, dbs: &[Db]) -> Result<()> {
+    let mut buf = Vec::new();
+    for (index, db) in dbs.iter().enumerate() {
+        if db.is_empty() {
+            continue;
+        }
+        buf.extend(command(&["select", &index.to_string()]).encode());
+        for (key, entry) in db.entries() {
+            for frame in recreate(key, &entry.value) {
+                buf.extend(frame.encode());
+            }
+            if let Some(at) = entry.expires_at {
+                let ms = clock::unix_time_ms(at).to_string();
+                buf.extend(command(&["pexpireat", key, &ms]).encode());
+            }
+        }
+    }
+    super::replace_file(path, &buf)
+}
+
+// the commands that create `key` holding `value`
+fn recreate(key: &str, value: &BackendValue) -> Vec<RespFrame> {
+    let (name, args): (&str, Vec<Vec<u8>>) = match value {
+        BackendValue::String(s) => return vec![command_bytes("set", key, vec![s.to_vec()])],
+        BackendValue::List(list) => ("rpush", list.iter().cloned().collect()),
+        BackendValue::Set(set) => ("sadd", set.iter().cloned().collect()),
+        BackendValue::ZSet(zset) => (
+            "zadd",
+            zset.iter()
+                .flat_map(|(member, score)| [score_arg(score), member.to_vec()])
+                .collect(),
+        ),
+        BackendValue::Hash(hash) => (
+            "hset",
+            hash.iter()
+                .flat_map(|(field, value)| [field.clone(), value.clone()])
+                .collect(),
+        ),
+    };
+    // sorted sets and hashes take their elements in pairs
+    let per_command = match value {
+        BackendValue::ZSet(_) | BackendValue::Hash(_) => ITEMS_PER_COMMAND * 2,
+        _ => ITEMS_PER_COMMAND,
+    };
+    args.chunks(per_command)
+        .map(|chunk| command_bytes(name, key, chunk.to_vec()))
+        .collect()
+}
+
+fn score_arg(score: f64) -> Vec<u8> {
+    if score.is_infinite() {
+        if score > 0.0 { "+inf" } else { "-inf" }.into()
+    } else {
+        score.to_string().into_bytes()
+    }
+}
+
+/// replay the AOF at `path` into `backend`. a transaction cut short by a crash is left out, as
+/// is an incomplete command at the end
+pub fn load(path: &Path, backend: &Backend) -> Result<()> {
+    let mut buf = BytesMut::from(&std::fs::read(path)?[..]);
+    let mut replay = Replay::default();
+    while !buf.is_empty() {
+        let frame = match RespFrame::decode(&mut buf) {
+            Ok(frame) => frame,
+            Err(RespError::NotComplete) => {
+                warn!("Ignoring an incomplete command at the end of the append only file");
+                break;
+            }
+            Err(e) => return Err(e.into()),
+        };
+        replay.apply(frame, backend)?;
+    }
+    Ok(())
+}
+
+impl Replay {
+    pub fn apply(&mut self, frame: RespFrame, backend: &Backend) -> Result<()> {
+        match (Command::try_from(frame)?, self.transaction.as_mut()) {
+            (Command::Multi(_), _) => self.transaction = Some(Vec::new()),
+            (Command::Exec(_), _) => {
+                let _guard = backend.exec_guard();
+                for cmd in self.transaction.take().unwrap_or_default() {
+                    replay(cmd, backend, &mut self.conn)?;
+                }
+            }
+            (cmd, Some(queued)) => queued.push(cmd),
+            (cmd, None) => {
+                let _guard = backend.command_guard();
+                replay(cmd, backend, &mut self.conn)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn replay(cmd: Command, backend: &Backend, conn: &mut ConnectionState) -> Result<()> {
+    let db = backend
+        .select(conn.selected_db)
+        .ok_or_else(|| anyhow!("invalid database {}", conn.selected_db))?;
+    if let RespFrame::Error(e) = execute_command(cmd, &db, conn) {
+        warn!("A replayed command failed: {:?}", e);
+    }
+    Ok(())
+}
+
+pub(crate) fn is_select(frame: &RespFrame) -> bool {
+    match frame {
+        RespFrame::Array(args) => matches!(
+            args.first(),
+            Some(RespFrame::BulkString(name)) if name.eq_ignore_ascii_case(b"select")
+        ),
+        _ => false,
+    }
+}
+
+/// `frame` as it should be logged once it replied `reply`, so that replaying it later has the
+/// same effect: relative expirations get their deadline, SPOP and the blocking pops become
+/// what they removed. None when it changed nothing. `backend` is the database it ran on
+pub(crate) fn propagated(
+    frame: RespFrame,
+    reply: &RespFrame,
+    backend: &Backend,
+) -> Option<RespFrame> {
+    let RespFrame::Array(args) = &frame else {
+        return Some(frame);
+    };
+    let arg = |index: usize| match args.get(index) {
+        Some(RespFrame::BulkString(arg)) => String::from_utf8_lossy(arg).into_owned(),
+        _ => String::new(),
+    };
+    match arg(0).to_ascii_lowercase().as_str() {
+        "expire" | "pexpire" | "expireat" | "pexpireat" => {
+            if reply != &RespFrame::Integer(1) {
+                return None;
+            }
+            let key = arg(1);
+            // a deadline already due deleted the key
+            match backend.read().peek(&key).and_then(|entry| entry.expires_at) {
+                Some(at) => {
+                    let ms = clock::unix_time_ms(at).to_string();
+                    Some(command(&["pexpireat", &key, &ms]))
+                }
+                None => Some(command(&["del", &key])),
+            }
+        }
+        "spop" => {
+            let members: Vec<Vec<u8>> = match reply {
+                RespFrame::BulkString(member) => vec![member.to_vec()],
+                RespFrame::Array(members) => members
+                    .iter()
+                    .filter_map(|member| match member {
+                        RespFrame::BulkString(member) => Some(member.to_vec()),
+                        _ => None,
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            };
+            (!members.is_empty()).then(|| command_bytes("srem", &arg(1), members))
+        }
+        // [key, element] or [key, member, score] from the key that was served
+        name @ ("blpop" | "brpop" | "bzpopmin" | "bzpopmax") => match reply {
+            RespFrame::Array(popped) => match popped.first() {
+                Some(RespFrame::BulkString(key)) => {
+                    Some(command(&[&name[1..], &String::from_utf8_lossy(key)]))
+                }
+                _ => None,
+            },
+            _ => None,
+        },
+        "blmove" => matches!(reply, RespFrame::BulkString(_))
+            .then(|| command(&["lmove", &arg(1), &arg(2), &arg(3), &arg(4)])),
+        _ => Some(frame),
+    }
+}
+
+pub(crate) fn command(args: &[&str]) -> RespFrame {
+    RespArray::new(
+        args.iter()
+            .map(|arg| BulkString::new(*arg).into())
+            .collect::<Vec<RespFrame>>(),
+    )
+    .into()
+}
+
+fn command_bytes(name: &str, key: &str, args: Vec<Vec<u8>>) -> RespFrame {
+    let mut frames: Vec<RespFrame> =
+        vec![BulkString::new(name).into(), BulkString::new(key).into()];
+    frames.extend(args.into_iter().map(|arg| BulkString::new(arg).into()));
+    RespArray::new(frames).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> Result<PathBuf> {
+        let dir =
+            std::env::temp_dir().join(format!("simple-redis-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+
+    fn frames(commands: &[&[&str]]) -> Vec<RespFrame> {
+        commands.iter().map(|args| command(args)).collect()
+    }
+
+    #[tokio::test]
+    async fn test_aof_writer_selects_databases() -> Result<()> {
+        let dir = temp_dir("aof-writer")?;
+        let path = dir.join("appendonly.aof");
+        let mut writer = AofWriter::open(&path, AppendFsync::Always).await?;
+        writer.append(0, frames(&[&["set", "a", "1"]])).await?;
+        writer.append(0, frames(&[&["set", "b", "2"]])).await?;
+        writer
+            .append(
+                2,
+                frames(&[&["multi"], &["select", "3"], &["set", "c", "3"], &["exec"]]),
+            )
+            .await?;
+        writer.append(2, frames(&[&["set", "d", "4"]])).await?;
+
+        let backend = Backend::new();
+        load(&path, &backend)?;
+        assert_eq!(backend.get("a"), Some(BulkString::new("1").into()));
+        assert_eq!(backend.get("b"), Some(BulkString::new("2").into()));
+        let db2 = backend.select(2).unwrap();
+        assert_eq!(db2.get("d"), Some(BulkString::new("4").into()));
+        assert_eq!(db2.get("c"), None);
+        let db3 = backend.select(3).unwrap();
+        assert_eq!(db3.get("c"), Some(BulkString::new("3").into()));
+
+        std::fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_aof_load_skips_unfinished_transaction() -> Result<()> {
+        let dir = temp_dir("aof-unfinished")?;
+        let path = dir.join("appendonly.aof");
+        let mut data = Vec::new();
+        for frame in frames(&[&["set", "a", "1"], &["multi"], &["set", "b", "2"]]) {
+            data.extend(frame.encode());
+        }
+        data.extend_from_slice(b"*3\r\n$3\r\nSET\r\n$1\r\nc");
+        std::fs::write(&path, data)?;
+
+        let backend = Backend::new();
+        load(&path, &backend)?;
+        assert_eq!(backend.get("a"), Some(BulkString::new("1").into()));
+        assert_eq!(backend.get("b"), None);
+        assert_eq!(backend.get("c"), None);
+
+        std::fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_aof_rewrite_recreates_every_type() -> Result<()> {
+        let dir = temp_dir("aof-rewrite")?;
+        let path = dir.join("appendonly.aof");
+        let backend = Backend::new();
+        backend.set("string".to_string(), BulkString::new("value"));
+        let list: BackendValue =
+            BackendValue::List((0..100).map(|i| i.to_string().into_bytes()).collect());
+        backend.set("list".to_string(), list.clone());
+        let set = BackendValue::Set([b"a".to_vec(), b"b".to_vec()].into());
+        backend.set("set".to_string(), set.clone());
+        let mut zset = crate::ZSet::new();
+        zset.insert(b"low".to_vec(), f64::NEG_INFINITY);
+        zset.insert(b"half".to_vec(), 0.5);
+        let zset = BackendValue::ZSet(zset);
+        backend.set("zset".to_string(), zset.clone());
+        let hash = BackendValue::Hash([(b"f".to_vec(), b"v".to_vec())].into());
+        let db1 = backend.select(1).unwrap();
+        db1.set("hash".to_string(), hash.clone());
+        db1.write()
+            .expire("hash", clock::now() + Duration::from_secs(100));
+
+        rewrite(&path, &backend.snapshot())?;
+        let loaded = Backend::new();
+        load(&path, &loaded)?;
+        assert_eq!(loaded.get("string"), Some(BulkString::new("value").into()));
+        assert_eq!(loaded.get("list"), Some(list));
+        assert_eq!(loaded.get("set"), Some(set));
+        assert_eq!(loaded.get("zset"), Some(zset));
+        let loaded_db1 = loaded.select(1).unwrap();
+        assert_eq!(loaded_db1.get("hash"), Some(hash));
+        assert!(loaded_db1.read().peek("hash").unwrap().expires_at.is_some());
+
+        std::fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_propagated_logs_the_effect() {
+        let backend = Backend::new();
+        backend.set("key".to_string(), BulkString::new("value"));
+        let at = clock::now() + Duration::from_secs(100);
+        backend.write().expire("key", at);
+        let ms = clock::unix_time_ms(at).to_string();
+        let pexpireat = command(&["pexpireat", "key", &ms]);
+        let frame = command(&["expire", "key", "100"]);
+        assert_eq!(
+            propagated(frame.clone(), &RespFrame::Integer(1), &backend),
+            Some(pexpireat)
+        );
+        assert_eq!(propagated(frame, &RespFrame::Integer(0), &backend), None);
+        // a deadline in the past deleted the key
+        let frame = command(&["pexpire", "gone", "-1"]);
+        assert_eq!(
+            propagated(frame, &RespFrame::Integer(1), &backend),
+            Some(command(&["del", "gone"]))
+        );
+
+        let popped = RespArray::new(vec![
+            BulkString::new("a").into(),
+            BulkString::new("b").into(),
+        ]);
+        let frame = command(&["spop", "set", "2"]);
+        assert_eq!(
+            propagated(frame, &popped.clone().into(), &backend),
+            Some(command(&["srem", "set", "a", "b"]))
+        );
+        let frame = command(&["spop", "set"]);
+        assert_eq!(
+            propagated(frame, &crate::RespNullBulkString.into(), &backend),
+            None
+        );
+
+        let frame = command(&["brpop", "a", "b", "0"]);
+        assert_eq!(
+            propagated(frame, &popped.into(), &backend),
+            Some(command(&["rpop", "a"]))
+        );
+        let frame = command(&["blmove", "src", "dst", "left", "right", "0"]);
+        assert_eq!(
+            propagated(frame, &BulkString::new("x").into(), &backend),
+            Some(command(&["lmove", "src", "dst", "left", "right"]))
+        );
+
+        let frame = command(&["set", "key", "value"]);
+        assert_eq!(
+            propagated(frame.clone(), &RespFrame::Null(crate::RespNull), &backend),
+            Some(frame)
+        );
+    }
+}
@@ -0,0 +1,103 @@
+pub mod aof;
+mod rdb;
+
+use std::{
+    fs,
+    path::Path,
+    sync::atomic::{AtomicBool, AtomicI64, Ordering},
+};
+
+use anyhow::Result;
+
+use crate::{clock, Backend};
+
+pub use aof::{Aof, AofWriter, AppendFsync, Replay};
+pub use rdb::{dump, load, load_into, parse, save, save_snapshot, RDB_VERSION};
+
+/// what the server knows about its snapshots
+#[derive(Debug)]
+pub struct Persistence {
+    // a SAVE or BGSAVE is writing the RDB file
+    saving: AtomicBool,
+    // unix time in seconds the last save completed at, the start time until then
+    last_save: AtomicI64,
+    aof: Aof,
+}
+
+/// held for the duration of a save, so that only one runs at a time. it can be moved to the
+/// task saving in the background
+#[derive(Debug)]
+pub struct SaveGuard {
+    backend: Backend,
+}
+
+impl Default for Persistence {
+    fn default() -> Self {
+        Persistence {
+            saving: AtomicBool::new(false),
+            last_save: AtomicI64::new(unix_time()),
+            aof: Aof::default(),
+        }
+    }
+}
+
+impl Persistence {
+    pub fn last_save(&self) -> i64 {
+        self.last_save.load(Ordering::Relaxed)
+    }
+
+    pub fn is_saving(&self) -> bool {
+        self.saving.load(Ordering::Acquire)
+    }
+
+    pub fn aof(&self) -> &Aof {
+        &self.aof
+    }
+}
+
+impl Backend {
+    /// None while another save is running
+    pub fn begin_save(&self) -> Option<SaveGuard> {
+        self.persistence()
+            .saving
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .ok()
+            .map(|_| SaveGuard {
+                backend: self.clone(),
+            })
+    }
+}
+
+impl SaveGuard {
+    /// the save succeeded, LASTSAVE reports it from now on
+    pub fn saved(&self) {
+        self.backend
+            .persistence()
+            .last_save
+            .store(unix_time(), Ordering::Relaxed);
+    }
+}
+
+impl Drop for SaveGuard {
+    fn drop(&mut self) {
+        self.backend
+            .persistence()
+            .saving
+            .store(false, Ordering::Release);
+    }
+}
+
+fn unix_time() -> i64 {
+    clock::unix_time_ms(clock::now()) / 1000
+}
+
+// written next to the target and renamed, so that a failed write leaves the old file intact
+fn replace_file(path: &Path, data: &[u8]) -> Result<()> {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let tmp = path.with_file_name(format!("temp-{}-{}", std::process::id(), name));
+    fs::write(&tmp, data)?;
+    fs::rename(&tmp, path).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp);
+    })?;
+    Ok(())
+}
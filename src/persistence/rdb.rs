@@ -0,0 +1,845 @@
+//! the RDB snapshot format: `REDIS` and a 4 digit version, auxiliary fields, then for each
+//! non-empty database a selector followed by its keys, and finally an EOF opcode and the
+//! CRC64 of everything before it
+
+use std::{
+    fs,
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::{clock, Backend, BackendValue, BulkString, Db, Entry, ZSet, DB_COUNT};
+
+/// the version written, files up to it can be read. version 10 brought the listpack
+/// encodings, 11 the listpack encoded set
+pub const RDB_VERSION: u32 = 11;
+
+const MAGIC: &[u8] = b"REDIS";
+
+// opcodes, each starts a record in place of a value type
+const OPCODE_IDLE: u8 = 0xF8;
+const OPCODE_FREQ: u8 = 0xF9;
+const OPCODE_AUX: u8 = 0xFA;
+const OPCODE_RESIZEDB: u8 = 0xFB;
+const OPCODE_EXPIRETIME_MS: u8 = 0xFC;
+const OPCODE_EXPIRETIME: u8 = 0xFD;
+const OPCODE_SELECTDB: u8 = 0xFE;
+const OPCODE_EOF: u8 = 0xFF;
+
+// value types, the ones up to TYPE_ZSET_2 are written, the compact encodings older and newer
+// versions use for small values are only read
+const TYPE_STRING: u8 = 0;
+const TYPE_LIST: u8 = 1;
+const TYPE_SET: u8 = 2;
+const TYPE_ZSET: u8 = 3;
+const TYPE_HASH: u8 = 4;
+const TYPE_ZSET_2: u8 = 5;
+const TYPE_LIST_ZIPLIST: u8 = 10;
+const TYPE_SET_INTSET: u8 = 11;
+const TYPE_ZSET_ZIPLIST: u8 = 12;
+const TYPE_HASH_ZIPLIST: u8 = 13;
+const TYPE_LIST_QUICKLIST: u8 = 14;
+const TYPE_HASH_LISTPACK: u8 = 16;
+const TYPE_ZSET_LISTPACK: u8 = 17;
+const TYPE_LIST_QUICKLIST_2: u8 = 18;
+const TYPE_SET_LISTPACK: u8 = 20;
+
+// how a quicklist node of TYPE_LIST_QUICKLIST_2 stores its elements
+const QUICKLIST_NODE_PLAIN: u64 = 1;
+const QUICKLIST_NODE_PACKED: u64 = 2;
+
+// the two top bits of a length byte: 6 bit length, 14 bit length, 32 or 64 bit length
+// following, or a specially encoded string
+const LEN_6BIT: u8 = 0;
+const LEN_14BIT: u8 = 1;
+const LEN_32BIT: u8 = 0x80;
+const LEN_64BIT: u8 = 0x81;
+const LEN_ENCVAL: u8 = 3;
+
+// special string encodings, in the low 6 bits
+const ENC_INT8: u8 = 0;
+const ENC_INT16: u8 = 1;
+const ENC_INT32: u8 = 2;
+const ENC_LZF: u8 = 3;
+
+/// write every database to `path`, holding their read locks throughout so that no write
+/// happens during the save
+pub fn save(path: &Path, backend: &Backend) -> Result<()> {
+    let dbs = backend.read_all();
+    let data = dump(&dbs.iter().map(|db| &**db).collect::<Vec<_>>())?;
+    super::replace_file(path, &data)
+}
+
+/// write a copy taken by `Backend::snapshot` to `path`
+pub fn save_snapshot(path: &Path, dbs: &[Db]) -> Result<()> {
+    let data = dump(&dbs.iter().collect::<Vec<_>>())?;
+    super::replace_file(path, &data)
+}
+
+/// a new backend holding the dataset saved in `path`
+pub fn load(path: &Path) -> Result<Backend> {
+    let backend = Backend::new();
+    load_into(path, &backend)?;
+    Ok(backend)
+}
+
+/// replace the dataset of `backend` with the one saved in `path`
+pub fn load_into(path: &Path, backend: &Backend) -> Result<()> {
+    let data = fs::read(path)?;
+    backend.restore(parse(&data)?);
+    Ok(())
+}
+
+/// the RDB encoding of the databases, by index
+pub fn dump(dbs: &[&Db]) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    buf.extend_from_slice(MAGIC);
+    buf.extend_from_slice(format!("{:04}", RDB_VERSION).as_bytes());
+    write_aux(&mut buf, "redis-ver", b"7.2.0");
+    write_aux(&mut buf, "redis-bits", b"64");
+    let ctime = clock::unix_time_ms(clock::now()) / 1000;
+    write_aux(&mut buf, "ctime", ctime.to_string().as_bytes());
+
+    for (index, db) in dbs.iter().enumerate() {
+        if db.is_empty() {
+            continue;
+        }
+        buf.push(OPCODE_SELECTDB);
+        write_len(&mut buf, index as u64);
+        buf.push(OPCODE_RESIZEDB);
+        write_len(&mut buf, db.len() as u64);
+        write_len(&mut buf, db.expires_len() as u64);
+        for (key, entry) in db.entries() {
+            write_entry(&mut buf, key, entry)?;
+        }
+    }
+
+    buf.push(OPCODE_EOF);
+    let checksum = crc64(0, &buf);
+    buf.extend_from_slice(&checksum.to_le_bytes());
+    Ok(buf)
+}
+
+fn write_aux(buf: &mut Vec<u8>, key: &str, value: &[u8]) {
+    buf.push(OPCODE_AUX);
+    write_string(buf, key.as_bytes());
+    write_string(buf, value);
+}
+
+fn write_entry(buf: &mut Vec<u8>, key: &str, entry: &Entry) -> Result<()> {
+    if let Some(at) = entry.expires_at {
+        buf.push(OPCODE_EXPIRETIME_MS);
+        buf.extend_from_slice(&clock::unix_time_ms(at).to_le_bytes());
+    }
+    match &entry.value {
+        BackendValue::String(s) => {
+            buf.push(TYPE_STRING);
+            write_string(buf, key.as_bytes());
+            write_string(buf, s);
+        }
+        BackendValue::List(list) => {
+            buf.push(TYPE_LIST);
+            write_string(buf, key.as_bytes());
+            write_len(buf, list.len() as u64);
+            for element in list {
+                write_string(buf, element);
+            }
+        }
+        BackendValue::Set(set) => {
+            buf.push(TYPE_SET);
+            write_string(buf, key.as_bytes());
+            write_len(buf, set.len() as u64);
+            for member in set {
+                write_string(buf, member);
+            }
+        }
+        BackendValue::ZSet(zset) => {
+            buf.push(TYPE_ZSET);
+            write_string(buf, key.as_bytes());
+            write_len(buf, zset.len() as u64);
+            for (member, score) in zset.iter() {
+                write_string(buf, member);
+                write_double(buf, score);
+            }
+        }
+        BackendValue::Hash(hash) => {
+            buf.push(TYPE_HASH);
+            write_string(buf, key.as_bytes());
+            write_len(buf, hash.len() as u64);
+            for (field, value) in hash {
+                write_string(buf, field);
+                write_string(buf, value);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_len(buf: &mut Vec<u8>, len: u64) {
+    if len < 1 << 6 {
+        buf.push(LEN_6BIT << 6 | len as u8);
+    } else if len < 1 << 14 {
+        buf.push(LEN_14BIT << 6 | (len >> 8) as u8);
+        buf.push(len as u8);
+    } else if len <= u32::MAX as u64 {
+        buf.push(LEN_32BIT);
+        buf.extend_from_slice(&(len as u32).to_be_bytes());
+    } else {
+        buf.push(LEN_64BIT);
+        buf.extend_from_slice(&len.to_be_bytes());
+    }
+}
+
+// integers that survive a round trip through their decimal form are stored as integers
+fn write_string(buf: &mut Vec<u8>, s: &[u8]) {
+    let int = std::str::from_utf8(s)
+        .ok()
+        .and_then(|s| s.parse::<i32>().ok().filter(|n| n.to_string() == s));
+    match int {
+        Some(n) if i8::try_from(n).is_ok() => {
+            buf.push(LEN_ENCVAL << 6 | ENC_INT8);
+            buf.push(n as i8 as u8);
+        }
+        Some(n) if i16::try_from(n).is_ok() => {
+            buf.push(LEN_ENCVAL << 6 | ENC_INT16);
+            buf.extend_from_slice(&(n as i16).to_le_bytes());
+        }
+        Some(n) => {
+            buf.push(LEN_ENCVAL << 6 | ENC_INT32);
+            buf.extend_from_slice(&n.to_le_bytes());
+        }
+        None => {
+            write_len(buf, s.len() as u64);
+            buf.extend_from_slice(s);
+        }
+    }
+}
+
+// a length byte then the digits, with lengths 253 to 255 standing for nan, inf and -inf
+fn write_double(buf: &mut Vec<u8>, n: f64) {
+    if n.is_nan() {
+        buf.push(253);
+    } else if n == f64::INFINITY {
+        buf.push(254);
+    } else if n == f64::NEG_INFINITY {
+        buf.push(255);
+    } else {
+        // the shortest form that reads back the same, always well below 253 bytes
+        let s = format!("{:e}", n);
+        buf.push(s.len() as u8);
+        buf.extend_from_slice(s.as_bytes());
+    }
+}
+
+/// the databases saved in an RDB file, all `DB_COUNT` of them. keys that expired since the
+/// save are left out
+pub fn parse(data: &[u8]) -> Result<Vec<Db>> {
+    let mut reader = Reader { data, pos: 0 };
+    if reader.take(MAGIC.len())? != MAGIC {
+        bail!("not an RDB file");
+    }
+    let version: u32 = std::str::from_utf8(reader.take(4)?)?.parse()?;
+    if version > RDB_VERSION {
+        bail!("can't handle RDB format version {}", version);
+    }
+
+    let now = clock::now();
+    let mut dbs: Vec<Db> = (0..DB_COUNT).map(|_| Db::new()).collect();
+    let mut index = 0;
+    let mut expires_at = None;
+    loop {
+        match reader.byte()? {
+            OPCODE_EOF => break,
+            OPCODE_SELECTDB => {
+                index = reader.len()? as usize;
+                if index >= DB_COUNT {
+                    bail!("database {} is out of range", index);
+                }
+            }
+            OPCODE_RESIZEDB => {
+                // only a hint for preallocation
+                reader.len()?;
+                reader.len()?;
+            }
+            OPCODE_AUX => {
+                reader.string()?;
+                reader.string()?;
+            }
+            OPCODE_EXPIRETIME_MS => {
+                let ms = u64::from_le_bytes(reader.take(8)?.try_into()?);
+                expires_at = Some(SystemTime::UNIX_EPOCH + Duration::from_millis(ms));
+            }
+            OPCODE_EXPIRETIME => {
+                let secs = u32::from_le_bytes(reader.take(4)?.try_into()?);
+                expires_at = Some(SystemTime::UNIX_EPOCH + Duration::from_secs(secs as u64));
+            }
+            // LRU idle time and LFU counter of the next key, not kept
+            OPCODE_IDLE => {
+                reader.len()?;
+            }
+            OPCODE_FREQ => {
+                reader.byte()?;
+            }
+            value_type => {
+                let key = String::from_utf8(reader.string()?)?;
+                let value = reader.value(value_type)?;
+                let mut entry = Entry::new(value);
+                entry.expires_at = expires_at.take();
+                if entry.expires_at.is_none_or(|at| at > now) {
+                    dbs[index].insert_entry(key, entry);
+                }
+            }
+        }
+    }
+
+    // the checksum is 0 when the writer had checksums disabled
+    let end = reader.pos;
+    let checksum = u64::from_le_bytes(reader.take(8)?.try_into()?);
+    if version >= 5 && checksum != 0 && checksum != crc64(0, &data[..end]) {
+        bail!("RDB checksum mismatch");
+    }
+    Ok(dbs)
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|&end| end <= self.data.len())
+            .ok_or_else(|| anyhow!("unexpected end of RDB file"))?;
+        let ret = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(ret)
+    }
+
+    fn byte(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    // a length, or the special encoding of a string as Err
+    fn len_or_encoding(&mut self) -> Result<std::result::Result<u64, u8>> {
+        let first = self.byte()?;
+        Ok(match first >> 6 {
+            LEN_6BIT => Ok((first & 0x3F) as u64),
+            LEN_14BIT => Ok(((first & 0x3F) as u64) << 8 | self.byte()? as u64),
+            LEN_ENCVAL => Err(first & 0x3F),
+            _ => match first {
+                LEN_32BIT => Ok(u32::from_be_bytes(self.take(4)?.try_into()?) as u64),
+                LEN_64BIT => Ok(u64::from_be_bytes(self.take(8)?.try_into()?)),
+                _ => bail!("invalid length encoding {:#x}", first),
+            },
+        })
+    }
+
+    fn len(&mut self) -> Result<u64> {
+        self.len_or_encoding()?
+            .map_err(|enc| anyhow!("expected a length, got string encoding {}", enc))
+    }
+
+    fn string(&mut self) -> Result<Vec<u8>> {
+        match self.len_or_encoding()? {
+            Ok(len) => Ok(self.take(len as usize)?.to_vec()),
+            Err(ENC_INT8) => Ok((self.byte()? as i8).to_string().into_bytes()),
+            Err(ENC_INT16) => {
+                let n = i16::from_le_bytes(self.take(2)?.try_into()?);
+                Ok(n.to_string().into_bytes())
+            }
+            Err(ENC_INT32) => {
+                let n = i32::from_le_bytes(self.take(4)?.try_into()?);
+                Ok(n.to_string().into_bytes())
+            }
+            Err(ENC_LZF) => {
+                let compressed_len = self.len()? as usize;
+                let len = self.len()? as usize;
+                lzf_decompress(self.take(compressed_len)?, len)
+            }
+            Err(enc) => bail!("unknown string encoding {}", enc),
+        }
+    }
+
+    fn double(&mut self) -> Result<f64> {
+        Ok(match self.byte()? {
+            253 => f64::NAN,
+            254 => f64::INFINITY,
+            255 => f64::NEG_INFINITY,
+            len => parse_double(self.take(len as usize)?)?,
+        })
+    }
+
+    // `len` strings
+    fn strings(&mut self) -> Result<Vec<Vec<u8>>> {
+        let len = self.len()?;
+        (0..len).map(|_| self.string()).collect()
+    }
+
+    // `len` pairs of strings
+    fn pairs(&mut self) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+        let len = self.len()?;
+        (0..len)
+            .map(|_| Ok((self.string()?, self.string()?)))
+            .collect()
+    }
+
+    fn value(&mut self, value_type: u8) -> Result<BackendValue> {
+        Ok(match value_type {
+            TYPE_STRING => BulkString::new(self.string()?).into(),
+            TYPE_LIST => BackendValue::List(self.strings()?.into()),
+            TYPE_SET => BackendValue::Set(self.strings()?.into_iter().collect()),
+            TYPE_ZSET | TYPE_ZSET_2 => {
+                let mut zset = ZSet::new();
+                for _ in 0..self.len()? {
+                    let member = self.string()?;
+                    let score = if value_type == TYPE_ZSET {
+                        self.double()?
+                    } else {
+                        f64::from_le_bytes(self.take(8)?.try_into()?)
+                    };
+                    zset.insert(member, score);
+                }
+                BackendValue::ZSet(zset)
+            }
+            TYPE_HASH => BackendValue::Hash(self.pairs()?.into_iter().collect()),
+            TYPE_LIST_ZIPLIST => BackendValue::List(ziplist(&self.string()?)?.into()),
+            TYPE_LIST_QUICKLIST => {
+                let mut list = Vec::new();
+                for _ in 0..self.len()? {
+                    list.extend(ziplist(&self.string()?)?);
+                }
+                BackendValue::List(list.into())
+            }
+            TYPE_LIST_QUICKLIST_2 => {
+                let mut list = Vec::new();
+                for _ in 0..self.len()? {
+                    match self.len()? {
+                        QUICKLIST_NODE_PLAIN => list.push(self.string()?),
+                        QUICKLIST_NODE_PACKED => list.extend(listpack(&self.string()?)?),
+                        container => bail!("unknown quicklist node container {}", container),
+                    }
+                }
+                BackendValue::List(list.into())
+            }
+            TYPE_SET_INTSET => BackendValue::Set(intset(&self.string()?)?.into_iter().collect()),
+            TYPE_SET_LISTPACK => {
+                BackendValue::Set(listpack(&self.string()?)?.into_iter().collect())
+            }
+            TYPE_ZSET_ZIPLIST | TYPE_ZSET_LISTPACK => {
+                let blob = self.string()?;
+                let elements = if value_type == TYPE_ZSET_ZIPLIST {
+                    ziplist(&blob)?
+                } else {
+                    listpack(&blob)?
+                };
+                let mut zset = ZSet::new();
+                for (member, score) in into_pairs(elements)? {
+                    zset.insert(member, parse_double(&score)?);
+                }
+                BackendValue::ZSet(zset)
+            }
+            TYPE_HASH_ZIPLIST | TYPE_HASH_LISTPACK => {
+                let blob = self.string()?;
+                let elements = if value_type == TYPE_HASH_ZIPLIST {
+                    ziplist(&blob)?
+                } else {
+                    listpack(&blob)?
+                };
+                BackendValue::Hash(into_pairs(elements)?.into_iter().collect())
+            }
+            _ => bail!("unsupported RDB value type {}", value_type),
+        })
+    }
+}
+
+fn parse_double(s: &[u8]) -> Result<f64> {
+    Ok(std::str::from_utf8(s)?.parse()?)
+}
+
+// fields and values, or members and scores, of a compact encoding that stores them in turn
+fn into_pairs(elements: Vec<Vec<u8>>) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+    if !elements.len().is_multiple_of(2) {
+        bail!("odd number of elements in a hash or sorted set");
+    }
+    let mut elements = elements.into_iter();
+    let mut pairs = Vec::new();
+    while let (Some(a), Some(b)) = (elements.next(), elements.next()) {
+        pairs.push((a, b));
+    }
+    Ok(pairs)
+}
+
+// the elements of a ziplist: a 10 byte header, then entries made of the previous entry's
+// length, an encoding and the data, then 0xFF
+fn ziplist(blob: &[u8]) -> Result<Vec<Vec<u8>>> {
+    let mut reader = Reader {
+        data: blob,
+        pos: 10,
+    };
+    let mut elements = Vec::new();
+    loop {
+        let prevlen = reader.byte()?;
+        if prevlen == 0xFF {
+            return Ok(elements);
+        }
+        if prevlen == 0xFE {
+            reader.take(4)?;
+        }
+        let enc = reader.byte()?;
+        let element = match enc >> 6 {
+            0 => reader.take((enc & 0x3F) as usize)?.to_vec(),
+            1 => {
+                let len = ((enc & 0x3F) as usize) << 8 | reader.byte()? as usize;
+                reader.take(len)?.to_vec()
+            }
+            2 => {
+                let len = u32::from_be_bytes(reader.take(4)?.try_into()?);
+                reader.take(len as usize)?.to_vec()
+            }
+            _ => {
+                let n = match enc {
+                    0xC0 => i16::from_le_bytes(reader.take(2)?.try_into()?) as i64,
+                    0xD0 => i32::from_le_bytes(reader.take(4)?.try_into()?) as i64,
+                    0xE0 => i64::from_le_bytes(reader.take(8)?.try_into()?),
+                    0xF0 => int24(reader.take(3)?),
+                    0xFE => reader.byte()? as i8 as i64,
+                    // 4 bit immediates from 0 to 12, stored plus one
+                    0xF1..=0xFD => (enc & 0x0F) as i64 - 1,
+                    _ => bail!("invalid ziplist encoding {:#x}", enc),
+                };
+                n.to_string().into_bytes()
+            }
+        };
+        elements.push(element);
+    }
+}
+
+// the elements of a listpack: a 6 byte header, then entries made of an encoding, the data
+// and the entry's length for walking backwards, then 0xFF
+fn listpack(blob: &[u8]) -> Result<Vec<Vec<u8>>> {
+    let mut reader = Reader { data: blob, pos: 6 };
+    let mut elements = Vec::new();
+    loop {
+        let start = reader.pos;
+        let enc = reader.byte()?;
+        let element = match enc {
+            0xFF => return Ok(elements),
+            0x00..=0x7F => enc.to_string().into_bytes(),
+            0x80..=0xBF => reader.take((enc & 0x3F) as usize)?.to_vec(),
+            0xC0..=0xDF => {
+                // 13 bit two's complement
+                let n = ((enc & 0x1F) as i64) << 8 | reader.byte()? as i64;
+                let n = if n >= 1 << 12 { n - (1 << 13) } else { n };
+                n.to_string().into_bytes()
+            }
+            0xE0..=0xEF => {
+                let len = ((enc & 0x0F) as usize) << 8 | reader.byte()? as usize;
+                reader.take(len)?.to_vec()
+            }
+            0xF0 => {
+                let len = u32::from_le_bytes(reader.take(4)?.try_into()?);
+                reader.take(len as usize)?.to_vec()
+            }
+            0xF1 => (i16::from_le_bytes(reader.take(2)?.try_into()?))
+                .to_string()
+                .into_bytes(),
+            0xF2 => int24(reader.take(3)?).to_string().into_bytes(),
+            0xF3 => (i32::from_le_bytes(reader.take(4)?.try_into()?))
+                .to_string()
+                .into_bytes(),
+            0xF4 => (i64::from_le_bytes(reader.take(8)?.try_into()?))
+                .to_string()
+                .into_bytes(),
+            _ => bail!("invalid listpack encoding {:#x}", enc),
+        };
+        // the backlen takes one byte per 7 bits of the entry length
+        let entry_len = reader.pos - start;
+        let backlen = match entry_len {
+            0..=127 => 1,
+            128..=16382 => 2,
+            16383..=2097150 => 3,
+            2097151..=268435454 => 4,
+            _ => 5,
+        };
+        reader.take(backlen)?;
+        elements.push(element);
+    }
+}
+
+// the members of an intset: the integer width and the count, then the sorted integers
+fn intset(blob: &[u8]) -> Result<Vec<Vec<u8>>> {
+    let mut reader = Reader { data: blob, pos: 0 };
+    let width = u32::from_le_bytes(reader.take(4)?.try_into()?) as usize;
+    let len = u32::from_le_bytes(reader.take(4)?.try_into()?);
+    (0..len)
+        .map(|_| {
+            let bytes = reader.take(width)?;
+            let n = match width {
+                2 => i16::from_le_bytes(bytes.try_into()?) as i64,
+                4 => i32::from_le_bytes(bytes.try_into()?) as i64,
+                8 => i64::from_le_bytes(bytes.try_into()?),
+                _ => bail!("invalid intset encoding {}", width),
+            };
+            Ok(n.to_string().into_bytes())
+        })
+        .collect()
+}
+
+fn int24(bytes: &[u8]) -> i64 {
+    // shifted into the top of an i32 so that the sign extends
+    (i32::from_le_bytes([0, bytes[0], bytes[1], bytes[2]]) >> 8) as i64
+}
+
+// LZF as redis compresses long strings with: a control byte below 32 starts a run of that
+// many plus one literal bytes, otherwise its top 3 bits (7 meaning more in the next byte) are
+// a length minus 2 and the rest with the next byte an offset back into the output
+fn lzf_decompress(input: &[u8], len: usize) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(len);
+    let mut i = 0;
+    let corrupt = || anyhow!("corrupt LZF string");
+    while i < input.len() {
+        let ctrl = input[i] as usize;
+        i += 1;
+        if ctrl < 32 {
+            let literal = input.get(i..i + ctrl + 1).ok_or_else(corrupt)?;
+            out.extend_from_slice(literal);
+            i += ctrl + 1;
+        } else {
+            let mut run = ctrl >> 5;
+            if run == 7 {
+                run += *input.get(i).ok_or_else(corrupt)? as usize;
+                i += 1;
+            }
+            let low = *input.get(i).ok_or_else(corrupt)? as usize;
+            i += 1;
+            let back = ((ctrl & 0x1F) << 8) + low + 1;
+            let start = out.len().checked_sub(back).ok_or_else(corrupt)?;
+            // the run may overlap the bytes it produces
+            for j in 0..run + 2 {
+                out.push(out[start + j]);
+            }
+        }
+    }
+    if out.len() != len {
+        return Err(corrupt());
+    }
+    Ok(out)
+}
+
+// CRC-64/Jones as redis computes it, reflected with no final xor
+const CRC64_TABLE: [u64; 256] = crc64_table();
+
+const fn crc64_table() -> [u64; 256] {
+    // 0xad93d23594c935a9 with its bits reversed
+    const POLY: u64 = 0x95ac_9329_ac4b_c9b5;
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u64;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ POLY
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+fn crc64(mut crc: u64, data: &[u8]) -> u64 {
+    for &b in data {
+        crc = CRC64_TABLE[((crc ^ b as u64) & 0xFF) as usize] ^ (crc >> 8);
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string_db(pairs: &[(&str, &str)]) -> Db {
+        let mut db = Db::new();
+        for (key, value) in pairs {
+            db.insert(key.to_string(), BulkString::new(*value).into());
+        }
+        db
+    }
+
+    fn get(db: &Db, key: &str) -> Option<BackendValue> {
+        db.peek(key).map(|e| e.value.clone())
+    }
+
+    #[test]
+    fn test_crc64() {
+        assert_eq!(crc64(0, b"123456789"), 0xe9c6_d914_c4b8_d9ca);
+    }
+
+    #[test]
+    fn test_lzf_decompress() -> Result<()> {
+        assert_eq!(lzf_decompress(b"\x02abc", 3)?, b"abc");
+        // "abc" then 6 bytes from 3 back
+        assert_eq!(lzf_decompress(b"\x02abc\x80\x02", 9)?, b"abcabcabc");
+        assert!(lzf_decompress(b"\x80\x02", 2).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_rdb_strings_round_trip() -> Result<()> {
+        let long = "x".repeat(20_000);
+        let mut db0 = string_db(&[("a", "1"), ("b", "-300"), ("c", "007"), ("d", &long)]);
+        db0.expire("a", clock::now() + Duration::from_secs(60));
+        let db3 = string_db(&[("big", "2147483648"), ("", "empty key")]);
+        let empty = Db::new();
+        let mut dbs = vec![&db0, &empty, &empty, &db3];
+        dbs.resize(DB_COUNT, &empty);
+
+        let data = dump(&dbs)?;
+        assert!(data.starts_with(b"REDIS0011"));
+        let loaded = parse(&data)?;
+        assert_eq!(loaded.len(), DB_COUNT);
+        for (key, value) in [("a", "1"), ("b", "-300"), ("c", "007"), ("d", &long)] {
+            assert_eq!(
+                get(&loaded[0], key),
+                Some(BulkString::new(value).into()),
+                "{}",
+                key
+            );
+        }
+        assert_eq!(
+            loaded[0]
+                .peek("a")
+                .and_then(|e| e.expires_at)
+                .map(clock::unix_time_ms),
+            db0.peek("a")
+                .and_then(|e| e.expires_at)
+                .map(clock::unix_time_ms)
+        );
+        assert_eq!(loaded[0].expires_len(), 1);
+        assert_eq!(
+            get(&loaded[3], ""),
+            Some(BulkString::new("empty key").into())
+        );
+        assert_eq!(loaded[3].len(), 2);
+        assert!(loaded[1].is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_rdb_rejects_corruption() -> Result<()> {
+        let db = string_db(&[("key", "value")]);
+        let mut data = dump(&[&db])?;
+        assert!(parse(&data[..data.len() - 1]).is_err());
+        let value_at = data.windows(5).position(|w| w == b"value").unwrap();
+        data[value_at] = b'V';
+        assert!(parse(&data).is_err());
+        assert!(parse(b"RADIS0011").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_rdb_all_types_round_trip() -> Result<()> {
+        let mut db = string_db(&[("string", "value")]);
+        let list = (0..1000).map(|i| i.to_string().into_bytes()).collect();
+        db.insert("list".to_string(), BackendValue::List(list));
+        let set = [b"a".to_vec(), b"-7".to_vec(), vec![0, 255]].into();
+        db.insert("set".to_string(), BackendValue::Set(set));
+        let mut zset = ZSet::new();
+        for (member, score) in [("one", 1.0), ("third", 1.0 / 3.0), ("huge", -1e300)] {
+            zset.insert(member.into(), score);
+        }
+        zset.insert(b"inf".to_vec(), f64::INFINITY);
+        db.insert("zset".to_string(), BackendValue::ZSet(zset));
+        let hash = [
+            (b"f".to_vec(), b"v".to_vec()),
+            (b"n".to_vec(), b"12".to_vec()),
+        ]
+        .into();
+        db.insert("hash".to_string(), BackendValue::Hash(hash));
+
+        let loaded = parse(&dump(&[&db])?)?;
+        assert_eq!(loaded[0].len(), 5);
+        for key in ["string", "list", "set", "zset", "hash"] {
+            assert_eq!(get(&loaded[0], key), get(&db, key), "{}", key);
+        }
+        Ok(())
+    }
+
+    // a file holding one value of `value_type` encoded as `blob`
+    fn compact(value_type: u8, blob: &[u8]) -> Vec<u8> {
+        let mut data = b"REDIS0010".to_vec();
+        data.extend_from_slice(&[OPCODE_SELECTDB, 0, value_type]);
+        write_string(&mut data, b"key");
+        write_string(&mut data, blob);
+        data.push(OPCODE_EOF);
+        data.extend_from_slice(&[0; 8]);
+        data
+    }
+
+    fn elements(values: &[&str]) -> Vec<Vec<u8>> {
+        values.iter().map(|v| v.as_bytes().to_vec()).collect()
+    }
+
+    #[test]
+    fn test_rdb_compact_encodings() -> Result<()> {
+        // "a", 5 as an immediate, 1000 as an int16
+        let ziplist = b"\x14\0\0\0\x0f\0\0\0\x03\0\0\x01a\x03\xf6\x02\xc0\xe8\x03\xff";
+        let loaded = parse(&compact(TYPE_LIST_ZIPLIST, ziplist))?;
+        assert_eq!(
+            get(&loaded[0], "key"),
+            Some(BackendValue::List(elements(&["a", "5", "1000"]).into()))
+        );
+
+        // "f" and 1 as a 7 bit int, "g" and -300 as a 13 bit int
+        let listpack = b"\x11\0\0\0\x04\0\x81f\x02\x01\x01\x81g\x02\xde\xd4\x02\xff";
+        let loaded = parse(&compact(TYPE_HASH_LISTPACK, listpack))?;
+        let hash = [
+            (b"f".to_vec(), b"1".to_vec()),
+            (b"g".to_vec(), b"-300".to_vec()),
+        ];
+        assert_eq!(
+            get(&loaded[0], "key"),
+            Some(BackendValue::Hash(hash.into()))
+        );
+
+        let loaded = parse(&compact(TYPE_ZSET_LISTPACK, listpack))?;
+        let Some(BackendValue::ZSet(zset)) = get(&loaded[0], "key") else {
+            panic!("not a sorted set");
+        };
+        assert_eq!(zset.score(b"g"), Some(-300.0));
+
+        // 16 bit integers 1, 2 and -3
+        let intset = b"\x02\0\0\0\x03\0\0\0\xfd\xff\x01\0\x02\0";
+        let loaded = parse(&compact(TYPE_SET_INTSET, intset))?;
+        assert_eq!(
+            get(&loaded[0], "key"),
+            Some(BackendValue::Set(
+                elements(&["1", "2", "-3"]).into_iter().collect()
+            ))
+        );
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rdb_skips_keys_expired_since_the_save() -> Result<()> {
+        let mut db = string_db(&[("short", "1"), ("long", "2")]);
+        db.expire("short", clock::now() + Duration::from_secs(1));
+        db.expire("long", clock::now() + Duration::from_secs(10));
+        let data = dump(&[&db])?;
+
+        tokio::time::advance(Duration::from_secs(5)).await;
+        let loaded = parse(&data)?;
+        assert!(!loaded[0].contains_key("short"));
+        assert!(loaded[0].contains_key("long"));
+        Ok(())
+    }
+}
@@ -8,7 +8,13 @@ use super::CRLF_LEN;
 impl RespEncode for f64 {
     fn encode(self) -> Vec<u8> {
         let mut buf = Vec::with_capacity(32);
-        let ret = if self.abs() > 1e+8 || self.abs() < 1e-8 {
+        // special values are spelled out by the spec instead of relying on float formatting
+        let ret = if self.is_nan() {
+            ",nan\r\n".to_string()
+        } else if self.is_infinite() {
+            let sign = if self < 0.0 { "-" } else { "" };
+            format!(",{}inf\r\n", sign)
+        } else if self.abs() > 1e+8 || self.abs() < 1e-8 {
             format!(",{:+e}\r\n", self)
         } else {
             let sign = if self < 0.0 { "" } else { "+" };
@@ -56,6 +62,32 @@ mod tests {
         assert_eq!(frame, 123.45);
     }
 
+    #[test]
+    fn test_double_decode_special_values() {
+        let mut buf = BytesMut::from(",inf\r\n");
+        assert_eq!(f64::decode(&mut buf).unwrap(), f64::INFINITY);
+
+        let mut buf = BytesMut::from(",-inf\r\n");
+        assert_eq!(f64::decode(&mut buf).unwrap(), f64::NEG_INFINITY);
+
+        let mut buf = BytesMut::from(",nan\r\n");
+        assert!(f64::decode(&mut buf).unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_double_special_values_round_trip() {
+        for v in [f64::INFINITY, f64::NEG_INFINITY] {
+            let mut buf = BytesMut::from(v.encode().as_slice());
+            assert_eq!(f64::decode(&mut buf).unwrap(), v);
+        }
+        assert_eq!(f64::INFINITY.encode(), b",inf\r\n");
+        assert_eq!(f64::NEG_INFINITY.encode(), b",-inf\r\n");
+
+        assert_eq!(f64::NAN.encode(), b",nan\r\n");
+        let mut buf = BytesMut::from(f64::NAN.encode().as_slice());
+        assert!(f64::decode(&mut buf).unwrap().is_nan());
+    }
+
     #[test]
     fn test_double() {
         let frame: RespFrame = 123.456.into();
@@ -44,6 +44,26 @@ mod tests {
         assert_eq!(frame, -123);
     }
 
+    #[test]
+    fn test_integer_decode_bounds() {
+        let mut buf = BytesMut::from(format!(":{}\r\n", i64::MAX).as_str());
+        assert_eq!(i64::decode(&mut buf).unwrap(), i64::MAX);
+
+        let mut buf = BytesMut::from(format!(":{}\r\n", i64::MIN).as_str());
+        assert_eq!(i64::decode(&mut buf).unwrap(), i64::MIN);
+
+        // i64::MAX + 1 and i64::MIN - 1
+        for s in [":9223372036854775808\r\n", ":-9223372036854775809\r\n"] {
+            let mut buf = BytesMut::from(s);
+            let ret = i64::decode(&mut buf);
+            assert!(matches!(ret, Err(RespError::ParseIntError(_))), "{:?}", ret);
+        }
+
+        let mut buf = BytesMut::from(":99999999999999999999\r\n");
+        let ret = i64::decode(&mut buf);
+        assert!(matches!(ret, Err(RespError::ParseIntError(_))));
+    }
+
     #[test]
     fn test_integer() {
         let frame: RespFrame = 123.into();
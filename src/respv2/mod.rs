@@ -172,6 +172,21 @@ mod tests {
         assert_eq!(frame, RespFrame::Double(3.12));
     }
 
+    #[test]
+    fn respv2_double_special_values_should_work() {
+        let mut buf = BytesMut::from(",inf\r\n");
+        let frame = RespFrame::decode(&mut buf).unwrap();
+        assert_eq!(frame, RespFrame::Double(f64::INFINITY));
+
+        let mut buf = BytesMut::from(",-inf\r\n");
+        let frame = RespFrame::decode(&mut buf).unwrap();
+        assert_eq!(frame, RespFrame::Double(f64::NEG_INFINITY));
+
+        let mut buf = BytesMut::from(",nan\r\n");
+        let frame = RespFrame::decode(&mut buf).unwrap();
+        assert!(matches!(frame, RespFrame::Double(v) if v.is_nan()));
+    }
+
     #[test]
     fn respv2_map_length_should_work() {
         let buf = b"%2\r\n+OK\r\n-ERR\r\n";
@@ -0,0 +1,141 @@
+mod common;
+
+use std::{path::Path, time::Duration};
+
+use anyhow::Result;
+use common::{call, command, read, spawn_server};
+use simple_redis::{persistence::aof, Backend, BackendValue, BulkString};
+use tokio::{io::AsyncWriteExt, net::TcpStream};
+
+#[tokio::test]
+async fn test_aof_logs_writes_and_rewrites() -> Result<()> {
+    let dir = std::env::temp_dir().join(format!("simple-redis-aof-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join("appendonly.aof");
+
+    let addr = spawn_server().await?;
+    let mut client = TcpStream::connect(addr).await?;
+    call(&mut client, &["set", "before", "1"]).await?;
+    let dir_arg = dir.to_string_lossy();
+    assert_eq!(
+        call(&mut client, &["config", "set", "dir", &dir_arg]).await?,
+        "+OK\r\n"
+    );
+    let reply = call(
+        &mut client,
+        &[
+            "config",
+            "set",
+            "appendonly",
+            "yes",
+            "appendfsync",
+            "always",
+        ],
+    );
+    assert_eq!(reply.await?, "+OK\r\n");
+
+    call(&mut client, &["rpush", "list", "a", "b"]).await?;
+    call(&mut client, &["get", "before"]).await?;
+    // a failed write isn't logged
+    call(&mut client, &["sadd", "list", "c"]).await?;
+    call(&mut client, &["select", "2"]).await?;
+    call(&mut client, &["multi"]).await?;
+    call(&mut client, &["hset", "hash", "f", "v"]).await?;
+    call(&mut client, &["select", "3"]).await?;
+    call(&mut client, &["set", "in", "3"]).await?;
+    let reply = call(&mut client, &["exec"]).await?;
+    assert_eq!(reply, "*3\r\n:+1\r\n+OK\r\n+OK\r\n");
+    call(&mut client, &["set", "after", "x"]).await?;
+
+    wait_for(|| logged(&path, "after")).await;
+    let loaded = Backend::new();
+    aof::load(&path, &loaded)?;
+    assert_eq!(loaded.get("before"), Some(BulkString::new("1").into()));
+    let list = BackendValue::List([b"a".to_vec(), b"b".to_vec()].into());
+    assert_eq!(loaded.get("list"), Some(list));
+    assert_eq!(
+        loaded.select(2).unwrap().get("hash"),
+        Some(BackendValue::Hash([(b"f".to_vec(), b"v".to_vec())].into()))
+    );
+    assert_eq!(
+        loaded.select(3).unwrap().get("in"),
+        Some(BulkString::new("3").into())
+    );
+
+    // the rewrite keeps the data, in fewer commands
+    let reply = call(&mut client, &["bgrewriteaof"]).await?;
+    assert_eq!(reply, "+Background append only file rewriting started\r\n");
+    call(&mut client, &["set", "later", "y"]).await?;
+    wait_for(|| logged(&path, "later") && !logged(&path, "multi")).await;
+
+    let reloaded = Backend::new();
+    aof::load(&path, &reloaded)?;
+    assert_eq!(
+        reloaded.select(2).unwrap().get("hash"),
+        Some(BackendValue::Hash([(b"f".to_vec(), b"v".to_vec())].into()))
+    );
+    assert_eq!(
+        reloaded.select(3).unwrap().get("after"),
+        Some(BulkString::new("x").into())
+    );
+
+    std::fs::remove_dir_all(dir)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_aof_logs_what_writes_did() -> Result<()> {
+    let dir = std::env::temp_dir().join(format!("simple-redis-aof-effects-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join("appendonly.aof");
+
+    let addr = spawn_server().await?;
+    let mut client = TcpStream::connect(addr).await?;
+    let dir_arg = dir.to_string_lossy();
+    call(&mut client, &["config", "set", "dir", &dir_arg]).await?;
+    let reply = call(&mut client, &["config", "set", "appendonly", "yes"]);
+    assert_eq!(reply.await?, "+OK\r\n");
+
+    // a blocked pop is logged once another client served it
+    let mut blocked = TcpStream::connect(addr).await?;
+    blocked.write_all(&command(&["blpop", "list", "0"])).await?;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    call(&mut client, &["rpush", "list", "a", "b"]).await?;
+    assert_eq!(read(&mut blocked).await?, "*2\r\n$4\r\nlist\r\n$1\r\na\r\n");
+
+    call(&mut client, &["sadd", "set", "x"]).await?;
+    call(&mut client, &["spop", "set"]).await?;
+    call(&mut client, &["set", "key", "value"]).await?;
+    call(&mut client, &["expire", "key", "100"]).await?;
+    call(&mut client, &["set", "after", "x"]).await?;
+
+    wait_for(|| logged(&path, "after")).await;
+    assert!(logged(&path, "lpop") && !logged(&path, "blpop"));
+    assert!(logged(&path, "srem") && !logged(&path, "spop"));
+    assert!(logged(&path, "pexpireat") && !logged(&path, "$6\r\nexpire"));
+    let loaded = Backend::new();
+    aof::load(&path, &loaded)?;
+    let list = BackendValue::List([b"b".to_vec()].into());
+    assert_eq!(loaded.get("list"), Some(list));
+    assert_eq!(loaded.get("set"), None);
+    assert!(loaded.read().peek("key").unwrap().expires_at.is_some());
+
+    std::fs::remove_dir_all(dir)?;
+    Ok(())
+}
+
+fn logged(path: &Path, text: &str) -> bool {
+    let data = std::fs::read(path).unwrap_or_default();
+    data.windows(text.len()).any(|w| w == text.as_bytes())
+}
+
+// the writer runs in the background
+async fn wait_for(mut done: impl FnMut() -> bool) {
+    for _ in 0..500 {
+        if done() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("timed out");
+}
@@ -0,0 +1,31 @@
+mod common;
+
+use std::time::Duration;
+
+use anyhow::Result;
+use common::{call, spawn_server};
+use tokio::{io::AsyncReadExt, net::TcpStream};
+
+#[tokio::test]
+async fn test_client_kill_closes_the_other_connection() -> Result<()> {
+    let addr = spawn_server().await?;
+
+    let mut killer = TcpStream::connect(addr).await?;
+    let mut victim = TcpStream::connect(addr).await?;
+    let victim_id = call(&mut victim, &["client", "id"]).await?;
+    let victim_id = victim_id.trim_start_matches([':', '+']).trim_end();
+
+    assert_eq!(
+        call(&mut killer, &["client", "kill", "id", victim_id]).await?,
+        ":+1\r\n"
+    );
+
+    // the server closed the victim's socket
+    let mut buf = [0; 16];
+    let n = tokio::time::timeout(Duration::from_secs(1), victim.read(&mut buf)).await??;
+    assert_eq!(n, 0);
+
+    // the killer is still served
+    assert_eq!(call(&mut killer, &["client", "getname"]).await?, "$-1\r\n");
+    Ok(())
+}
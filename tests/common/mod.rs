@@ -0,0 +1,56 @@
+// each test crate compiles these helpers, not all of them use every one
+#![allow(dead_code)]
+
+use std::{future::Future, net::SocketAddr};
+
+use anyhow::Result;
+use simple_redis::{
+    network::{run_server, Listener},
+    Backend,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+};
+
+/// serve a fresh backend on a random local port
+pub async fn spawn_server() -> Result<SocketAddr> {
+    let (addr, _) = spawn_server_until(std::future::pending()).await?;
+    Ok(addr)
+}
+
+/// serve a fresh backend until `signal` completes, the handle finishes once the server has
+/// shut down
+pub async fn spawn_server_until(
+    signal: impl Future<Output = ()> + Send + 'static,
+) -> Result<(SocketAddr, JoinHandle<Result<()>>)> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    let server = tokio::spawn(run_server(
+        vec![Listener::Tcp(listener)],
+        Backend::new(),
+        signal,
+    ));
+    Ok((addr, server))
+}
+
+// encode a command as a RESP array of bulk strings
+pub fn command(args: &[&str]) -> Vec<u8> {
+    let mut buf = format!("*{}\r\n", args.len()).into_bytes();
+    for arg in args {
+        buf.extend_from_slice(format!("${}\r\n{}\r\n", arg.len(), arg).as_bytes());
+    }
+    buf
+}
+
+pub async fn read(stream: &mut TcpStream) -> Result<String> {
+    let mut buf = vec![0; 1024];
+    let n = stream.read(&mut buf).await?;
+    Ok(String::from_utf8_lossy(&buf[..n]).into_owned())
+}
+
+pub async fn call(stream: &mut TcpStream, args: &[&str]) -> Result<String> {
+    stream.write_all(&command(args)).await?;
+    read(stream).await
+}
@@ -0,0 +1,35 @@
+mod common;
+
+use std::time::Duration;
+
+use anyhow::Result;
+use common::{call, command, read, spawn_server};
+use tokio::{io::AsyncWriteExt, net::TcpStream};
+
+#[tokio::test]
+async fn test_debug_sleep_outlives_a_client_timeout() -> Result<()> {
+    let addr = spawn_server().await?;
+
+    // the client gives up long before the server answers and hangs up
+    let mut sleeper = TcpStream::connect(addr).await?;
+    sleeper
+        .write_all(&command(&["debug", "sleep", "1"]))
+        .await?;
+    let ret = tokio::time::timeout(Duration::from_millis(100), read(&mut sleeper)).await;
+    assert!(ret.is_err());
+    drop(sleeper);
+
+    // other clients are served while it sleeps
+    let mut other = TcpStream::connect(addr).await?;
+    assert_eq!(call(&mut other, &["set", "k", "v"]).await?, "+OK\r\n");
+
+    // the reply to the closed connection is dropped and the server carries on
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    let mut late = TcpStream::connect(addr).await?;
+    assert_eq!(call(&mut late, &["get", "k"]).await?, "$1\r\nv\r\n");
+    assert_eq!(
+        call(&mut late, &["debug", "sleep", "0.05"]).await?,
+        "+OK\r\n"
+    );
+    Ok(())
+}
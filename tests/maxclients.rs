@@ -0,0 +1,47 @@
+mod common;
+
+use anyhow::Result;
+use common::{call, read};
+use simple_redis::{
+    network::{run_server, Listener},
+    Backend,
+};
+use tokio::net::{TcpListener, TcpStream};
+
+#[tokio::test]
+async fn test_connections_beyond_maxclients_are_refused() -> Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    let backend = Backend::new();
+    backend.config_mut().maxclients = 1;
+    let listeners = vec![Listener::Tcp(listener)];
+    tokio::spawn(run_server(listeners, backend, std::future::pending()));
+
+    let mut first = TcpStream::connect(addr).await?;
+    assert_eq!(call(&mut first, &["set", "k", "v"]).await?, "+OK\r\n");
+
+    let mut second = TcpStream::connect(addr).await?;
+    assert_eq!(
+        read(&mut second).await?,
+        "-ERR max number of clients reached\r\n"
+    );
+    assert_eq!(read(&mut second).await?, "");
+
+    // the permit comes back once the first client leaves
+    drop(first);
+    let mut third = loop {
+        let mut client = TcpStream::connect(addr).await?;
+        let reply = call(&mut client, &["info", "stats"]).await?;
+        if reply.starts_with('$') {
+            assert!(reply.contains("rejected_connections:"), "{}", reply);
+            break client;
+        }
+    };
+    let reply = call(&mut third, &["info", "clients"]).await?;
+    assert!(
+        reply.contains("connected_clients:1\r\nmaxclients:1\r\n"),
+        "{}",
+        reply
+    );
+    Ok(())
+}
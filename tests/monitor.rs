@@ -0,0 +1,27 @@
+mod common;
+
+use std::time::Duration;
+
+use anyhow::Result;
+use common::{call, read, spawn_server};
+use tokio::net::TcpStream;
+
+#[tokio::test]
+async fn test_monitor_sees_other_clients() -> Result<()> {
+    let addr = spawn_server().await?;
+    let mut monitor = TcpStream::connect(addr).await?;
+    assert_eq!(call(&mut monitor, &["monitor"]).await?, "+OK\r\n");
+
+    let mut client = TcpStream::connect(addr).await?;
+    let client_addr = client.local_addr()?;
+    assert_eq!(call(&mut client, &["set", "k", "a b"]).await?, "+OK\r\n");
+
+    let line = tokio::time::timeout(Duration::from_secs(1), read(&mut monitor)).await??;
+    let (timestamp, rest) = line.trim_start_matches('+').split_once(' ').unwrap();
+    assert!(timestamp.parse::<f64>().is_ok(), "{}", line);
+    assert_eq!(
+        rest,
+        format!("[0 {}] \"set\" \"k\" \"a b\"\r\n", client_addr)
+    );
+    Ok(())
+}
@@ -0,0 +1,30 @@
+mod common;
+
+use anyhow::Result;
+use common::{command, spawn_server};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+#[tokio::test]
+async fn test_pipelined_commands_are_answered_in_order() -> Result<()> {
+    let addr = spawn_server().await?;
+    let mut client = TcpStream::connect(addr).await?;
+
+    // every request in one write, each get answered by the set right before it
+    let mut requests = Vec::new();
+    let mut expected = String::new();
+    for i in 0..500 {
+        let (key, value) = (format!("k{}", i), format!("v{}", i));
+        requests.extend(command(&["set", &key, &value]));
+        requests.extend(command(&["get", &key]));
+        expected.push_str(&format!("+OK\r\n${}\r\n{}\r\n", value.len(), value));
+    }
+    client.write_all(&requests).await?;
+
+    let mut replies = vec![0; expected.len()];
+    client.read_exact(&mut replies).await?;
+    assert_eq!(String::from_utf8(replies)?, expected);
+    Ok(())
+}
@@ -0,0 +1,46 @@
+mod common;
+
+use std::time::Duration;
+
+use anyhow::Result;
+use common::{call, read, spawn_server};
+use tokio::net::TcpStream;
+
+#[tokio::test]
+async fn test_publish_reaches_subscribers() -> Result<()> {
+    let addr = spawn_server().await?;
+
+    let mut subscriber = TcpStream::connect(addr).await?;
+    let mut publisher = TcpStream::connect(addr).await?;
+    assert_eq!(
+        call(&mut subscriber, &["subscribe", "news"]).await?,
+        "*3\r\n$9\r\nsubscribe\r\n$4\r\nnews\r\n:+1\r\n"
+    );
+
+    // only subscriber commands are accepted in pub/sub mode
+    assert!(call(&mut subscriber, &["get", "news"])
+        .await?
+        .starts_with("-ERR Can't execute 'get'"));
+
+    assert_eq!(
+        call(&mut publisher, &["publish", "news", "hello"]).await?,
+        ":+1\r\n"
+    );
+    let message = tokio::time::timeout(Duration::from_secs(1), read(&mut subscriber)).await??;
+    assert_eq!(
+        message,
+        "*3\r\n$7\r\nmessage\r\n$4\r\nnews\r\n$5\r\nhello\r\n"
+    );
+
+    assert_eq!(
+        call(&mut subscriber, &["unsubscribe"]).await?,
+        "*3\r\n$11\r\nunsubscribe\r\n$4\r\nnews\r\n:+0\r\n"
+    );
+    // back to normal mode, nobody listens anymore
+    assert_eq!(call(&mut subscriber, &["get", "news"]).await?, "_\r\n");
+    assert_eq!(
+        call(&mut publisher, &["publish", "news", "hello"]).await?,
+        ":+0\r\n"
+    );
+    Ok(())
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use std::time::Duration;
+
+use anyhow::Result;
+use common::{call, command, spawn_server};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+#[tokio::test]
+async fn test_quit_closes_after_pipelined_commands() -> Result<()> {
+    let addr = spawn_server().await?;
+    let mut stream = TcpStream::connect(addr).await?;
+
+    // everything sent before QUIT is answered, nothing after it
+    let mut pipeline = command(&["set", "k", "v"]);
+    pipeline.extend(command(&["get", "k"]));
+    pipeline.extend(command(&["quit"]));
+    pipeline.extend(command(&["del", "k"]));
+    stream.write_all(&pipeline).await?;
+
+    let mut replies = Vec::new();
+    tokio::time::timeout(Duration::from_secs(1), stream.read_to_end(&mut replies)).await??;
+    assert_eq!(String::from_utf8(replies)?, "+OK\r\n$1\r\nv\r\n+OK\r\n");
+
+    // the DEL was never run
+    let mut other = TcpStream::connect(addr).await?;
+    assert_eq!(call(&mut other, &["get", "k"]).await?, "$1\r\nv\r\n");
+    Ok(())
+}
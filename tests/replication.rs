@@ -0,0 +1,115 @@
+mod common;
+
+use std::time::Duration;
+
+use anyhow::Result;
+use common::{call, command, read, spawn_server};
+use tokio::{io::AsyncWriteExt, net::TcpStream};
+
+#[tokio::test]
+async fn test_replica_follows_its_master() -> Result<()> {
+    let master_addr = spawn_server().await?;
+    let mut master = TcpStream::connect(master_addr).await?;
+    let mut replica = TcpStream::connect(spawn_server().await?).await?;
+    call(&mut master, &["set", "before", "1"]).await?;
+
+    let port = master_addr.port().to_string();
+    let reply = call(&mut replica, &["replicaof", "127.0.0.1", &port]).await?;
+    assert_eq!(reply, "+OK\r\n");
+    wait_for(&mut replica, &["get", "before"], "$1\r\n1\r\n").await?;
+
+    // later writes follow, in other databases and transactions too
+    call(&mut master, &["select", "2"]).await?;
+    call(&mut master, &["multi"]).await?;
+    call(&mut master, &["hset", "hash", "f", "v"]).await?;
+    call(&mut master, &["sadd", "set", "a"]).await?;
+    assert_eq!(call(&mut master, &["exec"]).await?, "*2\r\n:+1\r\n:+1\r\n");
+    call(&mut replica, &["select", "2"]).await?;
+    wait_for(&mut replica, &["hget", "hash", "f"], "$1\r\nv\r\n").await?;
+    // the replica acknowledges what it received
+    assert_eq!(call(&mut master, &["wait", "1", "5000"]).await?, ":+1\r\n");
+
+    // replicas get what a write did: the pop that served a blocked client, the member SPOP
+    // picked
+    let mut blocked = TcpStream::connect(master_addr).await?;
+    blocked.write_all(&command(&["blpop", "list", "0"])).await?;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    call(&mut master, &["select", "0"]).await?;
+    call(&mut master, &["rpush", "list", "a", "b"]).await?;
+    assert_eq!(read(&mut blocked).await?, "*2\r\n$4\r\nlist\r\n$1\r\na\r\n");
+    call(&mut master, &["sadd", "pool", "x", "y"]).await?;
+    call(&mut master, &["spop", "pool"]).await?;
+    let left = call(&mut master, &["smembers", "pool"]).await?;
+    call(&mut replica, &["select", "0"]).await?;
+    wait_for(&mut replica, &["smembers", "pool"], &left).await?;
+    wait_for(
+        &mut replica,
+        &["lrange", "list", "0", "-1"],
+        "*1\r\n$1\r\nb\r\n",
+    )
+    .await?;
+
+    let info = call(&mut master, &["info", "replication"]).await?;
+    assert!(
+        info.contains(
+            "role:master\r\nconnected_slaves:1\r\nslave0:ip=127.0.0.1,port=6379,state=online,"
+        ),
+        "{}",
+        info
+    );
+    assert!(info.contains("\r\nmaster_replid:"), "{}", info);
+    let info = call(&mut replica, &["info", "replication"]).await?;
+    assert!(info.contains("role:slave\r\n"), "{}", info);
+    assert!(info.contains("master_link_status:up\r\n"), "{}", info);
+
+    // a replica only takes writes from its master, until it is promoted
+    assert_eq!(
+        call(&mut replica, &["set", "key", "value"]).await?,
+        "-READONLY You can't write against a read only replica.\r\n"
+    );
+    assert_eq!(
+        call(&mut replica, &["replicaof", "no", "one"]).await?,
+        "+OK\r\n"
+    );
+    assert_eq!(
+        call(&mut replica, &["set", "key", "value"]).await?,
+        "+OK\r\n"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_replica_catches_up_after_reconnecting() -> Result<()> {
+    let master_addr = spawn_server().await?;
+    let mut master = TcpStream::connect(master_addr).await?;
+    let mut replica = TcpStream::connect(spawn_server().await?).await?;
+    let port = master_addr.port().to_string();
+    call(&mut replica, &["replicaof", "127.0.0.1", &port]).await?;
+    call(&mut master, &["set", "first", "1"]).await?;
+    wait_for(&mut replica, &["get", "first"], "$1\r\n1\r\n").await?;
+
+    // writes made while the link is down reach the replica once it is back
+    let clients = call(&mut master, &["client", "list"]).await?;
+    let id = clients
+        .lines()
+        .find(|line| line.contains("cmd=psync") || line.contains("cmd=replconf"))
+        .and_then(|line| line.strip_prefix("id="))
+        .and_then(|line| line.split(' ').next())
+        .unwrap()
+        .to_string();
+    call(&mut master, &["client", "kill", "id", &id]).await?;
+    call(&mut master, &["set", "second", "2"]).await?;
+    wait_for(&mut replica, &["get", "second"], "$1\r\n2\r\n").await?;
+    Ok(())
+}
+
+// replication runs in the background
+async fn wait_for(client: &mut TcpStream, args: &[&str], reply: &str) -> Result<()> {
+    for _ in 0..500 {
+        if call(client, args).await? == reply {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("timed out waiting for {:?}", args);
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use std::time::Duration;
+
+use anyhow::Result;
+use common::{call, command, read, spawn_server_until};
+use tokio::{io::AsyncWriteExt, net::TcpStream, sync::oneshot};
+
+#[tokio::test]
+async fn test_shutdown_answers_requests_in_flight() -> Result<()> {
+    let (trigger, signal) = oneshot::channel::<()>();
+    let (addr, server) = spawn_server_until(async {
+        let _ = signal.await;
+    })
+    .await?;
+
+    let mut idle = TcpStream::connect(addr).await?;
+    assert_eq!(call(&mut idle, &["set", "k", "v"]).await?, "+OK\r\n");
+    let mut busy = TcpStream::connect(addr).await?;
+    busy.write_all(&command(&["debug", "sleep", "0.2"])).await?;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    trigger.send(()).unwrap();
+    // the sleep finishes and is answered, then both connections are closed
+    assert_eq!(read(&mut busy).await?, "+OK\r\n");
+    assert_eq!(read(&mut busy).await?, "");
+    assert_eq!(read(&mut idle).await?, "");
+    server.await??;
+
+    assert!(TcpStream::connect(addr).await.is_err());
+    Ok(())
+}
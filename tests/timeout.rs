@@ -0,0 +1,35 @@
+mod common;
+
+use std::time::Duration;
+
+use anyhow::Result;
+use common::{call, read, spawn_server};
+use tokio::{net::TcpStream, time::timeout};
+
+#[tokio::test]
+async fn test_idle_client_is_disconnected_after_read_timeout() -> Result<()> {
+    let addr = spawn_server().await?;
+    let mut subscriber = TcpStream::connect(addr).await?;
+    call(&mut subscriber, &["subscribe", "news"]).await?;
+
+    let mut idle = TcpStream::connect(addr).await?;
+    let reply = call(&mut idle, &["config", "set", "tcp-read-timeout-ms", "100"]).await?;
+    assert_eq!(reply, "+OK\r\n");
+    // closed by the server once the timeout passes
+    let closed = timeout(Duration::from_secs(2), read(&mut idle)).await??;
+    assert_eq!(closed, "");
+
+    // a subscriber only waits for messages
+    let mut active = TcpStream::connect(addr).await?;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(
+        call(&mut active, &["publish", "news", "hi"]).await?,
+        ":+1\r\n"
+    );
+    let message = read(&mut subscriber).await?;
+    assert!(message.ends_with("$2\r\nhi\r\n"), "{}", message);
+
+    let list = call(&mut active, &["client", "list"]).await?;
+    assert_eq!(list.matches("id=").count(), 2, "{}", list);
+    Ok(())
+}
@@ -0,0 +1,143 @@
+mod common;
+
+use std::{net::SocketAddr, path::PathBuf, sync::Arc};
+
+use anyhow::Result;
+use common::command;
+use rcgen::{BasicConstraints, CertificateParams, CertifiedKey, IsCa, KeyPair};
+use simple_redis::{
+    network::{run_server, tls_acceptor, Listener},
+    Backend, ServerConfig,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+use tokio_rustls::{
+    client::TlsStream,
+    rustls::{
+        crypto::ring,
+        pki_types::{CertificateDer, PrivateKeyDer, ServerName},
+        ClientConfig, RootCertStore,
+    },
+    TlsConnector,
+};
+
+// write a PEM file next to the others of this test run
+fn write_pem(name: &str, pem: &str) -> Result<String> {
+    let dir: PathBuf =
+        std::env::temp_dir().join(format!("simple-redis-tls-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(name);
+    std::fs::write(&path, pem)?;
+    Ok(path.display().to_string())
+}
+
+// a TLS listener with a self-signed certificate for localhost, requiring client certificates
+// signed by `ca` if given
+async fn spawn_tls_server(
+    server: &CertifiedKey,
+    ca: Option<&str>,
+    prefix: &str,
+) -> Result<SocketAddr> {
+    let config = ServerConfig {
+        tls_cert_file: Some(write_pem(&format!("{}.crt", prefix), &server.cert.pem())?),
+        tls_key_file: Some(write_pem(
+            &format!("{}.key", prefix),
+            &server.key_pair.serialize_pem(),
+        )?),
+        tls_ca_file: ca
+            .map(|pem| write_pem(&format!("{}-ca.crt", prefix), pem))
+            .transpose()?,
+        ..Default::default()
+    };
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    let listeners = vec![Listener::Tls(listener, tls_acceptor(&config)?)];
+    tokio::spawn(run_server(
+        listeners,
+        Backend::new(),
+        std::future::pending(),
+    ));
+    Ok(addr)
+}
+
+async fn connect(
+    addr: SocketAddr,
+    server: &CertifiedKey,
+    client: Option<(CertificateDer<'static>, PrivateKeyDer<'static>)>,
+) -> Result<TlsStream<TcpStream>> {
+    let mut roots = RootCertStore::empty();
+    roots.add(server.cert.der().clone())?;
+    let builder = ClientConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions()?
+        .with_root_certificates(roots);
+    let config = match client {
+        Some((cert, key)) => builder.with_client_auth_cert(vec![cert], key)?,
+        None => builder.with_no_client_auth(),
+    };
+    let stream = TcpStream::connect(addr).await?;
+    let name = ServerName::try_from("localhost")?;
+    Ok(TlsConnector::from(Arc::new(config))
+        .connect(name, stream)
+        .await?)
+}
+
+async fn call(stream: &mut TlsStream<TcpStream>, args: &[&str]) -> Result<String> {
+    stream.write_all(&command(args)).await?;
+    let mut buf = vec![0; 1024];
+    let n = stream.read(&mut buf).await?;
+    Ok(String::from_utf8_lossy(&buf[..n]).into_owned())
+}
+
+#[tokio::test]
+async fn test_tls_with_self_signed_certificate() -> Result<()> {
+    let server = rcgen::generate_simple_self_signed(vec!["localhost".to_string()])?;
+    let addr = spawn_tls_server(&server, None, "plain").await?;
+
+    let mut client = connect(addr, &server, None).await?;
+    assert_eq!(
+        call(&mut client, &["set", "key", "value"]).await?,
+        "+OK\r\n"
+    );
+    assert_eq!(call(&mut client, &["get", "key"]).await?, "$5\r\nvalue\r\n");
+
+    // a client speaking plain RESP to the TLS port gets nothing back
+    let mut plain = TcpStream::connect(addr).await?;
+    plain.write_all(&command(&["get", "key"])).await?;
+    let mut buf = vec![0; 1024];
+    assert!(!matches!(plain.read(&mut buf).await, Ok(n) if n > 0 && buf.starts_with(b"$")));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_tls_requires_client_certificate_signed_by_ca() -> Result<()> {
+    let server = rcgen::generate_simple_self_signed(vec!["localhost".to_string()])?;
+    let mut params = CertificateParams::new(Vec::<String>::new())?;
+    params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    let ca_key = KeyPair::generate()?;
+    let ca = params.self_signed(&ca_key)?;
+    let addr = spawn_tls_server(&server, Some(&ca.pem()), "mutual").await?;
+
+    let client_key = KeyPair::generate()?;
+    let client_cert =
+        CertificateParams::new(vec!["client".to_string()])?.signed_by(&client_key, &ca, &ca_key)?;
+    let identity = (
+        client_cert.der().clone(),
+        PrivateKeyDer::try_from(client_key.serialize_der()).map_err(anyhow::Error::msg)?,
+    );
+    let mut client = connect(addr, &server, Some(identity)).await?;
+    assert_eq!(
+        call(&mut client, &["set", "key", "value"]).await?,
+        "+OK\r\n"
+    );
+
+    // without a certificate the server ends the handshake, which a TLS 1.3 client only sees
+    // on its first read
+    let refused = match connect(addr, &server, None).await {
+        Ok(mut client) => call(&mut client, &["get", "key"]).await,
+        Err(e) => Err(e),
+    };
+    assert!(!matches!(refused, Ok(reply) if reply.starts_with('$')));
+    Ok(())
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use anyhow::Result;
+use common::command;
+use simple_redis::{
+    network::{run_server, Listener},
+    Backend,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream, UnixStream},
+};
+
+#[tokio::test]
+async fn test_unix_socket_and_tcp_share_the_dataset() -> Result<()> {
+    let path = std::env::temp_dir().join(format!("simple-redis-{}.sock", std::process::id()));
+    let tcp = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = tcp.local_addr()?;
+    let listeners = vec![Listener::Tcp(tcp), Listener::bind_unix(&path)?];
+    tokio::spawn(run_server(
+        listeners,
+        Backend::new(),
+        std::future::pending(),
+    ));
+
+    let mut unix = UnixStream::connect(&path).await?;
+    unix.write_all(&command(&["set", "key", "from unix"]))
+        .await?;
+    let mut buf = vec![0; 1024];
+    let n = unix.read(&mut buf).await?;
+    assert_eq!(&buf[..n], b"+OK\r\n");
+
+    unix.write_all(&command(&["client", "list"])).await?;
+    let n = unix.read(&mut buf).await?;
+    let list = String::from_utf8_lossy(&buf[..n]);
+    assert!(
+        list.contains(&format!("addr={}:0", path.display())),
+        "{}",
+        list
+    );
+
+    let mut tcp = TcpStream::connect(addr).await?;
+    tcp.write_all(&command(&["get", "key"])).await?;
+    let n = tcp.read(&mut buf).await?;
+    assert_eq!(&buf[..n], b"$9\r\nfrom unix\r\n");
+    Ok(())
+}